@@ -0,0 +1,548 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Headless runner for .cartero files.
+//!
+//! This binary does not depend on GTK, so it can be used in CI pipelines or in
+//! any other environment where a display is not available.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, ExitCode, Stdio};
+use std::sync::{Arc, Mutex};
+
+use cartero::client::{
+    assertions_to_json, collection_items, json_report, junit_report, unix_now, ConsoleLevel,
+    CookieJar, RequestRun, RunItem, RunSettings, Runner, StatusRange,
+};
+use cartero::entities::{Environment, KeyValue, KeyValueTable, ProxySettings, ResponseData};
+use cartero::error::CarteroError;
+use serde_json::{json, Value};
+
+/// Everything went fine.
+const EXIT_OK: u8 = 0;
+
+/// A request file could not be loaded, or the request could not be sent.
+const EXIT_ERROR: u8 = 1;
+
+/// The command line arguments are not valid.
+const EXIT_USAGE: u8 = 2;
+
+/// A response was received, but its status code was marked as failing.
+const EXIT_STATUS: u8 = 3;
+
+/// A response was received, but it did not pass the assertions of its request.
+const EXIT_ASSERTION: u8 = 4;
+
+const USAGE: &str = "Usage: cartero-cli [OPTIONS] FILE...
+
+Sends the requests stored in one or more .cartero files. When FILE is a folder,
+the requests of the collection stored in it are sent, in the order set in the
+[run] table of its collection.toml file, or in alphabetical order. Cookies set by a
+response are sent by the requests that come after it, and OAuth 2.0 tokens are
+reused by the requests of the same client. When a user has to log in, the
+login page is opened in the web browser. The variables captured from a response
+can be used by the requests that come after it, but files are not modified.
+The assertions of a request are checked on its response, and the ones that
+fail are reported. What the scripts of a request print is written to stderr.
+
+Options:
+  -j, --json                 Print the responses as a JSON array
+  -o, --output FILE          Write the output to FILE instead of stdout
+  -d, --data FILE            Send the requests once per row of a CSV file, or of
+                             a JSON array of objects, using its values as
+                             variables. They override the request variables
+  -s, --stop-on-failure      Stop at the first request that cannot be sent, or
+                             whose captures, scripts or assertions fail
+      --junit FILE           Write a JUnit XML report of the run to FILE
+      --report FILE          Write a JSON report of the run to FILE
+  -f, --fail-status CODES    Exit with an error if a status code matches CODES,
+                             a comma separated list such as 404,500-599 or 4xx
+  -e, --env FILE             Use the variables of an environment file, such as
+                             dev.env.toml. Request variables take precedence
+      --var NAME=VALUE       Set or override a variable for every request
+  -t, --timeout SECONDS      Give up on requests that take longer than SECONDS,
+                             unless the request file sets its own timeout
+  -x, --proxy URL            Send requests through a proxy, such as
+                             http://localhost:8080 or socks5h://localhost:1080,
+                             unless the request file picks its own proxy
+  -U, --proxy-user USER:PASSWORD
+                             Authenticate with the proxy
+      --no-proxy HOSTS       A comma separated list of hosts that skip the proxy
+  -c, --cookie-jar FILE      Read cookies from FILE, in the Netscape format used
+                             by curl, and write them back when done
+  -h, --help                 Show this help and exit
+      --version              Show the version and exit
+
+Exit codes:
+  0  every request was sent
+  1  a request could not be loaded or sent, or a capture or a script failed
+  2  invalid command line arguments
+  3  a status code matched --fail-status
+  4  an assertion failed";
+
+#[derive(Debug, Default)]
+struct Options {
+    json: bool,
+    output: Option<PathBuf>,
+    data: Option<PathBuf>,
+    stop_on_failure: bool,
+    junit: Option<PathBuf>,
+    report: Option<PathBuf>,
+    fail_status: Vec<StatusRange>,
+    environment: Option<PathBuf>,
+    variables: Vec<KeyValue>,
+    timeout: Option<u64>,
+    proxy: ProxySettings,
+    cookie_jar: Option<PathBuf>,
+    files: Vec<PathBuf>,
+    help: bool,
+    version: bool,
+}
+
+fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value_for = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("missing value for {name}"))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => options.help = true,
+            "--version" => options.version = true,
+            "-j" | "--json" => options.json = true,
+            "-o" | "--output" => options.output = Some(PathBuf::from(value_for(&arg)?)),
+            "-d" | "--data" => options.data = Some(PathBuf::from(value_for(&arg)?)),
+            "-s" | "--stop-on-failure" => options.stop_on_failure = true,
+            "--junit" => options.junit = Some(PathBuf::from(value_for(&arg)?)),
+            "--report" => options.report = Some(PathBuf::from(value_for(&arg)?)),
+            "-f" | "--fail-status" => {
+                for code in value_for(&arg)?.split(',') {
+                    let range = StatusRange::try_from(code)
+                        .map_err(|_| format!("invalid status code: {}", code.trim()))?;
+                    options.fail_status.push(range);
+                }
+            }
+            "-e" | "--env" => options.environment = Some(PathBuf::from(value_for(&arg)?)),
+            "--var" => {
+                let var = value_for(&arg)?;
+                let Some((name, value)) = var.split_once('=') else {
+                    return Err(format!("invalid variable: {var}"));
+                };
+                options.variables.push(KeyValue::from((name, value)));
+            }
+            "-t" | "--timeout" => {
+                let timeout = value_for(&arg)?;
+                let Ok(timeout) = timeout.parse::<u64>() else {
+                    return Err(format!("invalid timeout: {timeout}"));
+                };
+                options.timeout = Some(timeout);
+            }
+            "-x" | "--proxy" => options.proxy.url = value_for(&arg)?,
+            "-U" | "--proxy-user" => {
+                let credentials = value_for(&arg)?;
+                let (username, password) =
+                    credentials.split_once(':').unwrap_or((&credentials, ""));
+                options.proxy.username = username.to_string();
+                options.proxy.password = password.to_string();
+            }
+            "--no-proxy" => options.proxy.no_proxy = value_for(&arg)?,
+            "-c" | "--cookie-jar" => options.cookie_jar = Some(PathBuf::from(value_for(&arg)?)),
+            "--" => options.files.extend(args.by_ref().map(PathBuf::from)),
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("unknown option: {flag}"));
+            }
+            file => options.files.push(PathBuf::from(file)),
+        }
+    }
+    Ok(options)
+}
+
+fn load_environment(path: &PathBuf) -> Result<Environment, CarteroError> {
+    let contents = std::fs::read_to_string(path)?;
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name = cartero::file::environment_name(&file_name);
+    let mut environment = cartero::file::parse_environment_toml(&name, &contents)?;
+    if let Some(dir) = path.parent() {
+        environment.tls = environment.tls.with_base_dir(dir);
+    }
+    Ok(environment)
+}
+
+/// Prints the login page of an OAuth 2.0 client, and opens it in the web browser.
+fn open_browser(url: &str) -> std::io::Result<()> {
+    eprintln!("cartero-cli: log in at {url}");
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    // The address was printed, so the user can still open it by hand.
+    let _ = command
+        .arg(url)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    Ok(())
+}
+
+/// Lists the request files to run. A folder gives the request files of its collection.
+fn run_items(files: &[PathBuf]) -> Result<Vec<RunItem>, (PathBuf, CarteroError)> {
+    let mut items = Vec::new();
+    for path in files {
+        if path.is_dir() {
+            items.extend(collection_items(path).map_err(|e| (path.clone(), e))?);
+        } else {
+            items.push(RunItem::file(path));
+        }
+    }
+    Ok(items)
+}
+
+fn load_data(path: &PathBuf) -> Result<Vec<KeyValueTable>, CarteroError> {
+    let contents = std::fs::read_to_string(path)?;
+    let file_name = path.to_string_lossy();
+    cartero::file::parse_data_file(&file_name, &contents)
+}
+
+/// The name of the run in the reports: the name of the collection folder when only
+/// one is run.
+fn run_name(files: &[PathBuf]) -> String {
+    match files {
+        [path] if path.is_dir() => std::fs::canonicalize(path)
+            .ok()
+            .and_then(|path| path.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_else(|| path.display().to_string()),
+        _ => String::from("cartero"),
+    }
+}
+
+fn pretty_json(document: &Value) -> String {
+    let mut json = serde_json::to_string_pretty(document).unwrap_or_default();
+    json.push('\n');
+    json
+}
+
+fn response_to_text(response: &ResponseData) -> String {
+    let mut text = format!("HTTP {}\n", response.status_code);
+    for header in response.headers.iter() {
+        text.push_str(&format!("{}: {}\n", header.name, header.value));
+    }
+    text.push('\n');
+    text.push_str(&response.body_str());
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text
+}
+
+fn response_to_json(response: &ResponseData) -> Value {
+    let headers: Vec<Value> = response
+        .headers
+        .iter()
+        .map(|h| json!({ "name": h.name, "value": h.value }))
+        .collect();
+    let redirects: Vec<Value> = response
+        .redirects
+        .iter()
+        .map(|r| json!({ "status": r.status_code, "url": r.url, "location": r.location }))
+        .collect();
+    let cookies: Vec<Value> = response
+        .cookies
+        .iter()
+        .map(|c| json!({ "name": c.name, "value": c.value, "domain": c.domain, "path": c.path }))
+        .collect();
+    json!({
+        "status": response.status_code,
+        "duration": response.duration as u64,
+        "size": response.size,
+        "headers": headers,
+        "body": response.body_str(),
+        "redirects": redirects,
+        "cookies": cookies,
+        "proxy": response.proxy,
+    })
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("cartero-cli: {e}\n\n{USAGE}");
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+    if options.help {
+        println!("{USAGE}");
+        return ExitCode::from(EXIT_OK);
+    }
+    if options.version {
+        println!("cartero-cli {}", env!("CARGO_PKG_VERSION"));
+        return ExitCode::from(EXIT_OK);
+    }
+    if options.files.is_empty() {
+        eprintln!("cartero-cli: no request files given\n\n{USAGE}");
+        return ExitCode::from(EXIT_USAGE);
+    }
+
+    let environment = match &options.environment {
+        Some(path) => match load_environment(path) {
+            Ok(environment) => Some(environment),
+            Err(e) => {
+                eprintln!("cartero-cli: {}: {}", path.display(), e.details());
+                return ExitCode::from(EXIT_ERROR);
+            }
+        },
+        None => None,
+    };
+
+    let cookie_jar = match &options.cookie_jar {
+        Some(path) if path.exists() => match std::fs::read_to_string(path) {
+            Ok(content) => CookieJar::parse_netscape(&content, unix_now()),
+            Err(e) => {
+                eprintln!("cartero-cli: {}: {e}", path.display());
+                return ExitCode::from(EXIT_ERROR);
+            }
+        },
+        _ => CookieJar::new(),
+    };
+    let items = match run_items(&options.files) {
+        Ok(items) => items,
+        Err((path, e)) => {
+            eprintln!("cartero-cli: {}: {}", path.display(), e.details());
+            return ExitCode::from(EXIT_ERROR);
+        }
+    };
+    let data = match &options.data {
+        Some(path) => match load_data(path) {
+            Ok(data) => data,
+            Err(e) => {
+                eprintln!("cartero-cli: {}: {}", path.display(), e.details());
+                return ExitCode::from(EXIT_ERROR);
+            }
+        },
+        None => Vec::new(),
+    };
+
+    let mut runner = Runner::new(RunSettings {
+        stop_on_failure: options.stop_on_failure,
+        timeout: options.timeout,
+        proxy: options.proxy.clone(),
+        variables: options.variables.clone(),
+    });
+    runner.environment = environment;
+    runner.cookie_jar = Arc::new(Mutex::new(cookie_jar));
+
+    let mut exit_code = EXIT_OK;
+    let mut text = String::new();
+    let mut documents = Vec::new();
+    let headings = items.len() > 1 || data.len() > 1;
+    let mut runs = Vec::new();
+    let mut show = |run: &RequestRun| {
+        let name = if data.len() > 1 {
+            format!("{} #{}", run.name, run.iteration)
+        } else {
+            run.name.clone()
+        };
+        for line in &run.console {
+            if line.level != ConsoleLevel::Error {
+                eprintln!("{name}: {}: {}", line.stage, line.text);
+            }
+        }
+        if !options.json && headings {
+            text.push_str(&format!("==> {name} <==\n"));
+        }
+        let response = match &run.outcome {
+            Ok(response) => response,
+            Err(e) => {
+                let message = e.details();
+                eprintln!("cartero-cli: {name}: {message}");
+                exit_code = EXIT_ERROR;
+                if options.json {
+                    let mut document = json!({ "file": run.name, "error": message });
+                    if !data.is_empty() {
+                        document["iteration"] = json!(run.iteration);
+                    }
+                    documents.push(document);
+                }
+                return;
+            }
+        };
+        let failed = options
+            .fail_status
+            .iter()
+            .any(|range| range.contains(response.status_code));
+        if failed && exit_code == EXIT_OK {
+            exit_code = EXIT_STATUS;
+        }
+        for result in &run.assertions {
+            if let Err(e) = &result.outcome {
+                let error = CarteroError::Assertion(result.description(), e.clone());
+                eprintln!("cartero-cli: {name}: {error}");
+                if exit_code == EXIT_OK {
+                    exit_code = EXIT_ASSERTION;
+                }
+            }
+        }
+        let errors: Vec<String> = run.errors.iter().map(CarteroError::details).collect();
+        for message in &errors {
+            eprintln!("cartero-cli: {name}: {message}");
+            exit_code = EXIT_ERROR;
+        }
+        if options.json {
+            let mut document = response_to_json(response);
+            document["file"] = json!(run.name);
+            if !data.is_empty() {
+                document["iteration"] = json!(run.iteration);
+            }
+            if !errors.is_empty() {
+                document["errors"] = json!(errors);
+            }
+            if !run.assertions.is_empty() {
+                document["assertions"] = assertions_to_json(&run.assertions);
+            }
+            documents.push(document);
+        } else {
+            text.push_str(&response_to_text(response));
+        }
+    };
+    let future = runner.run(&items, &data, &open_browser, |run| {
+        show(&run);
+        runs.push(run);
+    });
+    futures_lite::future::block_on(future);
+
+    let output = if options.json {
+        pretty_json(&json!(documents))
+    } else {
+        text
+    };
+    let written = match &options.output {
+        Some(path) => std::fs::write(path, output),
+        None => std::io::stdout().write_all(output.as_bytes()),
+    };
+    if let Err(e) = written {
+        eprintln!("cartero-cli: cannot write output: {e}");
+        return ExitCode::from(EXIT_ERROR);
+    }
+    let name = run_name(&options.files);
+    let reports = [
+        (&options.junit, junit_report(&name, &runs)),
+        (&options.report, pretty_json(&json_report(&name, &runs))),
+    ];
+    for (path, report) in reports {
+        let Some(path) = path else {
+            continue;
+        };
+        if let Err(e) = std::fs::write(path, report) {
+            eprintln!("cartero-cli: cannot write report: {e}");
+            return ExitCode::from(EXIT_ERROR);
+        }
+    }
+    if let Some(path) = &options.cookie_jar {
+        let content = runner.cookie_jar.lock().unwrap().to_netscape();
+        if let Err(e) = std::fs::write(path, content) {
+            eprintln!("cartero-cli: cannot write cookies: {e}");
+            return ExitCode::from(EXIT_ERROR);
+        }
+    }
+
+    ExitCode::from(exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_args;
+    use cartero::client::StatusRange;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let options = parse_args(args(&[
+            "--json",
+            "--env",
+            "dev.env.toml",
+            "-f",
+            "4xx,503",
+            "--var",
+            "TOKEN=a=b",
+            "-t",
+            "15",
+            "-x",
+            "socks5h://localhost:1080",
+            "-U",
+            "john:s3cr3t:x",
+            "--no-proxy",
+            "localhost,.internal",
+            "-c",
+            "cookies.txt",
+            "-d",
+            "users.csv",
+            "-s",
+            "--junit",
+            "report.xml",
+            "--report",
+            "report.json",
+            "login.cartero",
+            "books.cartero",
+        ]))
+        .unwrap();
+        assert!(options.json);
+        assert_eq!(options.environment, Some("dev.env.toml".into()));
+        assert_eq!(
+            options.fail_status,
+            vec![StatusRange(400, 499), StatusRange(503, 503)]
+        );
+        assert_eq!(options.variables[0].name, "TOKEN");
+        assert_eq!(options.variables[0].value, "a=b");
+        assert_eq!(options.timeout, Some(15));
+        assert_eq!(options.proxy.url, "socks5h://localhost:1080");
+        assert_eq!(options.proxy.username, "john");
+        assert_eq!(options.proxy.password, "s3cr3t:x");
+        assert_eq!(options.proxy.no_proxy, "localhost,.internal");
+        assert_eq!(options.cookie_jar, Some("cookies.txt".into()));
+        assert_eq!(options.data, Some("users.csv".into()));
+        assert!(options.stop_on_failure);
+        assert_eq!(options.junit, Some("report.xml".into()));
+        assert_eq!(options.report, Some("report.json".into()));
+        assert_eq!(options.files.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_args_errors() {
+        assert!(parse_args(args(&["--fail-status"])).is_err());
+        assert!(parse_args(args(&["--var", "TOKEN"])).is_err());
+        assert!(parse_args(args(&["--env"])).is_err());
+        assert!(parse_args(args(&["--timeout", "soon"])).is_err());
+        assert!(parse_args(args(&["--proxy"])).is_err());
+        assert!(parse_args(args(&["--cookie-jar"])).is_err());
+        assert!(parse_args(args(&["--data"])).is_err());
+        assert!(parse_args(args(&["--junit"])).is_err());
+        assert!(parse_args(args(&["--unknown"])).is_err());
+    }
+}
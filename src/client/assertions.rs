@@ -0,0 +1,446 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Checks a response against the assertions of its request, so that a request file
+//! can be used as a test of an API.
+
+use regex::Regex;
+use serde_json::Value;
+use thiserror::Error;
+
+use super::captures::json_path;
+use crate::client::CaptureError;
+use crate::entities::{Assertion, AssertionKind, ResponseData};
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum AssertionError {
+    #[error("invalid expected value {0}")]
+    InvalidExpected(String),
+
+    #[error("the response has no header {0}")]
+    MissingHeader(String),
+
+    #[error("the body does not contain {0}")]
+    NotInBody(String),
+
+    #[error("expected {expected}, got {actual}")]
+    Mismatch { expected: String, actual: String },
+
+    #[error(transparent)]
+    Capture(#[from] CaptureError),
+}
+
+/// The outcome of an assertion on a response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssertionResult {
+    pub assertion: Assertion,
+    pub outcome: Result<(), AssertionError>,
+}
+
+impl AssertionResult {
+    pub fn passed(&self) -> bool {
+        self.outcome.is_ok()
+    }
+
+    /// A short text that tells what the assertion checks, such as `status is 200`.
+    pub fn description(&self) -> String {
+        describe_assertion(&self.assertion)
+    }
+}
+
+/// A short text that tells what the assertion checks, such as `status is 200`.
+pub fn describe_assertion(assertion: &Assertion) -> String {
+    let subject = &assertion.subject;
+    let expected = &assertion.expected;
+    match assertion.kind {
+        AssertionKind::StatusEquals => format!("status is {expected}"),
+        AssertionKind::StatusInRange => format!("status is in {expected}"),
+        AssertionKind::HeaderExists => format!("header {subject} exists"),
+        AssertionKind::HeaderMatches => format!("header {subject} matches {expected}"),
+        AssertionKind::JsonPathEquals => format!("{subject} equals {expected}"),
+        AssertionKind::JsonPathMatches => format!("{subject} matches {expected}"),
+        AssertionKind::JsonPathType => format!("{subject} is of type {expected}"),
+        AssertionKind::BodyContains => format!("body contains {expected}"),
+        AssertionKind::DurationUnder => format!("duration is under {expected} ms"),
+    }
+}
+
+/// Checks every active assertion on the response, in order.
+pub fn run_assertions(assertions: &[Assertion], response: &ResponseData) -> Vec<AssertionResult> {
+    assertions
+        .iter()
+        .filter(|assertion| assertion.active)
+        .map(|assertion| AssertionResult {
+            assertion: assertion.clone(),
+            outcome: check_assertion(assertion, response),
+        })
+        .collect()
+}
+
+/// Checks a single assertion on the response.
+pub fn check_assertion(
+    assertion: &Assertion,
+    response: &ResponseData,
+) -> Result<(), AssertionError> {
+    let subject = assertion.subject.trim();
+    let expected = assertion.expected.as_str();
+    let mismatch = |actual: String| AssertionError::Mismatch {
+        expected: expected.to_string(),
+        actual,
+    };
+    match assertion.kind {
+        AssertionKind::StatusEquals => {
+            let code = parse_number(expected)?;
+            match u128::from(response.status_code) == code {
+                true => Ok(()),
+                false => Err(mismatch(response.status_code.to_string())),
+            }
+        }
+        AssertionKind::StatusInRange => {
+            let range = StatusRange::try_from(expected)?;
+            match range.contains(response.status_code) {
+                true => Ok(()),
+                false => Err(mismatch(response.status_code.to_string())),
+            }
+        }
+        AssertionKind::HeaderExists => header(response, subject).map(|_| ()),
+        AssertionKind::HeaderMatches => {
+            let regex = parse_regex(expected)?;
+            let value = header(response, subject)?;
+            match regex.is_match(value) {
+                true => Ok(()),
+                false => Err(mismatch(value.to_string())),
+            }
+        }
+        AssertionKind::JsonPathEquals => {
+            let value = json_value(response, subject)?;
+            let wanted =
+                serde_json::from_str(expected).unwrap_or_else(|_| Value::String(expected.into()));
+            let equal = value == wanted || value.as_str() == Some(expected);
+            match equal {
+                true => Ok(()),
+                false => Err(mismatch(json_text(&value))),
+            }
+        }
+        AssertionKind::JsonPathMatches => {
+            let regex = parse_regex(expected)?;
+            let value = json_text(&json_value(response, subject)?);
+            match regex.is_match(&value) {
+                true => Ok(()),
+                false => Err(mismatch(value)),
+            }
+        }
+        AssertionKind::JsonPathType => {
+            let wanted = expected.trim().to_lowercase();
+            if !JSON_TYPES.contains(&wanted.as_str()) {
+                return Err(AssertionError::InvalidExpected(expected.to_string()));
+            }
+            let actual = json_type(&json_value(response, subject)?);
+            match actual == wanted {
+                true => Ok(()),
+                false => Err(mismatch(actual.to_string())),
+            }
+        }
+        AssertionKind::BodyContains => match response.body_str().contains(expected) {
+            true => Ok(()),
+            false => Err(AssertionError::NotInBody(expected.to_string())),
+        },
+        AssertionKind::DurationUnder => {
+            let limit = parse_number(expected)?;
+            match response.duration < limit {
+                true => Ok(()),
+                false => Err(AssertionError::Mismatch {
+                    expected: format!("under {limit} ms"),
+                    actual: format!("{} ms", response.duration),
+                }),
+            }
+        }
+    }
+}
+
+/// The types that a JSON value can have, as written in a `JsonPathType` assertion.
+const JSON_TYPES: [&str; 6] = ["string", "number", "boolean", "array", "object", "null"];
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::String(_) => "string",
+        Value::Number(_) => "number",
+        Value::Bool(_) => "boolean",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+        Value::Null => "null",
+    }
+}
+
+/// Strings are given without their quotes, as they are also written when captured.
+fn json_text(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn json_value(response: &ResponseData, path: &str) -> Result<Value, AssertionError> {
+    let json: Value = serde_json::from_slice(&response.body).map_err(|_| CaptureError::NotJson)?;
+    let value = json_path(&json, path)?
+        .into_iter()
+        .next()
+        .cloned()
+        .ok_or_else(|| CaptureError::NoMatch(path.to_string()))?;
+    Ok(value)
+}
+
+fn header<'a>(response: &'a ResponseData, name: &str) -> Result<&'a str, AssertionError> {
+    response
+        .headers
+        .iter()
+        .find(|header| header.name.eq_ignore_ascii_case(name))
+        .map(|header| header.value.as_str())
+        .ok_or_else(|| AssertionError::MissingHeader(name.to_string()))
+}
+
+fn parse_number(value: &str) -> Result<u128, AssertionError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| AssertionError::InvalidExpected(value.to_string()))
+}
+
+fn parse_regex(pattern: &str) -> Result<Regex, AssertionError> {
+    Regex::new(pattern).map_err(|_| AssertionError::InvalidExpected(pattern.to_string()))
+}
+
+/// A range of status codes, written as `200-299`, as a class such as `2xx`, or as
+/// a single code such as `404`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusRange(pub u32, pub u32);
+
+impl StatusRange {
+    pub fn contains(&self, status: u32) -> bool {
+        self.0 <= status && status <= self.1
+    }
+}
+
+impl TryFrom<&str> for StatusRange {
+    type Error = AssertionError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let invalid = || AssertionError::InvalidExpected(value.to_string());
+        let parse = |code: &str| code.trim().parse::<u32>().map_err(|_| invalid());
+        let trimmed = value.trim();
+        if let Some(class) = trimmed
+            .strip_suffix("xx")
+            .or_else(|| trimmed.strip_suffix("XX"))
+        {
+            let class = parse(class)?;
+            if !(1..=5).contains(&class) {
+                return Err(invalid());
+            }
+            return Ok(StatusRange(class * 100, class * 100 + 99));
+        }
+        let Some((start, end)) = trimmed.split_once('-') else {
+            let code = parse(trimmed)?;
+            return Ok(StatusRange(code, code));
+        };
+        let (start, end) = (parse(start)?, parse(end)?);
+        match start <= end {
+            true => Ok(StatusRange(start, end)),
+            false => Err(invalid()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::{KeyValue, KeyValueTable};
+
+    use super::*;
+
+    fn response() -> ResponseData {
+        let body = r#"{"id": 42, "name": "Ada", "tags": ["admin"], "team": null}"#;
+        ResponseData {
+            status_code: 201,
+            duration: 120,
+            headers: KeyValueTable::new(&[
+                KeyValue::from(("Content-Type", "application/json; charset=utf-8")),
+                KeyValue::from(("Location", "/users/42")),
+            ]),
+            body: body.as_bytes().to_vec(),
+            ..Default::default()
+        }
+    }
+
+    fn check(kind: AssertionKind, subject: &str, expected: &str) -> Result<(), AssertionError> {
+        let assertion = Assertion {
+            kind,
+            subject: subject.into(),
+            expected: expected.into(),
+            active: true,
+        };
+        check_assertion(&assertion, &response())
+    }
+
+    #[test]
+    pub fn test_parse_status_ranges() {
+        assert_eq!(StatusRange::try_from("404"), Ok(StatusRange(404, 404)));
+        assert_eq!(StatusRange::try_from("500-599"), Ok(StatusRange(500, 599)));
+        assert_eq!(StatusRange::try_from("4xx"), Ok(StatusRange(400, 499)));
+        assert_eq!(StatusRange::try_from(" 2XX "), Ok(StatusRange(200, 299)));
+        assert!(StatusRange::try_from("9xx").is_err());
+        assert!(StatusRange::try_from("599-500").is_err());
+        assert!(StatusRange::try_from("abc").is_err());
+    }
+
+    #[test]
+    pub fn test_status_assertions() {
+        assert_eq!(check(AssertionKind::StatusEquals, "", "201"), Ok(()));
+        assert_eq!(
+            check(AssertionKind::StatusEquals, "", "200"),
+            Err(AssertionError::Mismatch {
+                expected: "200".into(),
+                actual: "201".into()
+            })
+        );
+        assert_eq!(check(AssertionKind::StatusInRange, "", "200-299"), Ok(()));
+        assert_eq!(check(AssertionKind::StatusInRange, "", "2xx"), Ok(()));
+        assert!(check(AssertionKind::StatusInRange, "", "4xx").is_err());
+        assert_eq!(
+            check(AssertionKind::StatusInRange, "", "299-200"),
+            Err(AssertionError::InvalidExpected("299-200".into()))
+        );
+        assert_eq!(
+            check(AssertionKind::StatusEquals, "", "OK"),
+            Err(AssertionError::InvalidExpected("OK".into()))
+        );
+    }
+
+    #[test]
+    pub fn test_header_assertions() {
+        assert_eq!(check(AssertionKind::HeaderExists, "location", ""), Ok(()));
+        assert_eq!(
+            check(AssertionKind::HeaderExists, "ETag", ""),
+            Err(AssertionError::MissingHeader("ETag".into()))
+        );
+        assert_eq!(
+            check(
+                AssertionKind::HeaderMatches,
+                "Content-Type",
+                "^application/json"
+            ),
+            Ok(())
+        );
+        assert!(check(AssertionKind::HeaderMatches, "Content-Type", "xml").is_err());
+        assert_eq!(
+            check(AssertionKind::HeaderMatches, "Content-Type", "("),
+            Err(AssertionError::InvalidExpected("(".into()))
+        );
+    }
+
+    #[test]
+    pub fn test_json_path_assertions() {
+        assert_eq!(check(AssertionKind::JsonPathEquals, "$.id", "42"), Ok(()));
+        assert_eq!(
+            check(AssertionKind::JsonPathEquals, "$.name", "Ada"),
+            Ok(())
+        );
+        assert_eq!(
+            check(AssertionKind::JsonPathEquals, "$.name", "\"Ada\""),
+            Ok(())
+        );
+        assert_eq!(
+            check(AssertionKind::JsonPathEquals, "$.team", "null"),
+            Ok(())
+        );
+        assert_eq!(
+            check(AssertionKind::JsonPathEquals, "$.id", "41"),
+            Err(AssertionError::Mismatch {
+                expected: "41".into(),
+                actual: "42".into()
+            })
+        );
+        assert_eq!(
+            check(AssertionKind::JsonPathMatches, "$.name", "^A"),
+            Ok(())
+        );
+        assert_eq!(
+            check(AssertionKind::JsonPathType, "$.tags", "array"),
+            Ok(())
+        );
+        assert_eq!(
+            check(AssertionKind::JsonPathType, "$.id", "string"),
+            Err(AssertionError::Mismatch {
+                expected: "string".into(),
+                actual: "number".into()
+            })
+        );
+        assert_eq!(
+            check(AssertionKind::JsonPathType, "$.id", "integer"),
+            Err(AssertionError::InvalidExpected("integer".into()))
+        );
+        assert_eq!(
+            check(AssertionKind::JsonPathEquals, "$.email", "x"),
+            Err(AssertionError::Capture(CaptureError::NoMatch(
+                "$.email".into()
+            )))
+        );
+    }
+
+    #[test]
+    pub fn test_body_and_duration_assertions() {
+        assert_eq!(check(AssertionKind::BodyContains, "", "\"Ada\""), Ok(()));
+        assert_eq!(
+            check(AssertionKind::BodyContains, "", "Grace"),
+            Err(AssertionError::NotInBody("Grace".into()))
+        );
+        assert_eq!(check(AssertionKind::DurationUnder, "", "500"), Ok(()));
+        assert_eq!(
+            check(AssertionKind::DurationUnder, "", "100"),
+            Err(AssertionError::Mismatch {
+                expected: "under 100 ms".into(),
+                actual: "120 ms".into()
+            })
+        );
+    }
+
+    #[test]
+    pub fn test_run_assertions_skips_inactive() {
+        let assertions = vec![
+            Assertion {
+                kind: AssertionKind::StatusEquals,
+                expected: "201".into(),
+                ..Default::default()
+            },
+            Assertion {
+                kind: AssertionKind::BodyContains,
+                expected: "missing".into(),
+                active: false,
+                ..Default::default()
+            },
+            Assertion {
+                kind: AssertionKind::HeaderExists,
+                subject: "ETag".into(),
+                ..Default::default()
+            },
+        ];
+        let results = run_assertions(&assertions, &response());
+        assert_eq!(results.len(), 2);
+        assert!(results[0].passed());
+        assert_eq!(results[0].description(), "status is 201");
+        assert!(!results[1].passed());
+        assert_eq!(results[1].description(), "header ETag exists");
+    }
+}
@@ -0,0 +1,686 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Takes values out of a response, so that the requests that come after it can use
+//! them as variables.
+//!
+//! JSONPath and XPath are supported in the forms that are useful to pick a single
+//! value:
+//!
+//! * JSONPath: `$`, `.name`, `['name']`, `[0]`, `[-1]`, `[*]`, `.*` and `..name`.
+//! * XPath: `/`, `//`, `name`, `*`, `.`, `..`, `@name`, `text()`, and the predicates
+//!   `[1]`, `[last()]`, `[@name]` and `[@name='value']`.
+//!
+//! When an expression matches more than one value, the first one is taken.
+
+use regex::Regex;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::entities::{
+    Capture, CaptureScope, CaptureSource, Environment, KeyValueTable, ResponseData,
+};
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum CaptureError {
+    #[error("invalid expression {0}")]
+    InvalidExpression(String),
+
+    #[error("the response is not JSON")]
+    NotJson,
+
+    #[error("the response is not XML")]
+    NotXml,
+
+    #[error("nothing matched {0}")]
+    NoMatch(String),
+}
+
+/// A value taken from a response, to be kept in the scope picked by its capture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedValue {
+    pub variable: String,
+    pub value: String,
+    pub scope: CaptureScope,
+}
+
+/// Takes the value of every active capture out of the response.
+///
+/// A capture that fails does not prevent the others from taking their values, so
+/// there is a result for every active capture, in the same order.
+pub fn run_captures(
+    captures: &[Capture],
+    response: &ResponseData,
+) -> Vec<Result<CapturedValue, (String, CaptureError)>> {
+    captures
+        .iter()
+        .filter(|capture| capture.active && !capture.variable.is_empty())
+        .map(|capture| match capture_value(&capture.source, response) {
+            Ok(value) => Ok(CapturedValue {
+                variable: capture.variable.clone(),
+                value,
+                scope: capture.scope,
+            }),
+            Err(e) => Err((capture.variable.clone(), e)),
+        })
+        .collect()
+}
+
+/// Keeps the captured values in their scopes. Values that go to the environment are
+/// kept in the session when there is no environment.
+pub fn store_captures(
+    values: &[CapturedValue],
+    request: &mut KeyValueTable,
+    mut environment: Option<&mut Environment>,
+    session: &mut KeyValueTable,
+) {
+    for captured in values {
+        let table = match (captured.scope, environment.as_deref_mut()) {
+            (CaptureScope::Request, _) => &mut *request,
+            (CaptureScope::Environment, Some(environment)) => &mut environment.variables,
+            (CaptureScope::Environment, None) | (CaptureScope::Session, _) => &mut *session,
+        };
+        table.set(&captured.variable, &captured.value);
+    }
+}
+
+/// Takes the value of a single capture out of the response.
+pub fn capture_value(
+    source: &CaptureSource,
+    response: &ResponseData,
+) -> Result<String, CaptureError> {
+    match source {
+        CaptureSource::StatusCode => Ok(response.status_code.to_string()),
+        CaptureSource::Header(name) => response
+            .headers
+            .iter()
+            .find(|header| header.name.eq_ignore_ascii_case(name))
+            .map(|header| header.value.clone())
+            .ok_or_else(|| CaptureError::NoMatch(name.clone())),
+        CaptureSource::Regex(pattern) => {
+            let regex = Regex::new(pattern)
+                .map_err(|_| CaptureError::InvalidExpression(pattern.clone()))?;
+            let body = response.body_str();
+            let captures = regex
+                .captures(&body)
+                .ok_or_else(|| CaptureError::NoMatch(pattern.clone()))?;
+            let matched = captures.get(1).or_else(|| captures.get(0));
+            Ok(matched.map(|m| m.as_str()).unwrap_or_default().to_string())
+        }
+        CaptureSource::JsonPath(path) => {
+            let json: Value =
+                serde_json::from_slice(&response.body).map_err(|_| CaptureError::NotJson)?;
+            let value = json_path(&json, path)?
+                .into_iter()
+                .next()
+                .ok_or_else(|| CaptureError::NoMatch(path.clone()))?;
+            Ok(match value {
+                Value::String(string) => string.clone(),
+                other => other.to_string(),
+            })
+        }
+        CaptureSource::XPath(path) => {
+            let body = response.body_str();
+            let document = roxmltree::Document::parse(&body).map_err(|_| CaptureError::NotXml)?;
+            xpath(&document, path)?
+                .into_iter()
+                .next()
+                .ok_or_else(|| CaptureError::NoMatch(path.clone()))
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum JsonSelector {
+    Name(String),
+    Index(i64),
+    Wildcard,
+}
+
+/// Splits a JSONPath expression into its steps. The flag tells whether the step
+/// also looks into the descendants, as in `..name`.
+fn parse_json_path(path: &str) -> Result<Vec<(bool, JsonSelector)>, CaptureError> {
+    let invalid = || CaptureError::InvalidExpression(path.to_string());
+    let trimmed = path.trim();
+    let mut rest = match trimmed.strip_prefix('$') {
+        Some(rest) => rest,
+        // Like most tools, a path without the root is taken as relative to it.
+        None if trimmed.starts_with('[') || trimmed.starts_with('.') => trimmed,
+        None => return parse_json_path(&format!("$.{trimmed}")),
+    };
+
+    let mut steps = Vec::new();
+    while !rest.is_empty() {
+        let descendants = rest.starts_with("..");
+        if let Some(after) = rest.strip_prefix("..").or_else(|| rest.strip_prefix('.')) {
+            if after.starts_with('[') {
+                rest = after;
+                if !descendants {
+                    return Err(invalid());
+                }
+            } else {
+                let end = after.find(['.', '[']).unwrap_or(after.len());
+                let name = &after[..end];
+                let selector = match name {
+                    "" => return Err(invalid()),
+                    "*" => JsonSelector::Wildcard,
+                    name => JsonSelector::Name(name.to_string()),
+                };
+                steps.push((descendants, selector));
+                rest = &after[end..];
+                continue;
+            }
+        }
+        let Some(after) = rest.strip_prefix('[') else {
+            return Err(invalid());
+        };
+        let (selector, after) = if let Some(quoted) = after.strip_prefix(['\'', '"']) {
+            let quote = after.chars().next().unwrap_or_default();
+            let end = quoted.find(quote).ok_or_else(invalid)?;
+            let after = quoted[end + 1..].strip_prefix(']').ok_or_else(invalid)?;
+            (JsonSelector::Name(quoted[..end].to_string()), after)
+        } else {
+            let end = after.find(']').ok_or_else(invalid)?;
+            let selector = match after[..end].trim() {
+                "*" => JsonSelector::Wildcard,
+                index => JsonSelector::Index(index.parse().map_err(|_| invalid())?),
+            };
+            (selector, &after[end + 1..])
+        };
+        steps.push((descendants, selector));
+        rest = after;
+    }
+    Ok(steps)
+}
+
+fn json_children<'a>(value: &'a Value, selector: &JsonSelector) -> Vec<&'a Value> {
+    match (value, selector) {
+        (Value::Object(map), JsonSelector::Name(name)) => map.get(name).into_iter().collect(),
+        (Value::Array(items), JsonSelector::Index(index)) => {
+            let index = if *index < 0 {
+                items.len() as i64 + index
+            } else {
+                *index
+            };
+            usize::try_from(index)
+                .ok()
+                .and_then(|index| items.get(index))
+                .into_iter()
+                .collect()
+        }
+        (Value::Object(map), JsonSelector::Wildcard) => map.values().collect(),
+        (Value::Array(items), JsonSelector::Wildcard) => items.iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The value and all the values nested in it, in document order.
+fn json_descendants(value: &Value) -> Vec<&Value> {
+    let mut values = vec![value];
+    let nested: Vec<&Value> = match value {
+        Value::Object(map) => map.values().collect(),
+        Value::Array(items) => items.iter().collect(),
+        _ => Vec::new(),
+    };
+    for value in nested {
+        values.extend(json_descendants(value));
+    }
+    values
+}
+
+pub(super) fn json_path<'a>(json: &'a Value, path: &str) -> Result<Vec<&'a Value>, CaptureError> {
+    let mut nodes = vec![json];
+    for (descendants, selector) in parse_json_path(path)? {
+        let context: Vec<&Value> = if descendants {
+            nodes.into_iter().flat_map(json_descendants).collect()
+        } else {
+            nodes
+        };
+        nodes = context
+            .into_iter()
+            .flat_map(|node| json_children(node, &selector))
+            .collect();
+    }
+    Ok(nodes)
+}
+
+#[derive(Debug, PartialEq)]
+enum XPathTest {
+    Element(String),
+    AnyElement,
+    Attribute(String),
+    Text,
+    SelfNode,
+    Parent,
+}
+
+#[derive(Debug, PartialEq)]
+enum XPathPredicate {
+    Position(usize),
+    Last,
+    HasAttribute(String),
+    AttributeEquals(String, String),
+}
+
+#[derive(Debug, PartialEq)]
+struct XPathStep {
+    descendants: bool,
+    test: XPathTest,
+    predicates: Vec<XPathPredicate>,
+}
+
+fn parse_xpath_step(step: &str, descendants: bool) -> Result<XPathStep, CaptureError> {
+    let invalid = || CaptureError::InvalidExpression(step.to_string());
+    let (test, mut rest) = match step.find('[') {
+        Some(start) => (&step[..start], &step[start..]),
+        None => (step, ""),
+    };
+    let test = match test.trim() {
+        "" => return Err(invalid()),
+        "." => XPathTest::SelfNode,
+        ".." => XPathTest::Parent,
+        "*" => XPathTest::AnyElement,
+        "text()" => XPathTest::Text,
+        test => match test.strip_prefix('@') {
+            Some(name) => XPathTest::Attribute(name.to_string()),
+            None => XPathTest::Element(test.to_string()),
+        },
+    };
+
+    let mut predicates = Vec::new();
+    while let Some(after) = rest.strip_prefix('[') {
+        let end = after.find(']').ok_or_else(invalid)?;
+        let predicate = after[..end].trim();
+        rest = &after[end + 1..];
+        let predicate = if predicate == "last()" {
+            XPathPredicate::Last
+        } else if let Some(attribute) = predicate.strip_prefix('@') {
+            match attribute.split_once('=') {
+                Some((name, value)) => {
+                    let value = value.trim();
+                    let value = value
+                        .strip_prefix('\'')
+                        .and_then(|v| v.strip_suffix('\''))
+                        .or_else(|| value.strip_prefix('"').and_then(|v| v.strip_suffix('"')))
+                        .ok_or_else(invalid)?;
+                    XPathPredicate::AttributeEquals(name.trim().to_string(), value.to_string())
+                }
+                None => XPathPredicate::HasAttribute(attribute.to_string()),
+            }
+        } else {
+            match predicate.parse::<usize>() {
+                Ok(position) if position > 0 => XPathPredicate::Position(position),
+                _ => return Err(invalid()),
+            }
+        };
+        predicates.push(predicate);
+    }
+    if !rest.is_empty() {
+        return Err(invalid());
+    }
+    Ok(XPathStep {
+        descendants,
+        test,
+        predicates,
+    })
+}
+
+fn parse_xpath(path: &str) -> Result<Vec<XPathStep>, CaptureError> {
+    let mut steps = Vec::new();
+    let mut rest = path.trim();
+    // A relative path is taken as an absolute one.
+    let mut descendants = false;
+    if let Some(after) = rest.strip_prefix("//") {
+        descendants = true;
+        rest = after;
+    } else if let Some(after) = rest.strip_prefix('/') {
+        rest = after;
+    }
+    loop {
+        let end = rest.find('/').unwrap_or(rest.len());
+        steps.push(parse_xpath_step(&rest[..end], descendants)?);
+        rest = &rest[end..];
+        if let Some(after) = rest.strip_prefix("//") {
+            descendants = true;
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('/') {
+            descendants = false;
+            rest = after;
+        } else {
+            break;
+        }
+    }
+    Ok(steps)
+}
+
+/// The result of an XPath step: a node of the document, or the value of an
+/// attribute or a text node, which have no steps after them.
+#[derive(Clone, Copy)]
+enum XPathItem<'a, 'input> {
+    Node(roxmltree::Node<'a, 'input>),
+    Value(&'a str),
+}
+
+fn xpath_matches(node: &roxmltree::Node, step: &XPathStep) -> bool {
+    match &step.test {
+        XPathTest::Element(name) => {
+            // Prefixes are ignored, so that namespaced documents can be queried.
+            let local = name.rsplit(':').next().unwrap_or(name);
+            node.is_element() && node.tag_name().name() == local
+        }
+        XPathTest::AnyElement => node.is_element(),
+        _ => false,
+    }
+}
+
+fn xpath_step<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    step: &XPathStep,
+) -> Vec<XPathItem<'a, 'input>> {
+    let context: Vec<roxmltree::Node> = if step.descendants {
+        node.descendants().collect()
+    } else {
+        vec![node]
+    };
+    let mut items = Vec::new();
+    for node in context {
+        let mut found: Vec<roxmltree::Node> = match &step.test {
+            XPathTest::SelfNode => vec![node],
+            XPathTest::Parent => node.parent().into_iter().collect(),
+            XPathTest::Attribute(_) | XPathTest::Text => Vec::new(),
+            _ => node
+                .children()
+                .filter(|child| xpath_matches(child, step))
+                .collect(),
+        };
+        for predicate in &step.predicates {
+            found = match predicate {
+                XPathPredicate::Position(position) => {
+                    found.get(position - 1).copied().into_iter().collect()
+                }
+                XPathPredicate::Last => found.last().copied().into_iter().collect(),
+                XPathPredicate::HasAttribute(name) => found
+                    .into_iter()
+                    .filter(|node| node.attribute(name.as_str()).is_some())
+                    .collect(),
+                XPathPredicate::AttributeEquals(name, value) => found
+                    .into_iter()
+                    .filter(|node| node.attribute(name.as_str()) == Some(value.as_str()))
+                    .collect(),
+            };
+        }
+        match &step.test {
+            XPathTest::Attribute(name) => {
+                let local = name.rsplit(':').next().unwrap_or(name);
+                items.extend(
+                    node.attributes()
+                        .filter(|attribute| name == "*" || attribute.name() == local)
+                        .map(|attribute| XPathItem::Value(attribute.value())),
+                );
+            }
+            XPathTest::Text => items.extend(
+                node.children()
+                    .filter(|child| child.is_text())
+                    .filter_map(|child| child.text())
+                    .map(XPathItem::Value),
+            ),
+            _ => items.extend(found.into_iter().map(XPathItem::Node)),
+        }
+    }
+    items
+}
+
+fn xpath(document: &roxmltree::Document, path: &str) -> Result<Vec<String>, CaptureError> {
+    let mut items = vec![XPathItem::Node(document.root())];
+    for step in parse_xpath(path)? {
+        items = items
+            .into_iter()
+            .flat_map(|item| match item {
+                XPathItem::Node(node) => xpath_step(node, &step),
+                XPathItem::Value(_) => Vec::new(),
+            })
+            .collect();
+    }
+    let values = items
+        .into_iter()
+        .map(|item| match item {
+            XPathItem::Value(value) => value.to_string(),
+            // The value of an element is all the text inside of it.
+            XPathItem::Node(node) => node
+                .descendants()
+                .filter(|node| node.is_text())
+                .filter_map(|node| node.text())
+                .collect(),
+        })
+        .collect();
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::KeyValue;
+
+    use super::*;
+
+    fn response(content_type: &str, body: &str) -> ResponseData {
+        ResponseData {
+            status_code: 201,
+            headers: KeyValueTable::new(&[
+                KeyValue::from(("Content-Type", content_type)),
+                KeyValue::from(("Location", "/users/42")),
+            ]),
+            body: body.as_bytes().to_vec(),
+            ..Default::default()
+        }
+    }
+
+    fn json(path: &str) -> Result<String, CaptureError> {
+        let body = r#"{
+            "token": "abc",
+            "user": {"id": 42, "admin": false, "name": null},
+            "items": [{"sku": "A1", "tags": ["x", "y"]}, {"sku": "B2"}],
+            "odd key": {"nested": {"sku": "C3"}}
+        }"#;
+        let response = response("application/json", body);
+        capture_value(&CaptureSource::JsonPath(path.into()), &response)
+    }
+
+    fn xml(path: &str) -> Result<String, CaptureError> {
+        let body = r#"<?xml version="1.0"?>
+            <soap:Envelope xmlns:soap="http://www.w3.org/2003/05/soap-envelope">
+              <soap:Body>
+                <login status="ok"><token>abc</token></login>
+                <items>
+                  <item id="1" kind="book">First</item>
+                  <item id="2">Second <b>bold</b></item>
+                </items>
+              </soap:Body>
+            </soap:Envelope>"#;
+        let response = response("application/xml", body);
+        capture_value(&CaptureSource::XPath(path.into()), &response)
+    }
+
+    #[test]
+    pub fn test_json_path() {
+        assert_eq!(json("$.token"), Ok("abc".into()));
+        assert_eq!(json("token"), Ok("abc".into()));
+        assert_eq!(json("$.user.id"), Ok("42".into()));
+        assert_eq!(json("$['user']['admin']"), Ok("false".into()));
+        assert_eq!(json("$.user.name"), Ok("null".into()));
+        assert_eq!(json("$.items[1].sku"), Ok("B2".into()));
+        assert_eq!(json("$.items[-1].sku"), Ok("B2".into()));
+        assert_eq!(json("items[0].tags[*]"), Ok("x".into()));
+        assert_eq!(json("$.items[0].tags"), Ok(r#"["x","y"]"#.into()));
+        assert_eq!(json("$[\"odd key\"].nested.sku"), Ok("C3".into()));
+        assert_eq!(json("$..nested.sku"), Ok("C3".into()));
+        assert_eq!(json("$.*.id"), Ok("42".into()));
+        assert_eq!(
+            json("$.missing"),
+            Err(CaptureError::NoMatch("$.missing".into()))
+        );
+        assert_eq!(
+            json("$.items[x]"),
+            Err(CaptureError::InvalidExpression("$.items[x]".into()))
+        );
+        assert_eq!(
+            json("$.items[0"),
+            Err(CaptureError::InvalidExpression("$.items[0".into()))
+        );
+    }
+
+    #[test]
+    pub fn test_json_path_needs_json() {
+        let response = response("text/html", "<html></html>");
+        let source = CaptureSource::JsonPath("$.token".into());
+        assert_eq!(
+            capture_value(&source, &response),
+            Err(CaptureError::NotJson)
+        );
+    }
+
+    #[test]
+    pub fn test_xpath() {
+        assert_eq!(xml("/Envelope/Body/login/token"), Ok("abc".into()));
+        assert_eq!(
+            xml("/soap:Envelope/soap:Body/login/token/text()"),
+            Ok("abc".into())
+        );
+        assert_eq!(xml("//token"), Ok("abc".into()));
+        assert_eq!(xml("//login/@status"), Ok("ok".into()));
+        assert_eq!(xml("//item[2]/@id"), Ok("2".into()));
+        assert_eq!(xml("//item[last()]"), Ok("Second bold".into()));
+        assert_eq!(xml("//item[@kind]/@id"), Ok("1".into()));
+        assert_eq!(xml("//item[@id='2']/text()"), Ok("Second ".into()));
+        assert_eq!(xml("//b/../@id"), Ok("2".into()));
+        assert_eq!(xml("//items/*/@id"), Ok("1".into()));
+        assert_eq!(
+            xml("//nothing"),
+            Err(CaptureError::NoMatch("//nothing".into()))
+        );
+        assert_eq!(
+            xml("//item[0]"),
+            Err(CaptureError::InvalidExpression("item[0]".into()))
+        );
+    }
+
+    #[test]
+    pub fn test_xpath_needs_xml() {
+        let response = response("application/json", "{}");
+        let source = CaptureSource::XPath("//token".into());
+        assert_eq!(capture_value(&source, &response), Err(CaptureError::NotXml));
+    }
+
+    #[test]
+    pub fn test_header_regex_and_status() {
+        let response = response("text/plain", "session=s3cr3t; expires=never");
+        let capture = |source: CaptureSource| capture_value(&source, &response);
+        assert_eq!(
+            capture(CaptureSource::Header("location".into())),
+            Ok("/users/42".into())
+        );
+        assert_eq!(
+            capture(CaptureSource::Header("ETag".into())),
+            Err(CaptureError::NoMatch("ETag".into()))
+        );
+        assert_eq!(
+            capture(CaptureSource::Regex("session=(\\w+)".into())),
+            Ok("s3cr3t".into())
+        );
+        assert_eq!(
+            capture(CaptureSource::Regex("expires=\\w+".into())),
+            Ok("expires=never".into())
+        );
+        assert_eq!(
+            capture(CaptureSource::Regex("(".into())),
+            Err(CaptureError::InvalidExpression("(".into()))
+        );
+        assert_eq!(capture(CaptureSource::StatusCode), Ok("201".into()));
+    }
+
+    #[test]
+    pub fn test_run_and_store_captures() {
+        let response = response("application/json", r#"{"token": "abc", "id": 7}"#);
+        let captures = [
+            Capture {
+                variable: "TOKEN".into(),
+                source: CaptureSource::JsonPath("$.token".into()),
+                scope: CaptureScope::Environment,
+                active: true,
+            },
+            Capture {
+                variable: "USER_ID".into(),
+                source: CaptureSource::JsonPath("$.id".into()),
+                ..Default::default()
+            },
+            Capture {
+                variable: "STATUS".into(),
+                source: CaptureSource::StatusCode,
+                scope: CaptureScope::Session,
+                active: true,
+            },
+            Capture {
+                variable: "MISSING".into(),
+                source: CaptureSource::Header("X-Missing".into()),
+                ..Default::default()
+            },
+            Capture {
+                variable: "INACTIVE".into(),
+                source: CaptureSource::StatusCode,
+                active: false,
+                ..Default::default()
+            },
+        ];
+        let outcome = run_captures(&captures, &response);
+        assert_eq!(outcome.len(), 4);
+        assert_eq!(
+            outcome[3],
+            Err(("MISSING".into(), CaptureError::NoMatch("X-Missing".into())))
+        );
+        let values: Vec<CapturedValue> = outcome.into_iter().flatten().collect();
+
+        let mut request = KeyValueTable::new(&[KeyValue::from(("USER_ID", "1"))]);
+        let mut environment = Environment {
+            variables: KeyValueTable::new(&[KeyValue::from(("TOKEN", "old"))]),
+            ..Default::default()
+        };
+        let mut session = KeyValueTable::default();
+        store_captures(&values, &mut request, Some(&mut environment), &mut session);
+        assert_eq!(
+            request,
+            KeyValueTable::new(&[KeyValue::from(("USER_ID", "7"))])
+        );
+        assert_eq!(
+            environment.variables,
+            KeyValueTable::new(&[KeyValue::from(("TOKEN", "abc"))])
+        );
+        assert_eq!(
+            session,
+            KeyValueTable::new(&[KeyValue::from(("STATUS", "201"))])
+        );
+
+        // Without an environment, its values are kept in the session.
+        let mut session = KeyValueTable::default();
+        store_captures(&values, &mut request, None, &mut session);
+        assert_eq!(
+            session,
+            KeyValueTable::new(&[
+                KeyValue::from(("TOKEN", "abc")),
+                KeyValue::from(("STATUS", "201")),
+            ])
+        );
+    }
+}
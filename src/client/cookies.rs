@@ -0,0 +1,525 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! A cookie jar, so that the cookies set by a response are sent by the next requests.
+//!
+//! isahc has its own cookie jar, but it cannot be listed or edited, so the cookies
+//! are kept here instead and added to the Cookie header of every request.
+
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use url::Url;
+
+/// A cookie jar that can be shared between the requests of several tabs.
+pub type SharedCookieJar = Arc<Mutex<CookieJar>>;
+
+/// Seconds since the Unix epoch, which is how the expiration of a cookie is kept.
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+// The conversions between days since the epoch and civil dates use the algorithms
+// by Howard Hinnant (https://howardhinnant.github.io/date_algorithms.html).
+
+/// Converts days since the Unix epoch into a year, a month and a day.
+pub fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Converts a year, a month and a day into days since the Unix epoch.
+pub fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    /// The host that the cookie belongs to, without a leading dot.
+    pub domain: String,
+    /// Whether the cookie is only sent to `domain` and not to its subdomains, which
+    /// happens when the server does not give a Domain attribute.
+    pub host_only: bool,
+    pub path: String,
+    /// When the cookie expires, in seconds since the Unix epoch. Session cookies do
+    /// not have an expiration.
+    pub expires: Option<u64>,
+    pub secure: bool,
+    pub http_only: bool,
+    /// The SameSite attribute as given by the server, or empty if it was not given.
+    pub same_site: String,
+}
+
+impl Cookie {
+    /// Parses the value of a Set-Cookie header sent in a response from the given URL.
+    ///
+    /// Returns `None` if the header is not valid or if it sets a cookie for a domain
+    /// that the URL does not belong to.
+    pub fn parse(header: &str, url: &str, now: u64) -> Option<Cookie> {
+        let url = Url::parse(url).ok()?;
+        let host = host_of(&url)?;
+        let mut attributes = header.split(';');
+        let (name, value) = attributes.next()?.split_once('=')?;
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        let mut cookie = Cookie {
+            name: name.to_string(),
+            value: value.trim().to_string(),
+            domain: host.clone(),
+            host_only: true,
+            path: default_path(url.path()),
+            ..Default::default()
+        };
+        let mut expires = None;
+        let mut max_age = None;
+        for attribute in attributes {
+            let (key, value) = match attribute.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => (attribute.trim(), ""),
+            };
+            match key.to_ascii_lowercase().as_str() {
+                "domain" => {
+                    let domain = value.trim_start_matches('.').to_lowercase();
+                    if domain.is_empty() {
+                        continue;
+                    }
+                    // A single label, such as com, would share the cookie with too many sites.
+                    if !domain_matches(&host, &domain) || (!domain.contains('.') && domain != host)
+                    {
+                        return None;
+                    }
+                    cookie.domain = domain;
+                    cookie.host_only = false;
+                }
+                "path" if value.starts_with('/') => cookie.path = value.to_string(),
+                "expires" => expires = parse_http_date(value),
+                "max-age" => max_age = value.parse::<i64>().ok(),
+                "secure" => cookie.secure = true,
+                "httponly" => cookie.http_only = true,
+                "samesite" => cookie.same_site = value.to_string(),
+                _ => {}
+            }
+        }
+        // Max-Age wins over Expires. A zero or negative Max-Age expires the cookie now.
+        cookie.expires = match max_age {
+            Some(seconds) if seconds <= 0 => Some(0),
+            Some(seconds) => Some(now.saturating_add(seconds as u64)),
+            None => expires,
+        };
+        Some(cookie)
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    /// Whether this cookie is sent in a request to the given URL.
+    pub fn matches(&self, url: &Url, now: u64) -> bool {
+        let Some(host) = host_of(url) else {
+            return false;
+        };
+        let domain_ok = if self.host_only {
+            host == self.domain
+        } else {
+            domain_matches(&host, &self.domain)
+        };
+        // Like browsers do, local servers are trusted with secure cookies over plain HTTP.
+        let secure_ok = !self.secure
+            || url.scheme() == "https"
+            || matches!(host.as_str(), "localhost" | "127.0.0.1" | "::1");
+        domain_ok && secure_ok && path_matches(url.path(), &self.path) && !self.is_expired(now)
+    }
+
+    /// Whether both cookies are the same cookie, even if their values differ.
+    fn same_as(&self, other: &Cookie) -> bool {
+        self.name == other.name && self.domain == other.domain && self.path == other.path
+    }
+}
+
+fn host_of(url: &Url) -> Option<String> {
+    let host = url.host_str()?;
+    Some(
+        host.trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_lowercase(),
+    )
+}
+
+fn domain_matches(host: &str, domain: &str) -> bool {
+    let is_ip = host.parse::<std::net::IpAddr>().is_ok();
+    host == domain
+        || (!is_ip
+            && host
+                .strip_suffix(domain)
+                .is_some_and(|prefix| prefix.ends_with('.')))
+}
+
+/// The path used when the server does not give one: the directory of the request path.
+fn default_path(path: &str) -> String {
+    match path.rfind('/') {
+        Some(index) if index > 0 && path.starts_with('/') => path[..index].to_string(),
+        _ => "/".to_string(),
+    }
+}
+
+fn path_matches(request_path: &str, cookie_path: &str) -> bool {
+    request_path == cookie_path
+        || (request_path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/') || request_path[cookie_path.len()..].starts_with('/')))
+}
+
+/// Parses the dates used by the Expires attribute, such as `Sun, 06 Nov 1994 08:49:37 GMT`,
+/// into seconds since the Unix epoch. Older formats, like `Sunday, 06-Nov-94 08:49:37 GMT`
+/// or the one of asctime, are accepted too.
+fn parse_http_date(value: &str) -> Option<u64> {
+    const MONTHS: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ];
+    let (mut day, mut month, mut year, mut time) = (None, None, None, None);
+    for token in value
+        .split([' ', '-', ','])
+        .filter(|token| !token.is_empty())
+    {
+        if token.contains(':') {
+            let parts: Vec<i64> = token.split(':').filter_map(|p| p.parse().ok()).collect();
+            if let [hours, minutes, seconds] = parts[..] {
+                time = Some(hours * 3600 + minutes * 60 + seconds);
+            }
+        } else if let Ok(number) = token.parse::<i64>() {
+            if day.is_none() && token.len() <= 2 {
+                day = Some(number);
+            } else {
+                year = Some(number);
+            }
+        } else if let Some(index) = token
+            .get(..3)
+            .and_then(|prefix| MONTHS.iter().position(|m| prefix.eq_ignore_ascii_case(m)))
+        {
+            month = Some(index as i64 + 1);
+        }
+    }
+    let (day, month, mut year, time) = (day?, month?, year?, time?);
+    if year < 70 {
+        year += 2000;
+    } else if year < 100 {
+        year += 1900;
+    }
+    if !(1..=31).contains(&day) || !(0..86400).contains(&time) {
+        return None;
+    }
+    let days = days_from_civil(year, month, day);
+    Some((days * 86400 + time).max(0) as u64)
+}
+
+/// The cookies received so far, which are sent again to the servers they belong to.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct CookieJar {
+    cookies: Vec<Cookie>,
+}
+
+impl CookieJar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cookies(&self) -> &[Cookie] {
+        &self.cookies
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cookies.is_empty()
+    }
+
+    /// Keeps a cookie, replacing the one with the same name, domain and path.
+    ///
+    /// An expired cookie is how servers ask to delete a cookie, so it removes the
+    /// stored one instead.
+    pub fn store(&mut self, cookie: Cookie, now: u64) {
+        let position = self.cookies.iter().position(|c| c.same_as(&cookie));
+        match position {
+            Some(index) if cookie.is_expired(now) => {
+                self.cookies.remove(index);
+            }
+            Some(index) => self.cookies[index] = cookie,
+            None if cookie.is_expired(now) => {}
+            None => self.cookies.push(cookie),
+        }
+    }
+
+    pub fn remove(&mut self, cookie: &Cookie) {
+        self.cookies.retain(|c| !c.same_as(cookie));
+    }
+
+    pub fn clear(&mut self) {
+        self.cookies.clear();
+    }
+
+    /// Builds the value of the Cookie header for a request to the given URL, or `None`
+    /// if no cookie has to be sent. Cookies with longer paths go first.
+    pub fn header_for(&self, url: &str, now: u64) -> Option<String> {
+        let url = Url::parse(url).ok()?;
+        let mut cookies: Vec<&Cookie> = self
+            .cookies
+            .iter()
+            .filter(|cookie| cookie.matches(&url, now))
+            .collect();
+        if cookies.is_empty() {
+            return None;
+        }
+        cookies.sort_by_key(|cookie| std::cmp::Reverse(cookie.path.len()));
+        let pairs: Vec<String> = cookies
+            .iter()
+            .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+            .collect();
+        Some(pairs.join("; "))
+    }
+
+    /// Reads a jar in the Netscape format used by the cookies.txt files of curl and wget.
+    ///
+    /// Lines that cannot be understood are skipped, and so are expired cookies.
+    pub fn parse_netscape(content: &str, now: u64) -> CookieJar {
+        let mut jar = CookieJar::new();
+        for line in content.lines() {
+            let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+                Some(line) => (line, true),
+                None => (line, false),
+            };
+            if line.starts_with('#') || line.trim().is_empty() {
+                continue;
+            }
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [domain, subdomains, path, secure, expires, name, value] = fields[..] else {
+                continue;
+            };
+            let Ok(expires) = expires.parse::<u64>() else {
+                continue;
+            };
+            let cookie = Cookie {
+                name: name.to_string(),
+                value: value.to_string(),
+                domain: domain.trim_start_matches('.').to_lowercase(),
+                host_only: !subdomains.eq_ignore_ascii_case("TRUE"),
+                path: path.to_string(),
+                expires: (expires > 0).then_some(expires),
+                secure: secure.eq_ignore_ascii_case("TRUE"),
+                http_only,
+                same_site: String::new(),
+            };
+            jar.store(cookie, now);
+        }
+        jar
+    }
+
+    /// Writes the jar in the Netscape format. Session cookies are kept with an
+    /// expiration of 0, like curl does.
+    pub fn to_netscape(&self) -> String {
+        let mut content = String::from("# Netscape HTTP Cookie File\n");
+        for cookie in &self.cookies {
+            let prefix = if cookie.http_only { "#HttpOnly_" } else { "" };
+            let domain = match cookie.host_only {
+                true => cookie.domain.clone(),
+                false => format!(".{}", cookie.domain),
+            };
+            let flag = |value: bool| if value { "TRUE" } else { "FALSE" };
+            content.push_str(&format!(
+                "{prefix}{domain}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+                flag(!cookie.host_only),
+                cookie.path,
+                flag(cookie.secure),
+                cookie.expires.unwrap_or_default(),
+                cookie.name,
+                cookie.value,
+            ));
+        }
+        content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use super::{civil_from_days, days_from_civil, parse_http_date, Cookie, CookieJar};
+
+    const NOW: u64 = 1_700_000_000;
+
+    #[test]
+    fn test_parse_cookie_defaults() {
+        let cookie = Cookie::parse("session=abc123", "https://api.example.com/v1/login", NOW);
+        assert_eq!(
+            cookie,
+            Some(Cookie {
+                name: "session".into(),
+                value: "abc123".into(),
+                domain: "api.example.com".into(),
+                host_only: true,
+                path: "/v1".into(),
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_cookie_attributes() {
+        let header = "id=a3fWa; Domain=.Example.com; Path=/; Max-Age=3600; Expires=Wed, 21 Oct 2015 07:28:00 GMT; Secure; HttpOnly; SameSite=Lax";
+        let cookie = Cookie::parse(header, "https://www.example.com/", NOW).unwrap();
+        assert_eq!(cookie.domain, "example.com");
+        assert!(!cookie.host_only);
+        assert_eq!(cookie.path, "/");
+        assert_eq!(cookie.expires, Some(NOW + 3600));
+        assert!(cookie.secure);
+        assert!(cookie.http_only);
+        assert_eq!(cookie.same_site, "Lax");
+    }
+
+    #[test]
+    fn test_parse_cookie_rejects_foreign_domains() {
+        let url = "https://www.example.com/";
+        assert!(Cookie::parse("a=1; Domain=other.com", url, NOW).is_none());
+        assert!(Cookie::parse("a=1; Domain=com", url, NOW).is_none());
+        assert!(Cookie::parse("=1", url, NOW).is_none());
+        assert!(Cookie::parse("novalue", url, NOW).is_none());
+    }
+
+    #[test]
+    fn test_parse_http_dates() {
+        let expected = Some(784111777);
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"), expected);
+        assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), expected);
+        assert_eq!(parse_http_date("Sun Nov  6 08:49:37 1994"), expected);
+        assert_eq!(parse_http_date("Thu, 01 Jan 1970 00:00:00 GMT"), Some(0));
+        assert_eq!(parse_http_date("tomorrow"), None);
+    }
+
+    #[test]
+    fn test_civil_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        for days in [-719468, -1, 0, 59, 11016, 19782, 2932896] {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+    }
+
+    #[test]
+    fn test_cookie_matches_urls() {
+        let url = |url: &str| Url::parse(url).unwrap();
+        let cookie = Cookie::parse(
+            "a=1; Domain=example.com; Path=/api; Secure",
+            "https://example.com/",
+            NOW,
+        )
+        .unwrap();
+        assert!(cookie.matches(&url("https://example.com/api"), NOW));
+        assert!(cookie.matches(&url("https://www.example.com/api/users"), NOW));
+        assert!(!cookie.matches(&url("http://example.com/api"), NOW));
+        assert!(!cookie.matches(&url("https://example.com/apis"), NOW));
+        assert!(!cookie.matches(&url("https://notexample.com/api"), NOW));
+
+        let host_only = Cookie::parse("b=2; Secure", "https://localhost:8080/", NOW).unwrap();
+        assert!(host_only.matches(&url("http://localhost:8080/"), NOW));
+        assert!(!host_only.matches(&url("http://www.localhost/"), NOW));
+    }
+
+    #[test]
+    fn test_jar_replaces_and_deletes_cookies() {
+        let url = "https://example.com/";
+        let mut jar = CookieJar::new();
+        jar.store(Cookie::parse("a=1", url, NOW).unwrap(), NOW);
+        jar.store(Cookie::parse("b=2", url, NOW).unwrap(), NOW);
+        jar.store(Cookie::parse("a=3", url, NOW).unwrap(), NOW);
+        assert_eq!(jar.header_for(url, NOW), Some("a=3; b=2".into()));
+
+        jar.store(Cookie::parse("a=; Max-Age=0", url, NOW).unwrap(), NOW);
+        assert_eq!(jar.header_for(url, NOW), Some("b=2".into()));
+
+        jar.store(Cookie::parse("c=4; Max-Age=10", url, NOW).unwrap(), NOW);
+        assert_eq!(jar.header_for(url, NOW + 20), Some("b=2".into()));
+
+        jar.clear();
+        assert!(jar.is_empty());
+        assert_eq!(jar.header_for(url, NOW), None);
+    }
+
+    #[test]
+    fn test_jar_sends_longer_paths_first() {
+        let mut jar = CookieJar::new();
+        jar.store(
+            Cookie::parse("a=1; Path=/", "https://example.com/", NOW).unwrap(),
+            NOW,
+        );
+        jar.store(
+            Cookie::parse("b=2; Path=/api", "https://example.com/", NOW).unwrap(),
+            NOW,
+        );
+        assert_eq!(
+            jar.header_for("https://example.com/api/users", NOW),
+            Some("b=2; a=1".into())
+        );
+        assert_eq!(
+            jar.header_for("https://example.com/", NOW),
+            Some("a=1".into())
+        );
+    }
+
+    #[test]
+    fn test_jar_netscape_roundtrip() {
+        let mut jar = CookieJar::new();
+        let url = "https://www.example.com/";
+        let headers = [
+            "session=abc; HttpOnly",
+            "theme=dark; Domain=example.com; Path=/; Max-Age=60; Secure",
+        ];
+        for header in headers {
+            jar.store(Cookie::parse(header, url, NOW).unwrap(), NOW);
+        }
+        let content = jar.to_netscape();
+        assert!(content.contains("#HttpOnly_www.example.com\tFALSE\t/\tFALSE\t0\tsession\tabc\n"));
+        assert!(content.contains(".example.com\tTRUE\t/\tTRUE\t1700000060\ttheme\tdark\n"));
+        assert_eq!(CookieJar::parse_netscape(&content, NOW), jar);
+
+        // Expired cookies are not loaded.
+        assert!(
+            CookieJar::parse_netscape(&content, NOW + 100)
+                .cookies()
+                .len()
+                == 1
+        );
+    }
+}
@@ -15,48 +15,166 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use crate::entities::{RequestMethod, ResponseData};
+use crate::entities::{
+    CertificateFormat, HttpVersion, ProxySettings, Redirect, RequestMethod, RequestProxy,
+    ResponseData, TlsSettings,
+};
 
-use super::{BoundRequest, RequestError};
-use futures_lite::io::AsyncReadExt;
+use super::{signing, unix_now, BoundPayload, BoundRequest, Cookie, RequestError};
+use futures_lite::io::{AssertAsync, AsyncReadExt};
 use isahc::{
-    http::{HeaderName, HeaderValue},
-    AsyncBody, Body,
+    auth::{Authentication, Credentials},
+    config::{
+        CaCertificate, ClientCertificate, Configurable, PrivateKey, RedirectPolicy, SslOption,
+        VersionNegotiation,
+    },
+    http::{HeaderName, HeaderValue, StatusCode, Uri},
+    AsyncBody, Body, RequestExt,
+};
+use std::{
+    io::Read,
+    str::FromStr,
+    time::{Duration, Instant},
 };
-use std::{io::Read, str::FromStr, time::Instant};
 
-impl From<&RequestMethod> for isahc::http::Method {
-    fn from(value: &RequestMethod) -> Self {
+impl TryFrom<&RequestMethod> for isahc::http::Method {
+    type Error = RequestError;
+
+    fn try_from(value: &RequestMethod) -> Result<Self, Self::Error> {
         match value {
-            RequestMethod::Head => isahc::http::Method::HEAD,
-            RequestMethod::Get => isahc::http::Method::GET,
-            RequestMethod::Post => isahc::http::Method::POST,
-            RequestMethod::Put => isahc::http::Method::PUT,
-            RequestMethod::Patch => isahc::http::Method::PATCH,
-            RequestMethod::Options => isahc::http::Method::OPTIONS,
-            RequestMethod::Delete => isahc::http::Method::DELETE,
-            RequestMethod::Trace => isahc::http::Method::TRACE,
+            RequestMethod::Head => Ok(isahc::http::Method::HEAD),
+            RequestMethod::Get => Ok(isahc::http::Method::GET),
+            RequestMethod::Post => Ok(isahc::http::Method::POST),
+            RequestMethod::Put => Ok(isahc::http::Method::PUT),
+            RequestMethod::Patch => Ok(isahc::http::Method::PATCH),
+            RequestMethod::Options => Ok(isahc::http::Method::OPTIONS),
+            RequestMethod::Delete => Ok(isahc::http::Method::DELETE),
+            RequestMethod::Trace => Ok(isahc::http::Method::TRACE),
+            RequestMethod::Custom(method) => isahc::http::Method::from_bytes(method.as_bytes())
+                .map_err(|_| RequestError::InvalidHttpVerb),
         }
     }
 }
 
-impl TryFrom<BoundRequest> for isahc::Request<Vec<u8>> {
+fn client_certificate(tls: &TlsSettings) -> Option<ClientCertificate> {
+    if tls.certificate.is_empty() {
+        return None;
+    }
+    let password = Some(tls.password.clone()).filter(|p| !p.is_empty());
+    let certificate = match tls.format {
+        CertificateFormat::Pem => {
+            // Without a separate key file, the key is expected next to the certificate.
+            let key = if tls.key.is_empty() {
+                password.map(|p| PrivateKey::pem_file(&tls.certificate, Some(p)))
+            } else {
+                Some(PrivateKey::pem_file(&tls.key, password))
+            };
+            ClientCertificate::pem_file(&tls.certificate, key)
+        }
+        CertificateFormat::Pkcs12 => ClientCertificate::pkcs12_file(&tls.certificate, password),
+    };
+    Some(certificate)
+}
+
+/// The proxy that a request goes through, unless its host is in the no-proxy list.
+///
+/// A request that uses the default proxy may still go through the proxy set in the
+/// environment variables, which is not known here.
+fn proxy_for(request: &BoundRequest) -> Option<&ProxySettings> {
+    let RequestProxy::Custom(proxy) = &request.proxy else {
+        return None;
+    };
+    let url = url::Url::parse(&request.url).ok();
+    let host = url
+        .as_ref()
+        .and_then(|url| url.host_str())
+        .unwrap_or_default();
+    (!proxy.bypasses(host)).then_some(proxy)
+}
+
+impl TryFrom<BoundRequest> for isahc::Request<AsyncBody> {
     type Error = RequestError;
 
     fn try_from(req: BoundRequest) -> Result<Self, Self::Error> {
-        let mut builder = isahc::Request::builder().uri(&req.url).method(&req.method);
-        let Some(headers) = builder.headers_mut() else {
-            return Err(RequestError::InvalidHeaders);
-        };
-        for (h, v) in &req.headers {
-            let key = HeaderName::from_str(h)?;
-            let value = HeaderValue::from_str(v)?;
-            headers.insert(key, value);
+        let timeout = req.options.timeout.filter(|t| *t > 0);
+        build_request(req, timeout.map(Duration::from_secs))
+    }
+}
+
+/// Builds the request that isahc sends, which has to be answered within `timeout`.
+fn build_request(
+    req: BoundRequest,
+    timeout: Option<Duration>,
+) -> Result<isahc::Request<AsyncBody>, RequestError> {
+    let method = isahc::http::Method::try_from(&req.method)?;
+    let options = &req.options;
+    let version = match options.http_version {
+        HttpVersion::Auto => VersionNegotiation::latest_compatible(),
+        HttpVersion::Http11 => VersionNegotiation::http11(),
+        HttpVersion::Http2 => VersionNegotiation::http2(),
+    };
+    // Redirects are followed by send_request, so that every hop can be reported.
+    let mut builder = isahc::Request::builder()
+        .uri(&req.url)
+        .method(method)
+        .redirect_policy(RedirectPolicy::None)
+        .version_negotiation(version)
+        .automatic_decompression(options.decompress);
+    if let Some(timeout) = timeout {
+        builder = builder.timeout(timeout);
+    }
+    if !options.verify_tls {
+        builder = builder.ssl_options(
+            SslOption::DANGER_ACCEPT_INVALID_CERTS | SslOption::DANGER_ACCEPT_INVALID_HOSTS,
+        );
+    }
+    if let Some(certificate) = client_certificate(&req.tls) {
+        builder = builder.ssl_client_certificate(certificate);
+    }
+    if !req.tls.ca_bundle.is_empty() {
+        builder = builder.ssl_ca_certificate(CaCertificate::file(&req.tls.ca_bundle));
+    }
+    if let Some(proxy) = proxy_for(&req) {
+        let uri = Uri::from_str(&proxy.url)
+            .map_err(|_| RequestError::InvalidProxy(proxy.display_url()))?;
+        builder = builder.proxy(Some(uri));
+        if !proxy.username.is_empty() {
+            builder = builder
+                .proxy_authentication(Authentication::basic())
+                .proxy_credentials(Credentials::new(&proxy.username, &proxy.password));
         }
-        let body = req.body.unwrap_or_default();
-        let req = builder.body(body)?;
-        Ok(req)
+    } else if req.proxy != RequestProxy::Default {
+        // Otherwise curl would still pick the proxy of the environment variables.
+        builder = builder.proxy(None);
+    }
+    if let Some(digest) = &req.digest {
+        // curl sends the request again with the answer to the challenge.
+        builder = builder
+            .authentication(Authentication::digest())
+            .credentials(Credentials::new(&digest.username, &digest.password));
+    }
+    let Some(headers) = builder.headers_mut() else {
+        return Err(RequestError::InvalidHeaders);
+    };
+    for header in req.headers.iter() {
+        let key = HeaderName::from_str(&header.name)?;
+        let value = HeaderValue::from_str(&header.value)?;
+        headers.append(key, value);
     }
+    let body = match req.open_body()? {
+        // curl sends the body again to answer the challenge, so it has to be in memory.
+        Some(_) if req.digest.is_some() => AsyncBody::from(req.read_body()?.into_owned()),
+        Some(stream) => {
+            let reader = AssertAsync::new(stream.reader);
+            match stream.length {
+                Some(length) => AsyncBody::from_reader_sized(reader, length),
+                None => AsyncBody::from_reader(reader),
+            }
+        }
+        None => AsyncBody::from(req.body.unwrap_or_default()),
+    };
+    let req = builder.body(body)?;
+    Ok(req)
 }
 
 impl TryFrom<&mut isahc::Response<Body>> for ResponseData {
@@ -85,6 +203,9 @@ impl TryFrom<&mut isahc::Response<Body>> for ResponseData {
             status_code,
             headers,
             body,
+            redirects: vec![],
+            proxy: None,
+            cookies: vec![],
         })
     }
 }
@@ -116,5 +237,752 @@ pub async fn extract_isahc_response(
         status_code,
         headers,
         body,
+        redirects: vec![],
+        proxy: None,
+        cookies: vec![],
+    })
+}
+
+/// Sends a bound request using isahc and collects the response.
+///
+/// This is the same code path used by the user interface when the Send button is
+/// clicked, so that any other frontend gets exactly the same behaviour.
+///
+/// If the request has a timeout and it runs out, either while waiting for the
+/// response or while reading its body, `RequestError::Timeout` is returned. The
+/// timeout covers the whole request, including the redirects that it follows.
+///
+/// When the request follows redirects, the redirects on the way to the final
+/// response are listed in `ResponseData::redirects`. If the final request went
+/// through a proxy, its URL is kept in `ResponseData::proxy`.
+///
+/// The cookies set on the way are listed in `ResponseData::cookies`. If the request
+/// has a cookie jar, they are also stored in it, and the cookies of the jar are sent
+/// with every request, including the ones that follow a redirect.
+///
+/// A request with a signer is signed right before it goes out, and so is every
+/// request that follows a redirect to the same host.
+pub async fn send_request(request: BoundRequest) -> Result<ResponseData, RequestError> {
+    let timeout = request.options.timeout.unwrap_or_default();
+    let start = Instant::now();
+    let deadline = (timeout > 0).then(|| start + Duration::from_secs(timeout));
+    let outcome = async {
+        let mut request = request;
+        let mut redirects = Vec::new();
+        let mut cookies = Vec::new();
+        loop {
+            let mut outgoing = with_jar_cookies(&request);
+            if let Some(signer) = &request.signer {
+                signing::sign(&mut outgoing, signer, unix_now())?;
+            }
+            // Every hop only gets the time that is left for the whole request.
+            let remaining = match deadline {
+                Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                    Some(remaining) if !remaining.is_zero() => Some(remaining),
+                    _ => return Err(RequestError::Timeout(timeout)),
+                },
+                None => None,
+            };
+            let request_obj = build_request(outgoing, remaining)?;
+            let mut response_obj = request_obj.send_async().await?;
+
+            let now = unix_now();
+            let received: Vec<Cookie> = response_obj
+                .headers()
+                .get_all("Set-Cookie")
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .filter_map(|value| Cookie::parse(value, &request.url, now))
+                .collect();
+            if let Some(jar) = &request.cookie_jar {
+                let mut jar = jar.lock().unwrap();
+                for cookie in &received {
+                    jar.store(cookie.clone(), now);
+                }
+            }
+            cookies.extend(received);
+
+            let status = response_obj.status();
+            let location = response_obj
+                .headers()
+                .get("Location")
+                .and_then(|value| value.to_str().ok());
+            let next = match location {
+                Some(location) if request.options.follow_redirects => {
+                    follow_redirect(&request, status, location)
+                }
+                _ => None,
+            };
+            let Some(next) = next else {
+                let mut response = extract_isahc_response(&mut response_obj, &start).await?;
+                response.redirects = redirects;
+                response.proxy = proxy_for(&request).map(ProxySettings::display_url);
+                response.cookies = cookies;
+                return Ok(response);
+            };
+
+            if redirects.len() as u32 >= request.options.max_redirects {
+                return Err(RequestError::TooManyRedirects(
+                    request.options.max_redirects,
+                ));
+            }
+            redirects.push(Redirect {
+                status_code: status.as_u16() as u32,
+                url: request.url.clone(),
+                location: next.url.clone(),
+            });
+            request = next;
+        }
+    };
+    outcome.await.map_err(|e| match e {
+        RequestError::NetworkError(e) if e.is_timeout() => RequestError::Timeout(timeout),
+        RequestError::IOError(e) if e.kind() == std::io::ErrorKind::TimedOut => {
+            RequestError::Timeout(timeout)
+        }
+        e => e,
     })
 }
+
+/// Adds the cookies of the jar of a request to its Cookie header.
+fn with_jar_cookies(request: &BoundRequest) -> BoundRequest {
+    let mut request = request.clone();
+    let cookies = request
+        .cookie_jar
+        .as_ref()
+        .and_then(|jar| jar.lock().unwrap().header_for(&request.url, unix_now()));
+    let Some(cookies) = cookies else {
+        return request;
+    };
+    let header = request
+        .headers
+        .iter_mut()
+        .find(|header| header.name.eq_ignore_ascii_case("Cookie"));
+    match header {
+        Some(header) => header.value = format!("{}; {cookies}", header.value),
+        None => request.headers.push(("Cookie", cookies.as_str()).into()),
+    }
+    request
+}
+
+/// Builds the request that follows a redirect response, or `None` if the response
+/// is not a redirect that can be followed.
+///
+/// Like browsers do, a 303 turns the request into a GET, and so do a 301 or a 302
+/// for POST requests. The body is dropped in those cases. The Authorization and
+/// Cookie headers, the Digest credentials and the signature are not sent to a
+/// different host.
+fn follow_redirect(
+    request: &BoundRequest,
+    status: StatusCode,
+    location: &str,
+) -> Option<BoundRequest> {
+    let turns_into_get = match status.as_u16() {
+        301 | 302 => request.method == RequestMethod::Post,
+        303 => request.method != RequestMethod::Head,
+        307 | 308 => false,
+        _ => return None,
+    };
+    let current = url::Url::parse(&request.url).ok()?;
+    let target = current.join(location).ok()?;
+
+    let mut next = request.clone();
+    next.url = target.to_string();
+    let mut dropped: Vec<&str> = Vec::new();
+    if turns_into_get {
+        next.method = RequestMethod::Get;
+        next.body = None;
+        next.payload = BoundPayload::None;
+        dropped.extend(["Content-Type", "Content-Length"]);
+    }
+    if current.host_str() != target.host_str() {
+        dropped.extend(["Authorization", "Cookie"]);
+        next.digest = None;
+        next.signer = None;
+    }
+    next.headers = request
+        .headers
+        .iter()
+        .filter(|h| !dropped.iter().any(|d| h.name.eq_ignore_ascii_case(d)))
+        .cloned()
+        .collect();
+    Some(next)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpListener, TcpStream};
+    use std::path::Path;
+    use std::process::{Command, Stdio};
+    use std::sync::mpsc::{channel, Receiver};
+    use std::sync::{Arc, Mutex};
+
+    use futures_lite::io::AsyncReadExt;
+    use isahc::http::StatusCode;
+
+    use super::follow_redirect;
+    use crate::client::{
+        send_request, signing, BoundFile, BoundPayload, BoundRequest, CookieJar, DigestCredentials,
+        RequestError, RequestSigner,
+    };
+    use crate::entities::{
+        HmacSettings, KeyValueTable, ProxySettings, RawEncoding, RequestMethod, RequestOptions,
+        RequestProxy, TlsSettings,
+    };
+
+    /// Answers the given responses, one per connection, from a local server.
+    fn serve(responses: Vec<String>) -> SocketAddr {
+        serve_and_record(responses).0
+    }
+
+    /// Like `serve`, but also hands over the head of every request that is received.
+    fn serve_and_record(responses: Vec<String>) -> (SocketAddr, Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let (sender, receiver) = channel();
+        std::thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = Vec::new();
+                let mut buffer = [0; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let read = stream.read(&mut buffer).unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                }
+                stream.write_all(response.as_bytes()).unwrap();
+                let _ = sender.send(String::from_utf8_lossy(&request).into_owned());
+            }
+        });
+        (address, receiver)
+    }
+
+    /// Runs the openssl command line tool, which returns false when it is not installed.
+    fn openssl(dir: &Path, args: &[&str]) -> bool {
+        Command::new("openssl")
+            .args(args)
+            .current_dir(dir)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+
+    fn redirect_to(location: &str) -> String {
+        format!("HTTP/1.1 302 Found\r\nLocation: {location}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+    }
+
+    #[test]
+    fn test_request_keeps_repeated_headers_in_order() {
+        let bound = BoundRequest {
+            url: "https://www.example.com/".into(),
+            method: RequestMethod::Get,
+            headers: KeyValueTable::new(&[
+                ("Accept", "application/json").into(),
+                ("Cookie", "session=1234").into(),
+                ("Accept", "text/html").into(),
+                ("Cookie", "theme=dark").into(),
+            ]),
+            body: None,
+            ..Default::default()
+        };
+
+        let request = isahc::Request::try_from(bound).unwrap();
+        let headers = request.headers();
+        let accept: Vec<&str> = headers
+            .get_all("Accept")
+            .iter()
+            .map(|v| v.to_str().unwrap())
+            .collect();
+        let cookies: Vec<&str> = headers
+            .get_all("Cookie")
+            .iter()
+            .map(|v| v.to_str().unwrap())
+            .collect();
+        assert_eq!(accept, vec!["application/json", "text/html"]);
+        assert_eq!(cookies, vec!["session=1234", "theme=dark"]);
+    }
+
+    #[test]
+    fn test_request_with_custom_method() {
+        let bound = BoundRequest {
+            url: "https://www.example.com/dav/".into(),
+            method: RequestMethod::Custom("PROPFIND".into()),
+            ..Default::default()
+        };
+        let request = isahc::Request::try_from(bound).unwrap();
+        assert_eq!(request.method().as_str(), "PROPFIND");
+
+        let bound = BoundRequest {
+            url: "https://www.example.com/".into(),
+            method: RequestMethod::Custom("NOT VALID".into()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            isahc::Request::try_from(bound),
+            Err(RequestError::InvalidHttpVerb)
+        ));
+    }
+
+    #[test]
+    fn test_request_streams_body_file() {
+        let path = std::env::temp_dir().join(format!("cartero-stream-{}.bin", std::process::id()));
+        std::fs::write(&path, b"\x00\xffbody").unwrap();
+        let bound = BoundRequest {
+            url: "https://www.example.com/blobs".into(),
+            method: RequestMethod::Put,
+            payload: BoundPayload::File(path.clone()),
+            ..Default::default()
+        };
+
+        let request = isahc::Request::try_from(bound.clone()).unwrap();
+        assert_eq!(request.body().len(), Some(6));
+        let mut content = Vec::new();
+        futures_lite::future::block_on(request.into_body().read_to_end(&mut content)).unwrap();
+        assert_eq!(content, b"\x00\xffbody");
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            isahc::Request::try_from(bound),
+            Err(RequestError::UnreadableFile(..))
+        ));
+    }
+
+    #[test]
+    fn test_request_streams_multipart_files() {
+        let path = std::env::temp_dir().join(format!("cartero-parts-{}.txt", std::process::id()));
+        std::fs::write(&path, b"contents").unwrap();
+        let bound = BoundRequest {
+            url: "https://www.example.com/upload".into(),
+            method: RequestMethod::Post,
+            payload: BoundPayload::Multipart {
+                fields: vec![("title".into(), "Hello".into())],
+                files: vec![BoundFile {
+                    name: "doc".into(),
+                    path: path.clone(),
+                    filename: "doc.txt".into(),
+                    content_type: "text/plain".into(),
+                }],
+                boundary: "XYZ".into(),
+            },
+            ..Default::default()
+        };
+
+        let request = isahc::Request::try_from(bound.clone()).unwrap();
+        let expected = "--XYZ\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nHello\r\n\
+            --XYZ\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"doc.txt\"\r\n\
+            Content-Type: text/plain\r\n\r\ncontents\r\n--XYZ--\r\n";
+        assert_eq!(request.body().len(), Some(expected.len() as u64));
+        let mut content = String::new();
+        futures_lite::future::block_on(request.into_body().read_to_string(&mut content)).unwrap();
+        assert_eq!(content, expected);
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            isahc::Request::try_from(bound),
+            Err(RequestError::UnreadableFile(..))
+        ));
+    }
+
+    #[test]
+    fn test_request_times_out() {
+        // A server that accepts the connection but never answers.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let bound = BoundRequest {
+            url: format!("http://{address}/"),
+            options: RequestOptions {
+                timeout: Some(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let outcome = futures_lite::future::block_on(send_request(bound));
+        assert!(matches!(outcome, Err(RequestError::Timeout(1))));
+        drop(listener);
+    }
+
+    #[test]
+    fn test_request_times_out_across_redirects() {
+        // Every answer takes most of the timeout, so two of them take too long.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let redirect = "HTTP/1.1 302 Found\r\nLocation: /next\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+            let ok = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nOK";
+            for response in [redirect, ok] {
+                let Ok((mut stream, _)) = listener.accept() else {
+                    return;
+                };
+                let _ = stream.read(&mut [0; 1024]);
+                std::thread::sleep(std::time::Duration::from_millis(700));
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        let bound = BoundRequest {
+            url: format!("http://{address}/"),
+            options: RequestOptions {
+                timeout: Some(1),
+                follow_redirects: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let start = std::time::Instant::now();
+        let outcome = futures_lite::future::block_on(send_request(bound));
+        assert!(matches!(outcome, Err(RequestError::Timeout(1))));
+        assert!(start.elapsed() < std::time::Duration::from_millis(1400));
+    }
+
+    #[test]
+    fn test_follow_redirect_keeps_method_on_307() {
+        let request = BoundRequest {
+            url: "https://www.example.com/v1/books".into(),
+            method: RequestMethod::Post,
+            headers: KeyValueTable::new(&[
+                ("Content-Type", "application/json").into(),
+                ("Authorization", "Bearer 1234").into(),
+            ]),
+            body: Some(b"{}".to_vec()),
+            payload: BoundPayload::Raw(RawEncoding::Json),
+            ..Default::default()
+        };
+        let next = follow_redirect(&request, StatusCode::TEMPORARY_REDIRECT, "/v2/books").unwrap();
+        assert_eq!(next.url, "https://www.example.com/v2/books");
+        assert_eq!(next.method, RequestMethod::Post);
+        assert_eq!(next.body, Some(b"{}".to_vec()));
+        assert_eq!(next.headers, request.headers);
+    }
+
+    #[test]
+    fn test_follow_redirect_turns_post_into_get() {
+        let request = BoundRequest {
+            url: "https://www.example.com/login".into(),
+            method: RequestMethod::Post,
+            headers: KeyValueTable::new(&[
+                ("Content-Type", "application/x-www-form-urlencoded").into(),
+                ("Authorization", "Bearer 1234").into(),
+                ("Accept", "text/html").into(),
+            ]),
+            body: Some(b"user=john".to_vec()),
+            payload: BoundPayload::Urlencoded(vec![("user".into(), "john".into())]),
+            ..Default::default()
+        };
+        let next =
+            follow_redirect(&request, StatusCode::SEE_OTHER, "https://auth.example.com/").unwrap();
+        assert_eq!(next.url, "https://auth.example.com/");
+        assert_eq!(next.method, RequestMethod::Get);
+        assert_eq!(next.body, None);
+        assert_eq!(next.payload, BoundPayload::None);
+        assert_eq!(
+            next.headers,
+            KeyValueTable::new(&[("Accept", "text/html").into()])
+        );
+
+        assert!(follow_redirect(&request, StatusCode::NOT_MODIFIED, "/").is_none());
+    }
+
+    #[test]
+    fn test_send_request_follows_redirects() {
+        let final_response =
+            "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nOK".to_string();
+        let address = serve(vec![
+            redirect_to("/second"),
+            redirect_to("/third"),
+            final_response,
+        ]);
+        let bound = BoundRequest {
+            url: format!("http://{address}/first"),
+            options: RequestOptions {
+                follow_redirects: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let response = futures_lite::future::block_on(send_request(bound)).unwrap();
+        assert_eq!(response.status_code, 200);
+        assert_eq!(response.body, b"OK");
+        let hops: Vec<(u32, &str)> = response
+            .redirects
+            .iter()
+            .map(|r| (r.status_code, r.location.as_str()))
+            .collect();
+        assert_eq!(
+            hops,
+            vec![
+                (302, format!("http://{address}/second").as_str()),
+                (302, format!("http://{address}/third").as_str()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_send_request_limits_redirects() {
+        let address = serve(vec![redirect_to("/second"), redirect_to("/third")]);
+        let bound = BoundRequest {
+            url: format!("http://{address}/first"),
+            options: RequestOptions {
+                follow_redirects: true,
+                max_redirects: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let outcome = futures_lite::future::block_on(send_request(bound));
+        assert!(matches!(outcome, Err(RequestError::TooManyRedirects(1))));
+    }
+
+    #[test]
+    fn test_send_request_does_not_follow_redirects_by_default() {
+        let address = serve(vec![redirect_to("/second")]);
+        let bound = BoundRequest {
+            url: format!("http://{address}/first"),
+            ..Default::default()
+        };
+        let response = futures_lite::future::block_on(send_request(bound)).unwrap();
+        assert_eq!(response.status_code, 302);
+        assert!(response.redirects.is_empty());
+    }
+
+    #[test]
+    fn test_send_request_through_proxy() {
+        let ok = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nOK";
+        let (address, requests) = serve_and_record(vec![ok.to_string()]);
+        let bound = BoundRequest {
+            url: "http://api.example.invalid/users".into(),
+            proxy: RequestProxy::Custom(ProxySettings {
+                url: format!("http://{address}"),
+                username: "john".into(),
+                password: "s3cr3t".into(),
+                no_proxy: "localhost".into(),
+            }),
+            ..Default::default()
+        };
+        let response = futures_lite::future::block_on(send_request(bound)).unwrap();
+        assert_eq!(response.body, b"OK");
+        assert_eq!(response.proxy, Some(format!("http://{address}")));
+
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("GET http://api.example.invalid/users HTTP/1.1\r\n"));
+        assert!(request.contains("Proxy-Authorization: Basic am9objpzM2NyM3Q=\r\n"));
+    }
+
+    #[test]
+    fn test_send_request_skips_proxy_for_no_proxy_hosts() {
+        let ok = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nOK";
+        let address = serve(vec![ok.to_string()]);
+        // Nothing listens on this port, so the request fails if it uses the proxy.
+        let closed = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let bound = BoundRequest {
+            url: format!("http://{address}/"),
+            proxy: RequestProxy::Custom(ProxySettings {
+                url: format!("http://{closed}"),
+                no_proxy: "localhost, 127.0.0.1".into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let response = futures_lite::future::block_on(send_request(bound)).unwrap();
+        assert_eq!(response.body, b"OK");
+        assert_eq!(response.proxy, None);
+    }
+
+    #[test]
+    fn test_send_request_stores_and_sends_cookies() {
+        let login = "HTTP/1.1 302 Found\r\nLocation: /home\r\nSet-Cookie: session=abc; Path=/; HttpOnly\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+        let ok = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nOK";
+        let (address, requests) = serve_and_record(vec![login.to_string(), ok.to_string()]);
+        let jar = Arc::new(Mutex::new(CookieJar::new()));
+        let bound = BoundRequest {
+            url: format!("http://{address}/login"),
+            headers: KeyValueTable::new(&[("Cookie", "theme=dark").into()]),
+            options: RequestOptions {
+                follow_redirects: true,
+                ..Default::default()
+            },
+            cookie_jar: Some(jar.clone()),
+            ..Default::default()
+        };
+        let response = futures_lite::future::block_on(send_request(bound)).unwrap();
+        assert_eq!(response.body, b"OK");
+        let cookies: Vec<(&str, &str)> = response
+            .cookies
+            .iter()
+            .map(|c| (c.name.as_str(), c.value.as_str()))
+            .collect();
+        assert_eq!(cookies, vec![("session", "abc")]);
+        assert_eq!(jar.lock().unwrap().cookies().len(), 1);
+
+        let first = requests.recv().unwrap().to_lowercase();
+        assert!(first.contains("cookie: theme=dark\r\n"));
+        let second = requests.recv().unwrap().to_lowercase();
+        assert!(second.starts_with("get /home http/1.1\r\n"));
+        assert!(second.contains("cookie: theme=dark; session=abc\r\n"));
+    }
+
+    #[test]
+    fn test_send_request_answers_digest_challenge() {
+        let challenge = "HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: Digest realm=\"api\", nonce=\"dcd98b7102dd2f0e\", qop=\"auth\"\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+        let ok = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nOK";
+        let (address, requests) = serve_and_record(vec![challenge.to_string(), ok.to_string()]);
+        let bound = BoundRequest {
+            url: format!("http://{address}/private"),
+            digest: Some(DigestCredentials {
+                username: "john".into(),
+                password: "secret".into(),
+            }),
+            ..Default::default()
+        };
+        let response = futures_lite::future::block_on(send_request(bound)).unwrap();
+        assert_eq!(response.status_code, 200);
+        assert_eq!(response.body, b"OK");
+
+        let first = requests.recv().unwrap().to_lowercase();
+        assert!(!first.contains("authorization:"));
+        let second = requests.recv().unwrap().to_lowercase();
+        assert!(second.contains("authorization: digest username=\"john\""));
+        assert!(second.contains("realm=\"api\""));
+        assert!(second.contains("uri=\"/private\""));
+    }
+
+    #[test]
+    fn test_send_request_signs_every_hop() {
+        let redirect = "HTTP/1.1 302 Found\r\nLocation: /home\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+        let ok = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nOK";
+        let (address, requests) = serve_and_record(vec![redirect.to_string(), ok.to_string()]);
+        let bound = BoundRequest {
+            url: format!("http://{address}/login"),
+            options: RequestOptions {
+                follow_redirects: true,
+                ..Default::default()
+            },
+            signer: Some(RequestSigner::Hmac(HmacSettings {
+                key: "key".into(),
+                message: "{path}".into(),
+                header: "X-Signature".into(),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        let response = futures_lite::future::block_on(send_request(bound.clone())).unwrap();
+        assert_eq!(response.status_code, 200);
+
+        for path in ["/login", "/home"] {
+            let mut expected = BoundRequest {
+                url: format!("http://{address}{path}"),
+                ..bound.clone()
+            };
+            signing::sign(&mut expected, bound.signer.as_ref().unwrap(), 0).unwrap();
+            let signature = expected.headers.header("X-Signature").unwrap()[0];
+            let head = requests.recv().unwrap().to_lowercase();
+            assert!(head.contains(&format!("x-signature: {signature}\r\n")));
+        }
+    }
+
+    #[test]
+    fn test_follow_redirect_drops_digest_for_other_hosts() {
+        let request = BoundRequest {
+            url: "https://www.example.com/".into(),
+            digest: Some(DigestCredentials {
+                username: "john".into(),
+                password: "secret".into(),
+            }),
+            signer: Some(RequestSigner::Hmac(HmacSettings::default())),
+            ..Default::default()
+        };
+        let next = follow_redirect(&request, StatusCode::FOUND, "/home").unwrap();
+        assert_eq!(next.digest, request.digest);
+        assert_eq!(next.signer, request.signer);
+        let next =
+            follow_redirect(&request, StatusCode::FOUND, "https://cdn.example.com/").unwrap();
+        assert_eq!(next.digest, None);
+        assert_eq!(next.signer, None);
+    }
+
+    #[test]
+    fn test_request_with_invalid_proxy() {
+        let bound = BoundRequest {
+            url: "http://www.example.com/".into(),
+            proxy: RequestProxy::Custom(ProxySettings {
+                url: "http://proxy host".into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let outcome = isahc::Request::try_from(bound);
+        assert!(matches!(outcome, Err(RequestError::InvalidProxy(_))));
+    }
+
+    #[test]
+    fn test_request_with_client_certificate() {
+        let dir = std::env::temp_dir().join(format!("cartero-mtls-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("san.ext"), "subjectAltName=IP:127.0.0.1").unwrap();
+        let generated = [
+            "req -x509 -newkey rsa:2048 -nodes -keyout ca.key -out ca.pem -days 1 -subj /CN=ca",
+            "req -newkey rsa:2048 -nodes -keyout server.key -out server.csr -subj /CN=server",
+            "x509 -req -in server.csr -CA ca.pem -CAkey ca.key -CAcreateserial -out server.pem -days 1 -extfile san.ext",
+            "req -newkey rsa:2048 -nodes -keyout client.key -out client.csr -subj /CN=client",
+            "x509 -req -in client.csr -CA ca.pem -CAkey ca.key -CAcreateserial -out client.pem -days 1",
+        ]
+        .iter()
+        .all(|command| openssl(&dir, &command.split(' ').collect::<Vec<_>>()));
+        if !generated {
+            // Without openssl there is no way to generate the certificates.
+            std::fs::remove_dir_all(&dir).unwrap();
+            return;
+        }
+
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut server = Command::new("openssl")
+            .args(["s_server", "-www", "-Verify", "1", "-accept"])
+            .arg(port.to_string())
+            .args([
+                "-cert",
+                "server.pem",
+                "-key",
+                "server.key",
+                "-CAfile",
+                "ca.pem",
+            ])
+            .current_dir(&dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+        while TcpStream::connect(("127.0.0.1", port)).is_err() {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+
+        let path = |name: &str| dir.join(name).to_string_lossy().to_string();
+        let request = |tls: TlsSettings| BoundRequest {
+            url: format!("https://127.0.0.1:{port}/"),
+            method: RequestMethod::Get,
+            tls,
+            ..Default::default()
+        };
+        let anonymous = futures_lite::future::block_on(send_request(request(TlsSettings {
+            ca_bundle: path("ca.pem"),
+            ..Default::default()
+        })));
+        let authenticated = futures_lite::future::block_on(send_request(request(TlsSettings {
+            certificate: path("client.pem"),
+            key: path("client.key"),
+            ca_bundle: path("ca.pem"),
+            ..Default::default()
+        })));
+        server.kill().unwrap();
+        server.wait().unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(anonymous.is_err());
+        assert_eq!(authenticated.unwrap().status_code, 200);
+    }
+}
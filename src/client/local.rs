@@ -15,32 +15,172 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use formdata::FormData;
 use isahc::http::header::{InvalidHeaderName, InvalidHeaderValue};
 use srtemplate::SrTemplate;
-use std::{
-    collections::HashMap,
-    io::{BufWriter, Write},
-};
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{Cursor, Read, Write};
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 use crate::{
-    entities::{EndpointData, KeyValueTable, RawEncoding, RequestMethod, RequestPayload},
+    entities::{
+        ApiKeyLocation, AwsSigV4Settings, EndpointData, FormFile, HmacSettings, KeyValue,
+        KeyValueTable, OAuth2Settings, ProxySettings, RawEncoding, RequestAuth, RequestMethod,
+        RequestOptions, RequestPayload, RequestProxy, TlsSettings,
+    },
     error::CarteroError,
 };
 
+use super::signing::RequestSigner;
+use super::SharedCookieJar;
+
 #[derive(Default, Debug, Clone)]
 pub struct BoundRequest {
     pub url: String,
     pub method: RequestMethod,
-    pub headers: HashMap<String, String>,
+    pub headers: KeyValueTable,
     pub body: Option<Vec<u8>>,
+    pub payload: BoundPayload,
+    pub options: RequestOptions,
+    /// The TLS settings, with their variables already processed.
+    pub tls: TlsSettings,
+    /// The proxy settings, with their variables already processed.
+    pub proxy: RequestProxy,
+    /// The jar that provides the cookies of the request and keeps the ones of the
+    /// response. Without a jar, cookies are not remembered.
+    pub cookie_jar: Option<SharedCookieJar>,
+    /// The credentials of HTTP Digest authentication, with their variables already
+    /// processed. The other schemes are already applied to the headers or the URL.
+    pub digest: Option<DigestCredentials>,
+    /// The OAuth 2.0 client, with its variables already processed. The access token
+    /// is added as a Bearer token by `oauth2::authorize` before the request is sent.
+    pub oauth2: Option<OAuth2Settings>,
+    /// How the request is signed, with its variables already processed. The signature
+    /// depends on the time, so it is only added by `send_request` as it goes out.
+    pub signer: Option<RequestSigner>,
+}
+
+impl BoundRequest {
+    /// Opens the body that is read from disk, which is the body of a file or of a
+    /// multipart form. The files are read as the stream is consumed.
+    ///
+    /// The other bodies are already in `BoundRequest::body`, so None is returned.
+    pub fn open_body(&self) -> Result<Option<BodyStream>, RequestError> {
+        match &self.payload {
+            BoundPayload::File(path) => {
+                let file = open_file(path)?;
+                let length = file.metadata().map(|metadata| metadata.len()).ok();
+                Ok(Some(BodyStream::default().chain(file, length)))
+            }
+            BoundPayload::Multipart {
+                fields,
+                files,
+                boundary,
+            } => multipart_stream(boundary, fields, files).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the whole body, reading it from disk when it is streamed.
+    ///
+    /// Files are otherwise streamed when the request is sent, so this is only for
+    /// what needs the body at once, such as a signature.
+    pub fn read_body(&self) -> Result<Cow<'_, [u8]>, RequestError> {
+        match self.open_body()? {
+            Some(mut stream) => {
+                let mut body = Vec::new();
+                stream.reader.read_to_end(&mut body)?;
+                Ok(Cow::Owned(body))
+            }
+            None => Ok(Cow::Borrowed(self.body.as_deref().unwrap_or_default())),
+        }
+    }
+}
+
+/// A body that is read from disk while the request is sent.
+pub struct BodyStream {
+    pub reader: Box<dyn Read + Send + Sync>,
+    /// The size of the body, if the size of every file is known.
+    pub length: Option<u64>,
+}
+
+impl Default for BodyStream {
+    fn default() -> Self {
+        Self {
+            reader: Box::new(std::io::empty()),
+            length: Some(0),
+        }
+    }
+}
+
+impl BodyStream {
+    /// Appends the contents of a reader to the end of the stream.
+    fn chain<R: Read + Send + Sync + 'static>(self, reader: R, length: Option<u64>) -> Self {
+        Self {
+            reader: Box::new(self.reader.chain(reader)),
+            length: self.length.zip(length).map(|(a, b)| a + b),
+        }
+    }
+
+    /// Appends some bytes to the end of the stream.
+    fn chain_bytes(self, bytes: Vec<u8>) -> Self {
+        let length = bytes.len() as u64;
+        self.chain(Cursor::new(bytes), Some(length))
+    }
+}
+
+/// The user name and password that answer the challenge of HTTP Digest authentication.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigestCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+/// The authentication of a request, with its variables already processed.
+enum BoundAuth {
+    None,
+    Header(String, String),
+    Query(String, String),
+    Digest(DigestCredentials),
+    OAuth2(OAuth2Settings),
+    Sign(RequestSigner),
+}
+
+/// The kind of body of a bound request, with the fields of the forms already bound.
+///
+/// The encoded body is still kept in `BoundRequest::body`, except for the bodies
+/// that are read from disk, see `BoundRequest::open_body`. This is used by the code
+/// exporters, so that every language can write the body in its own way.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub enum BoundPayload {
+    #[default]
+    None,
+    Urlencoded(Vec<(String, String)>),
+    Multipart {
+        fields: Vec<(String, String)>,
+        files: Vec<BoundFile>,
+        boundary: String,
+    },
+    Raw(RawEncoding),
+    /// A body read from the file at the given path.
+    File(PathBuf),
+}
+
+/// A file of a multipart form, with its variables and its defaults already resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundFile {
+    pub name: String,
+    pub path: PathBuf,
+    pub filename: String,
+    pub content_type: String,
 }
 
 #[derive(Default, Debug, Clone)]
 struct BoundBody {
     content: Vec<u8>,
     boundary: String,
+    payload: BoundPayload,
 }
 
 fn bind_urlencoded_payload(
@@ -59,19 +199,114 @@ fn bind_urlencoded_payload(
             Ok((key, value))
         })
         .collect::<Result<Vec<(String, String)>, CarteroError>>()?;
-    let body = serde_urlencoded::to_string(pairs).map_err(|_| RequestError::InvalidPayload)?;
+    let body = serde_urlencoded::to_string(&pairs).map_err(|_| RequestError::InvalidPayload)?;
     let content = Vec::from(body.as_str());
     Ok(Some(BoundBody {
         content,
         boundary: String::default(),
+        payload: BoundPayload::Urlencoded(pairs),
     }))
 }
 
+/// Guesses the Content-Type of a file from its extension.
+fn guess_content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "txt" => "text/plain",
+        "csv" => "text/csv",
+        "htm" | "html" => "text/html",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+fn bind_file(file: &FormFile, processor: &SrTemplate) -> Result<BoundFile, CarteroError> {
+    let name = processor.render(file.name.clone())?;
+    let path = processor.render(file.path.clone())?;
+    if path.is_empty() {
+        return Err(RequestError::MissingFile(name).into());
+    }
+    let path = PathBuf::from(path);
+    let filename = match &file.filename {
+        Some(filename) if !filename.is_empty() => processor.render(filename.clone())?,
+        _ => path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    };
+    let content_type = match &file.content_type {
+        Some(content_type) if !content_type.is_empty() => processor.render(content_type.clone())?,
+        _ => guess_content_type(&path).to_string(),
+    };
+    Ok(BoundFile {
+        name,
+        path,
+        filename,
+        content_type,
+    })
+}
+
+/// Quotes a name for the Content-Disposition header of a part, as browsers do.
+fn disposition_quote(value: &str) -> String {
+    value
+        .replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Builds the stream of a multipart form, which reads the files as they are reached.
+fn multipart_stream(
+    boundary: &str,
+    fields: &[(String, String)],
+    files: &[BoundFile],
+) -> Result<BodyStream, RequestError> {
+    let mut stream = BodyStream::default();
+    let mut text = Vec::new();
+    for (name, value) in fields {
+        write!(
+            text,
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n",
+            disposition_quote(name)
+        )?;
+        text.extend_from_slice(value.as_bytes());
+        text.extend_from_slice(b"\r\n");
+    }
+    for file in files {
+        let source = open_file(&file.path)?;
+        let length = source.metadata().map(|metadata| metadata.len()).ok();
+        write!(
+            text,
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+            disposition_quote(&file.name),
+            disposition_quote(&file.filename),
+            file.content_type
+        )?;
+        stream = stream
+            .chain_bytes(std::mem::take(&mut text))
+            .chain(source, length);
+        text.extend_from_slice(b"\r\n");
+    }
+    write!(text, "--{boundary}--\r\n")?;
+    Ok(stream.chain_bytes(text))
+}
+
 fn bind_multipart_payload(
     params: &KeyValueTable,
+    files: &[FormFile],
     processor: &SrTemplate,
 ) -> Result<Option<BoundBody>, CarteroError> {
-    if params.is_empty() {
+    if params.is_empty() && files.is_empty() {
         return Ok(None);
     }
     let pairs: Vec<(String, String)> = params
@@ -83,25 +318,29 @@ fn bind_multipart_payload(
             Ok((key, value))
         })
         .collect::<Result<Vec<(String, String)>, CarteroError>>()?;
-    let formdata = FormData {
-        fields: pairs,
-        files: vec![],
-    };
+    let files: Vec<BoundFile> = files
+        .iter()
+        .filter(|file| file.active)
+        .map(|file| bind_file(file, processor))
+        .collect::<Result<Vec<BoundFile>, CarteroError>>()?;
     let boundary = formdata::generate_boundary();
+    let boundary = String::from_utf8_lossy(&boundary).to_string();
 
-    let mut stream = BufWriter::new(Vec::new());
-    formdata::write_formdata(&mut stream, &boundary, &formdata)
-        .map_err(|_| RequestError::InvalidPayload)?;
-    stream.flush().map_err(RequestError::IOError)?;
-    let copy = stream.get_ref().clone();
+    // The files are not read here, but when the request is sent.
     Ok(Some(BoundBody {
-        content: copy,
-        boundary: String::from_utf8_lossy(&boundary).to_string(),
+        content: Vec::new(),
+        boundary: boundary.clone(),
+        payload: BoundPayload::Multipart {
+            fields: pairs,
+            files,
+            boundary,
+        },
     }))
 }
 
 fn bind_raw_payload(
     body: &[u8],
+    encoding: &RawEncoding,
     processor: &SrTemplate,
 ) -> Result<Option<BoundBody>, CarteroError> {
     if body.is_empty() {
@@ -113,9 +352,149 @@ fn bind_raw_payload(
     Ok(Some(BoundBody {
         content: body,
         boundary: String::default(),
+        payload: BoundPayload::Raw(encoding.clone()),
+    }))
+}
+
+/// Binds the body to a file. Only the path is processed, the contents are sent as is.
+///
+/// The file is only opened to check that it can be read, its contents are streamed
+/// when the request is sent.
+fn bind_file_payload(
+    path: &str,
+    processor: &SrTemplate,
+) -> Result<Option<BoundBody>, CarteroError> {
+    let path = processor.render(path)?;
+    if path.is_empty() {
+        return Err(RequestError::MissingBodyFile.into());
+    }
+    let path = PathBuf::from(path);
+    open_file(&path)?;
+    Ok(Some(BoundBody {
+        content: Vec::new(),
+        boundary: String::default(),
+        payload: BoundPayload::File(path),
     }))
 }
 
+fn open_file(path: &Path) -> Result<File, RequestError> {
+    File::open(path)
+        .map_err(|e| RequestError::UnreadableFile(path.to_string_lossy().to_string(), e))
+}
+
+/// Processes the variables of the TLS settings and checks that their files can be read.
+fn bind_tls(tls: &TlsSettings, processor: &SrTemplate) -> Result<TlsSettings, CarteroError> {
+    let tls = TlsSettings {
+        certificate: processor.render(&tls.certificate)?,
+        format: tls.format,
+        key: processor.render(&tls.key)?,
+        password: processor.render(&tls.password)?,
+        ca_bundle: processor.render(&tls.ca_bundle)?,
+    };
+    for path in [&tls.certificate, &tls.key, &tls.ca_bundle] {
+        if !path.is_empty() {
+            File::open(path).map_err(|e| RequestError::UnreadableFile(path.clone(), e))?;
+        }
+    }
+    Ok(tls)
+}
+
+/// Processes the variables of the proxy settings.
+fn bind_proxy(proxy: &RequestProxy, processor: &SrTemplate) -> Result<RequestProxy, CarteroError> {
+    let RequestProxy::Custom(proxy) = proxy else {
+        return Ok(proxy.clone());
+    };
+    Ok(RequestProxy::Custom(ProxySettings {
+        url: processor.render(&proxy.url)?,
+        username: processor.render(&proxy.username)?,
+        password: processor.render(&proxy.password)?,
+        no_proxy: processor.render(&proxy.no_proxy)?,
+    }))
+}
+
+/// Processes the variables of the authentication and encodes its credentials.
+fn bind_auth(auth: &RequestAuth, processor: &SrTemplate) -> Result<BoundAuth, CarteroError> {
+    let bound = match auth {
+        RequestAuth::None => BoundAuth::None,
+        RequestAuth::Basic { username, password } => {
+            let username = processor.render(username)?;
+            let password = processor.render(password)?;
+            let credentials = base64::encode(format!("{username}:{password}"));
+            BoundAuth::Header("Authorization".into(), format!("Basic {credentials}"))
+        }
+        RequestAuth::Bearer { token } => {
+            let token = processor.render(token)?;
+            BoundAuth::Header("Authorization".into(), format!("Bearer {token}"))
+        }
+        RequestAuth::ApiKey {
+            name,
+            value,
+            location,
+        } => {
+            let name = processor.render(name)?;
+            let value = processor.render(value)?;
+            match location {
+                ApiKeyLocation::Header => BoundAuth::Header(name, value),
+                ApiKeyLocation::Query => BoundAuth::Query(name, value),
+            }
+        }
+        RequestAuth::Digest { username, password } => BoundAuth::Digest(DigestCredentials {
+            username: processor.render(username)?,
+            password: processor.render(password)?,
+        }),
+        RequestAuth::OAuth2(settings) => BoundAuth::OAuth2(OAuth2Settings {
+            grant: settings.grant,
+            token_url: processor.render(&settings.token_url)?,
+            authorization_url: processor.render(&settings.authorization_url)?,
+            client_id: processor.render(&settings.client_id)?,
+            client_secret: processor.render(&settings.client_secret)?,
+            scope: processor.render(&settings.scope)?,
+            username: processor.render(&settings.username)?,
+            password: processor.render(&settings.password)?,
+        }),
+        RequestAuth::AwsSigV4(settings) => {
+            BoundAuth::Sign(RequestSigner::AwsSigV4(AwsSigV4Settings {
+                access_key: processor.render(&settings.access_key)?,
+                secret_key: processor.render(&settings.secret_key)?,
+                session_token: processor.render(&settings.session_token)?,
+                region: processor.render(&settings.region)?,
+                service: processor.render(&settings.service)?,
+            }))
+        }
+        RequestAuth::Hmac(settings) => BoundAuth::Sign(RequestSigner::Hmac(HmacSettings {
+            algorithm: settings.algorithm,
+            key: processor.render(&settings.key)?,
+            message: processor.render(&settings.message)?,
+            header: processor.render(&settings.header)?,
+            timestamp_header: processor.render(&settings.timestamp_header)?,
+            encoding: settings.encoding,
+        })),
+    };
+    Ok(bound)
+}
+
+/// Appends a parameter to the query string of a URL, before its fragment.
+fn append_query(url: &str, name: &str, value: &str) -> String {
+    let (url, fragment) = match url.split_once('#') {
+        Some((url, fragment)) => (url, Some(fragment)),
+        None => (url, None),
+    };
+    let pair = url::form_urlencoded::Serializer::new(String::new())
+        .append_pair(name, value)
+        .finish();
+    let separator = match url.split_once('?') {
+        Some((_, "")) => "",
+        Some(_) => "&",
+        None => "?",
+    };
+    let mut url = format!("{url}{separator}{pair}");
+    if let Some(fragment) = fragment {
+        url.push('#');
+        url.push_str(fragment);
+    }
+    url
+}
+
 fn bind_payload(
     body: &RequestPayload,
     processor: &SrTemplate,
@@ -123,11 +502,11 @@ fn bind_payload(
     match body {
         RequestPayload::None => Ok(None),
         RequestPayload::Urlencoded(payload) => bind_urlencoded_payload(payload, processor),
-        RequestPayload::Multipart { params } => bind_multipart_payload(params, processor),
-        RequestPayload::Raw {
-            content,
-            encoding: _,
-        } => bind_raw_payload(content, processor),
+        RequestPayload::Multipart { params, files } => {
+            bind_multipart_payload(params, files, processor)
+        }
+        RequestPayload::Raw { content, encoding } => bind_raw_payload(content, encoding, processor),
+        RequestPayload::File { path, .. } => bind_file_payload(path, processor),
     }
 }
 
@@ -139,12 +518,19 @@ impl TryFrom<EndpointData> for BoundRequest {
 
         let url = processor.render(&value.url)?;
         let method = value.method.clone();
+        let auth = bind_auth(&value.auth, &processor)?;
+        let url = match &auth {
+            BoundAuth::Query(name, value) => append_query(&url, name, value),
+            _ => url,
+        };
 
         let body = bind_payload(&value.body, &processor)?;
+        let tls = bind_tls(&value.tls, &processor)?;
+        let proxy = bind_proxy(&value.proxy, &processor)?;
         let content_type = match value.body {
             RequestPayload::None => None,
             RequestPayload::Urlencoded(_) => Some("application/x-www-form-urlencoded".to_string()),
-            RequestPayload::Multipart { params: _ } => Some(format!(
+            RequestPayload::Multipart { .. } => Some(format!(
                 "multipart/form-data; boundary={}",
                 body.clone().unwrap_or_default().boundary
             )),
@@ -156,29 +542,79 @@ impl TryFrom<EndpointData> for BoundRequest {
                 RawEncoding::Xml => Some("application/xml".into()),
                 RawEncoding::Json => Some("application/json".into()),
             },
+            RequestPayload::File {
+                ref content_type, ..
+            } => match (content_type, &body) {
+                (Some(content_type), _) if !content_type.is_empty() => Some(content_type.clone()),
+                (
+                    _,
+                    Some(BoundBody {
+                        payload: BoundPayload::File(path),
+                        ..
+                    }),
+                ) => Some(guess_content_type(path).to_string()),
+                _ => None,
+            },
         };
 
-        let mut base_headers = HashMap::new();
+        // Headers are kept in the same order they were defined in the endpoint, including
+        // any repeated header. The automatic Content-Type is only added when the user has
+        // not provided their own value for it.
+        let user_headers = value.process_headers();
+        let mut base_headers = KeyValueTable::default();
         if let Some(content_type) = content_type {
-            base_headers.insert("Content-Type".to_string(), content_type);
+            if user_headers.header("Content-Type").is_none() {
+                base_headers.push(KeyValue::from(("Content-Type".to_string(), content_type)));
+            }
         }
-        base_headers.extend(value.process_headers());
+        base_headers.extend(user_headers.iter().cloned());
 
-        let headers: Result<HashMap<String, String>, CarteroError> = base_headers
+        let mut headers = base_headers
             .iter()
-            .map(|(k, v)| {
-                let header_name = processor.render(k)?;
-                let header_value = processor.render(v)?;
-                Ok((header_name, header_value))
+            .map(|kv| {
+                let header_name = processor.render(&kv.name)?;
+                let header_value = processor.render(&kv.value)?;
+                Ok(KeyValue::from((header_name, header_value)))
             })
-            .collect();
-        let headers = headers?;
+            .collect::<Result<KeyValueTable, CarteroError>>()?;
+
+        // Like the Content-Type, the header of the authentication goes before the headers
+        // of the user, who can still replace it by setting the same header.
+        let (digest, oauth2, signer) = match auth {
+            BoundAuth::Header(name, value) => {
+                if headers.header(&name).is_none() {
+                    let position = base_headers.len() - user_headers.len();
+                    headers.insert(position, KeyValue::from((name, value)));
+                }
+                (None, None, None)
+            }
+            BoundAuth::Digest(credentials) => (Some(credentials), None, None),
+            BoundAuth::OAuth2(settings) => (None, Some(settings), None),
+            BoundAuth::Sign(signer) => (None, None, Some(signer)),
+            BoundAuth::None | BoundAuth::Query(..) => (None, None, None),
+        };
 
+        let (body, payload) = match body {
+            Some(BoundBody {
+                payload: payload @ (BoundPayload::File(_) | BoundPayload::Multipart { .. }),
+                ..
+            }) => (None, payload),
+            Some(body) => (Some(body.content), body.payload),
+            None => (None, BoundPayload::None),
+        };
         Ok(Self {
             url,
             method,
             headers,
-            body: body.map(|b| b.content),
+            body,
+            payload,
+            options: value.options.clone(),
+            tls,
+            proxy,
+            cookie_jar: None,
+            digest,
+            oauth2,
+            signer,
         })
     }
 }
@@ -208,10 +644,38 @@ pub enum RequestError {
 
     #[error("Unknown I/O error")]
     IOError(#[from] std::io::Error),
+
+    #[error("No file was chosen for the form field {0}")]
+    MissingFile(String),
+
+    #[error("No file was chosen for the request body")]
+    MissingBodyFile,
+
+    #[error("Cannot read the file {0}")]
+    UnreadableFile(String, #[source] std::io::Error),
+
+    #[error("Timed out after {0} s")]
+    Timeout(u64),
+
+    #[error("The request was cancelled")]
+    Cancelled,
+
+    #[error("Stopped after {0} redirects")]
+    TooManyRedirects(u32),
+
+    #[error("Invalid proxy URL \"{0}\"")]
+    InvalidProxy(String),
+
+    #[error("Cannot get an OAuth 2.0 token: {0}")]
+    OAuth2(String),
+
+    #[error("Cannot sign the request: {0}")]
+    Signing(String),
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::client::signing;
     use crate::entities::KeyValueTable;
 
     use super::*;
@@ -243,16 +707,20 @@ mod tests {
             headers,
             variables,
             body,
+            ..Default::default()
         };
 
         // Bind the request.
         let bound = BoundRequest::try_from(endpoint).unwrap();
 
         assert_eq!(bound.url, "https://api.example.com/v1/books");
-        assert_eq!(bound.headers["Authorization"], "Bearer 789078907890");
         assert_eq!(
-            bound.headers["Content-Type"],
-            "application/x-www-form-urlencoded"
+            bound.headers.header("Authorization"),
+            Some(vec!["Bearer 789078907890"])
+        );
+        assert_eq!(
+            bound.headers.header("Content-Type"),
+            Some(vec!["application/x-www-form-urlencoded"])
         );
         assert_eq!(bound.body, Some(Vec::from(b"name=John&surname=Smith")));
     }
@@ -276,6 +744,7 @@ mod tests {
         let variables = KeyValueTable::new(&variables);
         let body = RequestPayload::Multipart {
             params: KeyValueTable::new(&[("name", "John").into(), ("surname", "Smith").into()]),
+            files: vec![],
         };
         let endpoint = EndpointData {
             url,
@@ -283,17 +752,21 @@ mod tests {
             headers,
             variables,
             body,
+            ..Default::default()
         };
 
         // Bind the request.
         let bound = BoundRequest::try_from(endpoint).unwrap();
 
         assert_eq!(bound.url, "https://api.example.com/v1/books");
-        assert_eq!(bound.headers["Authorization"], "Bearer 789078907890");
+        assert_eq!(
+            bound.headers.header("Authorization"),
+            Some(vec!["Bearer 789078907890"])
+        );
 
-        let content_type = bound.headers["Content-Type"].clone();
+        let content_type = bound.headers.header("Content-Type").unwrap()[0];
         assert!(content_type.starts_with("multipart/form-data; boundary="));
-        let body = bound.body.unwrap();
+        let body = bound.read_body().unwrap();
         let body = String::from_utf8_lossy(&body);
         assert!(body.contains("name=\"name\""));
         assert!(body.contains("name=\"surname\""));
@@ -301,6 +774,318 @@ mod tests {
         assert!(body.contains("Smith"));
     }
 
+    #[test]
+    fn test_bind_of_multipart_files() {
+        let path = std::env::temp_dir().join(format!("cartero-bind-{}.png", std::process::id()));
+        std::fs::write(&path, b"\x89PNG contents").unwrap();
+
+        let mut renamed = FormFile::new("cv", &path.to_string_lossy());
+        renamed.filename = Some("{{NAME}}.pdf".into());
+        renamed.content_type = Some("application/pdf".into());
+        let mut inactive = FormFile::new("ignored", "/does/not/exist");
+        inactive.active = false;
+        let endpoint = EndpointData {
+            url: "https://api.example.com/upload".into(),
+            method: RequestMethod::Post,
+            variables: KeyValueTable::new(&[("NAME", "resume").into()]),
+            body: RequestPayload::Multipart {
+                params: KeyValueTable::new(&[("title", "Hello").into()]),
+                files: vec![
+                    FormFile::new("avatar", &path.to_string_lossy()),
+                    renamed,
+                    inactive,
+                ],
+            },
+            ..Default::default()
+        };
+
+        let bound = BoundRequest::try_from(endpoint).unwrap();
+        assert_eq!(bound.body, None);
+        let body = String::from_utf8_lossy(&bound.read_body().unwrap()).to_string();
+        std::fs::remove_file(&path).unwrap();
+
+        let file_name = path.file_name().unwrap().to_string_lossy();
+        assert!(body.contains(&format!(
+            "name=\"avatar\"; filename=\"{file_name}\"\r\nContent-Type: image/png\r\n\r\n\u{FFFD}PNG contents\r\n"
+        )));
+        assert!(body
+            .contains("name=\"cv\"; filename=\"resume.pdf\"\r\nContent-Type: application/pdf\r\n"));
+        assert!(!body.contains("ignored"));
+        assert!(body.find("name=\"title\"").unwrap() < body.find("name=\"avatar\"").unwrap());
+
+        let BoundPayload::Multipart { fields, files, .. } = bound.payload else {
+            panic!("the payload is not multipart");
+        };
+        assert_eq!(fields, vec![("title".to_string(), "Hello".to_string())]);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[1].filename, "resume.pdf");
+    }
+
+    #[test]
+    fn test_bind_of_missing_multipart_file() {
+        let endpoint = EndpointData {
+            body: RequestPayload::Multipart {
+                params: KeyValueTable::default(),
+                files: vec![FormFile::new("avatar", "/does/not/exist.png")],
+            },
+            ..Default::default()
+        };
+        // The files are only read when the request is sent.
+        let bound = BoundRequest::try_from(endpoint).unwrap();
+        assert!(matches!(
+            bound.open_body(),
+            Err(RequestError::UnreadableFile(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_bind_of_tls_settings() {
+        let path = std::env::temp_dir().join(format!("cartero-cert-{}.pem", std::process::id()));
+        std::fs::write(&path, "-----BEGIN CERTIFICATE-----\n").unwrap();
+        let dir = path.parent().unwrap().to_string_lossy().to_string();
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+
+        let endpoint = EndpointData {
+            variables: KeyValueTable::new(&[
+                ("CERTS", dir.as_str()).into(),
+                ("PASSWORD", "s3cr3t").into(),
+            ]),
+            tls: TlsSettings {
+                certificate: format!("{{{{CERTS}}}}/{name}"),
+                password: "{{PASSWORD}}".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let bound = BoundRequest::try_from(endpoint).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(bound.tls.certificate, path.to_string_lossy());
+        assert_eq!(bound.tls.password, "s3cr3t");
+    }
+
+    #[test]
+    fn test_bind_of_missing_certificate() {
+        let endpoint = EndpointData {
+            tls: TlsSettings {
+                ca_bundle: "/does/not/exist.pem".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let error = BoundRequest::try_from(endpoint).unwrap_err();
+        assert!(matches!(
+            error,
+            CarteroError::Request(RequestError::UnreadableFile(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_bind_of_basic_auth() {
+        let endpoint = EndpointData {
+            url: "https://www.example.com/".into(),
+            headers: KeyValueTable::new(&[("Accept", "application/json").into()]),
+            body: RequestPayload::Raw {
+                content: b"{}".to_vec(),
+                encoding: RawEncoding::Json,
+            },
+            variables: KeyValueTable::new(&[("PASSWORD", "secret").into()]),
+            auth: RequestAuth::Basic {
+                username: "john".into(),
+                password: "{{PASSWORD}}".into(),
+            },
+            ..Default::default()
+        };
+        let bound = BoundRequest::try_from(endpoint).unwrap();
+        assert_eq!(
+            bound.headers,
+            KeyValueTable::new(&[
+                ("Content-Type", "application/json").into(),
+                ("Authorization", "Basic am9objpzZWNyZXQ=").into(),
+                ("Accept", "application/json").into(),
+            ])
+        );
+        assert_eq!(bound.digest, None);
+    }
+
+    #[test]
+    fn test_bind_of_auth_does_not_replace_user_header() {
+        let endpoint = EndpointData {
+            headers: KeyValueTable::new(&[("authorization", "Bearer from-header").into()]),
+            auth: RequestAuth::Bearer {
+                token: "from-auth".into(),
+            },
+            ..Default::default()
+        };
+        let bound = BoundRequest::try_from(endpoint).unwrap();
+        assert_eq!(
+            bound.headers,
+            KeyValueTable::new(&[("authorization", "Bearer from-header").into()])
+        );
+    }
+
+    #[test]
+    fn test_bind_of_api_key() {
+        let endpoint = EndpointData {
+            url: "https://www.example.com/v1?page=2#top".into(),
+            variables: KeyValueTable::new(&[("KEY", "a b&c").into()]),
+            auth: RequestAuth::ApiKey {
+                name: "api_key".into(),
+                value: "{{KEY}}".into(),
+                location: ApiKeyLocation::Query,
+            },
+            ..Default::default()
+        };
+        let bound = BoundRequest::try_from(endpoint.clone()).unwrap();
+        assert_eq!(
+            bound.url,
+            "https://www.example.com/v1?page=2&api_key=a+b%26c#top"
+        );
+        assert!(bound.headers.is_empty());
+
+        let endpoint = EndpointData {
+            url: "https://www.example.com/v1".into(),
+            auth: RequestAuth::ApiKey {
+                name: "X-API-Key".into(),
+                value: "{{KEY}}".into(),
+                location: ApiKeyLocation::Header,
+            },
+            ..endpoint
+        };
+        let bound = BoundRequest::try_from(endpoint).unwrap();
+        assert_eq!(bound.url, "https://www.example.com/v1");
+        assert_eq!(
+            bound.headers,
+            KeyValueTable::new(&[("X-API-Key", "a b&c").into()])
+        );
+    }
+
+    #[test]
+    fn test_bind_of_digest_auth() {
+        let endpoint = EndpointData {
+            variables: KeyValueTable::new(&[("USER", "john").into()]),
+            auth: RequestAuth::Digest {
+                username: "{{USER}}".into(),
+                password: "secret".into(),
+            },
+            ..Default::default()
+        };
+        let bound = BoundRequest::try_from(endpoint).unwrap();
+        assert!(bound.headers.is_empty());
+        assert_eq!(
+            bound.digest,
+            Some(DigestCredentials {
+                username: "john".into(),
+                password: "secret".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_bind_of_oauth2() {
+        let endpoint = EndpointData {
+            variables: KeyValueTable::new(&[("IDP", "https://login.example.com").into()]),
+            auth: RequestAuth::OAuth2(OAuth2Settings {
+                token_url: "{{IDP}}/token".into(),
+                client_id: "cartero".into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let bound = BoundRequest::try_from(endpoint).unwrap();
+        assert!(bound.headers.is_empty());
+        let settings = bound.oauth2.unwrap();
+        assert_eq!(settings.token_url, "https://login.example.com/token");
+        assert_eq!(settings.client_id, "cartero");
+    }
+
+    #[test]
+    fn test_bind_of_hmac_keeps_the_signer() {
+        let endpoint = EndpointData {
+            url: "https://api.example.com/orders".into(),
+            method: RequestMethod::Post,
+            variables: KeyValueTable::new(&[("SECRET", "key").into(), ("ID", "42").into()]),
+            body: RequestPayload::Raw {
+                content: "{\"id\": {{ID}}}".into(),
+                encoding: RawEncoding::Json,
+            },
+            auth: RequestAuth::Hmac(HmacSettings {
+                key: "{{SECRET}}".into(),
+                message: "{body}".into(),
+                header: "X-Signature".into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mut bound = BoundRequest::try_from(endpoint).unwrap();
+        assert_eq!(bound.headers.header("X-Signature"), None);
+
+        // The signature covers the body once its variables are processed.
+        let signer = bound.signer.clone().unwrap();
+        signing::sign(&mut bound, &signer, 0).unwrap();
+        assert_eq!(
+            bound.headers.header("X-Signature"),
+            Some(vec![
+                "24e584546e5482c369ed90c9bcef65791f52eba6e9bde1a6eab581497686e16e"
+            ])
+        );
+    }
+
+    #[test]
+    fn test_bind_of_body_file() {
+        let path = std::env::temp_dir().join(format!("cartero-body-{}.bin", std::process::id()));
+        let content = b"\x00\xff{{NAME}}\r\n\x89".to_vec();
+        std::fs::write(&path, &content).unwrap();
+
+        let endpoint = EndpointData {
+            url: "https://api.example.com/blobs".into(),
+            method: RequestMethod::Put,
+            variables: KeyValueTable::new(&[("NAME", "john").into()]),
+            body: RequestPayload::File {
+                path: path.to_string_lossy().to_string(),
+                content_type: None,
+            },
+            ..Default::default()
+        };
+        let bound = BoundRequest::try_from(endpoint.clone()).unwrap();
+        assert_eq!(bound.body, None);
+        assert_eq!(bound.payload, BoundPayload::File(path.clone()));
+        assert_eq!(bound.read_body().unwrap().as_ref(), content.as_slice());
+        assert_eq!(
+            bound.headers,
+            KeyValueTable::new(&[("Content-Type", "application/octet-stream").into()])
+        );
+
+        let endpoint = EndpointData {
+            body: RequestPayload::File {
+                path: path.to_string_lossy().to_string(),
+                content_type: Some("application/x-protobuf".into()),
+            },
+            ..endpoint
+        };
+        let bound = BoundRequest::try_from(endpoint).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            bound.headers,
+            KeyValueTable::new(&[("Content-Type", "application/x-protobuf").into()])
+        );
+    }
+
+    #[test]
+    fn test_bind_of_missing_body_file() {
+        let endpoint = EndpointData {
+            body: RequestPayload::File {
+                path: String::new(),
+                content_type: None,
+            },
+            ..Default::default()
+        };
+        let error = BoundRequest::try_from(endpoint).unwrap_err();
+        assert!(matches!(
+            error,
+            CarteroError::Request(RequestError::MissingBodyFile)
+        ));
+    }
+
     #[test]
     pub fn test_bind_of_parameters_json() {
         // Build a request.
@@ -328,14 +1113,21 @@ mod tests {
             headers,
             variables,
             body,
+            ..Default::default()
         };
 
         // Bind the request.
         let bound = BoundRequest::try_from(endpoint).unwrap();
 
         assert_eq!(bound.url, "https://api.example.com/v1/books");
-        assert_eq!(bound.headers["Authorization"], "Bearer 789078907890");
-        assert_eq!(bound.headers["Content-Type"], "application/json");
+        assert_eq!(
+            bound.headers.header("Authorization"),
+            Some(vec!["Bearer 789078907890"])
+        );
+        assert_eq!(
+            bound.headers.header("Content-Type"),
+            Some(vec!["application/json"])
+        );
 
         let body = bound.body.unwrap();
         let body = String::from_utf8_lossy(&body);
@@ -369,14 +1161,21 @@ mod tests {
             headers,
             variables,
             body,
+            ..Default::default()
         };
 
         // Bind the request.
         let bound = BoundRequest::try_from(endpoint).unwrap();
 
         assert_eq!(bound.url, "https://api.example.com/v1/books");
-        assert_eq!(bound.headers["Authorization"], "Bearer 789078907890");
-        assert_eq!(bound.headers["Content-Type"], "application/xml");
+        assert_eq!(
+            bound.headers.header("Authorization"),
+            Some(vec!["Bearer 789078907890"])
+        );
+        assert_eq!(
+            bound.headers.header("Content-Type"),
+            Some(vec!["application/xml"])
+        );
 
         let body = bound.body.unwrap();
         let body = String::from_utf8_lossy(&body);
@@ -410,14 +1209,21 @@ mod tests {
             headers,
             variables,
             body,
+            ..Default::default()
         };
 
         // Bind the request.
         let bound = BoundRequest::try_from(endpoint).unwrap();
 
         assert_eq!(bound.url, "https://api.example.com/v1/books");
-        assert_eq!(bound.headers["Authorization"], "Bearer 789078907890");
-        assert_eq!(bound.headers["Content-Type"], "application/octet-stream");
+        assert_eq!(
+            bound.headers.header("Authorization"),
+            Some(vec!["Bearer 789078907890"])
+        );
+        assert_eq!(
+            bound.headers.header("Content-Type"),
+            Some(vec!["application/octet-stream"])
+        );
 
         let body = bound.body.unwrap();
         let body = String::from_utf8_lossy(&body);
@@ -452,14 +1258,21 @@ mod tests {
             headers,
             variables,
             body,
+            ..Default::default()
         };
 
         // Bind the request.
         let bound = BoundRequest::try_from(endpoint).unwrap();
 
         assert_eq!(bound.url, "https://api.example.com/v1/books");
-        assert_eq!(bound.headers["Authorization"], "Bearer 789078907890");
-        assert_eq!(bound.headers["Content-Type"], "application/ld+json");
+        assert_eq!(
+            bound.headers.header("Authorization"),
+            Some(vec!["Bearer 789078907890"])
+        );
+        assert_eq!(
+            bound.headers.header("Content-Type"),
+            Some(vec!["application/ld+json"])
+        );
 
         let body = bound.body.unwrap();
         let body = String::from_utf8_lossy(&body);
@@ -482,10 +1295,84 @@ mod tests {
             headers,
             variables,
             body,
+            ..Default::default()
+        };
+
+        let bound = BoundRequest::try_from(endpoint).unwrap();
+        assert_eq!(
+            bound.headers,
+            KeyValueTable::new(&[
+                ("Accept", "application/html").into(),
+                ("Accept", "application/xml").into(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_bind_keeps_header_order() {
+        let url = "https://www.example.com/v1/books".into();
+        let method = RequestMethod::Post;
+        let headers = KeyValueTable::new(&[
+            ("Cookie", "session={{SESSION}}").into(),
+            ("Accept", "application/json").into(),
+            KeyValue {
+                name: "Accept".into(),
+                value: "text/plain".into(),
+                active: false,
+                secret: false,
+            },
+            ("Cookie", "theme=dark").into(),
+        ]);
+        let variables = KeyValueTable::new(&[("SESSION", "1234").into()]);
+        let body = RequestPayload::Raw {
+            encoding: RawEncoding::Json,
+            content: Vec::from(b"{}"),
+        };
+        let endpoint = EndpointData {
+            url,
+            method,
+            headers,
+            variables,
+            body,
+            ..Default::default()
+        };
+
+        let bound = BoundRequest::try_from(endpoint).unwrap();
+        assert_eq!(
+            bound.headers,
+            KeyValueTable::new(&[
+                ("Content-Type", "application/json").into(),
+                ("Cookie", "session=1234").into(),
+                ("Accept", "application/json").into(),
+                ("Cookie", "theme=dark").into(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_bind_content_type_override_is_case_insensitive() {
+        let url = "https://www.example.com/v1/books".into();
+        let method = RequestMethod::Post;
+        let headers = KeyValueTable::new(&[("content-type", "application/ld+json").into()]);
+        let variables = KeyValueTable::default();
+        let body = RequestPayload::Raw {
+            encoding: RawEncoding::Json,
+            content: Vec::from(b"{}"),
+        };
+        let endpoint = EndpointData {
+            url,
+            method,
+            headers,
+            variables,
+            body,
+            ..Default::default()
         };
 
         let bound = BoundRequest::try_from(endpoint).unwrap();
-        assert_eq!(bound.headers["Accept"], "application/xml")
+        assert_eq!(
+            bound.headers,
+            KeyValueTable::new(&[("content-type", "application/ld+json").into()])
+        );
     }
 
     #[test]
@@ -504,10 +1391,14 @@ mod tests {
             headers,
             variables,
             body,
+            ..Default::default()
         };
 
         let bound = BoundRequest::try_from(endpoint).unwrap();
-        assert_eq!(bound.headers["Accept"], "application/json")
+        assert_eq!(
+            bound.headers.header("Accept"),
+            Some(vec!["application/json"])
+        );
     }
 
     #[test]
@@ -534,6 +1425,7 @@ mod tests {
             headers,
             variables,
             body,
+            ..Default::default()
         };
 
         // Bind the request.
@@ -15,8 +15,30 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+mod assertions;
+mod captures;
+mod cookies;
 mod isahc_conv;
 mod local;
+mod oauth2;
+mod report;
+mod runner;
+mod scripting;
+mod signing;
 
-pub use isahc_conv::extract_isahc_response;
+pub use assertions::{
+    check_assertion, describe_assertion, run_assertions, AssertionError, AssertionResult,
+    StatusRange,
+};
+pub use captures::{capture_value, run_captures, store_captures, CaptureError, CapturedValue};
+pub use cookies::{civil_from_days, days_from_civil, unix_now, Cookie, CookieJar, SharedCookieJar};
+pub use isahc_conv::{extract_isahc_response, send_request};
 pub use local::*;
+pub use oauth2::{access_token, authorize, SharedTokenCache, Token, TokenCache};
+pub use report::{assertions_to_json, json_report, junit_report};
+pub use runner::{collection_items, RequestRun, RunItem, RunSettings, Runner};
+pub use scripting::{
+    run_post_response, run_pre_request, ConsoleLevel, ConsoleLine, ScriptError, ScriptRun,
+    ScriptStage,
+};
+pub use signing::{uri_encode, RequestSigner};
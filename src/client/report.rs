@@ -0,0 +1,307 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+//! Writes the results of a collection run as reports that CI servers can read.
+
+use serde_json::{json, Value};
+
+use crate::client::{AssertionResult, ConsoleLevel, RequestRun};
+
+/// Gives the results of the assertions of a request as a JSON array.
+pub fn assertions_to_json(results: &[AssertionResult]) -> Value {
+    let results: Vec<Value> = results
+        .iter()
+        .map(|result| {
+            let mut document = json!({
+                "assertion": result.description(),
+                "passed": result.passed(),
+            });
+            if let Err(e) = &result.outcome {
+                document["message"] = json!(e.to_string());
+            }
+            document
+        })
+        .collect();
+    json!(results)
+}
+
+/// Gives the results of a run as a JSON document.
+pub fn json_report(name: &str, runs: &[RequestRun]) -> Value {
+    let passed = runs.iter().filter(|run| run.passed()).count();
+    let results: Vec<Value> = runs
+        .iter()
+        .map(|run| {
+            let mut document = json!({
+                "name": run.name,
+                "file": run.path.display().to_string(),
+                "iteration": run.iteration,
+                "passed": run.passed(),
+                "duration": run.duration() as u64,
+            });
+            match &run.outcome {
+                Ok(response) => {
+                    document["status"] = json!(response.status_code);
+                    document["size"] = json!(response.size);
+                }
+                Err(e) => document["error"] = json!(e.details()),
+            }
+            if !run.errors.is_empty() {
+                let errors: Vec<String> = run.errors.iter().map(|e| e.details()).collect();
+                document["errors"] = json!(errors);
+            }
+            if !run.assertions.is_empty() {
+                document["assertions"] = assertions_to_json(&run.assertions);
+            }
+            if !run.console.is_empty() {
+                let console: Vec<String> = run
+                    .console
+                    .iter()
+                    .map(|line| format!("{}: {}", line.stage, line.text))
+                    .collect();
+                document["console"] = json!(console);
+            }
+            document
+        })
+        .collect();
+    json!({
+        "name": name,
+        "iterations": runs.iter().map(|run| run.iteration).max().unwrap_or_default(),
+        "tests": runs.len(),
+        "passed": passed,
+        "failed": runs.len() - passed,
+        "duration": runs.iter().map(|run| run.duration()).sum::<u128>() as u64,
+        "results": results,
+    })
+}
+
+/// Gives the results of a run as a JUnit XML document.
+///
+/// Every iteration is a test suite, and every request is a test case. A request
+/// that could not be sent, or whose captures or scripts failed, is an error. A
+/// request whose assertions failed is a failure.
+pub fn junit_report(name: &str, runs: &[RequestRun]) -> String {
+    let iterations = runs
+        .iter()
+        .map(|run| run.iteration)
+        .max()
+        .unwrap_or_default();
+    let mut suites = String::new();
+    for iteration in 1..=iterations {
+        let runs: Vec<&RequestRun> = runs.iter().filter(|r| r.iteration == iteration).collect();
+        let suite = match iterations {
+            1 => name.to_string(),
+            _ => format!("{name} #{iteration}"),
+        };
+        suites.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{}\">\n",
+            escape_xml(&suite),
+            runs.len(),
+            runs.iter().filter(|run| is_failure(run)).count(),
+            runs.iter().filter(|run| is_error(run)).count(),
+            seconds(runs.iter().map(|run| run.duration()).sum()),
+        ));
+        for run in runs {
+            suites.push_str(&test_case(&suite, run));
+        }
+        suites.push_str("  </testsuite>\n");
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <testsuites name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{}\">\n\
+         {suites}</testsuites>\n",
+        escape_xml(name),
+        runs.len(),
+        runs.iter().filter(|run| is_failure(run)).count(),
+        runs.iter().filter(|run| is_error(run)).count(),
+        seconds(runs.iter().map(|run| run.duration()).sum()),
+    )
+}
+
+fn is_error(run: &RequestRun) -> bool {
+    run.outcome.is_err() || !run.errors.is_empty()
+}
+
+fn is_failure(run: &RequestRun) -> bool {
+    !is_error(run) && !run.passed()
+}
+
+fn test_case(suite: &str, run: &RequestRun) -> String {
+    let mut case = format!(
+        "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\"",
+        escape_xml(&run.name),
+        escape_xml(suite),
+        seconds(run.duration()),
+    );
+    let failures = run.failures();
+    let output: Vec<String> = run
+        .console
+        .iter()
+        .filter(|line| line.level != ConsoleLevel::Error)
+        .map(|line| format!("{}: {}", line.stage, line.text))
+        .collect();
+    if failures.is_empty() && output.is_empty() {
+        case.push_str("/>\n");
+        return case;
+    }
+    case.push_str(">\n");
+    if let Some(message) = failures.first() {
+        let kind = if is_error(run) { "error" } else { "failure" };
+        case.push_str(&format!(
+            "      <{kind} message=\"{}\">{}</{kind}>\n",
+            escape_xml(message),
+            escape_xml(&failures.join("\n")),
+        ));
+    }
+    if !output.is_empty() {
+        case.push_str(&format!(
+            "      <system-out>{}</system-out>\n",
+            escape_xml(&output.join("\n"))
+        ));
+    }
+    case.push_str("    </testcase>\n");
+    case
+}
+
+fn seconds(milliseconds: u128) -> String {
+    format!("{:.3}", milliseconds as f64 / 1000.0)
+}
+
+fn escape_xml(text: &str) -> String {
+    text.chars()
+        .filter(|c| matches!(c, '\t' | '\n' | '\r') || !c.is_control())
+        .fold(String::new(), |mut escaped, c| {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&apos;"),
+                c => escaped.push(c),
+            }
+            escaped
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{json_report, junit_report};
+    use crate::client::{
+        AssertionError, AssertionResult, ConsoleLevel, ConsoleLine, RequestRun, ScriptError,
+        ScriptStage,
+    };
+    use crate::entities::{Assertion, AssertionKind, ResponseData};
+    use crate::error::CarteroError;
+
+    fn runs() -> Vec<RequestRun> {
+        let response = ResponseData {
+            status_code: 401,
+            duration: 250,
+            ..Default::default()
+        };
+        let status = Assertion {
+            kind: AssertionKind::StatusEquals,
+            expected: "200".into(),
+            ..Default::default()
+        };
+        vec![
+            RequestRun {
+                name: "login.cartero".into(),
+                path: PathBuf::from("/books/login.cartero"),
+                iteration: 1,
+                outcome: Ok(ResponseData {
+                    status_code: 200,
+                    duration: 120,
+                    ..Default::default()
+                }),
+                errors: Vec::new(),
+                assertions: Vec::new(),
+                console: vec![ConsoleLine {
+                    stage: ScriptStage::PostResponse,
+                    level: ConsoleLevel::Output,
+                    text: "token <abc>".into(),
+                }],
+            },
+            RequestRun {
+                name: "books & authors.cartero".into(),
+                path: PathBuf::from("/books/books & authors.cartero"),
+                iteration: 1,
+                outcome: Ok(response),
+                errors: Vec::new(),
+                assertions: vec![AssertionResult {
+                    assertion: status,
+                    outcome: Err(AssertionError::Mismatch {
+                        expected: "200".into(),
+                        actual: "401".into(),
+                    }),
+                }],
+                console: Vec::new(),
+            },
+            RequestRun {
+                name: "login.cartero".into(),
+                path: PathBuf::from("/books/login.cartero"),
+                iteration: 2,
+                outcome: Err(CarteroError::Script(
+                    ScriptStage::PreRequest,
+                    ScriptError("stop".into()),
+                )),
+                errors: Vec::new(),
+                assertions: Vec::new(),
+                console: Vec::new(),
+            },
+        ]
+    }
+
+    #[test]
+    pub fn test_junit_report() {
+        let report = junit_report("books", &runs());
+        assert!(report.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"books\" tests=\"3\" failures=\"1\" errors=\"1\" time=\"0.370\">\n"));
+        assert!(report.contains("  <testsuite name=\"books #1\" tests=\"2\" failures=\"1\" errors=\"0\" time=\"0.370\">\n"));
+        assert!(
+            report.contains("      <system-out>post-response: token &lt;abc&gt;</system-out>\n")
+        );
+        assert!(report.contains("    <testcase name=\"books &amp; authors.cartero\" classname=\"books #1\" time=\"0.250\">\n      <failure message=\"Assertion failed, status is 200: expected 200, got 401\">"));
+        assert!(report.contains("      <error message=\"The pre-request script failed: stop\">"));
+        assert!(report.ends_with("  </testsuite>\n</testsuites>\n"));
+        assert!(roxmltree::Document::parse(&report).is_ok());
+    }
+
+    #[test]
+    pub fn test_json_report() {
+        let report = json_report("books", &runs());
+        assert_eq!(report["iterations"], 2);
+        assert_eq!(report["tests"], 3);
+        assert_eq!(report["passed"], 1);
+        assert_eq!(report["failed"], 2);
+        assert_eq!(report["duration"], 370);
+        assert_eq!(report["results"][0]["status"], 200);
+        assert_eq!(
+            report["results"][0]["console"][0],
+            "post-response: token <abc>"
+        );
+        assert_eq!(report["results"][1]["assertions"][0]["passed"], false);
+        assert_eq!(
+            report["results"][1]["assertions"][0]["message"],
+            "expected 200, got 401"
+        );
+        assert_eq!(report["results"][2]["iteration"], 2);
+        assert_eq!(
+            report["results"][2]["error"],
+            "The pre-request script failed: stop"
+        );
+    }
+}
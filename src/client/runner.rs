@@ -0,0 +1,483 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+//! Runs the request files of a collection one after the other, as a test suite.
+//!
+//! Every request goes through the same steps as when it is sent from its tab: the
+//! pre-request script, the request, the assertions, the captures and the
+//! post-response script. The values captured by a request, or set by its scripts,
+//! can be used by the requests that come after it. The whole run can be repeated
+//! once per row of a data file, whose values are given as variables.
+//!
+//! The files are read as they are on disk, and they are never modified.
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::client::{
+    authorize, run_assertions, run_captures, run_post_response, run_pre_request, send_request,
+    store_captures, AssertionResult, BoundRequest, ConsoleLine, CookieJar, ScriptStage,
+    SharedCookieJar, SharedTokenCache, TokenCache,
+};
+use crate::entities::{
+    CollectionData, Environment, KeyValue, KeyValueTable, ProxySettings, ResponseData,
+};
+use crate::error::CarteroError;
+use crate::file::{parse_collection_toml, COLLECTION_FILE, REQUEST_SUFFIX};
+
+/// A request file picked for a run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunItem {
+    pub path: PathBuf,
+    /// The name shown in the results, such as `auth/login.cartero`.
+    pub name: String,
+    /// The settings of the collection that holds the file, if it is part of one.
+    pub collection: Option<CollectionData>,
+}
+
+impl RunItem {
+    /// Picks a request file that is not part of a collection.
+    pub fn file(path: &Path) -> RunItem {
+        RunItem {
+            path: path.to_path_buf(),
+            name: path.display().to_string(),
+            collection: None,
+        }
+    }
+}
+
+/// Lists the request files of the collection stored in a folder, in the order that
+/// they should run.
+///
+/// The order is the one set in the `[run]` table of its `collection.toml` file. If
+/// there is none, every request file is run in the order of the sidebar: the files
+/// of a folder after the ones of its subfolders, sorted by name.
+///
+/// The files of the `[run]` table must be inside the folder, so an absolute path or
+/// a path that goes up with `..` is refused.
+pub fn collection_items(dir: &Path) -> Result<Vec<RunItem>, CarteroError> {
+    let settings = dir.join(COLLECTION_FILE);
+    let collection = if settings.exists() {
+        let mut collection = parse_collection_toml(&std::fs::read_to_string(settings)?)?;
+        collection.tls = collection.tls.with_base_dir(dir);
+        collection
+    } else {
+        CollectionData::default()
+    };
+
+    let names = if collection.run_order.is_empty() {
+        let mut names = Vec::new();
+        list_request_files(dir, "", &mut names)?;
+        names
+    } else {
+        let outside = collection.run_order.iter().find(|name| {
+            !Path::new(name)
+                .components()
+                .all(|part| matches!(part, Component::Normal(_) | Component::CurDir))
+        });
+        if let Some(name) = outside {
+            return Err(CarteroError::InvalidRunOrder(name.clone()));
+        }
+        collection.run_order.clone()
+    };
+    let items = names
+        .into_iter()
+        .map(|name| RunItem {
+            path: dir.join(&name),
+            name,
+            collection: Some(collection.clone()),
+        })
+        .collect();
+    Ok(items)
+}
+
+fn list_request_files(dir: &Path, prefix: &str, names: &mut Vec<String>) -> std::io::Result<()> {
+    let mut entries = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let is_dir = entry.file_type()?.is_dir();
+        if !name.starts_with('.') && (is_dir || name.ends_with(REQUEST_SUFFIX)) {
+            entries.push((is_dir, name));
+        }
+    }
+    entries.sort_by(|(a_dir, a), (b_dir, b)| {
+        b_dir
+            .cmp(a_dir)
+            .then_with(|| a.to_lowercase().cmp(&b.to_lowercase()))
+    });
+    for (is_dir, name) in entries {
+        let relative = format!("{prefix}{name}");
+        if is_dir {
+            list_request_files(&dir.join(&name), &format!("{relative}/"), names)?;
+        } else {
+            names.push(relative);
+        }
+    }
+    Ok(())
+}
+
+/// The settings that apply to every request of a run.
+#[derive(Debug, Clone, Default)]
+pub struct RunSettings {
+    /// Stop the run at the first request that fails.
+    pub stop_on_failure: bool,
+    /// The timeout of the requests that do not set their own, in seconds.
+    pub timeout: Option<u64>,
+    /// The proxy of the requests that do not pick their own.
+    pub proxy: ProxySettings,
+    /// Variables that override the ones of every request.
+    pub variables: Vec<KeyValue>,
+}
+
+/// What came out of running a request file.
+#[derive(Debug)]
+pub struct RequestRun {
+    pub name: String,
+    pub path: PathBuf,
+    /// The iteration of the run, starting at 1.
+    pub iteration: usize,
+    /// The response, or the reason why the file could not be loaded or sent.
+    pub outcome: Result<ResponseData, CarteroError>,
+    /// The errors of the captures that could not take their value, and of the
+    /// post-response script.
+    pub errors: Vec<CarteroError>,
+    pub assertions: Vec<AssertionResult>,
+    /// What the scripts of the request printed.
+    pub console: Vec<ConsoleLine>,
+}
+
+impl RequestRun {
+    /// Whether the request was sent, and nothing went wrong with its response.
+    pub fn passed(&self) -> bool {
+        self.outcome.is_ok() && self.errors.is_empty() && self.assertions.iter().all(|a| a.passed())
+    }
+
+    pub fn response(&self) -> Option<&ResponseData> {
+        self.outcome.as_ref().ok()
+    }
+
+    /// How long the request took, in milliseconds.
+    pub fn duration(&self) -> u128 {
+        self.response().map(|r| r.duration).unwrap_or_default()
+    }
+
+    /// The messages of everything that failed: the error that prevented the request
+    /// from being sent, the other errors, and the assertions that failed.
+    pub fn failures(&self) -> Vec<String> {
+        let error = self.outcome.as_ref().err();
+        let assertions = self.assertions.iter().filter_map(|result| {
+            let e = result.outcome.as_ref().err()?;
+            Some(CarteroError::Assertion(result.description(), e.clone()).details())
+        });
+        error
+            .into_iter()
+            .chain(self.errors.iter())
+            .map(CarteroError::details)
+            .chain(assertions)
+            .collect()
+    }
+}
+
+/// What is learned about a request besides its response.
+#[derive(Default)]
+struct Checks {
+    errors: Vec<CarteroError>,
+    assertions: Vec<AssertionResult>,
+    console: Vec<ConsoleLine>,
+}
+
+/// Sends request files in order, sharing what they capture with the ones that come after.
+pub struct Runner {
+    pub settings: RunSettings,
+    pub environment: Option<Environment>,
+    pub cookie_jar: SharedCookieJar,
+    pub tokens: SharedTokenCache,
+    /// The variables captured in the session, or in the environment when there is none.
+    pub variables: KeyValueTable,
+    /// The variables of every request file, with the values captured in the request
+    /// scope. They are used if the same file is run again.
+    requests: HashMap<PathBuf, KeyValueTable>,
+}
+
+impl Runner {
+    pub fn new(settings: RunSettings) -> Self {
+        Self {
+            settings,
+            environment: None,
+            cookie_jar: Arc::new(Mutex::new(CookieJar::new())),
+            tokens: Arc::new(Mutex::new(TokenCache::default())),
+            variables: KeyValueTable::default(),
+            requests: HashMap::new(),
+        }
+    }
+
+    /// Runs every item once per row of data, or once if there is no data.
+    ///
+    /// Each result is given to `progress` as soon as it is known, so the results
+    /// that came before are kept if the run is dropped halfway.
+    pub async fn run<F: FnMut(RequestRun)>(
+        &mut self,
+        items: &[RunItem],
+        data: &[KeyValueTable],
+        open_browser: &dyn Fn(&str) -> std::io::Result<()>,
+        mut progress: F,
+    ) {
+        let no_data = [KeyValueTable::default()];
+        let data = if data.is_empty() { &no_data[..] } else { data };
+        for (iteration, variables) in data.iter().enumerate() {
+            for item in items {
+                let run = self
+                    .run_item(item, iteration + 1, variables, open_browser)
+                    .await;
+                let failed = !run.passed();
+                progress(run);
+                if failed && self.settings.stop_on_failure {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Sends a request file, then checks the assertions of the request on its
+    /// response, takes the captures and runs the post-response script.
+    ///
+    /// The given variables override the ones of the request.
+    pub async fn run_item(
+        &mut self,
+        item: &RunItem,
+        iteration: usize,
+        variables: &KeyValueTable,
+        open_browser: &dyn Fn(&str) -> std::io::Result<()>,
+    ) -> RequestRun {
+        let mut checks = Checks::default();
+        let outcome = self.send(item, variables, open_browser, &mut checks).await;
+        RequestRun {
+            name: item.name.clone(),
+            path: item.path.clone(),
+            iteration,
+            outcome,
+            errors: checks.errors,
+            assertions: checks.assertions,
+            console: checks.console,
+        }
+    }
+
+    async fn send(
+        &mut self,
+        item: &RunItem,
+        variables: &KeyValueTable,
+        open_browser: &dyn Fn(&str) -> std::io::Result<()>,
+        run: &mut Checks,
+    ) -> Result<ResponseData, CarteroError> {
+        let contents = std::fs::read_to_string(&item.path)?;
+        let mut endpoint = crate::file::parse_toml(&contents)?;
+        if let Some(variables) = self.requests.get(&item.path) {
+            endpoint.variables = variables.clone();
+        }
+        let captures = std::mem::take(&mut endpoint.captures);
+        let assertions = std::mem::take(&mut endpoint.assertions);
+        let mut request_variables = endpoint.variables.clone();
+        if let Some(dir) = item.path.parent() {
+            endpoint = endpoint.with_base_dir(dir);
+        }
+        endpoint = endpoint.with_session(&self.variables);
+        if let Some(environment) = &self.environment {
+            endpoint = endpoint.with_environment(environment);
+        }
+        if let Some(collection) = &item.collection {
+            endpoint = endpoint.with_collection(collection);
+        }
+        endpoint.variables.extend(variables.iter().cloned());
+        endpoint
+            .variables
+            .extend(self.settings.variables.iter().cloned());
+        if let Some(timeout) = self.settings.timeout {
+            endpoint = endpoint.with_default_timeout(timeout);
+        }
+        endpoint = endpoint.with_default_proxy(&self.settings.proxy);
+
+        let pre_request = run_pre_request(&mut endpoint);
+        run.console.extend(pre_request.console);
+        if let Some(e) = pre_request.error {
+            return Err(CarteroError::Script(ScriptStage::PreRequest, e));
+        }
+        store_captures(
+            &pre_request.variables,
+            &mut request_variables,
+            self.environment.as_mut(),
+            &mut self.variables,
+        );
+        let mut scripted = endpoint.clone();
+        let mut request = BoundRequest::try_from(endpoint)?;
+        request.cookie_jar = Some(self.cookie_jar.clone());
+        authorize(&mut request, &self.tokens, open_browser).await?;
+        let response = send_request(request).await?;
+
+        let mut values = Vec::new();
+        for outcome in run_captures(&captures, &response) {
+            match outcome {
+                Ok(value) => values.push(value),
+                Err((variable, e)) => run.errors.push(CarteroError::Capture(variable, e)),
+            }
+        }
+        store_captures(
+            &values,
+            &mut request_variables,
+            self.environment.as_mut(),
+            &mut self.variables,
+        );
+        run.assertions = run_assertions(&assertions, &response);
+
+        for value in &values {
+            let variable = KeyValue::from((value.variable.as_str(), value.value.as_str()));
+            scripted.variables.push(variable);
+        }
+        let post_response = run_post_response(&scripted, &response);
+        run.console.extend(post_response.console);
+        match post_response.error {
+            Some(e) => run
+                .errors
+                .push(CarteroError::Script(ScriptStage::PostResponse, e)),
+            None => store_captures(
+                &post_response.variables,
+                &mut request_variables,
+                self.environment.as_mut(),
+                &mut self.variables,
+            ),
+        }
+        self.requests.insert(item.path.clone(), request_variables);
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{collection_items, RunItem, RunSettings, Runner};
+    use crate::entities::KeyValueTable;
+    use crate::error::CarteroError;
+
+    fn no_browser(_: &str) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn collection_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cartero-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("books")).unwrap();
+        std::fs::create_dir_all(dir.join(".git")).unwrap();
+        for file in [
+            "Zebra.cartero",
+            "login.cartero",
+            "books/list.cartero",
+            "notes.txt",
+        ] {
+            std::fs::write(dir.join(file), "").unwrap();
+        }
+        std::fs::write(dir.join(".git/HEAD.cartero"), "").unwrap();
+        dir
+    }
+
+    #[test]
+    pub fn test_collection_items_in_alphabetical_order() {
+        let dir = collection_dir("run-sorted");
+        let items = collection_items(&dir).unwrap();
+        let names: Vec<&str> = items.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["books/list.cartero", "login.cartero", "Zebra.cartero"]
+        );
+        assert_eq!(items[1].path, dir.join("login.cartero"));
+        assert!(items[1].collection.is_some());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    pub fn test_collection_items_in_manifest_order() {
+        let dir = collection_dir("run-manifest");
+        let manifest = "version = 1\n\n[run]\norder = ['login.cartero', 'books/list.cartero']\n";
+        std::fs::write(dir.join("collection.toml"), manifest).unwrap();
+        let items = collection_items(&dir).unwrap();
+        let names: Vec<&str> = items.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, vec!["login.cartero", "books/list.cartero"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    pub fn test_collection_items_stay_in_the_collection() {
+        let dir = collection_dir("run-outside");
+        for entry in [
+            "../secret.cartero",
+            "books/../../secret.cartero",
+            "/etc/passwd",
+        ] {
+            let manifest = format!("version = 1\n\n[run]\norder = ['login.cartero', '{entry}']\n");
+            std::fs::write(dir.join("collection.toml"), manifest).unwrap();
+            assert!(matches!(
+                collection_items(&dir),
+                Err(CarteroError::InvalidRunOrder(name)) if name == entry
+            ));
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    pub fn test_run_keeps_going_or_stops_on_failure() {
+        let dir = std::env::temp_dir().join(format!("cartero-run-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let failing = dir.join("failing.cartero");
+        let script = "version = 1\nurl = 'http://{{HOST}}/'\nmethod = 'GET'\n\n\
+                      [scripts]\npre-request = 'print(variable(\"HOST\")); throw \"stop\";'\n";
+        std::fs::write(&failing, script).unwrap();
+        let items = [
+            RunItem::file(&failing),
+            RunItem::file(&dir.join("missing.cartero")),
+        ];
+        let data = [
+            KeyValueTable::new(&[("HOST", "one.example.com").into()]),
+            KeyValueTable::new(&[("HOST", "two.example.com").into()]),
+        ];
+
+        let mut runner = Runner::new(RunSettings::default());
+        let mut runs = Vec::new();
+        let future = runner.run(&items, &data, &no_browser, |run| runs.push(run));
+        futures_lite::future::block_on(future);
+        assert_eq!(runs.len(), 4);
+        assert_eq!(runs[2].iteration, 2);
+        assert_eq!(runs[2].console[0].text, "two.example.com");
+        assert!(matches!(runs[2].outcome, Err(CarteroError::Script(..))));
+        assert!(matches!(runs[3].outcome, Err(CarteroError::FileError(_))));
+        assert!(runs.iter().all(|run| !run.passed()));
+
+        let settings = RunSettings {
+            stop_on_failure: true,
+            ..Default::default()
+        };
+        let mut runner = Runner::new(settings);
+        let mut runs = Vec::new();
+        let future = runner.run(&items, &data, &no_browser, |run| runs.push(run));
+        futures_lite::future::block_on(future);
+        assert_eq!(runs.len(), 1);
+        assert_eq!(
+            runs[0].failures(),
+            vec!["The pre-request script failed: Runtime error: stop (line 1, position 26)"]
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
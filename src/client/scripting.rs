@@ -0,0 +1,640 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Runs the Rhai scripts of a request, for the flows that need more logic than the
+//! captures and the assertions can give.
+//!
+//! The scripts run in a sandbox: they cannot read files, use the network or run
+//! commands, and they are stopped if they take too many operations. They can use the
+//! following API.
+//!
+//! * `request`: the request, in both scripts. The pre-request script runs before the
+//!   variables of the request are replaced, and its changes are sent. The changes
+//!   made in the post-response script are ignored.
+//!   * `request.url`, `request.method` and `request.body` can be read and set.
+//!   * `request.header(name)` gives the value of a header, or `()` if missing.
+//!   * `request.set_header(name, value)` and `request.remove_header(name)`.
+//! * `response`: the response, only in the post-response script.
+//!   * `response.status`, `response.duration` (in milliseconds), `response.size`
+//!     and `response.body`.
+//!   * `response.header(name)` gives the value of a header, or `()` if missing.
+//!   * `response.json()` parses the body as JSON.
+//! * `variable(name)` gives the value of a variable, or `()` if missing.
+//! * `set_variable(name, value)`, `set_environment_variable(name, value)` and
+//!   `set_session_variable(name, value)` set a variable in the same scopes as the
+//!   captures. The variables set by the pre-request script are also used by the
+//!   request that is about to be sent.
+//! * `print(value)` and `debug(value)` write to the console.
+//! * `timestamp()` and `timestamp_ms()` give the current Unix time.
+//! * `random_hex(bytes)` gives random bytes, such as a nonce, in hexadecimal.
+//! * `sha256(text)` and `hmac_sha256(key, text)` give the digest in hexadecimal.
+//! * `base64_encode(text)` and `base64_decode(text)`.
+//! * `parse_json(text)` and `to_json(value)`.
+//!
+//! A script fails when it throws, as in `throw "unexpected status"`. The variables
+//! that a failed script set are discarded.
+
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hmac::Hmac;
+use rhai::{Dynamic, Engine, EvalAltResult, Scope, INT};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use super::signing::{hex, mac};
+use super::{unix_now, CapturedValue};
+use crate::entities::{
+    CaptureScope, EndpointData, KeyValue, KeyValueTable, RawEncoding, RequestMethod,
+    RequestPayload, ResponseData,
+};
+
+/// The number of operations after which a script is stopped, so that a script that
+/// never ends does not block the application.
+const MAX_OPERATIONS: u64 = 5_000_000;
+
+/// The limits that stop a script that would use all the memory or the stack. Strings
+/// are large enough for the body of most responses.
+const MAX_STRING_SIZE: usize = 64 * 1024 * 1024;
+const MAX_ARRAY_SIZE: usize = 100_000;
+const MAX_MAP_SIZE: usize = 100_000;
+const MAX_CALL_LEVELS: usize = 64;
+const MAX_EXPR_DEPTH: usize = 64;
+const MAX_FUNCTION_EXPR_DEPTH: usize = 32;
+
+/// The most random bytes that `random_hex` gives at once.
+const MAX_RANDOM_BYTES: INT = 1024;
+
+/// When a script runs, around the request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptStage {
+    PreRequest,
+    PostResponse,
+}
+
+impl Display for ScriptStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScriptStage::PreRequest => write!(f, "pre-request"),
+            ScriptStage::PostResponse => write!(f, "post-response"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("{0}")]
+pub struct ScriptError(pub String);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleLevel {
+    /// Written with `print`.
+    Output,
+    /// Written with `debug`.
+    Debug,
+    /// The error that made the script fail.
+    Error,
+}
+
+/// A line written to the console by a script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsoleLine {
+    pub stage: ScriptStage,
+    pub level: ConsoleLevel,
+    pub text: String,
+}
+
+/// What came out of running a script.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScriptRun {
+    pub console: Vec<ConsoleLine>,
+    /// The variables set by the script, to be kept as the captured ones.
+    pub variables: Vec<CapturedValue>,
+    pub error: Option<ScriptError>,
+}
+
+/// The request, as seen by the scripts.
+#[derive(Debug, Clone)]
+struct ScriptRequest(EndpointData);
+
+/// The response, as seen by the scripts.
+#[derive(Debug, Clone)]
+struct ScriptResponse(Rc<ResponseData>);
+
+#[derive(Default)]
+struct ScriptState {
+    console: Vec<ConsoleLine>,
+    variables: Vec<CapturedValue>,
+}
+
+/// Runs the pre-request script of the endpoint. If the script succeeds, its changes
+/// are applied to the endpoint, and the variables that it set are added to it.
+pub fn run_pre_request(endpoint: &mut EndpointData) -> ScriptRun {
+    let script = endpoint.scripts.pre_request.clone();
+    if script.trim().is_empty() {
+        return ScriptRun::default();
+    }
+
+    let mut scope = Scope::new();
+    scope.push("request", ScriptRequest(endpoint.clone()));
+    let run = run_script(
+        ScriptStage::PreRequest,
+        &script,
+        &mut scope,
+        &endpoint.variables,
+    );
+    if run.error.is_none() {
+        if let Some(request) = scope.get_value::<ScriptRequest>("request") {
+            *endpoint = request.0;
+        }
+        for value in &run.variables {
+            let variable = KeyValue::from((value.variable.as_str(), value.value.as_str()));
+            endpoint.variables.push(variable);
+        }
+    }
+    run
+}
+
+/// Runs the post-response script of the endpoint on its response.
+pub fn run_post_response(endpoint: &EndpointData, response: &ResponseData) -> ScriptRun {
+    let script = &endpoint.scripts.post_response;
+    if script.trim().is_empty() {
+        return ScriptRun::default();
+    }
+
+    let mut scope = Scope::new();
+    scope.push("request", ScriptRequest(endpoint.clone()));
+    scope.push("response", ScriptResponse(Rc::new(response.clone())));
+    run_script(
+        ScriptStage::PostResponse,
+        script,
+        &mut scope,
+        &endpoint.variables,
+    )
+}
+
+fn run_script(
+    stage: ScriptStage,
+    script: &str,
+    scope: &mut Scope,
+    variables: &KeyValueTable,
+) -> ScriptRun {
+    let state = Rc::new(RefCell::new(ScriptState::default()));
+    let engine = script_engine(stage, &state, variables.clone());
+    let result = engine.run_with_scope(scope, script);
+    let ScriptState {
+        mut console,
+        variables,
+    } = state.take();
+
+    match result {
+        Ok(()) => ScriptRun {
+            console,
+            variables,
+            error: None,
+        },
+        Err(e) => {
+            let error = ScriptError(e.to_string());
+            console.push(ConsoleLine {
+                stage,
+                level: ConsoleLevel::Error,
+                text: error.to_string(),
+            });
+            ScriptRun {
+                console,
+                variables: Vec::new(),
+                error: Some(error),
+            }
+        }
+    }
+}
+
+fn script_engine(
+    stage: ScriptStage,
+    state: &Rc<RefCell<ScriptState>>,
+    variables: KeyValueTable,
+) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_string_size(MAX_STRING_SIZE)
+        .set_max_array_size(MAX_ARRAY_SIZE)
+        .set_max_map_size(MAX_MAP_SIZE)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_expr_depths(MAX_EXPR_DEPTH, MAX_FUNCTION_EXPR_DEPTH);
+
+    let console = state.clone();
+    engine.on_print(move |text| {
+        console.borrow_mut().console.push(ConsoleLine {
+            stage,
+            level: ConsoleLevel::Output,
+            text: text.to_string(),
+        });
+    });
+    let console = state.clone();
+    engine.on_debug(move |text, _, _| {
+        console.borrow_mut().console.push(ConsoleLine {
+            stage,
+            level: ConsoleLevel::Debug,
+            text: text.to_string(),
+        });
+    });
+
+    register_variables(&mut engine, state, variables);
+    register_utilities(&mut engine);
+    register_request(&mut engine);
+    register_response(&mut engine);
+    engine
+}
+
+fn register_variables(
+    engine: &mut Engine,
+    state: &Rc<RefCell<ScriptState>>,
+    variables: KeyValueTable,
+) {
+    let values = state.clone();
+    engine.register_fn("variable", move |name: &str| -> Dynamic {
+        let state = values.borrow();
+        let set = state
+            .variables
+            .iter()
+            .rev()
+            .find(|value| value.variable == name)
+            .map(|value| value.value.clone());
+        let value = set.or_else(|| {
+            variables
+                .iter()
+                .rev()
+                .find(|item| item.active && item.name == name)
+                .map(|item| item.value.clone())
+        });
+        value.map(Dynamic::from).unwrap_or(Dynamic::UNIT)
+    });
+
+    let setters = [
+        ("set_variable", CaptureScope::Request),
+        ("set_environment_variable", CaptureScope::Environment),
+        ("set_session_variable", CaptureScope::Session),
+    ];
+    for (function, scope) in setters {
+        let values = state.clone();
+        engine.register_fn(function, move |name: &str, value: Dynamic| {
+            values.borrow_mut().variables.push(CapturedValue {
+                variable: name.to_string(),
+                value: value.to_string(),
+                scope,
+            });
+        });
+    }
+}
+
+fn register_utilities(engine: &mut Engine) {
+    engine.register_fn("timestamp", || unix_now() as INT);
+    engine.register_fn("timestamp_ms", || {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as INT
+    });
+    engine.register_fn(
+        "random_hex",
+        |bytes: INT| -> Result<String, Box<EvalAltResult>> {
+            if !(0..=MAX_RANDOM_BYTES).contains(&bytes) {
+                return Err(format!("random_hex takes up to {MAX_RANDOM_BYTES} bytes").into());
+            }
+            let bytes: Vec<u8> = (0..bytes).map(|_| rand::random::<u8>()).collect();
+            Ok(hex(&bytes))
+        },
+    );
+    engine.register_fn("sha256", |text: &str| hex(&Sha256::digest(text.as_bytes())));
+    engine.register_fn("hmac_sha256", |key: &str, text: &str| {
+        hex(&mac::<Hmac<Sha256>>(key.as_bytes(), text.as_bytes()))
+    });
+    engine.register_fn("base64_encode", |text: &str| base64::encode(text));
+    engine.register_fn(
+        "base64_decode",
+        |text: &str| -> Result<String, Box<EvalAltResult>> {
+            let bytes = base64::decode(text.trim()).map_err(|e| e.to_string())?;
+            Ok(String::from_utf8_lossy(&bytes).into_owned())
+        },
+    );
+    engine.register_fn("parse_json", parse_json);
+    engine.register_fn(
+        "to_json",
+        |value: Dynamic| -> Result<String, Box<EvalAltResult>> {
+            let json: Value = rhai::serde::from_dynamic(&value)?;
+            Ok(json.to_string())
+        },
+    );
+}
+
+fn parse_json(text: &str) -> Result<Dynamic, Box<EvalAltResult>> {
+    let json: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    rhai::serde::to_dynamic(json)
+}
+
+/// The value of the last active header with the given name, or `()` if missing.
+fn header_value(headers: &KeyValueTable, name: &str) -> Dynamic {
+    headers
+        .iter()
+        .rev()
+        .find(|header| header.active && header.name.eq_ignore_ascii_case(name))
+        .map(|header| Dynamic::from(header.value.clone()))
+        .unwrap_or(Dynamic::UNIT)
+}
+
+fn register_request(engine: &mut Engine) {
+    engine
+        .register_type_with_name::<ScriptRequest>("Request")
+        .register_get_set(
+            "url",
+            |request: &mut ScriptRequest| request.0.url.clone(),
+            |request: &mut ScriptRequest, url: String| request.0.url = url,
+        )
+        .register_get("method", |request: &mut ScriptRequest| {
+            request.0.method.as_str().to_string()
+        })
+        .register_set(
+            "method",
+            |request: &mut ScriptRequest, method: &str| -> Result<(), Box<EvalAltResult>> {
+                request.0.method = RequestMethod::try_from(method)
+                    .map_err(|_| format!("invalid method {method}"))?;
+                Ok(())
+            },
+        )
+        .register_get_set(
+            "body",
+            |request: &mut ScriptRequest| match &request.0.body {
+                RequestPayload::Raw { content, .. } => {
+                    String::from_utf8_lossy(content).into_owned()
+                }
+                _ => String::new(),
+            },
+            |request: &mut ScriptRequest, body: String| match &mut request.0.body {
+                RequestPayload::Raw { content, .. } => *content = body.as_bytes().to_vec(),
+                payload => {
+                    *payload = RequestPayload::Raw {
+                        encoding: RawEncoding::OctetStream,
+                        content: body.as_bytes().to_vec(),
+                    }
+                }
+            },
+        )
+        .register_fn("header", |request: &mut ScriptRequest, name: &str| {
+            header_value(&request.0.headers, name)
+        })
+        .register_fn(
+            "set_header",
+            |request: &mut ScriptRequest, name: &str, value: Dynamic| {
+                let headers = &mut request.0.headers;
+                headers.retain(|header| !header.name.eq_ignore_ascii_case(name));
+                headers.push(KeyValue::from((name, value.to_string().as_str())));
+            },
+        )
+        .register_fn(
+            "remove_header",
+            |request: &mut ScriptRequest, name: &str| {
+                let headers = &mut request.0.headers;
+                headers.retain(|header| !header.name.eq_ignore_ascii_case(name));
+            },
+        );
+}
+
+fn register_response(engine: &mut Engine) {
+    engine
+        .register_type_with_name::<ScriptResponse>("Response")
+        .register_get("status", |response: &mut ScriptResponse| {
+            INT::from(response.0.status_code)
+        })
+        .register_get("duration", |response: &mut ScriptResponse| {
+            response.0.duration as INT
+        })
+        .register_get("size", |response: &mut ScriptResponse| {
+            response.0.size as INT
+        })
+        .register_get("body", |response: &mut ScriptResponse| {
+            response.0.body_str()
+        })
+        .register_fn("header", |response: &mut ScriptResponse, name: &str| {
+            header_value(&response.0.headers, name)
+        })
+        .register_fn("json", |response: &mut ScriptResponse| {
+            parse_json(&response.0.body_str())
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scripted(pre_request: &str, post_response: &str) -> EndpointData {
+        EndpointData {
+            url: "https://api.example.com/items".into(),
+            headers: KeyValueTable::new(&[KeyValue::from(("Accept", "text/plain"))]),
+            variables: KeyValueTable::new(&[KeyValue::from(("USER", "ada"))]),
+            scripts: crate::entities::RequestScripts {
+                pre_request: pre_request.into(),
+                post_response: post_response.into(),
+            },
+            ..Default::default()
+        }
+    }
+
+    fn response() -> ResponseData {
+        ResponseData {
+            status_code: 200,
+            duration: 35,
+            headers: KeyValueTable::new(&[KeyValue::from(("Content-Type", "application/json"))]),
+            body: br#"{"items": [{"id": 7, "name": "first"}, {"id": 9, "name": "second"}]}"#
+                .to_vec(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    pub fn test_pre_request_changes_the_request() {
+        let mut endpoint = scripted(
+            r#"
+                request.method = "POST";
+                request.url += "?user=" + variable("USER");
+                request.set_header("Accept", "application/json");
+                request.set_header("X-Nonce", "{{NONCE}}");
+                request.body = `{"user": "${variable("USER")}"}`;
+                set_variable("NONCE", "n-1");
+                print("nonce " + variable("NONCE"));
+            "#,
+            "",
+        );
+        let run = run_pre_request(&mut endpoint);
+        assert_eq!(run.error, None);
+        assert_eq!(endpoint.method, RequestMethod::Post);
+        assert_eq!(endpoint.url, "https://api.example.com/items?user=ada");
+        assert_eq!(
+            endpoint.headers,
+            KeyValueTable::new(&[
+                KeyValue::from(("Accept", "application/json")),
+                KeyValue::from(("X-Nonce", "{{NONCE}}")),
+            ])
+        );
+        assert_eq!(
+            endpoint.body,
+            RequestPayload::Raw {
+                encoding: RawEncoding::OctetStream,
+                content: br#"{"user": "ada"}"#.to_vec(),
+            }
+        );
+        assert_eq!(endpoint.variables[1], KeyValue::from(("NONCE", "n-1")));
+        assert_eq!(
+            run.console,
+            vec![ConsoleLine {
+                stage: ScriptStage::PreRequest,
+                level: ConsoleLevel::Output,
+                text: "nonce n-1".into(),
+            }]
+        );
+        assert_eq!(
+            run.variables,
+            vec![CapturedValue {
+                variable: "NONCE".into(),
+                value: "n-1".into(),
+                scope: CaptureScope::Request,
+            }]
+        );
+    }
+
+    #[test]
+    pub fn test_post_response_reads_the_response() {
+        let endpoint = scripted(
+            "",
+            r#"
+                if response.status != 200 {
+                    throw "unexpected status";
+                }
+                let items = response.json().items;
+                let second = items.filter(|item| item.name == "second")[0];
+                set_session_variable("ITEM", second.id);
+                set_environment_variable("TYPE", response.header("content-type"));
+                debug(response.duration);
+            "#,
+        );
+        let run = run_post_response(&endpoint, &response());
+        assert_eq!(run.error, None);
+        assert_eq!(
+            run.variables,
+            vec![
+                CapturedValue {
+                    variable: "ITEM".into(),
+                    value: "9".into(),
+                    scope: CaptureScope::Session,
+                },
+                CapturedValue {
+                    variable: "TYPE".into(),
+                    value: "application/json".into(),
+                    scope: CaptureScope::Environment,
+                },
+            ]
+        );
+        assert_eq!(run.console[0].level, ConsoleLevel::Debug);
+        assert_eq!(run.console[0].text, "35");
+    }
+
+    #[test]
+    pub fn test_failed_scripts_change_nothing() {
+        let mut endpoint = scripted(
+            r#"
+                request.url = "https://other.example.com";
+                set_variable("TOKEN", "abc");
+                print("before");
+                throw "no token";
+            "#,
+            "set_variable(",
+        );
+        let original = endpoint.clone();
+        let run = run_pre_request(&mut endpoint);
+        assert_eq!(endpoint, original);
+        assert!(run.variables.is_empty());
+        assert!(run.error.as_ref().unwrap().0.contains("no token"));
+        assert_eq!(run.console.len(), 2);
+        assert_eq!(run.console[1].level, ConsoleLevel::Error);
+
+        let run = run_post_response(&endpoint, &response());
+        assert!(run.error.is_some());
+
+        let mut endpoint = scripted("request.method = \"NOT A METHOD\";", "");
+        assert!(run_pre_request(&mut endpoint).error.is_some());
+    }
+
+    #[test]
+    pub fn test_endless_scripts_are_stopped() {
+        let mut endpoint = scripted("loop { }", "");
+        let run = run_pre_request(&mut endpoint);
+        assert!(run.error.is_some());
+    }
+
+    #[test]
+    pub fn test_runaway_scripts_are_stopped() {
+        for (script, error) in [
+            (
+                "fn deeper(n) { deeper(n + 1) } deeper(0);",
+                "Stack overflow",
+            ),
+            (
+                r#"let text = "x"; loop { text += text; }"#,
+                "Length of string too large",
+            ),
+            (
+                "let list = [0]; loop { list += list; }",
+                "Size of array/BLOB too large",
+            ),
+        ] {
+            let mut endpoint = scripted(script, "");
+            let run = run_pre_request(&mut endpoint);
+            assert!(run.error.unwrap().0.starts_with(error), "{script}");
+        }
+    }
+
+    #[test]
+    pub fn test_script_utilities() {
+        let endpoint = scripted(
+            "",
+            r#"
+                print(sha256("abc"));
+                print(hmac_sha256("key", "The quick brown fox jumps over the lazy dog"));
+                print(base64_decode(base64_encode("user:pass")));
+                print(random_hex(8).len());
+                print(to_json(parse_json(`{"a": [1, true, null]}`)));
+                print(timestamp() > 1700000000);
+            "#,
+        );
+        let run = run_post_response(&endpoint, &response());
+        assert_eq!(run.error, None);
+        let lines: Vec<&str> = run.console.iter().map(|line| line.text.as_str()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+                "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8",
+                "user:pass",
+                "16",
+                r#"{"a":[1,true,null]}"#,
+                "true",
+            ]
+        );
+    }
+}
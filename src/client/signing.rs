@@ -0,0 +1,648 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Signs requests once their URL, headers and body are final.
+//!
+//! AWS Signature Version 4 follows the rules of the published AWS test suite. The
+//! generic HMAC signature signs a string built from a template, which may use these
+//! placeholders:
+//!
+//! * `{method}`, `{url}`, `{host}`, `{path}` and `{query}`, from the request line.
+//! * `{timestamp}`, the seconds since the Unix epoch.
+//! * `{body}`, and `{body_sha256}` for its SHA-256 hash in hex.
+//! * `{header:NAME}`, the value of a header, with repeated values joined by commas.
+//!
+//! `\n` and `\t` in the template are turned into a new line and a tab.
+
+use std::collections::BTreeMap;
+
+use hmac::digest::KeyInit;
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+use url::Url;
+
+use crate::entities::{
+    AwsSigV4Settings, HmacAlgorithm, HmacSettings, KeyValue, KeyValueTable, SignatureEncoding,
+};
+
+use super::{civil_from_days, BoundRequest, RequestError};
+
+/// How a request is signed, with its variables already processed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestSigner {
+    AwsSigV4(AwsSigV4Settings),
+    Hmac(HmacSettings),
+}
+
+/// Adds the signature of the request to its headers, as if it was sent at `now`.
+///
+/// Like the other kinds of authentication, a request that already has the header of
+/// the signature is left as is.
+pub fn sign(
+    request: &mut BoundRequest,
+    signer: &RequestSigner,
+    now: u64,
+) -> Result<(), RequestError> {
+    let url = Url::parse(&request.url)
+        .map_err(|_| RequestError::Signing(format!("invalid URL \"{}\"", request.url)))?;
+    match signer {
+        RequestSigner::AwsSigV4(settings) => sign_aws(request, &url, settings, now),
+        RequestSigner::Hmac(settings) => sign_hmac(request, &url, settings, now),
+    }
+}
+
+pub(super) fn mac<M: Mac + KeyInit>(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = <M as KeyInit>::new_from_slice(key).expect("HMAC takes keys of any size");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+pub(super) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Replaces the headers with the given name with a single one.
+fn set_header(headers: &mut KeyValueTable, name: &str, value: &str) {
+    headers.retain(|header| !header.name.eq_ignore_ascii_case(name));
+    headers.push(KeyValue::from((name, value)));
+}
+
+/// The host of the URL, with the port when it is not the default one.
+fn host(url: &Url) -> String {
+    let host = url.host_str().unwrap_or_default();
+    match url.port() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+    }
+}
+
+/// Formats seconds since the Unix epoch in the basic ISO 8601 format that AWS uses,
+/// such as `20150830T123600Z`.
+fn amz_date(now: u64) -> String {
+    let (year, month, day) = civil_from_days((now / 86400) as i64);
+    let time = now % 86400;
+    format!(
+        "{year:04}{month:02}{day:02}T{:02}{:02}{:02}Z",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// Percent-encodes everything but the unreserved characters of RFC 3986.
+pub fn uri_encode(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+fn percent_decode(text: &str) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes.get(i + 1..i + 3).and_then(|hex| {
+            std::str::from_utf8(hex)
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        });
+        match (bytes[i], escaped) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    decoded
+}
+
+/// The path, with every segment encoded once. The dot segments were already removed
+/// when the URL was parsed.
+fn canonical_uri(url: &Url) -> String {
+    match url.path_segments() {
+        Some(segments) => segments
+            .map(|segment| format!("/{}", uri_encode(&percent_decode(segment))))
+            .collect(),
+        None => "/".into(),
+    }
+}
+
+/// The parameters of the query string, encoded and sorted by name and then by value.
+fn canonical_query(url: &Url) -> String {
+    let mut pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(name, value)| (uri_encode(name.as_bytes()), uri_encode(value.as_bytes())))
+        .collect();
+    pairs.sort();
+    pairs
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Returns the canonical headers and the list of signed headers. Every header of the
+/// request is signed, plus the Host header that is added when it is sent.
+fn canonical_headers(headers: &KeyValueTable, url: &Url) -> (String, String) {
+    let mut grouped: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    // The cookies of the jar are only added when the request is sent, and they
+    // change on every redirect, so the Cookie header is never signed.
+    for header in headers
+        .iter()
+        .filter(|header| !header.name.eq_ignore_ascii_case("Cookie"))
+    {
+        let value = header
+            .value
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        grouped
+            .entry(header.name.to_lowercase())
+            .or_default()
+            .push(value);
+    }
+    grouped
+        .entry("host".into())
+        .or_insert_with(|| vec![host(url)]);
+    let canonical = grouped
+        .iter()
+        .map(|(name, values)| format!("{name}:{}\n", values.join(",")))
+        .collect();
+    let signed = grouped.keys().cloned().collect::<Vec<_>>().join(";");
+    (canonical, signed)
+}
+
+fn sign_aws(
+    request: &mut BoundRequest,
+    url: &Url,
+    settings: &AwsSigV4Settings,
+    now: u64,
+) -> Result<(), RequestError> {
+    if request.headers.header("Authorization").is_some() {
+        return Ok(());
+    }
+    let timestamp = amz_date(now);
+    let date = &timestamp[..8];
+    set_header(&mut request.headers, "X-Amz-Date", &timestamp);
+    if !settings.session_token.is_empty() {
+        set_header(
+            &mut request.headers,
+            "X-Amz-Security-Token",
+            &settings.session_token,
+        );
+    }
+    let payload_hash = body_sha256(request)?;
+    // S3 does not accept requests that do not say the hash of their body.
+    if settings.service == "s3" {
+        set_header(&mut request.headers, "X-Amz-Content-Sha256", &payload_hash);
+    }
+
+    let (headers, signed_headers) = canonical_headers(&request.headers, url);
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{headers}\n{signed_headers}\n{payload_hash}",
+        request.method.as_str(),
+        canonical_uri(url),
+        canonical_query(url),
+    );
+    let scope = format!(
+        "{date}/{}/{}/aws4_request",
+        settings.region, settings.service
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{timestamp}\n{scope}\n{}",
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+    let key = [
+        date,
+        settings.region.as_str(),
+        settings.service.as_str(),
+        "aws4_request",
+    ]
+    .iter()
+    .fold(
+        format!("AWS4{}", settings.secret_key).into_bytes(),
+        |key, part| mac::<Hmac<Sha256>>(&key, part.as_bytes()),
+    );
+    let signature = hex(&mac::<Hmac<Sha256>>(&key, string_to_sign.as_bytes()));
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+        settings.access_key
+    );
+    request
+        .headers
+        .push(KeyValue::from(("Authorization", authorization.as_str())));
+    Ok(())
+}
+
+/// Hashes the body of the request, reading the files in chunks instead of all at once.
+fn body_sha256(request: &BoundRequest) -> Result<String, RequestError> {
+    let Some(mut stream) = request.open_body()? else {
+        let body = request.body.as_deref().unwrap_or_default();
+        return Ok(hex(&Sha256::digest(body)));
+    };
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut stream.reader, &mut hasher)?;
+    Ok(hex(&hasher.finalize()))
+}
+
+/// Builds the string that is signed out of the template of an HMAC signature.
+fn render_message(
+    template: &str,
+    request: &BoundRequest,
+    url: &Url,
+    now: u64,
+) -> Result<String, RequestError> {
+    let mut message = String::new();
+    let mut chars = template.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') => message.push('\n'),
+                Some('t') => message.push('\t'),
+                Some('\\') => message.push('\\'),
+                Some(other) => {
+                    message.push('\\');
+                    message.push(other);
+                }
+                None => message.push('\\'),
+            },
+            '{' => {
+                let name: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                let value = match name.as_str() {
+                    "method" => request.method.as_str().to_string(),
+                    "url" => request.url.clone(),
+                    "host" => host(url),
+                    "path" => url.path().to_string(),
+                    "query" => url.query().unwrap_or_default().to_string(),
+                    "timestamp" => now.to_string(),
+                    "body" => String::from_utf8_lossy(&request.read_body()?).into_owned(),
+                    "body_sha256" => body_sha256(request)?,
+                    _ => match name.strip_prefix("header:") {
+                        Some(header) => request
+                            .headers
+                            .iter()
+                            .filter(|kv| kv.name.eq_ignore_ascii_case(header))
+                            .map(|kv| kv.value.as_str())
+                            .collect::<Vec<_>>()
+                            .join(","),
+                        None => {
+                            return Err(RequestError::Signing(format!(
+                                "unknown placeholder {{{name}}}"
+                            )))
+                        }
+                    },
+                };
+                message.push_str(&value);
+            }
+            c => message.push(c),
+        }
+    }
+    Ok(message)
+}
+
+fn sign_hmac(
+    request: &mut BoundRequest,
+    url: &Url,
+    settings: &HmacSettings,
+    now: u64,
+) -> Result<(), RequestError> {
+    if settings.header.is_empty() {
+        return Err(RequestError::Signing(
+            "no header was given for the signature".into(),
+        ));
+    }
+    if request.headers.header(&settings.header).is_some() {
+        return Ok(());
+    }
+    if !settings.timestamp_header.is_empty() {
+        set_header(
+            &mut request.headers,
+            &settings.timestamp_header,
+            &now.to_string(),
+        );
+    }
+    let message = render_message(&settings.message, request, url, now)?;
+    let (key, message) = (settings.key.as_bytes(), message.as_bytes());
+    let signature = match settings.algorithm {
+        HmacAlgorithm::Sha1 => mac::<Hmac<Sha1>>(key, message),
+        HmacAlgorithm::Sha256 => mac::<Hmac<Sha256>>(key, message),
+        HmacAlgorithm::Sha512 => mac::<Hmac<Sha512>>(key, message),
+    };
+    let signature = match settings.encoding {
+        SignatureEncoding::Hex => hex(&signature),
+        SignatureEncoding::Base64 => base64::encode(&signature),
+    };
+    request.headers.push(KeyValue::from((
+        settings.header.as_str(),
+        signature.as_str(),
+    )));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::BoundPayload;
+    use crate::entities::RequestMethod;
+
+    use super::*;
+
+    /// 2015-08-30T12:36:00Z, the time of the AWS Signature Version 4 test suite.
+    const SUITE_TIME: u64 = 1440938160;
+
+    fn suite_credentials() -> RequestSigner {
+        RequestSigner::AwsSigV4(AwsSigV4Settings {
+            access_key: "AKIDEXAMPLE".into(),
+            secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".into(),
+            region: "us-east-1".into(),
+            service: "service".into(),
+            ..Default::default()
+        })
+    }
+
+    fn request(method: RequestMethod, url: &str, headers: &[(&str, &str)]) -> BoundRequest {
+        BoundRequest {
+            url: url.into(),
+            method,
+            headers: KeyValueTable::new(
+                &headers
+                    .iter()
+                    .map(|&header| KeyValue::from(header))
+                    .collect::<Vec<_>>(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    fn authorization(request: &BoundRequest) -> &str {
+        request.headers.header("Authorization").unwrap()[0]
+    }
+
+    #[test]
+    pub fn test_amz_date() {
+        assert_eq!(amz_date(SUITE_TIME), "20150830T123600Z");
+        assert_eq!(amz_date(0), "19700101T000000Z");
+        assert_eq!(amz_date(951782400), "20000229T000000Z");
+    }
+
+    #[test]
+    pub fn test_sigv4_get_vanilla() {
+        let mut request = request(RequestMethod::Get, "https://example.amazonaws.com/", &[]);
+        sign(&mut request, &suite_credentials(), SUITE_TIME).unwrap();
+        assert_eq!(
+            request.headers.header("X-Amz-Date"),
+            Some(vec!["20150830T123600Z"])
+        );
+        assert_eq!(
+            authorization(&request),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, SignedHeaders=host;x-amz-date, Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+    }
+
+    #[test]
+    pub fn test_sigv4_does_not_sign_cookies() {
+        let mut request = request(
+            RequestMethod::Get,
+            "https://example.amazonaws.com/",
+            &[("Cookie", "session=1")],
+        );
+        sign(&mut request, &suite_credentials(), SUITE_TIME).unwrap();
+        // The same signature as the vanilla request, which has no cookie.
+        assert!(authorization(&request).ends_with(
+            "SignedHeaders=host;x-amz-date, Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        ));
+    }
+
+    #[test]
+    pub fn test_sigv4_get_vanilla_query_order() {
+        let mut request = request(
+            RequestMethod::Get,
+            "https://example.amazonaws.com/?Param2=value2&Param1=value1",
+            &[],
+        );
+        sign(&mut request, &suite_credentials(), SUITE_TIME).unwrap();
+        assert!(authorization(&request).ends_with(
+            "SignedHeaders=host;x-amz-date, Signature=b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500"
+        ));
+    }
+
+    #[test]
+    pub fn test_sigv4_post_vanilla() {
+        let mut request = request(RequestMethod::Post, "https://example.amazonaws.com/", &[]);
+        sign(&mut request, &suite_credentials(), SUITE_TIME).unwrap();
+        assert!(authorization(&request).ends_with(
+            "SignedHeaders=host;x-amz-date, Signature=5da7c1a2acd57cee7505fc6676e4e544621c30862966e37dddb68e92efbe5d6b"
+        ));
+    }
+
+    #[test]
+    pub fn test_sigv4_post_x_www_form_urlencoded() {
+        let mut request = request(
+            RequestMethod::Post,
+            "https://example.amazonaws.com/",
+            &[("Content-Type", "application/x-www-form-urlencoded")],
+        );
+        request.body = Some(b"Param1=value1".to_vec());
+        sign(&mut request, &suite_credentials(), SUITE_TIME).unwrap();
+        assert!(authorization(&request).ends_with(
+            "SignedHeaders=content-type;host;x-amz-date, Signature=ff11897932ad3f4e8b18135d722051e5ac45fc38421b1da7b9d196a0fe09473a"
+        ));
+    }
+
+    #[test]
+    pub fn test_sigv4_body_file() {
+        let path = std::env::temp_dir().join(format!("cartero-sign-{}.txt", std::process::id()));
+        std::fs::write(&path, b"Param1=value1").unwrap();
+        let mut request = request(
+            RequestMethod::Post,
+            "https://example.amazonaws.com/",
+            &[("Content-Type", "application/x-www-form-urlencoded")],
+        );
+        request.payload = BoundPayload::File(path.clone());
+        let outcome = sign(&mut request, &suite_credentials(), SUITE_TIME);
+        std::fs::remove_file(&path).unwrap();
+        outcome.unwrap();
+        // The same signature as the body sent from memory.
+        assert!(authorization(&request).ends_with(
+            "SignedHeaders=content-type;host;x-amz-date, Signature=ff11897932ad3f4e8b18135d722051e5ac45fc38421b1da7b9d196a0fe09473a"
+        ));
+    }
+
+    #[test]
+    pub fn test_sigv4_iam_list_users() {
+        // The example of the AWS documentation about creating a signed request.
+        let mut request = request(
+            RequestMethod::Get,
+            "https://iam.amazonaws.com/?Action=ListUsers&Version=2010-05-08",
+            &[(
+                "Content-Type",
+                "application/x-www-form-urlencoded; charset=utf-8",
+            )],
+        );
+        let signer = RequestSigner::AwsSigV4(AwsSigV4Settings {
+            access_key: "AKIDEXAMPLE".into(),
+            secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".into(),
+            region: "us-east-1".into(),
+            service: "iam".into(),
+            ..Default::default()
+        });
+        sign(&mut request, &signer, SUITE_TIME).unwrap();
+        assert_eq!(
+            authorization(&request),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/iam/aws4_request, SignedHeaders=content-type;host;x-amz-date, Signature=5d672d79c15b13162d9279b0855cfba6789a8edb4c82c400e06b5924a6f2b5d7"
+        );
+    }
+
+    #[test]
+    pub fn test_sigv4_keeps_authorization_of_the_user() {
+        let mut request = request(
+            RequestMethod::Get,
+            "https://example.amazonaws.com/",
+            &[("Authorization", "Bearer token")],
+        );
+        sign(&mut request, &suite_credentials(), SUITE_TIME).unwrap();
+        assert_eq!(
+            request.headers.header("Authorization"),
+            Some(vec!["Bearer token"])
+        );
+        assert!(request.headers.header("X-Amz-Date").is_none());
+    }
+
+    #[test]
+    pub fn test_sigv4_session_token_is_signed() {
+        let mut request = request(RequestMethod::Get, "https://example.amazonaws.com/", &[]);
+        let signer = RequestSigner::AwsSigV4(AwsSigV4Settings {
+            session_token: "FQoGZXIvYXdzEXAMPLE".into(),
+            ..match suite_credentials() {
+                RequestSigner::AwsSigV4(settings) => settings,
+                _ => unreachable!(),
+            }
+        });
+        sign(&mut request, &signer, SUITE_TIME).unwrap();
+        assert_eq!(
+            request.headers.header("X-Amz-Security-Token"),
+            Some(vec!["FQoGZXIvYXdzEXAMPLE"])
+        );
+        assert!(
+            authorization(&request).contains("SignedHeaders=host;x-amz-date;x-amz-security-token,")
+        );
+    }
+
+    #[test]
+    pub fn test_canonical_uri() {
+        let url = Url::parse("https://example.com/a b/%41%2F/./c/../d").unwrap();
+        assert_eq!(canonical_uri(&url), "/a%20b/A%2F/d");
+        let url = Url::parse("https://example.com").unwrap();
+        assert_eq!(canonical_uri(&url), "/");
+    }
+
+    #[test]
+    pub fn test_hmac_signature() {
+        let mut request = request(RequestMethod::Get, "https://example.com/", &[]);
+        let signer = RequestSigner::Hmac(HmacSettings {
+            key: "key".into(),
+            message: "The quick brown fox jumps over the lazy dog".into(),
+            header: "X-Signature".into(),
+            ..Default::default()
+        });
+        sign(&mut request, &signer, SUITE_TIME).unwrap();
+        assert_eq!(
+            request.headers.header("X-Signature"),
+            Some(vec![
+                "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+            ])
+        );
+
+        let mut request = self::request(RequestMethod::Get, "https://example.com/", &[]);
+        let signer = RequestSigner::Hmac(HmacSettings {
+            algorithm: HmacAlgorithm::Sha1,
+            key: "key".into(),
+            message: "The quick brown fox jumps over the lazy dog".into(),
+            header: "X-Signature".into(),
+            encoding: SignatureEncoding::Base64,
+            ..Default::default()
+        });
+        sign(&mut request, &signer, SUITE_TIME).unwrap();
+        assert_eq!(
+            request.headers.header("X-Signature"),
+            Some(vec!["3nybhbi3iqa8ino29wqQcBydtNk="])
+        );
+    }
+
+    #[test]
+    pub fn test_hmac_message_template() {
+        let mut request = request(
+            RequestMethod::Post,
+            "https://api.example.com:8443/v1/orders?page=2",
+            &[("X-Client", "cartero")],
+        );
+        request.body = Some(b"{}".to_vec());
+        let message = render_message(
+            "{method}\\n{host}{path}?{query}\\n{timestamp}\\t{header:x-client}\\n{body}\\n{body_sha256}",
+            &request,
+            &Url::parse(&request.url).unwrap(),
+            SUITE_TIME,
+        )
+        .unwrap();
+        assert_eq!(
+            message,
+            "POST\napi.example.com:8443/v1/orders?page=2\n1440938160\tcartero\n{}\n44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a"
+        );
+        assert!(matches!(
+            render_message("{nonce}", &request, &Url::parse(&request.url).unwrap(), 0),
+            Err(RequestError::Signing(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_hmac_sets_timestamp_header() {
+        let mut request = request(RequestMethod::Get, "https://example.com/", &[]);
+        let signer = RequestSigner::Hmac(HmacSettings {
+            key: "key".into(),
+            message: "{header:X-Timestamp}".into(),
+            header: "X-Signature".into(),
+            timestamp_header: "X-Timestamp".into(),
+            ..Default::default()
+        });
+        sign(&mut request, &signer, SUITE_TIME).unwrap();
+        assert_eq!(
+            request.headers.header("X-Timestamp"),
+            Some(vec!["1440938160"])
+        );
+        let expected = hex(&mac::<Hmac<Sha256>>(b"key", b"1440938160"));
+        assert_eq!(
+            request.headers.header("X-Signature"),
+            Some(vec![expected.as_str()])
+        );
+
+        let signer = RequestSigner::Hmac(HmacSettings {
+            key: "key".into(),
+            ..Default::default()
+        });
+        assert!(sign(&mut request, &signer, SUITE_TIME).is_err());
+    }
+}
@@ -1,7 +1,8 @@
 use srtemplate::SrTemplateError;
 use thiserror::Error;
 
-use crate::client::RequestError;
+use crate::client::{AssertionError, CaptureError, RequestError, ScriptError, ScriptStage};
+use crate::import::ImportError;
 
 #[derive(Debug, Error)]
 pub enum CarteroError {
@@ -29,9 +30,49 @@ pub enum CarteroError {
     #[error("Error manipulating TOML")]
     SerializationError(#[from] toml::ser::Error),
 
+    #[error("Error manipulating JSON")]
+    JsonError(#[from] serde_json::Error),
+
     #[error("Error during variable interpolation: {0}")]
     VariableInterpolationError(#[from] SrTemplateError),
 
     #[error("Outdated schema, please update the software")]
     OutdatedSchema,
+
+    #[error("Invalid file name")]
+    InvalidFileName,
+
+    #[error("A file with that name already exists")]
+    FileAlreadyExists,
+
+    #[error("Cannot move to the chosen folder")]
+    InvalidDestination,
+
+    #[error("Cannot import: {0}")]
+    Import(#[from] ImportError),
+
+    #[error("Cannot capture {0}: {1}")]
+    Capture(String, CaptureError),
+
+    #[error("Assertion failed, {0}: {1}")]
+    Assertion(String, AssertionError),
+
+    #[error("The {0} script failed: {1}")]
+    Script(ScriptStage, ScriptError),
+
+    #[error("Invalid data file: {0}")]
+    InvalidDataFile(String),
+
+    #[error("The run order of the collection points outside of it: {0}")]
+    InvalidRunOrder(String),
+}
+
+impl CarteroError {
+    /// The message of this error, followed by the message of its cause if it has one.
+    pub fn details(&self) -> String {
+        match std::error::Error::source(self) {
+            Some(source) => format!("{self}: {source}"),
+            None => self.to_string(),
+        }
+    }
 }
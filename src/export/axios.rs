@@ -0,0 +1,268 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::client::{BoundPayload, BoundRequest};
+
+use super::{body_text, json_quote, merged_headers, reads_files, CodeGenerator};
+
+pub struct NodeAxios;
+
+impl CodeGenerator for NodeAxios {
+    fn name(&self) -> &'static str {
+        "Node.js (axios)"
+    }
+
+    fn language(&self) -> &'static str {
+        "js"
+    }
+
+    fn generate(&self, request: &BoundRequest) -> String {
+        let method: String = request.method.clone().into();
+        let multipart = matches!(request.payload, BoundPayload::Multipart { .. });
+        let headers = merged_headers(request, multipart);
+
+        let mut code = String::new();
+        if request.digest.is_some() {
+            code.push_str(
+                "// axios cannot answer HTTP Digest challenges, so no credentials are sent.\n",
+            );
+        }
+        code.push_str("const axios = require(\"axios\");\n");
+        if reads_files(request) {
+            code.push_str("const fs = require(\"fs\");\n");
+        }
+        code.push('\n');
+        let data = match &request.payload {
+            BoundPayload::Urlencoded(fields) => {
+                code.push_str("const data = new URLSearchParams();\n");
+                for (name, value) in fields {
+                    code.push_str(&format!(
+                        "data.append({}, {});\n",
+                        json_quote(name),
+                        json_quote(value)
+                    ));
+                }
+                code.push('\n');
+                true
+            }
+            BoundPayload::Multipart { fields, files, .. } => {
+                // FormData is global since Node.js 18, and axios sets the boundary.
+                code.push_str("const data = new FormData();\n");
+                for (name, value) in fields {
+                    code.push_str(&format!(
+                        "data.append({}, {});\n",
+                        json_quote(name),
+                        json_quote(value)
+                    ));
+                }
+                for file in files {
+                    code.push_str(&format!(
+                        "data.append({}, new Blob([fs.readFileSync({})], {{ type: {} }}), {});\n",
+                        json_quote(&file.name),
+                        json_quote(&file.path.to_string_lossy()),
+                        json_quote(&file.content_type),
+                        json_quote(&file.filename)
+                    ));
+                }
+                code.push('\n');
+                true
+            }
+            BoundPayload::Raw(_) => match body_text(request) {
+                Some(body) => {
+                    code.push_str(&format!("const data = {};\n\n", json_quote(&body)));
+                    true
+                }
+                None => false,
+            },
+            BoundPayload::File(path) => {
+                code.push_str(&format!(
+                    "const data = fs.readFileSync({});\n\n",
+                    json_quote(&path.to_string_lossy())
+                ));
+                true
+            }
+            BoundPayload::None => false,
+        };
+
+        code.push_str("axios({\n");
+        code.push_str(&format!("  method: {},\n", json_quote(&method)));
+        code.push_str(&format!("  url: {},\n", json_quote(&request.url)));
+        if !headers.is_empty() {
+            code.push_str("  headers: {\n");
+            for (name, value) in &headers {
+                code.push_str(&format!(
+                    "    {}: {},\n",
+                    json_quote(name),
+                    json_quote(value)
+                ));
+            }
+            code.push_str("  },\n");
+        }
+        if data {
+            code.push_str("  data,\n");
+        }
+        code.push_str("}).then((response) => {\n");
+        code.push_str("  console.log(response.status);\n");
+        code.push_str("  console.log(response.data);\n");
+        code.push_str("});\n");
+        code
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::{fixtures, CodeGenerator};
+
+    use super::NodeAxios;
+
+    #[test]
+    fn test_axios_get() {
+        assert_eq!(
+            NodeAxios.generate(&fixtures::get_request()),
+            r#"const axios = require("axios");
+
+axios({
+  method: "GET",
+  url: "https://www.example.com/users?page=2",
+  headers: {
+    "Accept": "application/json",
+    "Cookie": "a=1; b=2",
+  },
+}).then((response) => {
+  console.log(response.status);
+  console.log(response.data);
+});
+"#
+        );
+    }
+
+    #[test]
+    fn test_axios_urlencoded() {
+        assert_eq!(
+            NodeAxios.generate(&fixtures::urlencoded_request()),
+            r#"const axios = require("axios");
+
+const data = new URLSearchParams();
+data.append("user", "john");
+data.append("password", "it's a secret");
+
+axios({
+  method: "POST",
+  url: "https://www.example.com/login",
+  headers: {
+    "Content-Type": "application/x-www-form-urlencoded",
+  },
+  data,
+}).then((response) => {
+  console.log(response.status);
+  console.log(response.data);
+});
+"#
+        );
+    }
+
+    #[test]
+    fn test_axios_multipart() {
+        assert_eq!(
+            NodeAxios.generate(&fixtures::multipart_request()),
+            r#"const axios = require("axios");
+
+const data = new FormData();
+data.append("title", "Hello");
+
+axios({
+  method: "POST",
+  url: "https://www.example.com/posts",
+  data,
+}).then((response) => {
+  console.log(response.status);
+  console.log(response.data);
+});
+"#
+        );
+    }
+
+    #[test]
+    fn test_axios_upload() {
+        assert_eq!(
+            NodeAxios.generate(&fixtures::upload_request()),
+            r#"const axios = require("axios");
+const fs = require("fs");
+
+const data = new FormData();
+data.append("title", "Hello");
+data.append("avatar", new Blob([fs.readFileSync("/home/john/me.png")], { type: "image/png" }), "avatar.png");
+
+axios({
+  method: "POST",
+  url: "https://www.example.com/upload",
+  data,
+}).then((response) => {
+  console.log(response.status);
+  console.log(response.data);
+});
+"#
+        );
+    }
+
+    #[test]
+    fn test_axios_raw() {
+        assert_eq!(
+            NodeAxios.generate(&fixtures::json_request()),
+            r#"const axios = require("axios");
+
+const data = "{\"name\": \"O'Brien\"}";
+
+axios({
+  method: "PUT",
+  url: "https://www.example.com/users/1",
+  headers: {
+    "Content-Type": "application/json",
+  },
+  data,
+}).then((response) => {
+  console.log(response.status);
+  console.log(response.data);
+});
+"#
+        );
+    }
+
+    #[test]
+    fn test_axios_body_file() {
+        assert_eq!(
+            NodeAxios.generate(&fixtures::file_request()),
+            r#"const axios = require("axios");
+const fs = require("fs");
+
+const data = fs.readFileSync("/home/john/me.png");
+
+axios({
+  method: "PUT",
+  url: "https://www.example.com/avatar",
+  headers: {
+    "Content-Type": "image/png",
+  },
+  data,
+}).then((response) => {
+  console.log(response.status);
+  console.log(response.data);
+});
+"#
+        );
+    }
+}
@@ -0,0 +1,447 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::client::{BoundPayload, BoundRequest};
+use crate::entities::RequestMethod;
+
+use super::{body_text, shell_method, CodeGenerator, ShellDialect};
+
+/// Exports a request as a curl command line for the given shell.
+pub struct Curl(pub ShellDialect);
+
+/// Quotes a value given to the -F option, when it would be split by curl otherwise.
+fn form_quote(value: &str) -> String {
+    if value.contains([';', ',', '"']) {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
+impl Curl {
+    /// Builds the arguments of the command, already quoted for the shell.
+    fn arguments(&self, request: &BoundRequest) -> Vec<String> {
+        let dialect = self.0;
+        let mut arguments = Vec::new();
+
+        // curl waits for a response body when HEAD is given with -X.
+        let method = if request.method == RequestMethod::Head {
+            "--head".to_string()
+        } else {
+            format!("-X {}", shell_method(dialect, &request.method))
+        };
+        arguments.push(format!("{method} {}", dialect.quote(&request.url)));
+        if let Some(digest) = &request.digest {
+            let credentials = format!("{}:{}", digest.username, digest.password);
+            arguments.push(format!("--digest -u {}", dialect.quote(&credentials)));
+        }
+
+        // Headers are exported in the same order they will be sent, so that
+        // repeated headers such as Cookie or Accept are kept in order.
+        let multipart = matches!(request.payload, BoundPayload::Multipart { .. });
+        for header in request.headers.iter() {
+            // curl computes the boundary of the form, so it sets the Content-Type.
+            if multipart && header.name.eq_ignore_ascii_case("content-type") {
+                continue;
+            }
+            // "Name:" would remove the header, "Name;" sends it empty.
+            let header = if header.value.is_empty() {
+                format!("{};", header.name)
+            } else {
+                format!("{}: {}", header.name, header.value)
+            };
+            arguments.push(format!("-H {}", dialect.quote(&header)));
+        }
+
+        match &request.payload {
+            BoundPayload::Multipart { fields, files, .. } => {
+                for (name, value) in fields {
+                    // -F reads files for values starting with @ or < and takes
+                    // options such as ;type= from the value, --form-string does not.
+                    let literal = value.starts_with(['@', '<']) || value.contains(';');
+                    let option = if literal { "--form-string" } else { "-F" };
+                    let field = format!("{name}={value}");
+                    arguments.push(format!("{option} {}", dialect.quote(&field)));
+                }
+                for file in files {
+                    let path = file.path.to_string_lossy();
+                    let mut field = format!("{}=@{}", file.name, form_quote(&path));
+                    if file
+                        .path
+                        .file_name()
+                        .map(|n| n.to_string_lossy())
+                        .as_deref()
+                        != Some(file.filename.as_str())
+                    {
+                        field.push_str(&format!(";filename={}", form_quote(&file.filename)));
+                    }
+                    field.push_str(&format!(";type={}", file.content_type));
+                    arguments.push(format!("-F {}", dialect.quote(&field)));
+                }
+            }
+            BoundPayload::Urlencoded(_) | BoundPayload::Raw(_) => {
+                // Command lines cannot carry every byte, so bodies which are
+                // not valid UTF-8 cannot be exported exactly.
+                if let Some(body) = body_text(request) {
+                    let option = if body.starts_with('@') {
+                        "--data-raw"
+                    } else {
+                        "--data-binary"
+                    };
+                    arguments.push(format!("{option} {}", dialect.quote(&body)));
+                }
+            }
+            BoundPayload::File(path) => {
+                // Unlike -d, --data-binary keeps the newlines of the file.
+                let body = format!("@{}", path.to_string_lossy());
+                arguments.push(format!("--data-binary {}", dialect.quote(&body)));
+            }
+            BoundPayload::None => {}
+        }
+
+        arguments
+    }
+}
+
+impl CodeGenerator for Curl {
+    fn name(&self) -> &'static str {
+        match self.0 {
+            ShellDialect::Posix => "cURL",
+            ShellDialect::Cmd => "cURL (Windows cmd)",
+            ShellDialect::PowerShell => "cURL (PowerShell)",
+        }
+    }
+
+    fn language(&self) -> &'static str {
+        match self.0 {
+            ShellDialect::Posix => "sh",
+            ShellDialect::Cmd => "dosbatch",
+            ShellDialect::PowerShell => "powershell",
+        }
+    }
+
+    fn generate(&self, request: &BoundRequest) -> String {
+        // In PowerShell, curl is an alias of Invoke-WebRequest.
+        let program = match self.0 {
+            ShellDialect::PowerShell => "curl.exe",
+            _ => "curl",
+        };
+        let separator = self.0.line_continuation();
+        let arguments = self.arguments(request).join(separator);
+        format!("{program} {arguments}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{BoundFile, BoundPayload, BoundRequest};
+    use crate::entities::{
+        EndpointData, FormFile, KeyValueTable, RawEncoding, RequestMethod, RequestPayload,
+    };
+    use crate::export::{fixtures, CodeGenerator, ShellDialect};
+    use crate::import::curl::parse_curl;
+    use crate::import::shell::split;
+
+    use super::Curl;
+
+    const POSIX: Curl = Curl(ShellDialect::Posix);
+
+    fn assert_round_trip(endpoint: EndpointData) {
+        let request = BoundRequest::try_from(endpoint.clone()).unwrap();
+        let command = POSIX.generate(&request);
+        assert_eq!(parse_curl(&command).unwrap(), endpoint);
+    }
+
+    #[test]
+    fn test_curl_get() {
+        assert_eq!(
+            POSIX.generate(&fixtures::get_request()),
+            "curl -X GET 'https://www.example.com/users?page=2' \\
+  -H 'Accept: application/json' \\
+  -H 'Cookie: a=1' \\
+  -H 'Cookie: b=2'"
+        );
+    }
+
+    #[test]
+    fn test_curl_head_does_not_use_request_option() {
+        let request = BoundRequest {
+            url: "https://www.example.com/".into(),
+            method: RequestMethod::Head,
+            ..Default::default()
+        };
+        assert_eq!(
+            POSIX.generate(&request),
+            "curl --head 'https://www.example.com/'"
+        );
+    }
+
+    #[test]
+    fn test_curl_urlencoded() {
+        assert_eq!(
+            POSIX.generate(&fixtures::urlencoded_request()),
+            "curl -X POST 'https://www.example.com/login' \\
+  -H 'Content-Type: application/x-www-form-urlencoded' \\
+  --data-binary 'user=john&password=it%27s+a+secret'"
+        );
+    }
+
+    #[test]
+    fn test_curl_multipart() {
+        assert_eq!(
+            POSIX.generate(&fixtures::multipart_request()),
+            "curl -X POST 'https://www.example.com/posts' \\
+  -F 'title=Hello'"
+        );
+    }
+
+    #[test]
+    fn test_curl_upload() {
+        assert_eq!(
+            POSIX.generate(&fixtures::upload_request()),
+            "curl -X POST 'https://www.example.com/upload' \\
+  -F 'title=Hello' \\
+  -F 'avatar=@/home/john/me.png;filename=avatar.png;type=image/png'"
+        );
+    }
+
+    #[test]
+    fn test_curl_upload_quotes_file_names() {
+        let request = BoundRequest {
+            url: "https://www.example.com/".into(),
+            method: RequestMethod::Post,
+            payload: BoundPayload::Multipart {
+                fields: vec![],
+                files: vec![BoundFile {
+                    name: "doc".into(),
+                    path: "/tmp/a;b.txt".into(),
+                    filename: "a;b.txt".into(),
+                    content_type: "text/plain".into(),
+                }],
+                boundary: "XYZ".into(),
+            },
+            ..Default::default()
+        };
+        let words = split(&POSIX.generate(&request)).unwrap();
+        assert_eq!(words[4..], ["-F", "doc=@\"/tmp/a;b.txt\";type=text/plain"]);
+    }
+
+    #[test]
+    fn test_curl_raw() {
+        assert_eq!(
+            POSIX.generate(&fixtures::json_request()),
+            r#"curl -X PUT 'https://www.example.com/users/1' \
+  -H 'Content-Type: application/json' \
+  --data-binary '{"name": "O'\''Brien"}'"#
+        );
+    }
+
+    #[test]
+    fn test_curl_quotes_hostile_values() {
+        let request = BoundRequest {
+            url: "https://www.example.com/search?q=it's&x=$(whoami)".into(),
+            method: RequestMethod::Post,
+            headers: KeyValueTable::new(&[
+                ("X-Quote", "a 'quoted' \"value\"").into(),
+                ("X-Empty", "").into(),
+                ("Content-Type", "text/plain").into(),
+            ]),
+            body: Some(b"line 1\nline 2 with `backticks` and \\ slashes".to_vec()),
+            payload: BoundPayload::Raw(RawEncoding::OctetStream),
+            ..Default::default()
+        };
+        let command = POSIX.generate(&request);
+        assert_eq!(
+            split(&command).unwrap(),
+            vec![
+                "curl",
+                "-X",
+                "POST",
+                "https://www.example.com/search?q=it's&x=$(whoami)",
+                "-H",
+                "X-Quote: a 'quoted' \"value\"",
+                "-H",
+                "X-Empty;",
+                "-H",
+                "Content-Type: text/plain",
+                "--data-binary",
+                "line 1\nline 2 with `backticks` and \\ slashes",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_curl_multipart_values_are_not_read_as_files() {
+        let request = BoundRequest {
+            url: "https://www.example.com/".into(),
+            method: RequestMethod::Post,
+            payload: BoundPayload::Multipart {
+                fields: vec![
+                    ("email".into(), "@john".into()),
+                    ("path".into(), "<etc/passwd".into()),
+                    ("mime".into(), "a;type=text/html".into()),
+                    ("name".into(), "John".into()),
+                ],
+                files: vec![],
+                boundary: "XYZ".into(),
+            },
+            ..Default::default()
+        };
+        let command = POSIX.generate(&request);
+        assert_eq!(
+            split(&command).unwrap()[4..],
+            [
+                "--form-string",
+                "email=@john",
+                "--form-string",
+                "path=<etc/passwd",
+                "--form-string",
+                "mime=a;type=text/html",
+                "-F",
+                "name=John",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_curl_raw_body_starting_with_at_sign() {
+        let request = BoundRequest {
+            url: "https://www.example.com/".into(),
+            method: RequestMethod::Post,
+            body: Some(b"@mention".to_vec()),
+            payload: BoundPayload::Raw(RawEncoding::OctetStream),
+            ..Default::default()
+        };
+        let words = split(&POSIX.generate(&request)).unwrap();
+        assert_eq!(words[4..], ["--data-raw", "@mention"]);
+    }
+
+    #[test]
+    fn test_curl_round_trip_with_xml_body() {
+        assert_round_trip(EndpointData {
+            url: "https://www.example.com/users".into(),
+            method: RequestMethod::Post,
+            body: RequestPayload::Raw {
+                encoding: RawEncoding::Xml,
+                content: b"<user name=\"O'Brien\">\n  <tag>a</tag>\n</user>".to_vec(),
+            },
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn test_curl_round_trip_with_multipart_body() {
+        let path = std::env::temp_dir().join(format!("cartero-curl-{}.png", std::process::id()));
+        std::fs::write(&path, b"\x89PNG contents").unwrap();
+        assert_round_trip(EndpointData {
+            url: "https://www.example.com/posts".into(),
+            method: RequestMethod::Post,
+            body: RequestPayload::Multipart {
+                params: KeyValueTable::new(&[
+                    ("title", "It's here").into(),
+                    ("handle", "@john").into(),
+                ]),
+                files: vec![FormFile {
+                    filename: Some("me; too.png".into()),
+                    content_type: Some("image/png".into()),
+                    ..FormFile::new("avatar", &path.to_string_lossy())
+                }],
+            },
+            ..Default::default()
+        });
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_curl_round_trip_with_body_file() {
+        let path = std::env::temp_dir().join(format!("cartero-curl-{}.bin", std::process::id()));
+        std::fs::write(&path, b"\x00\xff\r\n").unwrap();
+        assert_round_trip(EndpointData {
+            url: "https://www.example.com/blobs".into(),
+            method: RequestMethod::Put,
+            body: RequestPayload::File {
+                path: path.to_string_lossy().to_string(),
+                content_type: Some("application/x-protobuf".into()),
+            },
+            ..Default::default()
+        });
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_curl_cmd() {
+        let curl = Curl(ShellDialect::Cmd);
+        assert_eq!(
+            curl.generate(&fixtures::json_request()),
+            r#"curl -X PUT "https://www.example.com/users/1" ^
+  -H "Content-Type: application/json" ^
+  --data-binary "{""name"": ""O'Brien""}""#
+        );
+    }
+
+    #[test]
+    fn test_curl_cmd_percent_signs() {
+        let curl = Curl(ShellDialect::Cmd);
+        let request = BoundRequest {
+            url: "https://www.example.com/search?q=50%25%20off!".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            curl.generate(&request),
+            r#"curl -X GET "https://www.example.com/search?q=50"^%"25"^%"20off!""#
+        );
+    }
+
+    #[test]
+    fn test_curl_powershell() {
+        let curl = Curl(ShellDialect::PowerShell);
+        assert_eq!(
+            curl.generate(&fixtures::json_request()),
+            r#"curl.exe -X PUT 'https://www.example.com/users/1' `
+  -H 'Content-Type: application/json' `
+  --data-binary '{"name": "O''Brien"}'"#
+        );
+    }
+
+    #[test]
+    fn test_curl_body_file() {
+        assert_eq!(
+            POSIX.generate(&fixtures::file_request()),
+            "curl -X PUT 'https://www.example.com/avatar' \\
+  -H 'Content-Type: image/png' \\
+  --data-binary '@/home/john/me.png'"
+        );
+    }
+
+    #[test]
+    fn test_curl_custom_method() {
+        assert_eq!(
+            POSIX.generate(&fixtures::custom_request()),
+            "curl -X PROPFIND 'https://www.example.com/dav/' \\
+  -H 'Depth: 1'"
+        );
+    }
+
+    #[test]
+    fn test_curl_digest() {
+        assert_eq!(
+            POSIX.generate(&fixtures::digest_request()),
+            "curl -X GET 'https://www.example.com/private' \\
+  --digest -u 'john:it'\\''s a secret'"
+        );
+    }
+}
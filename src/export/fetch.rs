@@ -0,0 +1,252 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::client::{BoundPayload, BoundRequest};
+
+use super::{body_text, json_quote, merged_headers, reads_files, CodeGenerator};
+
+pub struct JavascriptFetch;
+
+impl CodeGenerator for JavascriptFetch {
+    fn name(&self) -> &'static str {
+        "JavaScript (fetch)"
+    }
+
+    fn language(&self) -> &'static str {
+        "js"
+    }
+
+    fn generate(&self, request: &BoundRequest) -> String {
+        let method: String = request.method.clone().into();
+        let multipart = matches!(request.payload, BoundPayload::Multipart { .. });
+        let headers = merged_headers(request, multipart);
+
+        let mut code = String::new();
+        if request.digest.is_some() {
+            code.push_str(
+                "// fetch cannot answer HTTP Digest challenges, so no credentials are sent.\n",
+            );
+        }
+        if reads_files(request) {
+            code.push_str("import { readFileSync } from \"node:fs\";\n\n");
+        }
+        let body = match &request.payload {
+            BoundPayload::Urlencoded(fields) => {
+                code.push_str("const body = new URLSearchParams();\n");
+                for (name, value) in fields {
+                    code.push_str(&format!(
+                        "body.append({}, {});\n",
+                        json_quote(name),
+                        json_quote(value)
+                    ));
+                }
+                code.push('\n');
+                true
+            }
+            BoundPayload::Multipart { fields, files, .. } => {
+                // The browser computes the Content-Type and the boundary by itself.
+                code.push_str("const body = new FormData();\n");
+                for (name, value) in fields {
+                    code.push_str(&format!(
+                        "body.append({}, {});\n",
+                        json_quote(name),
+                        json_quote(value)
+                    ));
+                }
+                for file in files {
+                    code.push_str(&format!(
+                        "body.append({}, new Blob([readFileSync({})], {{ type: {} }}), {});\n",
+                        json_quote(&file.name),
+                        json_quote(&file.path.to_string_lossy()),
+                        json_quote(&file.content_type),
+                        json_quote(&file.filename)
+                    ));
+                }
+                code.push('\n');
+                true
+            }
+            BoundPayload::Raw(_) => match body_text(request) {
+                Some(body) => {
+                    code.push_str(&format!("const body = {};\n\n", json_quote(&body)));
+                    true
+                }
+                None => false,
+            },
+            BoundPayload::File(path) => {
+                code.push_str(&format!(
+                    "const body = readFileSync({});\n\n",
+                    json_quote(&path.to_string_lossy())
+                ));
+                true
+            }
+            BoundPayload::None => false,
+        };
+
+        code.push_str(&format!(
+            "const response = await fetch({}, {{\n",
+            json_quote(&request.url)
+        ));
+        code.push_str(&format!("  method: {},\n", json_quote(&method)));
+        if !headers.is_empty() {
+            code.push_str("  headers: {\n");
+            for (name, value) in &headers {
+                code.push_str(&format!(
+                    "    {}: {},\n",
+                    json_quote(name),
+                    json_quote(value)
+                ));
+            }
+            code.push_str("  },\n");
+        }
+        if body {
+            code.push_str("  body,\n");
+        }
+        code.push_str("});\n");
+        code.push_str("console.log(response.status);\n");
+        code.push_str("console.log(await response.text());\n");
+        code
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::{fixtures, CodeGenerator};
+
+    use super::JavascriptFetch;
+
+    #[test]
+    fn test_fetch_get() {
+        assert_eq!(
+            JavascriptFetch.generate(&fixtures::get_request()),
+            r#"const response = await fetch("https://www.example.com/users?page=2", {
+  method: "GET",
+  headers: {
+    "Accept": "application/json",
+    "Cookie": "a=1; b=2",
+  },
+});
+console.log(response.status);
+console.log(await response.text());
+"#
+        );
+    }
+
+    #[test]
+    fn test_fetch_urlencoded() {
+        assert_eq!(
+            JavascriptFetch.generate(&fixtures::urlencoded_request()),
+            r#"const body = new URLSearchParams();
+body.append("user", "john");
+body.append("password", "it's a secret");
+
+const response = await fetch("https://www.example.com/login", {
+  method: "POST",
+  headers: {
+    "Content-Type": "application/x-www-form-urlencoded",
+  },
+  body,
+});
+console.log(response.status);
+console.log(await response.text());
+"#
+        );
+    }
+
+    #[test]
+    fn test_fetch_multipart() {
+        assert_eq!(
+            JavascriptFetch.generate(&fixtures::multipart_request()),
+            r#"const body = new FormData();
+body.append("title", "Hello");
+
+const response = await fetch("https://www.example.com/posts", {
+  method: "POST",
+  body,
+});
+console.log(response.status);
+console.log(await response.text());
+"#
+        );
+    }
+
+    #[test]
+    fn test_fetch_upload() {
+        assert_eq!(
+            JavascriptFetch.generate(&fixtures::upload_request()),
+            r#"import { readFileSync } from "node:fs";
+
+const body = new FormData();
+body.append("title", "Hello");
+body.append("avatar", new Blob([readFileSync("/home/john/me.png")], { type: "image/png" }), "avatar.png");
+
+const response = await fetch("https://www.example.com/upload", {
+  method: "POST",
+  body,
+});
+console.log(response.status);
+console.log(await response.text());
+"#
+        );
+    }
+
+    #[test]
+    fn test_fetch_raw() {
+        assert_eq!(
+            JavascriptFetch.generate(&fixtures::json_request()),
+            r#"const body = "{\"name\": \"O'Brien\"}";
+
+const response = await fetch("https://www.example.com/users/1", {
+  method: "PUT",
+  headers: {
+    "Content-Type": "application/json",
+  },
+  body,
+});
+console.log(response.status);
+console.log(await response.text());
+"#
+        );
+    }
+
+    #[test]
+    fn test_fetch_body_file() {
+        assert_eq!(
+            JavascriptFetch.generate(&fixtures::file_request()),
+            r#"import { readFileSync } from "node:fs";
+
+const body = readFileSync("/home/john/me.png");
+
+const response = await fetch("https://www.example.com/avatar", {
+  method: "PUT",
+  headers: {
+    "Content-Type": "image/png",
+  },
+  body,
+});
+console.log(response.status);
+console.log(await response.text());
+"#
+        );
+    }
+
+    #[test]
+    fn test_fetch_warns_about_digest() {
+        let code = JavascriptFetch.generate(&fixtures::digest_request());
+        assert!(code.starts_with("// fetch cannot answer HTTP Digest challenges"));
+        assert!(!code.contains("secret"));
+    }
+}
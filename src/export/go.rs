@@ -0,0 +1,307 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::client::{BoundPayload, BoundRequest};
+
+use super::{body_text, json_quote, plain_headers, CodeGenerator};
+
+pub struct GoNetHttp;
+
+impl CodeGenerator for GoNetHttp {
+    fn name(&self) -> &'static str {
+        "Go (net/http)"
+    }
+
+    fn language(&self) -> &'static str {
+        "go"
+    }
+
+    fn generate(&self, request: &BoundRequest) -> String {
+        let method: String = request.method.clone().into();
+        let multipart = matches!(request.payload, BoundPayload::Multipart { .. });
+        let uploads =
+            matches!(&request.payload, BoundPayload::Multipart { files, .. } if !files.is_empty());
+        let file = matches!(request.payload, BoundPayload::File(_));
+        let body = if multipart || file {
+            None
+        } else {
+            body_text(request)
+        };
+
+        let mut imports = vec!["fmt", "io", "net/http"];
+        if multipart {
+            imports.extend(["bytes", "mime/multipart"]);
+            if uploads {
+                imports.extend(["net/textproto", "os"]);
+            }
+        } else if file {
+            imports.push("os");
+        } else if body.is_some() {
+            imports.push("strings");
+        }
+        imports.sort_unstable();
+
+        let mut code = String::new();
+        if request.digest.is_some() {
+            code.push_str(
+                "// net/http cannot answer HTTP Digest challenges, so no credentials are sent.\n",
+            );
+        }
+        code.push_str("package main\n\nimport (\n");
+        for import in imports {
+            code.push_str(&format!("\t\"{import}\"\n"));
+        }
+        code.push_str(")\n\nfunc main() {\n");
+
+        let reader = match (&request.payload, &body) {
+            (BoundPayload::Multipart { fields, files, .. }, _) => {
+                code.push_str("\tbody := &bytes.Buffer{}\n");
+                code.push_str("\twriter := multipart.NewWriter(body)\n");
+                for (name, value) in fields {
+                    code.push_str(&format!(
+                        "\twriter.WriteField({}, {})\n",
+                        json_quote(name),
+                        json_quote(value)
+                    ));
+                }
+                for file in files {
+                    // Quotes are escaped in the same way as CreateFormFile does.
+                    let escape = |value: &str| value.replace('\\', "\\\\").replace('"', "\\\"");
+                    let disposition = format!(
+                        "form-data; name=\"{}\"; filename=\"{}\"",
+                        escape(&file.name),
+                        escape(&file.filename)
+                    );
+                    code.push_str("\t{\n");
+                    code.push_str(&format!(
+                        "\t\tfile, err := os.Open({})\n",
+                        json_quote(&file.path.to_string_lossy())
+                    ));
+                    code.push_str("\t\tif err != nil {\n\t\t\tpanic(err)\n\t\t}\n");
+                    code.push_str("\t\tdefer file.Close()\n");
+                    code.push_str("\t\theader := textproto.MIMEHeader{}\n");
+                    code.push_str(&format!(
+                        "\t\theader.Set(\"Content-Disposition\", {})\n",
+                        json_quote(&disposition)
+                    ));
+                    code.push_str(&format!(
+                        "\t\theader.Set(\"Content-Type\", {})\n",
+                        json_quote(&file.content_type)
+                    ));
+                    code.push_str("\t\tpart, err := writer.CreatePart(header)\n");
+                    code.push_str("\t\tif err != nil {\n\t\t\tpanic(err)\n\t\t}\n");
+                    code.push_str("\t\tif _, err := io.Copy(part, file); err != nil {\n\t\t\tpanic(err)\n\t\t}\n");
+                    code.push_str("\t}\n");
+                }
+                code.push_str("\twriter.Close()\n\n");
+                "body"
+            }
+            (BoundPayload::File(path), _) => {
+                code.push_str(&format!(
+                    "\tbody, err := os.Open({})\n",
+                    json_quote(&path.to_string_lossy())
+                ));
+                code.push_str("\tif err != nil {\n\t\tpanic(err)\n\t}\n");
+                code.push_str("\tdefer body.Close()\n\n");
+                "body"
+            }
+            (_, Some(body)) => {
+                code.push_str(&format!(
+                    "\tbody := strings.NewReader({})\n",
+                    json_quote(body)
+                ));
+                "body"
+            }
+            _ => "nil",
+        };
+
+        code.push_str(&format!(
+            "\treq, err := http.NewRequest({}, {}, {})\n",
+            json_quote(&method),
+            json_quote(&request.url),
+            reader
+        ));
+        code.push_str("\tif err != nil {\n\t\tpanic(err)\n\t}\n");
+        // The multipart writer knows the boundary, so it gives the Content-Type.
+        for (name, value) in plain_headers(request, multipart) {
+            code.push_str(&format!(
+                "\treq.Header.Add({}, {})\n",
+                json_quote(&name),
+                json_quote(&value)
+            ));
+        }
+        if multipart {
+            code.push_str("\treq.Header.Set(\"Content-Type\", writer.FormDataContentType())\n");
+        }
+
+        code.push_str("\n\tres, err := http.DefaultClient.Do(req)\n");
+        code.push_str("\tif err != nil {\n\t\tpanic(err)\n\t}\n");
+        code.push_str("\tdefer res.Body.Close()\n\n");
+        code.push_str("\tcontent, err := io.ReadAll(res.Body)\n");
+        code.push_str("\tif err != nil {\n\t\tpanic(err)\n\t}\n");
+        code.push_str("\tfmt.Println(res.Status)\n");
+        code.push_str("\tfmt.Println(string(content))\n");
+        code.push_str("}\n");
+        code
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::{fixtures, CodeGenerator};
+
+    use super::GoNetHttp;
+
+    #[test]
+    fn test_go_get() {
+        assert_eq!(
+            GoNetHttp.generate(&fixtures::get_request()),
+            r#"package main
+
+import (
+	"fmt"
+	"io"
+	"net/http"
+)
+
+func main() {
+	req, err := http.NewRequest("GET", "https://www.example.com/users?page=2", nil)
+	if err != nil {
+		panic(err)
+	}
+	req.Header.Add("Accept", "application/json")
+	req.Header.Add("Cookie", "a=1")
+	req.Header.Add("Cookie", "b=2")
+
+	res, err := http.DefaultClient.Do(req)
+	if err != nil {
+		panic(err)
+	}
+	defer res.Body.Close()
+
+	content, err := io.ReadAll(res.Body)
+	if err != nil {
+		panic(err)
+	}
+	fmt.Println(res.Status)
+	fmt.Println(string(content))
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_go_urlencoded() {
+        let code = GoNetHttp.generate(&fixtures::urlencoded_request());
+        assert!(code.contains("\t\"strings\"\n"));
+        assert!(code.contains(
+            "\tbody := strings.NewReader(\"user=john&password=it%27s+a+secret\")\n\
+             \treq, err := http.NewRequest(\"POST\", \"https://www.example.com/login\", body)\n"
+        ));
+        assert!(code.contains(
+            "\treq.Header.Add(\"Content-Type\", \"application/x-www-form-urlencoded\")\n"
+        ));
+    }
+
+    #[test]
+    fn test_go_multipart() {
+        assert_eq!(
+            GoNetHttp.generate(&fixtures::multipart_request()),
+            r#"package main
+
+import (
+	"bytes"
+	"fmt"
+	"io"
+	"mime/multipart"
+	"net/http"
+)
+
+func main() {
+	body := &bytes.Buffer{}
+	writer := multipart.NewWriter(body)
+	writer.WriteField("title", "Hello")
+	writer.Close()
+
+	req, err := http.NewRequest("POST", "https://www.example.com/posts", body)
+	if err != nil {
+		panic(err)
+	}
+	req.Header.Set("Content-Type", writer.FormDataContentType())
+
+	res, err := http.DefaultClient.Do(req)
+	if err != nil {
+		panic(err)
+	}
+	defer res.Body.Close()
+
+	content, err := io.ReadAll(res.Body)
+	if err != nil {
+		panic(err)
+	}
+	fmt.Println(res.Status)
+	fmt.Println(string(content))
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_go_upload() {
+        let code = GoNetHttp.generate(&fixtures::upload_request());
+        assert!(code.contains("\t\"net/textproto\"\n\t\"os\"\n)\n"));
+        assert!(code.contains(
+            r#"	writer.WriteField("title", "Hello")
+	{
+		file, err := os.Open("/home/john/me.png")
+		if err != nil {
+			panic(err)
+		}
+		defer file.Close()
+		header := textproto.MIMEHeader{}
+		header.Set("Content-Disposition", "form-data; name=\"avatar\"; filename=\"avatar.png\"")
+		header.Set("Content-Type", "image/png")
+		part, err := writer.CreatePart(header)
+		if err != nil {
+			panic(err)
+		}
+		if _, err := io.Copy(part, file); err != nil {
+			panic(err)
+		}
+	}
+	writer.Close()
+"#
+        ));
+    }
+
+    #[test]
+    fn test_go_raw() {
+        let code = GoNetHttp.generate(&fixtures::json_request());
+        assert!(code.contains("\tbody := strings.NewReader(\"{\\\"name\\\": \\\"O'Brien\\\"}\")\n"));
+        assert!(code.contains("\treq.Header.Add(\"Content-Type\", \"application/json\")\n"));
+    }
+
+    #[test]
+    fn test_go_body_file() {
+        let code = GoNetHttp.generate(&fixtures::file_request());
+        assert!(code.contains("\t\"os\"\n"));
+        assert!(!code.contains("strings"));
+        assert!(code.contains("\tbody, err := os.Open(\"/home/john/me.png\")\n"));
+        assert!(code.contains("\tdefer body.Close()\n"));
+        assert!(code.contains("http.NewRequest(\"PUT\", \"https://www.example.com/avatar\", body)"));
+    }
+}
@@ -0,0 +1,204 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::client::{BoundPayload, BoundRequest};
+
+use super::{body_text, plain_headers, shell_method, shell_quote, CodeGenerator, ShellDialect};
+
+pub struct Httpie;
+
+/// Escapes the characters that HTTPie would take as a separator in a field name.
+fn escape_field_name(name: &str) -> String {
+    let mut escaped = String::new();
+    for c in name.chars() {
+        if matches!(c, '\\' | ':' | '=' | '@') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+impl CodeGenerator for Httpie {
+    fn name(&self) -> &'static str {
+        "HTTPie"
+    }
+
+    fn language(&self) -> &'static str {
+        "sh"
+    }
+
+    fn generate(&self, request: &BoundRequest) -> String {
+        let method = shell_method(ShellDialect::Posix, &request.method);
+
+        let (flag, form, files) = match &request.payload {
+            BoundPayload::Urlencoded(fields) => (Some("--form"), Some(fields), &[][..]),
+            BoundPayload::Multipart { fields, files, .. } => {
+                (Some("--multipart"), Some(fields), files.as_slice())
+            }
+            _ => (None, None, &[][..]),
+        };
+
+        let mut words = vec!["http".to_string()];
+        if let Some(flag) = flag {
+            words.push(flag.into());
+        }
+        if let Some(digest) = &request.digest {
+            let credentials = format!("{}:{}", digest.username, digest.password);
+            words.push(format!(
+                "--auth-type=digest --auth={}",
+                shell_quote(&credentials)
+            ));
+        }
+        words.push(method);
+        words.push(shell_quote(&request.url));
+
+        // HTTPie computes the Content-Type of the forms by itself.
+        let mut items = Vec::new();
+        for (name, value) in plain_headers(request, form.is_some()) {
+            // An empty value would remove the header, a semicolon sends it empty.
+            let item = if value.is_empty() {
+                format!("{name};")
+            } else {
+                format!("{name}:{value}")
+            };
+            items.push(shell_quote(&item));
+        }
+        match form {
+            Some(fields) => {
+                for (name, value) in fields {
+                    let item = format!("{}={}", escape_field_name(name), value);
+                    items.push(shell_quote(&item));
+                }
+                for file in files {
+                    let item = format!(
+                        "{}@{};type={}",
+                        escape_field_name(&file.name),
+                        file.path.to_string_lossy(),
+                        file.content_type
+                    );
+                    items.push(shell_quote(&item));
+                }
+            }
+            None => match &request.payload {
+                // HTTPie sends the file given with @ as the whole body.
+                BoundPayload::File(path) => {
+                    items.push(shell_quote(&format!("@{}", path.to_string_lossy())))
+                }
+                _ => {
+                    if let Some(body) = body_text(request) {
+                        items.push(format!("--raw {}", shell_quote(&body)));
+                    }
+                }
+            },
+        }
+
+        // HTTPie always sends the files with the name they have in the disk.
+        let mut command = String::new();
+        for file in files {
+            let disk_name = file.path.file_name().map(|name| name.to_string_lossy());
+            if disk_name.as_deref() != Some(file.filename.as_str()) {
+                command.push_str(&format!(
+                    "# HTTPie cannot rename files, {} is not sent as {}.\n",
+                    file.name, file.filename
+                ));
+            }
+        }
+        command.push_str(&words.join(" "));
+        for item in items {
+            command.push_str(" \\\n  ");
+            command.push_str(&item);
+        }
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::{fixtures, CodeGenerator};
+
+    use super::Httpie;
+
+    #[test]
+    fn test_httpie_get() {
+        assert_eq!(
+            Httpie.generate(&fixtures::get_request()),
+            "http GET 'https://www.example.com/users?page=2' \\
+  'Accept:application/json' \\
+  'Cookie:a=1' \\
+  'Cookie:b=2'"
+        );
+    }
+
+    #[test]
+    fn test_httpie_urlencoded() {
+        assert_eq!(
+            Httpie.generate(&fixtures::urlencoded_request()),
+            "http --form POST 'https://www.example.com/login' \\
+  'user=john' \\
+  'password=it'\\''s a secret'"
+        );
+    }
+
+    #[test]
+    fn test_httpie_multipart() {
+        assert_eq!(
+            Httpie.generate(&fixtures::multipart_request()),
+            "http --multipart POST 'https://www.example.com/posts' \\
+  'title=Hello'"
+        );
+    }
+
+    #[test]
+    fn test_httpie_upload() {
+        assert_eq!(
+            Httpie.generate(&fixtures::upload_request()),
+            "# HTTPie cannot rename files, avatar is not sent as avatar.png.
+http --multipart POST 'https://www.example.com/upload' \\
+  'title=Hello' \\
+  'avatar@/home/john/me.png;type=image/png'"
+        );
+    }
+
+    #[test]
+    fn test_httpie_raw() {
+        assert_eq!(
+            Httpie.generate(&fixtures::json_request()),
+            "http PUT 'https://www.example.com/users/1' \\
+  'Content-Type:application/json' \\
+  --raw '{\"name\": \"O'\\''Brien\"}'"
+        );
+    }
+
+    #[test]
+    fn test_httpie_body_file() {
+        assert_eq!(
+            Httpie.generate(&fixtures::file_request()),
+            "http PUT 'https://www.example.com/avatar' \\
+  'Content-Type:image/png' \\
+  '@/home/john/me.png'"
+        );
+    }
+
+    #[test]
+    fn test_httpie_digest() {
+        assert_eq!(
+            Httpie.generate(&fixtures::digest_request()),
+            "http --auth-type=digest --auth='john:it'\\''s a secret' GET 'https://www.example.com/private'"
+        );
+    }
+}
@@ -0,0 +1,503 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Code generators that turn a bound request into a snippet of code.
+//!
+//! Every generator works from a `BoundRequest`, so the variables have already
+//! been replaced and the headers are exactly the ones that would be sent. The
+//! `BoundPayload` of the request tells each generator which kind of body it has
+//! to write, so that forms can be exported using the native API of every language.
+
+use std::borrow::Cow;
+
+use crate::client::{BoundPayload, BoundRequest};
+use crate::entities::RequestMethod;
+
+pub mod axios;
+pub mod curl;
+pub mod fetch;
+pub mod go;
+pub mod httpie;
+pub mod powershell;
+pub mod python;
+pub mod reqwest;
+pub mod wget;
+
+pub trait CodeGenerator: Sync {
+    /// The name of the generator, as presented to the user.
+    fn name(&self) -> &'static str;
+
+    /// The GtkSourceView identifier of the language of the generated code.
+    fn language(&self) -> &'static str;
+
+    /// Generates the code that performs the given request.
+    fn generate(&self, request: &BoundRequest) -> String;
+}
+
+/// Every available generator, in the order they should be presented.
+pub fn generators() -> &'static [&'static dyn CodeGenerator] {
+    &[
+        &curl::Curl(ShellDialect::Posix),
+        &curl::Curl(ShellDialect::Cmd),
+        &curl::Curl(ShellDialect::PowerShell),
+        &python::PythonRequests,
+        &fetch::JavascriptFetch,
+        &axios::NodeAxios,
+        &httpie::Httpie,
+        &wget::Wget,
+        &powershell::PowerShell,
+        &reqwest::RustReqwest,
+        &go::GoNetHttp,
+    ]
+}
+
+/// Returns the body of the request as text, or None if the request has no body.
+///
+/// A form without files is encoded here, but the files of a form are never read.
+fn body_text(request: &BoundRequest) -> Option<String> {
+    let body = match &request.payload {
+        BoundPayload::Multipart { files, .. } if files.is_empty() => request.read_body().ok(),
+        _ => request.body.as_deref().map(Cow::Borrowed),
+    };
+    body.map(|body| String::from_utf8_lossy(&body).to_string())
+}
+
+/// Returns whether the code has to read files from disk to build the body.
+fn reads_files(request: &BoundRequest) -> bool {
+    match &request.payload {
+        BoundPayload::Multipart { files, .. } => !files.is_empty(),
+        BoundPayload::File(_) => true,
+        _ => false,
+    }
+}
+
+/// Returns the headers of the request, with the repeated headers merged.
+///
+/// This is for the languages whose libraries take the headers as a dictionary.
+/// The values of a repeated header are joined in the order they appear, using a
+/// semicolon for cookies and a comma for everything else, as RFC 9110 allows.
+///
+/// When `skip_content_type` is true, the Content-Type header is not included,
+/// for the cases where the library computes it by itself, such as multipart forms.
+fn merged_headers(request: &BoundRequest, skip_content_type: bool) -> Vec<(String, String)> {
+    let mut merged: Vec<(String, String)> = Vec::new();
+    for header in request.headers.iter() {
+        if skip_content_type && header.name.eq_ignore_ascii_case("content-type") {
+            continue;
+        }
+        match merged
+            .iter_mut()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&header.name))
+        {
+            Some((name, value)) => {
+                let separator = if name.eq_ignore_ascii_case("cookie") {
+                    "; "
+                } else {
+                    ", "
+                };
+                value.push_str(separator);
+                value.push_str(&header.value);
+            }
+            None => merged.push((header.name.clone(), header.value.clone())),
+        }
+    }
+    merged
+}
+
+/// Returns the headers of the request, skipping the Content-Type if requested.
+fn plain_headers(request: &BoundRequest, skip_content_type: bool) -> Vec<(String, String)> {
+    request
+        .headers
+        .iter()
+        .filter(|h| !(skip_content_type && h.name.eq_ignore_ascii_case("content-type")))
+        .map(|h| (h.name.clone(), h.value.clone()))
+        .collect()
+}
+
+/// The shell a command line is written for, which decides how words are quoted.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ShellDialect {
+    /// sh, bash, zsh and any other shell following the POSIX quoting rules.
+    #[default]
+    Posix,
+    /// The Windows command prompt, cmd.exe.
+    Cmd,
+    /// PowerShell, either Windows PowerShell or PowerShell 7.
+    PowerShell,
+}
+
+impl ShellDialect {
+    /// Quotes a word, so that the program receives it exactly as given.
+    pub fn quote(&self, word: &str) -> String {
+        match self {
+            ShellDialect::Posix => format!("'{}'", word.replace('\'', "'\\''")),
+            ShellDialect::Cmd => {
+                // cmd.exe cannot have a newline inside quotes, but a caret followed
+                // by an empty line puts one between two quoted parts of the word.
+                // Carriage returns are always removed by cmd.exe, so they are lost.
+                // Variables are expanded even inside quotes, so every percent sign
+                // leaves the quotes and is escaped with a caret, which is how the
+                // interactive prompt takes it literally. Delayed expansion is off
+                // in the prompt, so exclamation marks need no escaping.
+                let parts: Vec<String> = word
+                    .replace('\r', "")
+                    .split('\n')
+                    .map(|part| quote_msvcrt(part).replace('%', "\"^%\""))
+                    .collect();
+                parts.join("^\n\n")
+            }
+            ShellDialect::PowerShell => {
+                // PowerShell also takes the typographic single quotes as quotes.
+                let mut quoted = String::from("'");
+                for c in word.chars() {
+                    if matches!(c, '\'' | '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}') {
+                        quoted.push(c);
+                    }
+                    quoted.push(c);
+                }
+                quoted.push('\'');
+                quoted
+            }
+        }
+    }
+
+    /// The characters that continue a command in the next line.
+    pub fn line_continuation(&self) -> &'static str {
+        match self {
+            ShellDialect::Posix => " \\\n  ",
+            ShellDialect::Cmd => " ^\n  ",
+            ShellDialect::PowerShell => " `\n  ",
+        }
+    }
+}
+
+/// Quotes a word for the command line parser of the Microsoft C runtime.
+///
+/// Backslashes are only special before a double quote, where they have to
+/// be doubled. Quotes are written twice, which keeps cmd.exe inside quotes.
+fn quote_msvcrt(word: &str) -> String {
+    let mut quoted = String::from("\"");
+    let mut backslashes = 0;
+    for c in word.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                quoted.push_str(&"\\".repeat(backslashes * 2));
+                quoted.push_str("\"\"");
+                backslashes = 0;
+            }
+            _ => {
+                quoted.push_str(&"\\".repeat(backslashes));
+                quoted.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+/// Returns the method as a word of a command line, only quoted when needed.
+///
+/// Custom methods may have characters such as `$` or `|`, which are valid in a
+/// method name but have a meaning for the shell.
+fn shell_method(dialect: ShellDialect, method: &RequestMethod) -> String {
+    let name = method.as_str();
+    if name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        name.to_string()
+    } else {
+        dialect.quote(name)
+    }
+}
+
+/// Quotes a word for a POSIX shell, using single quotes.
+fn shell_quote(word: &str) -> String {
+    ShellDialect::Posix.quote(word)
+}
+
+/// Quotes a string using the JSON syntax, which is also valid in JavaScript and Go.
+fn json_quote(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod fixtures {
+    use crate::client::{BoundFile, BoundPayload, BoundRequest, DigestCredentials};
+    use crate::entities::{KeyValueTable, RawEncoding, RequestMethod};
+
+    pub fn get_request() -> BoundRequest {
+        BoundRequest {
+            url: "https://www.example.com/users?page=2".into(),
+            method: RequestMethod::Get,
+            headers: KeyValueTable::new(&[
+                ("Accept", "application/json").into(),
+                ("Cookie", "a=1").into(),
+                ("Cookie", "b=2").into(),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    pub fn urlencoded_request() -> BoundRequest {
+        BoundRequest {
+            url: "https://www.example.com/login".into(),
+            method: RequestMethod::Post,
+            headers: KeyValueTable::new(&[
+                ("Content-Type", "application/x-www-form-urlencoded").into()
+            ]),
+            body: Some(b"user=john&password=it%27s+a+secret".to_vec()),
+            payload: BoundPayload::Urlencoded(vec![
+                ("user".into(), "john".into()),
+                ("password".into(), "it's a secret".into()),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    pub fn multipart_request() -> BoundRequest {
+        BoundRequest {
+            url: "https://www.example.com/posts".into(),
+            method: RequestMethod::Post,
+            headers: KeyValueTable::new(&[
+                ("Content-Type", "multipart/form-data; boundary=XYZ").into()
+            ]),
+            payload: BoundPayload::Multipart {
+                fields: vec![("title".into(), "Hello".into())],
+                files: vec![],
+                boundary: "XYZ".into(),
+            },
+            ..Default::default()
+        }
+    }
+
+    pub fn upload_request() -> BoundRequest {
+        BoundRequest {
+            url: "https://www.example.com/upload".into(),
+            method: RequestMethod::Post,
+            headers: KeyValueTable::new(&[
+                ("Content-Type", "multipart/form-data; boundary=XYZ").into()
+            ]),
+            payload: BoundPayload::Multipart {
+                fields: vec![("title".into(), "Hello".into())],
+                files: vec![BoundFile {
+                    name: "avatar".into(),
+                    path: "/home/john/me.png".into(),
+                    filename: "avatar.png".into(),
+                    content_type: "image/png".into(),
+                }],
+                boundary: "XYZ".into(),
+            },
+            ..Default::default()
+        }
+    }
+
+    pub fn file_request() -> BoundRequest {
+        BoundRequest {
+            url: "https://www.example.com/avatar".into(),
+            method: RequestMethod::Put,
+            headers: KeyValueTable::new(&[("Content-Type", "image/png").into()]),
+            body: Some(b"\x89PNG\r\n".to_vec()),
+            payload: BoundPayload::File("/home/john/me.png".into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn digest_request() -> BoundRequest {
+        BoundRequest {
+            url: "https://www.example.com/private".into(),
+            method: RequestMethod::Get,
+            digest: Some(DigestCredentials {
+                username: "john".into(),
+                password: "it's a secret".into(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    pub fn custom_request() -> BoundRequest {
+        BoundRequest {
+            url: "https://www.example.com/dav/".into(),
+            method: RequestMethod::Custom("PROPFIND".into()),
+            headers: KeyValueTable::new(&[("Depth", "1").into()]),
+            ..Default::default()
+        }
+    }
+
+    pub fn json_request() -> BoundRequest {
+        BoundRequest {
+            url: "https://www.example.com/users/1".into(),
+            method: RequestMethod::Put,
+            headers: KeyValueTable::new(&[("Content-Type", "application/json").into()]),
+            body: Some(br#"{"name": "O'Brien"}"#.to_vec()),
+            payload: BoundPayload::Raw(RawEncoding::Json),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fixtures, merged_headers, shell_method, shell_quote, ShellDialect};
+    use crate::entities::RequestMethod;
+
+    #[test]
+    fn test_merged_headers_join_repeated_values() {
+        let request = fixtures::get_request();
+        assert_eq!(
+            merged_headers(&request, false),
+            vec![
+                ("Accept".into(), "application/json".into()),
+                ("Cookie".into(), "a=1; b=2".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+
+    #[test]
+    fn test_shell_method() {
+        let posix = ShellDialect::Posix;
+        assert_eq!(shell_method(posix, &RequestMethod::Get), "GET");
+        let propfind = RequestMethod::Custom("PROPFIND".into());
+        assert_eq!(shell_method(posix, &propfind), "PROPFIND");
+        let odd = RequestMethod::Custom("PU$H".into());
+        assert_eq!(shell_method(posix, &odd), "'PU$H'");
+    }
+
+    #[test]
+    fn test_cmd_quote() {
+        let cmd = ShellDialect::Cmd;
+        assert_eq!(cmd.quote("a & b"), r#""a & b""#);
+        assert_eq!(cmd.quote(r#"say "hi""#), r#""say ""hi""""#);
+        assert_eq!(cmd.quote(r"C:\dir\"), r#""C:\dir\\""#);
+        assert_eq!(cmd.quote(r#"a\"b"#), r#""a\\""b""#);
+        assert_eq!(cmd.quote("a\r\nb"), "\"a\"^\n\n\"b\"");
+        assert_eq!(cmd.quote("100% %PATH%"), r#""100"^%" "^%"PATH"^%"""#);
+        assert_eq!(cmd.quote("Hi!"), r#""Hi!""#);
+        assert_eq!(cmd.quote("$HOME `date`"), r#""$HOME `date`""#);
+        assert_eq!(cmd.quote("it's"), r#""it's""#);
+
+        let words = [
+            "a & b | c > d",
+            r#"say "hi""#,
+            r#"C:\dir\ a\"b"#,
+            "100% %PATH% %%PATH%% %\"PATH\"%",
+            "Hi! ^caret^",
+            "one\r\ntwo\nthree",
+        ];
+        for word in words {
+            let args = msvcrt_args(&cmd_prompt(&cmd.quote(word)));
+            assert_eq!(args, vec![word.replace('\r', "")]);
+        }
+    }
+
+    /// Runs a line through the variable expansion and the caret handling of the
+    /// interactive cmd.exe prompt, with PATH as the only defined variable.
+    fn cmd_prompt(line: &str) -> String {
+        let mut expanded = String::new();
+        let mut rest = line;
+        while let Some(start) = rest.find('%') {
+            expanded.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            match after.find('%') {
+                Some(end) if after[..end].eq_ignore_ascii_case("PATH") => {
+                    expanded.push_str("C:\\Windows");
+                    rest = &after[end + 1..];
+                }
+                _ => {
+                    expanded.push('%');
+                    rest = after;
+                }
+            }
+        }
+        expanded.push_str(rest);
+
+        let mut line = String::new();
+        let mut quoted = false;
+        let mut chars = expanded.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => {
+                    quoted = !quoted;
+                    line.push(c);
+                }
+                // A caret before a line break drops it and keeps the next character.
+                '^' if !quoted => match chars.next() {
+                    Some('\n') => line.extend(chars.next()),
+                    next => line.extend(next),
+                },
+                _ => line.push(c),
+            }
+        }
+        line
+    }
+
+    /// Splits a command line into arguments as the Microsoft C runtime does.
+    fn msvcrt_args(line: &str) -> Vec<String> {
+        let mut args = vec![];
+        let mut arg: Option<String> = None;
+        let mut quoted = false;
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    let mut count = 1;
+                    while chars.next_if_eq(&'\\').is_some() {
+                        count += 1;
+                    }
+                    let arg = arg.get_or_insert_with(String::new);
+                    if chars.peek() == Some(&'"') {
+                        arg.push_str(&"\\".repeat(count / 2));
+                        if count % 2 == 1 {
+                            arg.push(chars.next().unwrap());
+                        }
+                    } else {
+                        arg.push_str(&"\\".repeat(count));
+                    }
+                }
+                '"' if quoted && chars.next_if_eq(&'"').is_some() => {
+                    arg.get_or_insert_with(String::new).push('"');
+                }
+                '"' => {
+                    quoted = !quoted;
+                    arg.get_or_insert_with(String::new);
+                }
+                ' ' | '\t' if !quoted => args.extend(arg.take()),
+                _ => arg.get_or_insert_with(String::new).push(c),
+            }
+        }
+        args.extend(arg);
+        args
+    }
+
+    #[test]
+    fn test_powershell_quote() {
+        let powershell = ShellDialect::PowerShell;
+        assert_eq!(powershell.quote("it's $HOME"), "'it''s $HOME'");
+        assert_eq!(powershell.quote("it\u{2019}s"), "'it\u{2019}\u{2019}s'");
+        assert_eq!(powershell.quote(r#"say "hi""#), r#"'say "hi"'"#);
+        assert_eq!(powershell.quote("`date` $(ls)"), "'`date` $(ls)'");
+        assert_eq!(powershell.quote("100% %PATH%"), "'100% %PATH%'");
+    }
+}
@@ -0,0 +1,300 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::client::{BoundPayload, BoundRequest};
+use crate::entities::RequestMethod;
+
+use super::{body_text, merged_headers, CodeGenerator};
+
+pub struct PowerShell;
+
+/// Quotes a string as a PowerShell single quoted string.
+fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+/// Groups the fields of a form by name, since hashtables cannot repeat keys.
+fn group_fields(fields: &[(String, String)]) -> Vec<(&str, Vec<&str>)> {
+    let mut groups: Vec<(&str, Vec<&str>)> = Vec::new();
+    for (name, value) in fields {
+        match groups
+            .iter_mut()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        {
+            Some((_, values)) => values.push(value),
+            None => groups.push((name, vec![value])),
+        }
+    }
+    groups
+}
+
+impl CodeGenerator for PowerShell {
+    fn name(&self) -> &'static str {
+        "PowerShell"
+    }
+
+    fn language(&self) -> &'static str {
+        "powershell"
+    }
+
+    fn generate(&self, request: &BoundRequest) -> String {
+        // Windows PowerShell refuses a Content-Type in the headers, so it
+        // is given with -ContentType, unless the form computes it by itself.
+        let content_type = request
+            .headers
+            .header("Content-Type")
+            .and_then(|values| values.first().map(|v| v.to_string()));
+        let headers = merged_headers(request, true);
+
+        let mut code = String::new();
+        let mut arguments = vec![format!("-Uri {}", quote(&request.url))];
+        match &request.method {
+            RequestMethod::Custom(method) => {
+                code.push_str("# -CustomMethod requires PowerShell 6 or newer.\n");
+                arguments.push(format!("-CustomMethod {}", quote(method)));
+            }
+            method => arguments.push(format!("-Method {}", quote(method.as_str()))),
+        }
+
+        if !headers.is_empty() {
+            code.push_str("$headers = @{\n");
+            for (name, value) in &headers {
+                code.push_str(&format!("    {} = {}\n", quote(name), quote(value)));
+            }
+            code.push_str("}\n");
+            arguments.push("-Headers $headers".into());
+        }
+        if let Some(digest) = &request.digest {
+            code.push_str(&format!(
+                "$password = ConvertTo-SecureString {} -AsPlainText -Force\n",
+                quote(&digest.password)
+            ));
+            code.push_str(&format!(
+                "$credential = New-Object System.Management.Automation.PSCredential({}, $password)\n",
+                quote(&digest.username)
+            ));
+            arguments.push("-Credential $credential".into());
+        }
+
+        match &request.payload {
+            BoundPayload::Multipart { fields, files, .. } => {
+                code.push_str("# -Form requires PowerShell 6.1 or newer.\n");
+                if !files.is_empty() {
+                    code.push_str(
+                        "# Files are sent with their own name, as application/octet-stream.\n",
+                    );
+                }
+                code.push_str("$form = [ordered]@{\n");
+                for (name, values) in group_fields(fields) {
+                    let value = match values.as_slice() {
+                        [value] => quote(value),
+                        values => {
+                            let values: Vec<String> = values.iter().map(|v| quote(v)).collect();
+                            format!("@({})", values.join(", "))
+                        }
+                    };
+                    code.push_str(&format!("    {} = {}\n", quote(name), value));
+                }
+                for file in files {
+                    code.push_str(&format!(
+                        "    {} = Get-Item -LiteralPath {}\n",
+                        quote(&file.name),
+                        quote(&file.path.to_string_lossy())
+                    ));
+                }
+                code.push_str("}\n");
+                arguments.push("-Form $form".into());
+            }
+            BoundPayload::Urlencoded(_) | BoundPayload::Raw(_) => {
+                if let Some(content_type) = &content_type {
+                    arguments.push(format!("-ContentType {}", quote(content_type)));
+                }
+                if let Some(body) = body_text(request) {
+                    code.push_str(&format!("$body = {}\n", quote(&body)));
+                    arguments.push("-Body $body".into());
+                }
+            }
+            BoundPayload::File(path) => {
+                if let Some(content_type) = &content_type {
+                    arguments.push(format!("-ContentType {}", quote(content_type)));
+                }
+                arguments.push(format!("-InFile {}", quote(&path.to_string_lossy())));
+            }
+            BoundPayload::None => {
+                if let Some(content_type) = &content_type {
+                    arguments.push(format!("-ContentType {}", quote(content_type)));
+                }
+            }
+        }
+
+        if !code.is_empty() {
+            code.push('\n');
+        }
+        code.push_str(&format!(
+            "$response = Invoke-WebRequest {}\n",
+            arguments.join(" ")
+        ));
+        code.push_str("$response.StatusCode\n");
+        code.push_str("$response.Content\n");
+        code
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{BoundPayload, BoundRequest};
+    use crate::entities::{KeyValueTable, RequestMethod};
+    use crate::export::{fixtures, CodeGenerator};
+
+    use super::PowerShell;
+
+    #[test]
+    fn test_powershell_get() {
+        assert_eq!(
+            PowerShell.generate(&fixtures::get_request()),
+            "$headers = @{
+    'Accept' = 'application/json'
+    'Cookie' = 'a=1; b=2'
+}
+
+$response = Invoke-WebRequest -Uri 'https://www.example.com/users?page=2' -Method 'GET' -Headers $headers
+$response.StatusCode
+$response.Content
+"
+        );
+    }
+
+    #[test]
+    fn test_powershell_urlencoded() {
+        assert_eq!(
+            PowerShell.generate(&fixtures::urlencoded_request()),
+            "$body = 'user=john&password=it%27s+a+secret'
+
+$response = Invoke-WebRequest -Uri 'https://www.example.com/login' -Method 'POST' -ContentType 'application/x-www-form-urlencoded' -Body $body
+$response.StatusCode
+$response.Content
+"
+        );
+    }
+
+    #[test]
+    fn test_powershell_multipart() {
+        assert_eq!(
+            PowerShell.generate(&fixtures::multipart_request()),
+            "# -Form requires PowerShell 6.1 or newer.
+$form = [ordered]@{
+    'title' = 'Hello'
+}
+
+$response = Invoke-WebRequest -Uri 'https://www.example.com/posts' -Method 'POST' -Form $form
+$response.StatusCode
+$response.Content
+"
+        );
+    }
+
+    #[test]
+    fn test_powershell_multipart_with_repeated_fields() {
+        let request = BoundRequest {
+            url: "https://www.example.com/tags".into(),
+            method: RequestMethod::Post,
+            headers: KeyValueTable::default(),
+            body: None,
+            payload: BoundPayload::Multipart {
+                fields: vec![("tag".into(), "a".into()), ("tag".into(), "b".into())],
+                files: vec![],
+                boundary: "XYZ".into(),
+            },
+            ..Default::default()
+        };
+        assert!(PowerShell
+            .generate(&request)
+            .contains("    'tag' = @('a', 'b')\n"));
+    }
+
+    #[test]
+    fn test_powershell_upload() {
+        assert_eq!(
+            PowerShell.generate(&fixtures::upload_request()),
+            "# -Form requires PowerShell 6.1 or newer.
+# Files are sent with their own name, as application/octet-stream.
+$form = [ordered]@{
+    'title' = 'Hello'
+    'avatar' = Get-Item -LiteralPath '/home/john/me.png'
+}
+
+$response = Invoke-WebRequest -Uri 'https://www.example.com/upload' -Method 'POST' -Form $form
+$response.StatusCode
+$response.Content
+"
+        );
+    }
+
+    #[test]
+    fn test_powershell_raw() {
+        assert_eq!(
+            PowerShell.generate(&fixtures::json_request()),
+            "$body = '{\"name\": \"O''Brien\"}'
+
+$response = Invoke-WebRequest -Uri 'https://www.example.com/users/1' -Method 'PUT' -ContentType 'application/json' -Body $body
+$response.StatusCode
+$response.Content
+"
+        );
+    }
+
+    #[test]
+    fn test_powershell_body_file() {
+        assert_eq!(
+            PowerShell.generate(&fixtures::file_request()),
+            r#"$response = Invoke-WebRequest -Uri 'https://www.example.com/avatar' -Method 'PUT' -ContentType 'image/png' -InFile '/home/john/me.png'
+$response.StatusCode
+$response.Content
+"#
+        );
+    }
+
+    #[test]
+    fn test_powershell_custom_method() {
+        assert_eq!(
+            PowerShell.generate(&fixtures::custom_request()),
+            r#"# -CustomMethod requires PowerShell 6 or newer.
+$headers = @{
+    'Depth' = '1'
+}
+
+$response = Invoke-WebRequest -Uri 'https://www.example.com/dav/' -CustomMethod 'PROPFIND' -Headers $headers
+$response.StatusCode
+$response.Content
+"#
+        );
+    }
+
+    #[test]
+    fn test_powershell_digest() {
+        assert_eq!(
+            PowerShell.generate(&fixtures::digest_request()),
+            "$password = ConvertTo-SecureString 'it''s a secret' -AsPlainText -Force
+$credential = New-Object System.Management.Automation.PSCredential('john', $password)
+
+$response = Invoke-WebRequest -Uri 'https://www.example.com/private' -Method 'GET' -Credential $credential
+$response.StatusCode
+$response.Content
+"
+        );
+    }
+}
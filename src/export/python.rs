@@ -0,0 +1,276 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::client::{BoundPayload, BoundRequest};
+
+use super::{body_text, merged_headers, CodeGenerator};
+
+pub struct PythonRequests;
+
+/// Quotes a string as a Python string literal.
+fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+impl CodeGenerator for PythonRequests {
+    fn name(&self) -> &'static str {
+        "Python (requests)"
+    }
+
+    fn language(&self) -> &'static str {
+        "python3"
+    }
+
+    fn generate(&self, request: &BoundRequest) -> String {
+        let method: String = request.method.clone().into();
+        let multipart = matches!(request.payload, BoundPayload::Multipart { .. });
+        let headers = merged_headers(request, multipart);
+
+        let mut code = String::from("import requests\n");
+        if request.digest.is_some() {
+            code.push_str("from requests.auth import HTTPDigestAuth\n");
+        }
+        code.push('\n');
+        code.push_str(&format!("url = {}\n", quote(&request.url)));
+
+        let mut arguments = vec![quote(&method), "url".to_string()];
+        if !headers.is_empty() {
+            code.push_str("headers = {\n");
+            for (name, value) in &headers {
+                code.push_str(&format!("    {}: {},\n", quote(name), quote(value)));
+            }
+            code.push_str("}\n");
+            arguments.push("headers=headers".into());
+        }
+        if let Some(digest) = &request.digest {
+            code.push_str(&format!(
+                "auth = HTTPDigestAuth({}, {})\n",
+                quote(&digest.username),
+                quote(&digest.password)
+            ));
+            arguments.push("auth=auth".into());
+        }
+
+        match &request.payload {
+            BoundPayload::Urlencoded(fields) => {
+                code.push_str("data = [\n");
+                for (name, value) in fields {
+                    code.push_str(&format!("    ({}, {}),\n", quote(name), quote(value)));
+                }
+                code.push_str("]\n");
+                arguments.push("data=data".into());
+            }
+            BoundPayload::Multipart { fields, files, .. } => {
+                // A tuple without file name makes requests send a plain form field.
+                code.push_str("files = [\n");
+                for (name, value) in fields {
+                    code.push_str(&format!(
+                        "    ({}, (None, {})),\n",
+                        quote(name),
+                        quote(value)
+                    ));
+                }
+                for file in files {
+                    code.push_str(&format!(
+                        "    ({}, ({}, open({}, \"rb\"), {})),\n",
+                        quote(&file.name),
+                        quote(&file.filename),
+                        quote(&file.path.to_string_lossy()),
+                        quote(&file.content_type)
+                    ));
+                }
+                code.push_str("]\n");
+                arguments.push("files=files".into());
+            }
+            BoundPayload::Raw(_) => {
+                if let Some(body) = body_text(request) {
+                    code.push_str(&format!("data = {}.encode(\"utf-8\")\n", quote(&body)));
+                    arguments.push("data=data".into());
+                }
+            }
+            BoundPayload::File(path) => {
+                code.push_str(&format!(
+                    "data = open({}, \"rb\")\n",
+                    quote(&path.to_string_lossy())
+                ));
+                arguments.push("data=data".into());
+            }
+            BoundPayload::None => {}
+        }
+
+        code.push_str(&format!(
+            "\nresponse = requests.request({})\n",
+            arguments.join(", ")
+        ));
+        code.push_str("print(response.status_code)\n");
+        code.push_str("print(response.text)\n");
+        code
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::{fixtures, CodeGenerator};
+
+    use super::PythonRequests;
+
+    #[test]
+    fn test_python_get() {
+        assert_eq!(
+            PythonRequests.generate(&fixtures::get_request()),
+            r#"import requests
+
+url = "https://www.example.com/users?page=2"
+headers = {
+    "Accept": "application/json",
+    "Cookie": "a=1; b=2",
+}
+
+response = requests.request("GET", url, headers=headers)
+print(response.status_code)
+print(response.text)
+"#
+        );
+    }
+
+    #[test]
+    fn test_python_urlencoded() {
+        assert_eq!(
+            PythonRequests.generate(&fixtures::urlencoded_request()),
+            r#"import requests
+
+url = "https://www.example.com/login"
+headers = {
+    "Content-Type": "application/x-www-form-urlencoded",
+}
+data = [
+    ("user", "john"),
+    ("password", "it's a secret"),
+]
+
+response = requests.request("POST", url, headers=headers, data=data)
+print(response.status_code)
+print(response.text)
+"#
+        );
+    }
+
+    #[test]
+    fn test_python_multipart() {
+        assert_eq!(
+            PythonRequests.generate(&fixtures::multipart_request()),
+            r#"import requests
+
+url = "https://www.example.com/posts"
+files = [
+    ("title", (None, "Hello")),
+]
+
+response = requests.request("POST", url, files=files)
+print(response.status_code)
+print(response.text)
+"#
+        );
+    }
+
+    #[test]
+    fn test_python_upload() {
+        assert_eq!(
+            PythonRequests.generate(&fixtures::upload_request()),
+            r#"import requests
+
+url = "https://www.example.com/upload"
+files = [
+    ("title", (None, "Hello")),
+    ("avatar", ("avatar.png", open("/home/john/me.png", "rb"), "image/png")),
+]
+
+response = requests.request("POST", url, files=files)
+print(response.status_code)
+print(response.text)
+"#
+        );
+    }
+
+    #[test]
+    fn test_python_raw() {
+        assert_eq!(
+            PythonRequests.generate(&fixtures::json_request()),
+            r#"import requests
+
+url = "https://www.example.com/users/1"
+headers = {
+    "Content-Type": "application/json",
+}
+data = "{\"name\": \"O'Brien\"}".encode("utf-8")
+
+response = requests.request("PUT", url, headers=headers, data=data)
+print(response.status_code)
+print(response.text)
+"#
+        );
+    }
+
+    #[test]
+    fn test_python_body_file() {
+        assert_eq!(
+            PythonRequests.generate(&fixtures::file_request()),
+            r#"import requests
+
+url = "https://www.example.com/avatar"
+headers = {
+    "Content-Type": "image/png",
+}
+data = open("/home/john/me.png", "rb")
+
+response = requests.request("PUT", url, headers=headers, data=data)
+print(response.status_code)
+print(response.text)
+"#
+        );
+    }
+
+    #[test]
+    fn test_python_digest() {
+        assert_eq!(
+            PythonRequests.generate(&fixtures::digest_request()),
+            r#"import requests
+from requests.auth import HTTPDigestAuth
+
+url = "https://www.example.com/private"
+auth = HTTPDigestAuth("john", "it's a secret")
+
+response = requests.request("GET", url, auth=auth)
+print(response.status_code)
+print(response.text)
+"#
+        );
+    }
+}
@@ -0,0 +1,286 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::client::{BoundPayload, BoundRequest};
+use crate::entities::RequestMethod;
+
+use super::{body_text, plain_headers, reads_files, CodeGenerator};
+
+pub struct RustReqwest;
+
+/// Quotes a string as a Rust string literal.
+fn quote(text: &str) -> String {
+    format!("{text:?}")
+}
+
+impl CodeGenerator for RustReqwest {
+    fn name(&self) -> &'static str {
+        "Rust (reqwest)"
+    }
+
+    fn language(&self) -> &'static str {
+        "rust"
+    }
+
+    fn generate(&self, request: &BoundRequest) -> String {
+        let form = matches!(
+            request.payload,
+            BoundPayload::Urlencoded(_) | BoundPayload::Multipart { .. }
+        );
+        // Reading the files may also fail with an I/O error, and the
+        // name of a custom method is only checked when it is parsed.
+        let method = match &request.method {
+            RequestMethod::Custom(name) => format!("reqwest::Method::from_bytes(b{name:?})?"),
+            method => format!("reqwest::Method::{}", method.as_str()),
+        };
+        let custom = matches!(request.method, RequestMethod::Custom(_));
+        let error = if reads_files(request) || custom {
+            "Box<dyn std::error::Error>"
+        } else {
+            "reqwest::Error"
+        };
+
+        let mut code = String::new();
+        if request.digest.is_some() {
+            code.push_str(
+                "// reqwest cannot answer HTTP Digest challenges, so no credentials are sent.\n",
+            );
+        }
+        code.push_str("#[tokio::main]\n");
+        code.push_str(&format!("async fn main() -> Result<(), {error}> {{\n"));
+
+        if let BoundPayload::Multipart { fields, files, .. } = &request.payload {
+            code.push_str("    // Requires the multipart feature of reqwest.\n");
+            code.push_str("    let form = reqwest::multipart::Form::new()");
+            for (name, value) in fields {
+                code.push_str(&format!(
+                    "\n        .text({}, {})",
+                    quote(name),
+                    quote(value)
+                ));
+            }
+            for file in files {
+                code.push_str(&format!(
+                    "\n        .part(\n            {},\n            reqwest::multipart::Part::bytes(std::fs::read({})?)\n                .file_name({})\n                .mime_str({})?,\n        )",
+                    quote(&file.name),
+                    quote(&file.path.to_string_lossy()),
+                    quote(&file.filename),
+                    quote(&file.content_type)
+                ));
+            }
+            code.push_str(";\n");
+        }
+
+        code.push_str("    let response = reqwest::Client::new()\n");
+        code.push_str(&format!(
+            "        .request({}, {})\n",
+            method,
+            quote(&request.url)
+        ));
+        // reqwest computes the Content-Type of the forms by itself.
+        for (name, value) in plain_headers(request, form) {
+            code.push_str(&format!(
+                "        .header({}, {})\n",
+                quote(&name),
+                quote(&value)
+            ));
+        }
+        match &request.payload {
+            BoundPayload::Urlencoded(fields) => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|(name, value)| format!("({}, {})", quote(name), quote(value)))
+                    .collect();
+                code.push_str(&format!("        .form(&[{}])\n", fields.join(", ")));
+            }
+            BoundPayload::Multipart { .. } => code.push_str("        .multipart(form)\n"),
+            BoundPayload::Raw(_) => {
+                if let Some(body) = body_text(request) {
+                    code.push_str(&format!("        .body({})\n", quote(&body)));
+                }
+            }
+            BoundPayload::File(path) => code.push_str(&format!(
+                "        .body(std::fs::read({})?)\n",
+                quote(&path.to_string_lossy())
+            )),
+            BoundPayload::None => {}
+        }
+        code.push_str("        .send()\n");
+        code.push_str("        .await?;\n");
+        code.push_str("    println!(\"{}\", response.status());\n");
+        code.push_str("    println!(\"{}\", response.text().await?);\n");
+        code.push_str("    Ok(())\n");
+        code.push_str("}\n");
+        code
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::{fixtures, CodeGenerator};
+
+    use super::RustReqwest;
+
+    #[test]
+    fn test_reqwest_get() {
+        assert_eq!(
+            RustReqwest.generate(&fixtures::get_request()),
+            r#"#[tokio::main]
+async fn main() -> Result<(), reqwest::Error> {
+    let response = reqwest::Client::new()
+        .request(reqwest::Method::GET, "https://www.example.com/users?page=2")
+        .header("Accept", "application/json")
+        .header("Cookie", "a=1")
+        .header("Cookie", "b=2")
+        .send()
+        .await?;
+    println!("{}", response.status());
+    println!("{}", response.text().await?);
+    Ok(())
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_reqwest_urlencoded() {
+        assert_eq!(
+            RustReqwest.generate(&fixtures::urlencoded_request()),
+            r#"#[tokio::main]
+async fn main() -> Result<(), reqwest::Error> {
+    let response = reqwest::Client::new()
+        .request(reqwest::Method::POST, "https://www.example.com/login")
+        .form(&[("user", "john"), ("password", "it's a secret")])
+        .send()
+        .await?;
+    println!("{}", response.status());
+    println!("{}", response.text().await?);
+    Ok(())
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_reqwest_multipart() {
+        assert_eq!(
+            RustReqwest.generate(&fixtures::multipart_request()),
+            r#"#[tokio::main]
+async fn main() -> Result<(), reqwest::Error> {
+    // Requires the multipart feature of reqwest.
+    let form = reqwest::multipart::Form::new()
+        .text("title", "Hello");
+    let response = reqwest::Client::new()
+        .request(reqwest::Method::POST, "https://www.example.com/posts")
+        .multipart(form)
+        .send()
+        .await?;
+    println!("{}", response.status());
+    println!("{}", response.text().await?);
+    Ok(())
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_reqwest_upload() {
+        assert_eq!(
+            RustReqwest.generate(&fixtures::upload_request()),
+            r#"#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Requires the multipart feature of reqwest.
+    let form = reqwest::multipart::Form::new()
+        .text("title", "Hello")
+        .part(
+            "avatar",
+            reqwest::multipart::Part::bytes(std::fs::read("/home/john/me.png")?)
+                .file_name("avatar.png")
+                .mime_str("image/png")?,
+        );
+    let response = reqwest::Client::new()
+        .request(reqwest::Method::POST, "https://www.example.com/upload")
+        .multipart(form)
+        .send()
+        .await?;
+    println!("{}", response.status());
+    println!("{}", response.text().await?);
+    Ok(())
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_reqwest_raw() {
+        assert_eq!(
+            RustReqwest.generate(&fixtures::json_request()),
+            r#"#[tokio::main]
+async fn main() -> Result<(), reqwest::Error> {
+    let response = reqwest::Client::new()
+        .request(reqwest::Method::PUT, "https://www.example.com/users/1")
+        .header("Content-Type", "application/json")
+        .body("{\"name\": \"O'Brien\"}")
+        .send()
+        .await?;
+    println!("{}", response.status());
+    println!("{}", response.text().await?);
+    Ok(())
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_reqwest_body_file() {
+        assert_eq!(
+            RustReqwest.generate(&fixtures::file_request()),
+            r#"#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let response = reqwest::Client::new()
+        .request(reqwest::Method::PUT, "https://www.example.com/avatar")
+        .header("Content-Type", "image/png")
+        .body(std::fs::read("/home/john/me.png")?)
+        .send()
+        .await?;
+    println!("{}", response.status());
+    println!("{}", response.text().await?);
+    Ok(())
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_reqwest_custom_method() {
+        assert_eq!(
+            RustReqwest.generate(&fixtures::custom_request()),
+            r#"#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let response = reqwest::Client::new()
+        .request(reqwest::Method::from_bytes(b"PROPFIND")?, "https://www.example.com/dav/")
+        .header("Depth", "1")
+        .send()
+        .await?;
+    println!("{}", response.status());
+    println!("{}", response.text().await?);
+    Ok(())
+}
+"#
+        );
+    }
+}
@@ -0,0 +1,161 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::client::{BoundPayload, BoundRequest};
+
+use super::{body_text, shell_method, shell_quote, CodeGenerator, ShellDialect};
+
+pub struct Wget;
+
+impl CodeGenerator for Wget {
+    fn name(&self) -> &'static str {
+        "Wget"
+    }
+
+    fn language(&self) -> &'static str {
+        "sh"
+    }
+
+    fn generate(&self, request: &BoundRequest) -> String {
+        let method = shell_method(ShellDialect::Posix, &request.method);
+
+        // wget cannot build forms, so every body is sent as it was encoded,
+        // together with the Content-Type that describes it. The contents of
+        // the uploaded files cannot be written in the command line, though.
+        let uploads =
+            matches!(&request.payload, BoundPayload::Multipart { files, .. } if !files.is_empty());
+        let mut command = String::new();
+        if uploads {
+            command.push_str("# wget cannot upload files, so the form is not sent.\n");
+        }
+        command.push_str(&format!("wget --method={method} --output-document=-"));
+        for header in request.headers.iter() {
+            let header = format!("{}: {}", header.name, header.value);
+            command.push_str(&format!(" \\\n  --header={}", shell_quote(&header)));
+        }
+        // wget answers the Digest challenge when it is given the credentials.
+        if let Some(digest) = &request.digest {
+            command.push_str(&format!(
+                " \\\n  --user={} --password={}",
+                shell_quote(&digest.username),
+                shell_quote(&digest.password)
+            ));
+        }
+        if let BoundPayload::File(path) = &request.payload {
+            let path = path.to_string_lossy();
+            command.push_str(&format!(" \\\n  --body-file={}", shell_quote(&path)));
+        } else if let Some(body) = body_text(request).filter(|_| !uploads) {
+            command.push_str(&format!(" \\\n  --body-data={}", shell_quote(&body)));
+        }
+        command.push_str(&format!(" \\\n  {}", shell_quote(&request.url)));
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::{fixtures, CodeGenerator};
+
+    use super::Wget;
+
+    #[test]
+    fn test_wget_get() {
+        assert_eq!(
+            Wget.generate(&fixtures::get_request()),
+            "wget --method=GET --output-document=- \\
+  --header='Accept: application/json' \\
+  --header='Cookie: a=1' \\
+  --header='Cookie: b=2' \\
+  'https://www.example.com/users?page=2'"
+        );
+    }
+
+    #[test]
+    fn test_wget_urlencoded() {
+        assert_eq!(
+            Wget.generate(&fixtures::urlencoded_request()),
+            "wget --method=POST --output-document=- \\
+  --header='Content-Type: application/x-www-form-urlencoded' \\
+  --body-data='user=john&password=it%27s+a+secret' \\
+  'https://www.example.com/login'"
+        );
+    }
+
+    #[test]
+    fn test_wget_multipart() {
+        assert_eq!(
+            Wget.generate(&fixtures::multipart_request()),
+            "wget --method=POST --output-document=- \\
+  --header='Content-Type: multipart/form-data; boundary=XYZ' \\
+  --body-data='--XYZ\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nHello\r\n--XYZ--\r\n' \\
+  'https://www.example.com/posts'"
+        );
+    }
+
+    #[test]
+    fn test_wget_upload() {
+        assert_eq!(
+            Wget.generate(&fixtures::upload_request()),
+            "# wget cannot upload files, so the form is not sent.
+wget --method=POST --output-document=- \\
+  --header='Content-Type: multipart/form-data; boundary=XYZ' \\
+  'https://www.example.com/upload'"
+        );
+    }
+
+    #[test]
+    fn test_wget_raw() {
+        assert_eq!(
+            Wget.generate(&fixtures::json_request()),
+            "wget --method=PUT --output-document=- \\
+  --header='Content-Type: application/json' \\
+  --body-data='{\"name\": \"O'\\''Brien\"}' \\
+  'https://www.example.com/users/1'"
+        );
+    }
+
+    #[test]
+    fn test_wget_body_file() {
+        assert_eq!(
+            Wget.generate(&fixtures::file_request()),
+            "wget --method=PUT --output-document=- \\
+  --header='Content-Type: image/png' \\
+  --body-file='/home/john/me.png' \\
+  'https://www.example.com/avatar'"
+        );
+    }
+
+    #[test]
+    fn test_wget_custom_method() {
+        assert_eq!(
+            Wget.generate(&fixtures::custom_request()),
+            "wget --method=PROPFIND --output-document=- \\
+  --header='Depth: 1' \\
+  'https://www.example.com/dav/'"
+        );
+    }
+
+    #[test]
+    fn test_wget_digest() {
+        assert_eq!(
+            Wget.generate(&fixtures::digest_request()),
+            "wget --method=GET --output-document=- \\
+  --user='john' --password='it'\\''s a secret' \\
+  'https://www.example.com/private'"
+        );
+    }
+}
@@ -1,12 +1,14 @@
 use std::collections::HashMap;
 
-use gtk::gio;
-use gtk::prelude::FileExtManual;
 use serde::{Deserialize, Serialize};
 
 use crate::client::RequestError;
 use crate::entities::{
-    EndpointData, KeyValue, KeyValueTable, RawEncoding, RequestMethod, RequestPayload,
+    ApiKeyLocation, Assertion, AssertionKind, AwsSigV4Settings, Capture, CaptureScope,
+    CaptureSource, CertificateFormat, CollectionData, EndpointData, Environment, FormFile,
+    HmacAlgorithm, HmacSettings, HttpVersion, KeyValue, KeyValueTable, OAuth2Grant, OAuth2Settings,
+    ProxySettings, RawEncoding, RequestAuth, RequestMethod, RequestOptions, RequestPayload,
+    RequestProxy, RequestScripts, SignatureEncoding, TlsSettings,
 };
 use crate::error::CarteroError;
 
@@ -162,6 +164,50 @@ impl From<FilePayloadRawFormat> for RawEncoding {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FilePayloadFile {
+    name: String,
+    path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filename: Option<String>,
+    #[serde(rename = "content-type", skip_serializing_if = "Option::is_none")]
+    content_type: Option<String>,
+    #[serde(default = "default_active", skip_serializing_if = "is_active")]
+    active: bool,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+fn is_active(active: &bool) -> bool {
+    *active
+}
+
+impl From<FormFile> for FilePayloadFile {
+    fn from(value: FormFile) -> Self {
+        Self {
+            name: value.name,
+            path: value.path,
+            filename: value.filename,
+            content_type: value.content_type,
+            active: value.active,
+        }
+    }
+}
+
+impl From<FilePayloadFile> for FormFile {
+    fn from(value: FilePayloadFile) -> Self {
+        Self {
+            name: value.name,
+            path: value.path,
+            filename: value.filename,
+            content_type: value.content_type,
+            active: value.active,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum FilePayload {
@@ -174,12 +220,20 @@ pub enum FilePayload {
     #[serde(rename = "multipart")]
     Multipart {
         variables: Option<KeyValuedFileTable>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        files: Vec<FilePayloadFile>,
     },
     #[serde(rename = "raw")]
     Raw {
         format: Option<FilePayloadRawFormat>,
         body: String,
     },
+    #[serde(rename = "file")]
+    File {
+        path: String,
+        #[serde(rename = "content-type", skip_serializing_if = "Option::is_none")]
+        content_type: Option<String>,
+    },
 }
 
 #[derive(Serialize, Deserialize)]
@@ -196,13 +250,15 @@ impl From<RequestPayload> for FilePayload {
             RequestPayload::Urlencoded(payload) => Self::UrlEncoded {
                 variables: Some(payload.into()),
             },
-            RequestPayload::Multipart { params } => Self::Multipart {
+            RequestPayload::Multipart { params, files } => Self::Multipart {
                 variables: Some(params.into()),
+                files: files.into_iter().map(FilePayloadFile::from).collect(),
             },
             RequestPayload::Raw { encoding, content } => Self::Raw {
                 format: Some(encoding.into()),
                 body: String::from_utf8_lossy(&content.clone()).to_string(),
             },
+            RequestPayload::File { path, content_type } => Self::File { path, content_type },
         }
     }
 }
@@ -211,8 +267,9 @@ impl From<FilePayload> for RequestPayload {
     fn from(value: FilePayload) -> Self {
         match value {
             FilePayload::None => Self::None,
-            FilePayload::Multipart { variables } => Self::Multipart {
+            FilePayload::Multipart { variables, files } => Self::Multipart {
                 params: variables.unwrap_or_default().into(),
+                files: files.into_iter().map(FormFile::from).collect(),
             },
             FilePayload::UrlEncoded { variables } => {
                 Self::Urlencoded(variables.unwrap_or_default().into())
@@ -221,6 +278,7 @@ impl From<FilePayload> for RequestPayload {
                 encoding: format.unwrap_or_default().into(),
                 content: Vec::from(body.clone()),
             },
+            FilePayload::File { path, content_type } => Self::File { path, content_type },
         }
     }
 }
@@ -243,6 +301,648 @@ impl From<Body> for RequestPayload {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub enum FileHttpVersion {
+    #[serde(rename = "auto")]
+    Auto,
+    #[serde(rename = "1.1")]
+    Http11,
+    #[serde(rename = "2")]
+    Http2,
+}
+
+impl From<HttpVersion> for FileHttpVersion {
+    fn from(value: HttpVersion) -> Self {
+        match value {
+            HttpVersion::Auto => Self::Auto,
+            HttpVersion::Http11 => Self::Http11,
+            HttpVersion::Http2 => Self::Http2,
+        }
+    }
+}
+
+impl From<FileHttpVersion> for HttpVersion {
+    fn from(value: FileHttpVersion) -> Self {
+        match value {
+            FileHttpVersion::Auto => Self::Auto,
+            FileHttpVersion::Http11 => Self::Http11,
+            FileHttpVersion::Http2 => Self::Http2,
+        }
+    }
+}
+
+/// The options of a request. Only the options that are not set to their default
+/// value are written, so that most files do not need an options table at all.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct RequestOptionsFile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timeout: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    follow_redirects: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_redirects: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    verify_tls: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    http_version: Option<FileHttpVersion>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decompress: Option<bool>,
+}
+
+impl From<RequestOptionsFile> for RequestOptions {
+    fn from(value: RequestOptionsFile) -> Self {
+        let defaults = RequestOptions::default();
+        Self {
+            timeout: value.timeout,
+            follow_redirects: value.follow_redirects.unwrap_or(defaults.follow_redirects),
+            max_redirects: value.max_redirects.unwrap_or(defaults.max_redirects),
+            verify_tls: value.verify_tls.unwrap_or(defaults.verify_tls),
+            http_version: value
+                .http_version
+                .map(HttpVersion::from)
+                .unwrap_or(defaults.http_version),
+            decompress: value.decompress.unwrap_or(defaults.decompress),
+        }
+    }
+}
+
+fn changed<T: PartialEq>(value: T, default: T) -> Option<T> {
+    Some(value).filter(|v| *v != default)
+}
+
+impl From<RequestOptions> for RequestOptionsFile {
+    fn from(value: RequestOptions) -> Self {
+        let defaults = RequestOptions::default();
+        Self {
+            timeout: value.timeout,
+            follow_redirects: changed(value.follow_redirects, defaults.follow_redirects),
+            max_redirects: changed(value.max_redirects, defaults.max_redirects),
+            verify_tls: changed(value.verify_tls, defaults.verify_tls),
+            http_version: changed(value.http_version, defaults.http_version)
+                .map(FileHttpVersion::from),
+            decompress: changed(value.decompress, defaults.decompress),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum FileCertificateFormat {
+    #[serde(rename = "pem")]
+    Pem,
+    #[serde(rename = "pkcs12")]
+    Pkcs12,
+}
+
+impl From<CertificateFormat> for FileCertificateFormat {
+    fn from(value: CertificateFormat) -> Self {
+        match value {
+            CertificateFormat::Pem => Self::Pem,
+            CertificateFormat::Pkcs12 => Self::Pkcs12,
+        }
+    }
+}
+
+impl From<FileCertificateFormat> for CertificateFormat {
+    fn from(value: FileCertificateFormat) -> Self {
+        match value {
+            FileCertificateFormat::Pem => Self::Pem,
+            FileCertificateFormat::Pkcs12 => Self::Pkcs12,
+        }
+    }
+}
+
+/// The TLS settings of a request, an environment or a collection.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct TlsFile {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    certificate: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<FileCertificateFormat>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    key: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    password: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    ca_bundle: String,
+}
+
+impl From<TlsFile> for TlsSettings {
+    fn from(value: TlsFile) -> Self {
+        Self {
+            certificate: value.certificate,
+            format: value
+                .format
+                .map(CertificateFormat::from)
+                .unwrap_or_default(),
+            key: value.key,
+            password: value.password,
+            ca_bundle: value.ca_bundle,
+        }
+    }
+}
+
+/// Returns the TLS settings as they are written in a file, or `None` if they are empty.
+fn tls_file(value: TlsSettings) -> Option<TlsFile> {
+    if value == TlsSettings::default() {
+        return None;
+    }
+    Some(TlsFile {
+        certificate: value.certificate,
+        format: changed(value.format, CertificateFormat::default())
+            .map(FileCertificateFormat::from),
+        key: value.key,
+        password: value.password,
+        ca_bundle: value.ca_bundle,
+    })
+}
+
+/// The proxy of a request. It is not written when the default proxy is used.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct ProxyFile {
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    direct: bool,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    url: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    username: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    password: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    no_proxy: String,
+}
+
+impl From<ProxyFile> for RequestProxy {
+    fn from(value: ProxyFile) -> Self {
+        if value.direct {
+            return Self::Direct;
+        }
+        Self::Custom(ProxySettings {
+            url: value.url,
+            username: value.username,
+            password: value.password,
+            no_proxy: value.no_proxy,
+        })
+    }
+}
+
+/// Returns the proxy as it is written in a file, or `None` if the default one is used.
+fn proxy_file(value: RequestProxy) -> Option<ProxyFile> {
+    match value {
+        RequestProxy::Default => None,
+        RequestProxy::Direct => Some(ProxyFile {
+            direct: true,
+            ..Default::default()
+        }),
+        RequestProxy::Custom(proxy) => Some(ProxyFile {
+            direct: false,
+            url: proxy.url,
+            username: proxy.username,
+            password: proxy.password,
+            no_proxy: proxy.no_proxy,
+        }),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub enum FileApiKeyLocation {
+    #[default]
+    #[serde(rename = "header")]
+    Header,
+    #[serde(rename = "query")]
+    Query,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub enum FileOAuth2Grant {
+    #[default]
+    #[serde(rename = "client-credentials")]
+    ClientCredentials,
+    #[serde(rename = "password")]
+    Password,
+    #[serde(rename = "authorization-code")]
+    AuthorizationCode,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub enum FileHmacAlgorithm {
+    #[serde(rename = "sha1")]
+    Sha1,
+    #[default]
+    #[serde(rename = "sha256")]
+    Sha256,
+    #[serde(rename = "sha512")]
+    Sha512,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub enum FileSignatureEncoding {
+    #[default]
+    #[serde(rename = "hex")]
+    Hex,
+    #[serde(rename = "base64")]
+    Base64,
+}
+
+/// The authentication of a request. It is not written when the request has none.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "type")]
+pub enum AuthFile {
+    #[serde(rename = "basic")]
+    Basic {
+        username: String,
+        #[serde(default)]
+        password: String,
+    },
+    #[serde(rename = "bearer")]
+    Bearer { token: String },
+    #[serde(rename = "api-key")]
+    ApiKey {
+        name: String,
+        value: String,
+        #[serde(default, rename = "in")]
+        location: FileApiKeyLocation,
+    },
+    #[serde(rename = "digest")]
+    Digest {
+        username: String,
+        #[serde(default)]
+        password: String,
+    },
+    #[serde(rename = "oauth2")]
+    OAuth2 {
+        #[serde(default)]
+        grant: FileOAuth2Grant,
+        token_url: String,
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        authorization_url: String,
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        client_id: String,
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        client_secret: String,
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        scope: String,
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        username: String,
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        password: String,
+    },
+    #[serde(rename = "aws-sigv4")]
+    AwsSigV4 {
+        access_key: String,
+        secret_key: String,
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        session_token: String,
+        region: String,
+        service: String,
+    },
+    #[serde(rename = "hmac")]
+    Hmac {
+        #[serde(default)]
+        algorithm: FileHmacAlgorithm,
+        key: String,
+        message: String,
+        header: String,
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        timestamp_header: String,
+        #[serde(default)]
+        encoding: FileSignatureEncoding,
+    },
+}
+
+impl From<AuthFile> for RequestAuth {
+    fn from(value: AuthFile) -> Self {
+        match value {
+            AuthFile::Basic { username, password } => Self::Basic { username, password },
+            AuthFile::Bearer { token } => Self::Bearer { token },
+            AuthFile::ApiKey {
+                name,
+                value,
+                location,
+            } => Self::ApiKey {
+                name,
+                value,
+                location: match location {
+                    FileApiKeyLocation::Header => ApiKeyLocation::Header,
+                    FileApiKeyLocation::Query => ApiKeyLocation::Query,
+                },
+            },
+            AuthFile::Digest { username, password } => Self::Digest { username, password },
+            AuthFile::OAuth2 {
+                grant,
+                token_url,
+                authorization_url,
+                client_id,
+                client_secret,
+                scope,
+                username,
+                password,
+            } => Self::OAuth2(OAuth2Settings {
+                grant: match grant {
+                    FileOAuth2Grant::ClientCredentials => OAuth2Grant::ClientCredentials,
+                    FileOAuth2Grant::Password => OAuth2Grant::Password,
+                    FileOAuth2Grant::AuthorizationCode => OAuth2Grant::AuthorizationCode,
+                },
+                token_url,
+                authorization_url,
+                client_id,
+                client_secret,
+                scope,
+                username,
+                password,
+            }),
+            AuthFile::AwsSigV4 {
+                access_key,
+                secret_key,
+                session_token,
+                region,
+                service,
+            } => Self::AwsSigV4(AwsSigV4Settings {
+                access_key,
+                secret_key,
+                session_token,
+                region,
+                service,
+            }),
+            AuthFile::Hmac {
+                algorithm,
+                key,
+                message,
+                header,
+                timestamp_header,
+                encoding,
+            } => Self::Hmac(HmacSettings {
+                algorithm: match algorithm {
+                    FileHmacAlgorithm::Sha1 => HmacAlgorithm::Sha1,
+                    FileHmacAlgorithm::Sha256 => HmacAlgorithm::Sha256,
+                    FileHmacAlgorithm::Sha512 => HmacAlgorithm::Sha512,
+                },
+                key,
+                message,
+                header,
+                timestamp_header,
+                encoding: match encoding {
+                    FileSignatureEncoding::Hex => SignatureEncoding::Hex,
+                    FileSignatureEncoding::Base64 => SignatureEncoding::Base64,
+                },
+            }),
+        }
+    }
+}
+
+/// Returns the authentication as it is written in a file, or `None` if there is none.
+fn auth_file(value: RequestAuth) -> Option<AuthFile> {
+    let auth = match value {
+        RequestAuth::None => return None,
+        RequestAuth::Basic { username, password } => AuthFile::Basic { username, password },
+        RequestAuth::Bearer { token } => AuthFile::Bearer { token },
+        RequestAuth::ApiKey {
+            name,
+            value,
+            location,
+        } => AuthFile::ApiKey {
+            name,
+            value,
+            location: match location {
+                ApiKeyLocation::Header => FileApiKeyLocation::Header,
+                ApiKeyLocation::Query => FileApiKeyLocation::Query,
+            },
+        },
+        RequestAuth::Digest { username, password } => AuthFile::Digest { username, password },
+        RequestAuth::OAuth2(settings) => AuthFile::OAuth2 {
+            grant: match settings.grant {
+                OAuth2Grant::ClientCredentials => FileOAuth2Grant::ClientCredentials,
+                OAuth2Grant::Password => FileOAuth2Grant::Password,
+                OAuth2Grant::AuthorizationCode => FileOAuth2Grant::AuthorizationCode,
+            },
+            token_url: settings.token_url,
+            authorization_url: settings.authorization_url,
+            client_id: settings.client_id,
+            client_secret: settings.client_secret,
+            scope: settings.scope,
+            username: settings.username,
+            password: settings.password,
+        },
+        RequestAuth::AwsSigV4(settings) => AuthFile::AwsSigV4 {
+            access_key: settings.access_key,
+            secret_key: settings.secret_key,
+            session_token: settings.session_token,
+            region: settings.region,
+            service: settings.service,
+        },
+        RequestAuth::Hmac(settings) => AuthFile::Hmac {
+            algorithm: match settings.algorithm {
+                HmacAlgorithm::Sha1 => FileHmacAlgorithm::Sha1,
+                HmacAlgorithm::Sha256 => FileHmacAlgorithm::Sha256,
+                HmacAlgorithm::Sha512 => FileHmacAlgorithm::Sha512,
+            },
+            key: settings.key,
+            message: settings.message,
+            header: settings.header,
+            timestamp_header: settings.timestamp_header,
+            encoding: match settings.encoding {
+                SignatureEncoding::Hex => FileSignatureEncoding::Hex,
+                SignatureEncoding::Base64 => FileSignatureEncoding::Base64,
+            },
+        },
+    };
+    Some(auth)
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub enum FileCaptureSource {
+    #[default]
+    #[serde(rename = "json")]
+    JsonPath,
+    #[serde(rename = "xml")]
+    XPath,
+    #[serde(rename = "header")]
+    Header,
+    #[serde(rename = "regex")]
+    Regex,
+    #[serde(rename = "status")]
+    StatusCode,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub enum FileCaptureScope {
+    #[default]
+    #[serde(rename = "request")]
+    Request,
+    #[serde(rename = "environment")]
+    Environment,
+    #[serde(rename = "session")]
+    Session,
+}
+
+/// A capture, such as `{ variable = "TOKEN", from = "json", expression = "$.token" }`.
+#[derive(Serialize, Deserialize, Debug)]
+struct CaptureFile {
+    variable: String,
+    #[serde(default)]
+    from: FileCaptureSource,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    expression: String,
+    #[serde(default)]
+    scope: FileCaptureScope,
+    #[serde(default = "default_active", skip_serializing_if = "is_active")]
+    active: bool,
+}
+
+impl From<CaptureFile> for Capture {
+    fn from(value: CaptureFile) -> Self {
+        let expression = value.expression;
+        Capture {
+            variable: value.variable,
+            source: match value.from {
+                FileCaptureSource::JsonPath => CaptureSource::JsonPath(expression),
+                FileCaptureSource::XPath => CaptureSource::XPath(expression),
+                FileCaptureSource::Header => CaptureSource::Header(expression),
+                FileCaptureSource::Regex => CaptureSource::Regex(expression),
+                FileCaptureSource::StatusCode => CaptureSource::StatusCode,
+            },
+            scope: match value.scope {
+                FileCaptureScope::Request => CaptureScope::Request,
+                FileCaptureScope::Environment => CaptureScope::Environment,
+                FileCaptureScope::Session => CaptureScope::Session,
+            },
+            active: value.active,
+        }
+    }
+}
+
+impl From<Capture> for CaptureFile {
+    fn from(value: Capture) -> Self {
+        let (from, expression) = match value.source {
+            CaptureSource::JsonPath(expression) => (FileCaptureSource::JsonPath, expression),
+            CaptureSource::XPath(expression) => (FileCaptureSource::XPath, expression),
+            CaptureSource::Header(expression) => (FileCaptureSource::Header, expression),
+            CaptureSource::Regex(expression) => (FileCaptureSource::Regex, expression),
+            CaptureSource::StatusCode => (FileCaptureSource::StatusCode, String::new()),
+        };
+        CaptureFile {
+            variable: value.variable,
+            from,
+            expression,
+            scope: match value.scope {
+                CaptureScope::Request => FileCaptureScope::Request,
+                CaptureScope::Environment => FileCaptureScope::Environment,
+                CaptureScope::Session => FileCaptureScope::Session,
+            },
+            active: value.active,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub enum FileAssertionKind {
+    #[default]
+    #[serde(rename = "status")]
+    StatusEquals,
+    #[serde(rename = "status-range")]
+    StatusInRange,
+    #[serde(rename = "header-exists")]
+    HeaderExists,
+    #[serde(rename = "header-matches")]
+    HeaderMatches,
+    #[serde(rename = "json-equals")]
+    JsonPathEquals,
+    #[serde(rename = "json-matches")]
+    JsonPathMatches,
+    #[serde(rename = "json-type")]
+    JsonPathType,
+    #[serde(rename = "body-contains")]
+    BodyContains,
+    #[serde(rename = "duration-under")]
+    DurationUnder,
+}
+
+/// An assertion, such as `{ check = "json-equals", subject = "$.id", expected = "42" }`.
+#[derive(Serialize, Deserialize, Debug)]
+struct AssertionFile {
+    #[serde(default)]
+    check: FileAssertionKind,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    subject: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    expected: String,
+    #[serde(default = "default_active", skip_serializing_if = "is_active")]
+    active: bool,
+}
+
+impl From<AssertionFile> for Assertion {
+    fn from(value: AssertionFile) -> Self {
+        Assertion {
+            kind: match value.check {
+                FileAssertionKind::StatusEquals => AssertionKind::StatusEquals,
+                FileAssertionKind::StatusInRange => AssertionKind::StatusInRange,
+                FileAssertionKind::HeaderExists => AssertionKind::HeaderExists,
+                FileAssertionKind::HeaderMatches => AssertionKind::HeaderMatches,
+                FileAssertionKind::JsonPathEquals => AssertionKind::JsonPathEquals,
+                FileAssertionKind::JsonPathMatches => AssertionKind::JsonPathMatches,
+                FileAssertionKind::JsonPathType => AssertionKind::JsonPathType,
+                FileAssertionKind::BodyContains => AssertionKind::BodyContains,
+                FileAssertionKind::DurationUnder => AssertionKind::DurationUnder,
+            },
+            subject: value.subject,
+            expected: value.expected,
+            active: value.active,
+        }
+    }
+}
+
+impl From<Assertion> for AssertionFile {
+    fn from(value: Assertion) -> Self {
+        AssertionFile {
+            check: match value.kind {
+                AssertionKind::StatusEquals => FileAssertionKind::StatusEquals,
+                AssertionKind::StatusInRange => FileAssertionKind::StatusInRange,
+                AssertionKind::HeaderExists => FileAssertionKind::HeaderExists,
+                AssertionKind::HeaderMatches => FileAssertionKind::HeaderMatches,
+                AssertionKind::JsonPathEquals => FileAssertionKind::JsonPathEquals,
+                AssertionKind::JsonPathMatches => FileAssertionKind::JsonPathMatches,
+                AssertionKind::JsonPathType => FileAssertionKind::JsonPathType,
+                AssertionKind::BodyContains => FileAssertionKind::BodyContains,
+                AssertionKind::DurationUnder => FileAssertionKind::DurationUnder,
+            },
+            subject: value.subject,
+            expected: value.expected,
+            active: value.active,
+        }
+    }
+}
+
+/// The scripts of a request, which are usually written as multi-line strings.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+struct ScriptsFile {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pre_request: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    post_response: String,
+}
+
+impl From<ScriptsFile> for RequestScripts {
+    fn from(value: ScriptsFile) -> Self {
+        RequestScripts {
+            pre_request: value.pre_request,
+            post_response: value.post_response,
+        }
+    }
+}
+
+fn scripts_file(value: RequestScripts) -> Option<ScriptsFile> {
+    if value.is_empty() {
+        return None;
+    }
+    Some(ScriptsFile {
+        pre_request: value.pre_request,
+        post_response: value.post_response,
+    })
+}
+
 #[derive(Deserialize, Serialize)]
 struct RequestFile {
     version: usize,
@@ -251,6 +951,20 @@ struct RequestFile {
     body: Option<Body>,
     headers: Option<KeyValuedFileTable>,
     variables: Option<KeyValuedFileTable>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    options: Option<RequestOptionsFile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tls: Option<TlsFile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    proxy: Option<ProxyFile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    auth: Option<AuthFile>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    captures: Vec<CaptureFile>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    assertions: Vec<AssertionFile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scripts: Option<ScriptsFile>,
 }
 
 impl TryFrom<RequestFile> for EndpointData {
@@ -266,6 +980,13 @@ impl TryFrom<RequestFile> for EndpointData {
         let body = value.body.map(RequestPayload::from).unwrap_or_default();
         let headers = value.headers.unwrap_or_default().into();
         let variables = value.variables.unwrap_or_default().into();
+        let options = value.options.unwrap_or_default().into();
+        let tls = value.tls.unwrap_or_default().into();
+        let proxy = value.proxy.map(RequestProxy::from).unwrap_or_default();
+        let auth = value.auth.map(RequestAuth::from).unwrap_or_default();
+        let captures = value.captures.into_iter().map(Capture::from).collect();
+        let assertions = value.assertions.into_iter().map(Assertion::from).collect();
+        let scripts = value.scripts.map(RequestScripts::from).unwrap_or_default();
 
         let request = EndpointData {
             url: value.url.clone(),
@@ -273,6 +994,13 @@ impl TryFrom<RequestFile> for EndpointData {
             body,
             variables,
             headers,
+            options,
+            tls,
+            proxy,
+            auth,
+            captures,
+            assertions,
+            scripts,
         };
         Ok(request)
     }
@@ -280,13 +1008,17 @@ impl TryFrom<RequestFile> for EndpointData {
 
 impl From<EndpointData> for RequestFile {
     fn from(value: EndpointData) -> RequestFile {
-        let method: &str = value.method.into();
+        let method = value.method.as_str();
         let body = match value.body {
             RequestPayload::None => None,
             otherwise => Some(otherwise.into()),
         };
         let headers = value.headers.into();
         let variables = value.variables.into();
+        let options = match value.options {
+            options if options == RequestOptions::default() => None,
+            options => Some(options.into()),
+        };
         RequestFile {
             version: 1,
             url: value.url.clone(),
@@ -294,6 +1026,17 @@ impl From<EndpointData> for RequestFile {
             body,
             headers: Some(headers),
             variables: Some(variables),
+            options,
+            tls: tls_file(value.tls),
+            proxy: proxy_file(value.proxy),
+            auth: auth_file(value.auth),
+            captures: value.captures.into_iter().map(CaptureFile::from).collect(),
+            assertions: value
+                .assertions
+                .into_iter()
+                .map(AssertionFile::from)
+                .collect(),
+            scripts: scripts_file(value.scripts),
         }
     }
 }
@@ -308,25 +1051,269 @@ pub fn store_toml(endpoint: &EndpointData) -> Result<String, CarteroError> {
     toml::to_string(&file).map_err(|e| e.into())
 }
 
-pub async fn read_file(file: &gio::File) -> Result<String, CarteroError> {
-    file.load_contents_future()
-        .await
-        .map(|data| String::from_utf8_lossy(&data.0).to_string())
-        .map_err(|err| {
-            println!("{err:?}");
-            CarteroError::FileDialogError
+#[derive(Deserialize, Serialize)]
+struct EnvironmentFile {
+    version: usize,
+    variables: Option<KeyValuedFileTable>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tls: Option<TlsFile>,
+}
+
+/// The suffix used by the environment files, such as `dev.env.toml`.
+pub const ENVIRONMENT_SUFFIX: &str = ".env.toml";
+
+/// Guesses the name of an environment given the name of the file that holds it.
+///
+/// The name of `dev.env.toml` is `dev`. Files that do not follow that convention
+/// just lose their extension.
+pub fn environment_name(file_name: &str) -> String {
+    match file_name.strip_suffix(ENVIRONMENT_SUFFIX) {
+        Some(name) => name.to_string(),
+        None => match file_name.rsplit_once('.') {
+            Some((name, _)) if !name.is_empty() => name.to_string(),
+            _ => file_name.to_string(),
+        },
+    }
+}
+
+pub fn parse_environment_toml(name: &str, file: &str) -> Result<Environment, CarteroError> {
+    let contents = toml::from_str::<EnvironmentFile>(file)?;
+    if contents.version != 1 {
+        return Err(CarteroError::OutdatedSchema);
+    }
+    Ok(Environment {
+        name: name.to_string(),
+        variables: contents.variables.unwrap_or_default().into(),
+        tls: contents.tls.unwrap_or_default().into(),
+    })
+}
+
+pub fn store_environment_toml(environment: &Environment) -> Result<String, CarteroError> {
+    let file = EnvironmentFile {
+        version: 1,
+        variables: Some(environment.variables.clone().into()),
+        tls: tls_file(environment.tls.clone()),
+    };
+    toml::to_string(&file).map_err(|e| e.into())
+}
+
+#[derive(Deserialize, Serialize)]
+struct CollectionFile {
+    version: usize,
+    headers: Option<KeyValuedFileTable>,
+    variables: Option<KeyValuedFileTable>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tls: Option<TlsFile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    run: Option<RunFile>,
+}
+
+/// The `[run]` table of a collection, read by the collection runner.
+#[derive(Deserialize, Serialize)]
+struct RunFile {
+    #[serde(default)]
+    order: Vec<String>,
+}
+
+fn run_file(order: Vec<String>) -> Option<RunFile> {
+    if order.is_empty() {
+        return None;
+    }
+    Some(RunFile { order })
+}
+
+/// The name of the file that holds the settings of a collection.
+pub const COLLECTION_FILE: &str = "collection.toml";
+
+/// The name of the file that keeps the cookies of a collection, when they are remembered.
+pub const COOKIE_FILE: &str = "cookies.txt";
+
+pub fn parse_collection_toml(file: &str) -> Result<CollectionData, CarteroError> {
+    let contents = toml::from_str::<CollectionFile>(file)?;
+    if contents.version != 1 {
+        return Err(CarteroError::OutdatedSchema);
+    }
+    Ok(CollectionData {
+        headers: contents.headers.unwrap_or_default().into(),
+        variables: contents.variables.unwrap_or_default().into(),
+        tls: contents.tls.unwrap_or_default().into(),
+        run_order: contents.run.map(|run| run.order).unwrap_or_default(),
+    })
+}
+
+pub fn store_collection_toml(collection: &CollectionData) -> Result<String, CarteroError> {
+    let file = CollectionFile {
+        version: 1,
+        headers: Some(collection.headers.clone().into()),
+        variables: Some(collection.variables.clone().into()),
+        tls: tls_file(collection.tls.clone()),
+        run: run_file(collection.run_order.clone()),
+    };
+    toml::to_string(&file).map_err(|e| e.into())
+}
+
+/// The extension used by the request files.
+pub const REQUEST_SUFFIX: &str = ".cartero";
+
+/// Validates a file name typed by the user to create or rename an item of a collection.
+///
+/// Returns `None` if the name cannot be used as a file name, such as an empty name or a
+/// name with path separators. When `suffix` is given, it is appended unless the name
+/// already ends with it.
+pub fn collection_item_name(name: &str, suffix: Option<&str>) -> Option<String> {
+    let name = name.trim();
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+        return None;
+    }
+    match suffix {
+        Some(suffix) if !name.ends_with(suffix) => Some(format!("{name}{suffix}")),
+        _ => Some(name.to_string()),
+    }
+}
+
+/// Picks the name for a copy of a request file, such as `login copy.cartero`.
+///
+/// The `exists` function should tell whether a file name is already taken in the
+/// folder, so that a number is added until the name is free.
+pub fn duplicate_file_name<F: Fn(&str) -> bool>(file_name: &str, exists: F) -> String {
+    numbered_file_name(file_name, " copy", exists)
+}
+
+/// Picks a free name for a new request file, such as `login 2.cartero`.
+///
+/// The name is kept as it is when it is not taken yet.
+pub fn unique_file_name<F: Fn(&str) -> bool>(file_name: &str, exists: F) -> String {
+    numbered_file_name(file_name, "", exists)
+}
+
+/// Appends the label to the stem of the file name, followed by a number starting
+/// at 2 for as long as the name is taken.
+fn numbered_file_name<F: Fn(&str) -> bool>(file_name: &str, label: &str, exists: F) -> String {
+    let (stem, suffix) = match file_name.strip_suffix(REQUEST_SUFFIX) {
+        Some(stem) => (stem, REQUEST_SUFFIX),
+        None => (file_name, ""),
+    };
+    let mut candidate = format!("{stem}{label}{suffix}");
+    let mut count = 2;
+    while exists(&candidate) {
+        candidate = format!("{stem}{label} {count}{suffix}");
+        count += 1;
+    }
+    candidate
+}
+
+/// Reads the rows of a data file used to run a collection more than once.
+///
+/// Each row gives the variables of an iteration. A file whose name ends with
+/// `.json` must hold an array of objects, whose values that are not strings are
+/// kept as JSON. Any other file is read as CSV, with the names of the variables
+/// in its first line.
+pub fn parse_data_file(
+    file_name: &str,
+    contents: &str,
+) -> Result<Vec<KeyValueTable>, CarteroError> {
+    if file_name.to_lowercase().ends_with(".json") {
+        parse_data_json(contents)
+    } else {
+        parse_data_csv(contents)
+    }
+}
+
+fn parse_data_json(contents: &str) -> Result<Vec<KeyValueTable>, CarteroError> {
+    let invalid = || CarteroError::InvalidDataFile("expected an array of objects".into());
+    let document = serde_json::from_str::<serde_json::Value>(contents)?;
+    let rows = document.as_array().ok_or_else(invalid)?;
+    rows.iter()
+        .map(|row| {
+            let row = row.as_object().ok_or_else(invalid)?;
+            let variables = row
+                .iter()
+                .map(|(name, value)| {
+                    let value = match value {
+                        serde_json::Value::String(value) => value.clone(),
+                        serde_json::Value::Null => String::new(),
+                        value => value.to_string(),
+                    };
+                    KeyValue::from((name.as_str(), value.as_str()))
+                })
+                .collect();
+            Ok(variables)
         })
+        .collect()
 }
 
-pub async fn write_file(file: &gio::File, contents: &str) -> Result<(), CarteroError> {
-    file.replace_contents_future(contents.to_string(), None, true, gio::FileCreateFlags::NONE)
-        .await
-        .map_err(|result| {
-            let error = result.1;
-            println!("{error:?}");
-            CarteroError::FileDialogError
-        })?;
-    Ok(())
+fn parse_data_csv(contents: &str) -> Result<Vec<KeyValueTable>, CarteroError> {
+    let mut lines = csv_records(contents.trim_start_matches('\u{feff}'))?.into_iter();
+    let Some((_, names)) = lines.next() else {
+        return Ok(Vec::new());
+    };
+    lines
+        .map(|(line, fields)| {
+            if fields.len() != names.len() {
+                return Err(CarteroError::InvalidDataFile(format!(
+                    "line {line} has {} fields, expected {}",
+                    fields.len(),
+                    names.len()
+                )));
+            }
+            let variables = names
+                .iter()
+                .zip(fields.iter())
+                .map(|(name, value)| KeyValue::from((name.as_str(), value.as_str())))
+                .collect();
+            Ok(variables)
+        })
+        .collect()
+}
+
+/// Splits a CSV document into records, each one with the line where it starts.
+///
+/// Fields may be quoted, in which case they can hold commas, line breaks and
+/// quotes written twice. Empty lines are skipped.
+fn csv_records(contents: &str) -> Result<Vec<(usize, Vec<String>)>, CarteroError> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut line = 1;
+    let mut start = 1;
+    let mut chars = contents.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            '\n' if quoted => {
+                line += 1;
+                field.push(c);
+            }
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            '\r' if !quoted && chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                if fields.len() > 1 || !fields[0].is_empty() {
+                    records.push((start, std::mem::take(&mut fields)));
+                }
+                fields.clear();
+                line += 1;
+                start = line;
+            }
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(CarteroError::InvalidDataFile(format!(
+            "line {start} has an unterminated quote"
+        )));
+    }
+    fields.push(field);
+    if fields.len() > 1 || !fields[0].is_empty() {
+        records.push((start, fields));
+    }
+    Ok(records)
 }
 
 #[cfg(test)]
@@ -334,9 +1321,15 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
+        client::RequestError,
         entities::{
-            EndpointData, KeyValue, KeyValueTable, RawEncoding, RequestMethod, RequestPayload,
+            ApiKeyLocation, Assertion, AssertionKind, AwsSigV4Settings, Capture, CaptureScope,
+            CaptureSource, CertificateFormat, CollectionData, EndpointData, Environment, FormFile,
+            HmacAlgorithm, HmacSettings, HttpVersion, KeyValue, KeyValueTable, OAuth2Grant,
+            OAuth2Settings, ProxySettings, RawEncoding, RequestAuth, RequestMethod, RequestOptions,
+            RequestPayload, RequestProxy, RequestScripts, SignatureEncoding, TlsSettings,
         },
+        error::CarteroError,
         file::KeyValueDetail,
     };
 
@@ -440,7 +1433,7 @@ mod tests {
         assert_eq!(table.len(), 5);
         assert_eq!(
             table,
-            KeyValueTable::new(&vec![
+            KeyValueTable::new(&[
                 ("Accept", "*/*").into(),
                 ("Accept", "application/json").into(),
                 ("Accept", "application/ld+json").into(),
@@ -626,7 +1619,7 @@ Accept-Encoding = 'gzip'
         given_headers.sort();
         assert_eq!(
             given_headers,
-            KeyValueTable::new(&vec![
+            KeyValueTable::new(&[
                 KeyValue {
                     name: "Accept".into(),
                     value: "application/json".into(),
@@ -677,7 +1670,7 @@ body = 'hello'
         let toml = "
 version = 1
 url = 'https://www.google.com'
-method = 'THROW'
+method = 'THROW UP'
 ";
         assert!(super::parse_toml(toml).is_err());
     }
@@ -739,6 +1732,7 @@ Accept = 'text/html'
             headers,
             variables: KeyValueTable::default(),
             body,
+            ..Default::default()
         };
 
         let content = super::store_toml(&r).unwrap();
@@ -769,6 +1763,7 @@ Accept = 'text/html'
             headers,
             variables: KeyValueTable::default(),
             body,
+            ..Default::default()
         };
 
         let content = super::store_toml(&r).unwrap();
@@ -817,6 +1812,7 @@ body = 'hello'
             headers,
             variables: KeyValueTable::default(),
             body,
+            ..Default::default()
         };
 
         let content = super::store_toml(&r).unwrap();
@@ -833,7 +1829,7 @@ body = 'hello'
         // One thing important to test: since this is eventually a hashmap, the result
         // will be sorted by key name, but the order of the elements must match the
         // original order.
-        let headers = KeyValueTable::new(&vec![
+        let headers = KeyValueTable::new(&[
             KeyValue {
                 name: "X-Client-Id".into(),
                 value: "123412341234".into(),
@@ -874,6 +1870,7 @@ body = 'hello'
             headers,
             variables,
             body,
+            ..Default::default()
         };
 
         let content = super::store_toml(&r).unwrap();
@@ -883,7 +1880,7 @@ body = 'hello'
         assert_eq!(r.body, parsed.body);
 
         assert_eq!(
-            KeyValueTable::new(&vec![
+            KeyValueTable::new(&[
                 r.headers[1].clone(),
                 r.headers[2].clone(),
                 r.headers[0].clone(),
@@ -900,4 +1897,788 @@ body = 'hello'
             parsed.variables
         );
     }
+
+    #[test]
+    pub fn test_can_deserialize_multipart_files() {
+        let toml = "
+version = 1
+url = 'https://www.example.com/upload'
+method = 'POST'
+
+[body]
+type = 'multipart'
+
+[body.variables]
+title = 'Hello'
+
+[[body.files]]
+name = 'avatar'
+path = 'images/avatar.png'
+
+[[body.files]]
+name = 'cv'
+path = '/home/john/cv.pdf'
+filename = 'resume.pdf'
+content-type = 'application/pdf'
+active = false
+";
+        let endpoint = super::parse_toml(toml).unwrap();
+        let mut cv = FormFile::new("cv", "/home/john/cv.pdf");
+        cv.filename = Some("resume.pdf".into());
+        cv.content_type = Some("application/pdf".into());
+        cv.active = false;
+        assert_eq!(
+            endpoint.body,
+            RequestPayload::Multipart {
+                params: KeyValueTable::new(&[("title", "Hello").into()]),
+                files: vec![FormFile::new("avatar", "images/avatar.png"), cv],
+            }
+        );
+    }
+
+    #[test]
+    pub fn test_multipart_files_roundtrip() {
+        let mut renamed = FormFile::new("cv", "cv.pdf");
+        renamed.filename = Some("resume.pdf".into());
+        let endpoint = EndpointData {
+            url: "https://www.example.com/upload".into(),
+            method: RequestMethod::Post,
+            body: RequestPayload::Multipart {
+                params: KeyValueTable::default(),
+                files: vec![FormFile::new("avatar", "/tmp/avatar.png"), renamed],
+            },
+            ..Default::default()
+        };
+        let content = super::store_toml(&endpoint).unwrap();
+        assert!(!content.contains("content-type"));
+        assert!(!content.contains("active"));
+        assert_eq!(super::parse_toml(&content).unwrap(), endpoint);
+    }
+
+    #[test]
+    pub fn test_can_deserialize_body_file() {
+        let toml = "
+version = 1
+url = 'https://www.example.com/blobs'
+method = 'PUT'
+
+[body]
+type = 'file'
+path = 'fixtures/user.bin'
+content-type = 'application/x-protobuf'
+";
+        let endpoint = super::parse_toml(toml).unwrap();
+        assert_eq!(
+            endpoint.body,
+            RequestPayload::File {
+                path: "fixtures/user.bin".into(),
+                content_type: Some("application/x-protobuf".into()),
+            }
+        );
+    }
+
+    #[test]
+    pub fn test_body_file_roundtrip() {
+        let endpoint = EndpointData {
+            url: "https://www.example.com/blobs".into(),
+            method: RequestMethod::Put,
+            body: RequestPayload::File {
+                path: "images/avatar.png".into(),
+                content_type: None,
+            },
+            ..Default::default()
+        };
+        let content = super::store_toml(&endpoint).unwrap();
+        assert!(content.contains("type = \"file\""));
+        assert!(!content.contains("content-type"));
+        assert_eq!(super::parse_toml(&content).unwrap(), endpoint);
+    }
+
+    #[test]
+    pub fn test_custom_method_roundtrip() {
+        let endpoint = EndpointData {
+            url: "https://www.example.com/dav/".into(),
+            method: RequestMethod::Custom("PROPFIND".into()),
+            ..Default::default()
+        };
+        let content = super::store_toml(&endpoint).unwrap();
+        assert!(content.contains("method = \"PROPFIND\""));
+        assert_eq!(super::parse_toml(&content).unwrap(), endpoint);
+
+        let toml = "
+version = 1
+url = 'https://www.example.com'
+method = 'NOT VALID'
+";
+        assert!(matches!(
+            super::parse_toml(toml),
+            Err(CarteroError::Request(RequestError::InvalidHttpVerb))
+        ));
+    }
+
+    #[test]
+    pub fn test_options_roundtrip() {
+        let endpoint = EndpointData {
+            url: "https://www.example.com/slow".into(),
+            options: RequestOptions {
+                timeout: Some(5),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let content = super::store_toml(&endpoint).unwrap();
+        assert!(content.contains("[options]\ntimeout = 5\n"));
+        assert_eq!(super::parse_toml(&content).unwrap(), endpoint);
+
+        let endpoint = EndpointData {
+            url: "https://www.example.com".into(),
+            ..Default::default()
+        };
+        let content = super::store_toml(&endpoint).unwrap();
+        assert!(!content.contains("[options]"));
+    }
+
+    #[test]
+    pub fn test_can_deserialize_options() {
+        let toml = "
+version = 1
+url = 'https://localhost:8443/'
+method = 'GET'
+
+[options]
+follow-redirects = true
+max-redirects = 3
+verify-tls = false
+http-version = '1.1'
+decompress = false
+";
+        let endpoint = super::parse_toml(toml).unwrap();
+        assert_eq!(
+            endpoint.options,
+            RequestOptions {
+                timeout: None,
+                follow_redirects: true,
+                max_redirects: 3,
+                verify_tls: false,
+                http_version: HttpVersion::Http11,
+                decompress: false,
+            }
+        );
+        let content = super::store_toml(&endpoint).unwrap();
+        assert!(content.contains("http-version = \"1.1\""));
+        assert!(!content.contains("timeout"));
+        assert_eq!(super::parse_toml(&content).unwrap(), endpoint);
+    }
+
+    #[test]
+    pub fn test_proxy_roundtrip() {
+        let endpoint = EndpointData {
+            url: "https://www.example.com".into(),
+            proxy: RequestProxy::Custom(ProxySettings {
+                url: "socks5h://localhost:1080".into(),
+                no_proxy: "localhost".into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let content = super::store_toml(&endpoint).unwrap();
+        assert!(content
+            .contains("[proxy]\nurl = \"socks5h://localhost:1080\"\nno-proxy = \"localhost\"\n"));
+        assert_eq!(super::parse_toml(&content).unwrap(), endpoint);
+
+        let endpoint = EndpointData {
+            proxy: RequestProxy::Direct,
+            ..endpoint
+        };
+        let content = super::store_toml(&endpoint).unwrap();
+        assert!(content.contains("[proxy]\ndirect = true\n"));
+        assert_eq!(super::parse_toml(&content).unwrap(), endpoint);
+
+        let endpoint = EndpointData {
+            proxy: RequestProxy::Default,
+            ..endpoint
+        };
+        let content = super::store_toml(&endpoint).unwrap();
+        assert!(!content.contains("[proxy]"));
+    }
+
+    #[test]
+    pub fn test_auth_roundtrip() {
+        let endpoint = EndpointData {
+            url: "https://www.example.com".into(),
+            auth: RequestAuth::Basic {
+                username: "john".into(),
+                password: "{{PASSWORD}}".into(),
+            },
+            ..Default::default()
+        };
+        let content = super::store_toml(&endpoint).unwrap();
+        assert!(content.contains(
+            "[auth]\ntype = \"basic\"\nusername = \"john\"\npassword = \"{{PASSWORD}}\"\n"
+        ));
+        assert_eq!(super::parse_toml(&content).unwrap(), endpoint);
+
+        let endpoint = EndpointData {
+            auth: RequestAuth::ApiKey {
+                name: "api_key".into(),
+                value: "{{KEY}}".into(),
+                location: ApiKeyLocation::Query,
+            },
+            ..endpoint
+        };
+        let content = super::store_toml(&endpoint).unwrap();
+        assert!(content.contains(
+            "type = \"api-key\"\nname = \"api_key\"\nvalue = \"{{KEY}}\"\nin = \"query\"\n"
+        ));
+        assert_eq!(super::parse_toml(&content).unwrap(), endpoint);
+
+        let endpoint = EndpointData {
+            auth: RequestAuth::None,
+            ..endpoint
+        };
+        let content = super::store_toml(&endpoint).unwrap();
+        assert!(!content.contains("[auth]"));
+    }
+
+    #[test]
+    pub fn test_can_deserialize_auth() {
+        let toml = r#"
+            version = 1
+            url = "https://www.example.com"
+            method = "GET"
+
+            [auth]
+            type = "digest"
+            username = "john"
+        "#;
+        let endpoint = super::parse_toml(toml).unwrap();
+        assert_eq!(
+            endpoint.auth,
+            RequestAuth::Digest {
+                username: "john".into(),
+                password: "".into(),
+            }
+        );
+
+        let toml = r#"
+            version = 1
+            url = "https://www.example.com"
+            method = "GET"
+
+            [auth]
+            type = "api-key"
+            name = "X-API-Key"
+            value = "1234"
+        "#;
+        let endpoint = super::parse_toml(toml).unwrap();
+        assert_eq!(
+            endpoint.auth,
+            RequestAuth::ApiKey {
+                name: "X-API-Key".into(),
+                value: "1234".into(),
+                location: ApiKeyLocation::Header,
+            }
+        );
+
+        let toml = r#"
+            version = 1
+            url = "https://www.example.com"
+            method = "GET"
+
+            [auth]
+            type = "kerberos"
+        "#;
+        assert!(super::parse_toml(toml).is_err());
+    }
+
+    #[test]
+    pub fn test_oauth2_roundtrip() {
+        let endpoint = EndpointData {
+            url: "https://api.example.com/me".into(),
+            auth: RequestAuth::OAuth2(OAuth2Settings {
+                grant: OAuth2Grant::AuthorizationCode,
+                token_url: "https://login.example.com/token".into(),
+                authorization_url: "https://login.example.com/authorize".into(),
+                client_id: "cartero".into(),
+                scope: "openid profile".into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let content = super::store_toml(&endpoint).unwrap();
+        assert!(content.contains(
+            "[auth]\ntype = \"oauth2\"\ngrant = \"authorization-code\"\ntoken_url = \"https://login.example.com/token\"\nauthorization_url = \"https://login.example.com/authorize\"\nclient_id = \"cartero\"\nscope = \"openid profile\"\n"
+        ));
+        assert!(!content.contains("client_secret"));
+        assert_eq!(super::parse_toml(&content).unwrap(), endpoint);
+
+        let toml = r#"
+            version = 1
+            url = "https://api.example.com/me"
+            method = "GET"
+
+            [auth]
+            type = "oauth2"
+            token_url = "{{IDP}}/token"
+            client_id = "cartero"
+            client_secret = "{{SECRET}}"
+        "#;
+        let endpoint = super::parse_toml(toml).unwrap();
+        assert_eq!(
+            endpoint.auth,
+            RequestAuth::OAuth2(OAuth2Settings {
+                grant: OAuth2Grant::ClientCredentials,
+                token_url: "{{IDP}}/token".into(),
+                client_id: "cartero".into(),
+                client_secret: "{{SECRET}}".into(),
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    pub fn test_signature_roundtrip() {
+        let endpoint = EndpointData {
+            url: "https://execute-api.us-east-1.amazonaws.com/prod/items".into(),
+            auth: RequestAuth::AwsSigV4(AwsSigV4Settings {
+                access_key: "{{AWS_ACCESS_KEY_ID}}".into(),
+                secret_key: "{{AWS_SECRET_ACCESS_KEY}}".into(),
+                region: "us-east-1".into(),
+                service: "execute-api".into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let content = super::store_toml(&endpoint).unwrap();
+        assert!(content.contains("[auth]\ntype = \"aws-sigv4\"\n"));
+        assert!(!content.contains("session_token"));
+        assert_eq!(super::parse_toml(&content).unwrap(), endpoint);
+
+        let toml = r#"
+            version = 1
+            url = "https://api.example.com/orders"
+            method = "POST"
+
+            [auth]
+            type = "hmac"
+            key = "{{SECRET}}"
+            message = "{method}\\n{path}\\n{timestamp}"
+            header = "X-Signature"
+            timestamp_header = "X-Timestamp"
+            encoding = "base64"
+        "#;
+        let endpoint = super::parse_toml(toml).unwrap();
+        let auth = RequestAuth::Hmac(HmacSettings {
+            algorithm: HmacAlgorithm::Sha256,
+            key: "{{SECRET}}".into(),
+            message: "{method}\\n{path}\\n{timestamp}".into(),
+            header: "X-Signature".into(),
+            timestamp_header: "X-Timestamp".into(),
+            encoding: SignatureEncoding::Base64,
+        });
+        assert_eq!(endpoint.auth, auth);
+        let content = super::store_toml(&endpoint).unwrap();
+        assert!(content.contains("algorithm = \"sha256\"\n"));
+        assert_eq!(super::parse_toml(&content).unwrap().auth, auth);
+    }
+
+    #[test]
+    pub fn test_captures_roundtrip() {
+        let endpoint = EndpointData {
+            url: "https://api.example.com/login".into(),
+            method: RequestMethod::Post,
+            captures: vec![
+                Capture {
+                    variable: "TOKEN".into(),
+                    source: CaptureSource::JsonPath("$.access_token".into()),
+                    scope: CaptureScope::Environment,
+                    active: true,
+                },
+                Capture {
+                    variable: "STATUS".into(),
+                    source: CaptureSource::StatusCode,
+                    scope: CaptureScope::Session,
+                    active: false,
+                },
+            ],
+            ..Default::default()
+        };
+        let content = super::store_toml(&endpoint).unwrap();
+        assert!(content.contains(
+            "[[captures]]\nvariable = \"TOKEN\"\nfrom = \"json\"\nexpression = \"$.access_token\"\nscope = \"environment\"\n"
+        ));
+        assert!(content.contains(
+            "[[captures]]\nvariable = \"STATUS\"\nfrom = \"status\"\nscope = \"session\"\nactive = false\n"
+        ));
+        assert_eq!(super::parse_toml(&content).unwrap(), endpoint);
+
+        let toml = r#"
+            version = 1
+            url = "https://api.example.com/login"
+            method = "POST"
+
+            [[captures]]
+            variable = "SESSION"
+            from = "header"
+            expression = "X-Session-Id"
+
+            [[captures]]
+            variable = "ID"
+            from = "xml"
+            expression = "//user/@id"
+        "#;
+        let endpoint = super::parse_toml(toml).unwrap();
+        assert_eq!(
+            endpoint.captures,
+            vec![
+                Capture {
+                    variable: "SESSION".into(),
+                    source: CaptureSource::Header("X-Session-Id".into()),
+                    ..Default::default()
+                },
+                Capture {
+                    variable: "ID".into(),
+                    source: CaptureSource::XPath("//user/@id".into()),
+                    ..Default::default()
+                },
+            ]
+        );
+        let content = super::store_toml(&EndpointData::default()).unwrap();
+        assert!(!content.contains("captures"));
+    }
+
+    #[test]
+    pub fn test_assertions_roundtrip() {
+        let endpoint = EndpointData {
+            url: "https://api.example.com/users/42".into(),
+            assertions: vec![
+                Assertion {
+                    kind: AssertionKind::StatusInRange,
+                    expected: "2xx".into(),
+                    ..Default::default()
+                },
+                Assertion {
+                    kind: AssertionKind::JsonPathEquals,
+                    subject: "$.id".into(),
+                    expected: "42".into(),
+                    active: false,
+                },
+            ],
+            ..Default::default()
+        };
+        let content = super::store_toml(&endpoint).unwrap();
+        assert!(content.contains("[[assertions]]\ncheck = \"status-range\"\nexpected = \"2xx\"\n"));
+        assert!(content.contains(
+            "[[assertions]]\ncheck = \"json-equals\"\nsubject = \"$.id\"\nexpected = \"42\"\nactive = false\n"
+        ));
+        assert_eq!(super::parse_toml(&content).unwrap(), endpoint);
+
+        let toml = r#"
+            version = 1
+            url = "https://api.example.com/users/42"
+            method = "GET"
+
+            [[assertions]]
+            check = "header-exists"
+            subject = "ETag"
+
+            [[assertions]]
+            check = "duration-under"
+            expected = "500"
+        "#;
+        let endpoint = super::parse_toml(toml).unwrap();
+        assert_eq!(
+            endpoint.assertions,
+            vec![
+                Assertion {
+                    kind: AssertionKind::HeaderExists,
+                    subject: "ETag".into(),
+                    ..Default::default()
+                },
+                Assertion {
+                    kind: AssertionKind::DurationUnder,
+                    expected: "500".into(),
+                    ..Default::default()
+                },
+            ]
+        );
+        let content = super::store_toml(&EndpointData::default()).unwrap();
+        assert!(!content.contains("assertions"));
+    }
+
+    #[test]
+    pub fn test_scripts_roundtrip() {
+        let endpoint = EndpointData {
+            url: "https://api.example.com/items".into(),
+            scripts: RequestScripts {
+                pre_request: "set_variable(\"NONCE\", random_hex(16));\nprint(\"ready\");\n".into(),
+                post_response: String::new(),
+            },
+            ..Default::default()
+        };
+        let content = super::store_toml(&endpoint).unwrap();
+        assert!(content.contains("[scripts]\npre-request = "));
+        assert!(!content.contains("post-response"));
+        assert_eq!(super::parse_toml(&content).unwrap(), endpoint);
+
+        let toml = r#"
+            version = 1
+            url = "https://api.example.com/items"
+            method = "GET"
+
+            [scripts]
+            post-response = """
+            if response.status != 200 {
+                throw "unexpected status";
+            }
+            """
+        "#;
+        let endpoint = super::parse_toml(toml).unwrap();
+        assert_eq!(endpoint.scripts.pre_request, "");
+        assert!(endpoint
+            .scripts
+            .post_response
+            .contains("throw \"unexpected status\";"));
+
+        let content = super::store_toml(&EndpointData::default()).unwrap();
+        assert!(!content.contains("scripts"));
+    }
+
+    #[test]
+    pub fn test_can_deserialize_tls() {
+        let toml = "
+version = 1
+url = 'https://localhost:8443/'
+method = 'GET'
+
+[tls]
+certificate = 'certs/client.p12'
+format = 'pkcs12'
+password = '{{P12_PASSWORD}}'
+ca-bundle = 'certs/ca.pem'
+";
+        let endpoint = super::parse_toml(toml).unwrap();
+        assert_eq!(
+            endpoint.tls,
+            TlsSettings {
+                certificate: "certs/client.p12".into(),
+                format: CertificateFormat::Pkcs12,
+                key: String::new(),
+                password: "{{P12_PASSWORD}}".into(),
+                ca_bundle: "certs/ca.pem".into(),
+            }
+        );
+        let content = super::store_toml(&endpoint).unwrap();
+        assert!(!content.contains("key ="));
+        assert_eq!(super::parse_toml(&content).unwrap(), endpoint);
+
+        let endpoint = EndpointData {
+            url: "https://www.example.com".into(),
+            ..Default::default()
+        };
+        let content = super::store_toml(&endpoint).unwrap();
+        assert!(!content.contains("[tls]"));
+    }
+
+    #[test]
+    pub fn test_environment_name() {
+        assert_eq!(super::environment_name("dev.env.toml"), "dev");
+        assert_eq!(super::environment_name("prod.eu.env.toml"), "prod.eu");
+        assert_eq!(super::environment_name("staging.toml"), "staging");
+        assert_eq!(super::environment_name("local"), "local");
+    }
+
+    #[test]
+    pub fn test_can_deserialize_environment() {
+        let toml = "
+version = 1
+
+[variables]
+API_ROOT = 'dev.example.com'
+TOKEN = { value = '1234', active = true, secret = true }
+";
+        let environment = super::parse_environment_toml("dev", toml).unwrap();
+        assert_eq!(environment.name, "dev");
+        assert_eq!(
+            environment.variables,
+            KeyValueTable::new(&[
+                ("API_ROOT", "dev.example.com").into(),
+                KeyValue {
+                    name: "TOKEN".into(),
+                    value: "1234".into(),
+                    active: true,
+                    secret: true,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    pub fn test_environment_without_variables() {
+        let environment = super::parse_environment_toml("empty", "version = 1").unwrap();
+        assert!(environment.variables.is_empty());
+    }
+
+    #[test]
+    pub fn test_environment_outdated_schema() {
+        let result = super::parse_environment_toml("dev", "version = 2");
+        assert!(matches!(result, Err(CarteroError::OutdatedSchema)));
+    }
+
+    #[test]
+    pub fn test_environment_roundtrip() {
+        let environment = Environment {
+            name: "prod".into(),
+            variables: KeyValueTable::new(&[
+                ("API_ROOT", "example.com").into(),
+                ("TOKEN", "abcd").into(),
+            ]),
+            tls: TlsSettings {
+                certificate: "/etc/certs/prod.pem".into(),
+                key: "/etc/certs/prod.key".into(),
+                ..Default::default()
+            },
+        };
+        let toml = super::store_environment_toml(&environment).unwrap();
+        let parsed = super::parse_environment_toml("prod", &toml).unwrap();
+        assert_eq!(parsed, environment);
+    }
+
+    #[test]
+    pub fn test_can_deserialize_collection() {
+        let toml = "
+version = 1
+
+[headers]
+Accept = 'application/json'
+X-Client = ['cartero', 'tests']
+
+[variables]
+API_ROOT = 'example.com'
+";
+        let collection = super::parse_collection_toml(toml).unwrap();
+        assert_eq!(
+            collection.headers,
+            KeyValueTable::new(&[
+                ("Accept", "application/json").into(),
+                ("X-Client", "cartero").into(),
+                ("X-Client", "tests").into(),
+            ])
+        );
+        assert_eq!(
+            collection.variables,
+            KeyValueTable::new(&[("API_ROOT", "example.com").into()])
+        );
+    }
+
+    #[test]
+    pub fn test_collection_outdated_schema() {
+        let result = super::parse_collection_toml("version = 2");
+        assert!(matches!(result, Err(CarteroError::OutdatedSchema)));
+    }
+
+    #[test]
+    pub fn test_collection_roundtrip() {
+        let collection = CollectionData {
+            headers: KeyValueTable::new(&[("Accept", "application/json").into()]),
+            variables: KeyValueTable::new(&[("API_ROOT", "example.com").into()]),
+            tls: TlsSettings {
+                ca_bundle: "certs/ca.pem".into(),
+                ..Default::default()
+            },
+            run_order: vec!["login.cartero".into(), "books/list.cartero".into()],
+        };
+        let toml = super::store_collection_toml(&collection).unwrap();
+        let parsed = super::parse_collection_toml(&toml).unwrap();
+        assert_eq!(parsed, collection);
+    }
+
+    #[test]
+    pub fn test_parse_csv_data_file() {
+        let csv = "USER,PASSWORD\r\nalice,\"s3cr3t, \"\"quoted\"\"\"\r\n\nbob,\"two\nlines\"\n";
+        let rows = super::parse_data_file("users.csv", csv).unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                KeyValueTable::new(&[
+                    ("USER", "alice").into(),
+                    ("PASSWORD", "s3cr3t, \"quoted\"").into(),
+                ]),
+                KeyValueTable::new(&[("USER", "bob").into(), ("PASSWORD", "two\nlines").into()]),
+            ]
+        );
+
+        let result = super::parse_data_file("users.csv", "USER,PASSWORD\nalice\n");
+        assert!(matches!(result, Err(CarteroError::InvalidDataFile(_))));
+        let result = super::parse_data_file("users.csv", "USER\n\"alice\n");
+        assert!(matches!(result, Err(CarteroError::InvalidDataFile(_))));
+    }
+
+    #[test]
+    pub fn test_parse_json_data_file() {
+        let json = r#"[{"USER": "alice", "ID": 1, "ADMIN": true, "TEAM": null}]"#;
+        let rows = super::parse_data_file("users.JSON", json).unwrap();
+        assert_eq!(
+            rows,
+            vec![KeyValueTable::new(&[
+                ("USER", "alice").into(),
+                ("ID", "1").into(),
+                ("ADMIN", "true").into(),
+                ("TEAM", "").into(),
+            ])]
+        );
+
+        let result = super::parse_data_file("users.json", r#"{"USER": "alice"}"#);
+        assert!(matches!(result, Err(CarteroError::InvalidDataFile(_))));
+        let result = super::parse_data_file("users.json", "[1, 2]");
+        assert!(matches!(result, Err(CarteroError::InvalidDataFile(_))));
+    }
+
+    #[test]
+    pub fn test_collection_item_name() {
+        let suffix = Some(super::REQUEST_SUFFIX);
+        assert_eq!(
+            super::collection_item_name(" login ", suffix),
+            Some("login.cartero".into())
+        );
+        assert_eq!(
+            super::collection_item_name("login.cartero", suffix),
+            Some("login.cartero".into())
+        );
+        assert_eq!(
+            super::collection_item_name("users", None),
+            Some("users".into())
+        );
+        assert_eq!(super::collection_item_name("", suffix), None);
+        assert_eq!(super::collection_item_name("..", None), None);
+        assert_eq!(super::collection_item_name("a/b", suffix), None);
+        assert_eq!(super::collection_item_name("a\\b", suffix), None);
+    }
+
+    #[test]
+    pub fn test_duplicate_file_name() {
+        let taken = ["login copy.cartero", "login copy 2.cartero"];
+        assert_eq!(
+            super::duplicate_file_name("login.cartero", |_| false),
+            "login copy.cartero"
+        );
+        assert_eq!(
+            super::duplicate_file_name("login.cartero", |name| taken.contains(&name)),
+            "login copy 3.cartero"
+        );
+        assert_eq!(super::duplicate_file_name("notes", |_| false), "notes copy");
+    }
+
+    #[test]
+    pub fn test_unique_file_name() {
+        let taken = ["GET users.cartero", "GET users 2.cartero"];
+        assert_eq!(
+            super::unique_file_name("login.cartero", |name| taken.contains(&name)),
+            "login.cartero"
+        );
+        assert_eq!(
+            super::unique_file_name("GET users.cartero", |name| taken.contains(&name)),
+            "GET users 3.cartero"
+        );
+    }
 }
@@ -0,0 +1,861 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Reads the requests written as curl commands, such as the ones copied from the
+//! developer tools of a web browser.
+
+use crate::client::uri_encode;
+use crate::entities::{
+    EndpointData, FormFile, KeyValue, KeyValueTable, RawEncoding, RequestAuth, RequestMethod,
+    RequestPayload,
+};
+
+use super::{
+    drop_automatic_content_type, raw_encoding, shell, ImportError, URLENCODED_CONTENT_TYPE,
+};
+
+/// Short options that take a value, either as the next word or attached (`-XPOST`).
+const SHORT_OPTIONS_WITH_VALUE: &str = "AbcCdDeEFHKmoPQrtTuUwxXyYz";
+
+/// Long options that take a value but that do not have any effect on the request.
+const IGNORED_LONG_OPTIONS_WITH_VALUE: &[&str] = &[
+    "cacert",
+    "capath",
+    "cert",
+    "cert-type",
+    "ciphers",
+    "config",
+    "connect-timeout",
+    "connect-to",
+    "continue-at",
+    "cookie-jar",
+    "dump-header",
+    "interface",
+    "keepalive-time",
+    "key",
+    "key-type",
+    "limit-rate",
+    "max-filesize",
+    "max-redirs",
+    "max-time",
+    "output",
+    "output-dir",
+    "pass",
+    "proxy",
+    "proxy-user",
+    "quote",
+    "range",
+    "resolve",
+    "retry",
+    "retry-delay",
+    "retry-max-time",
+    "speed-limit",
+    "speed-time",
+    "stderr",
+    "telnet-option",
+    "time-cond",
+    "trace",
+    "trace-ascii",
+    "upload-file",
+    "write-out",
+];
+
+/// Long options understood by the importer that take a value.
+const LONG_OPTIONS_WITH_VALUE: &[&str] = &[
+    "cookie",
+    "data",
+    "data-ascii",
+    "data-binary",
+    "data-raw",
+    "data-urlencode",
+    "form",
+    "form-string",
+    "header",
+    "json",
+    "oauth2-bearer",
+    "referer",
+    "request",
+    "url",
+    "user",
+    "user-agent",
+];
+
+/// Maps a short option to the name of the equivalent long option.
+fn long_name(short: char) -> Option<&'static str> {
+    match short {
+        'A' => Some("user-agent"),
+        'b' => Some("cookie"),
+        'd' => Some("data"),
+        'e' => Some("referer"),
+        'F' => Some("form"),
+        'G' => Some("get"),
+        'H' => Some("header"),
+        'I' => Some("head"),
+        'u' => Some("user"),
+        'X' => Some("request"),
+        _ => None,
+    }
+}
+
+/// An option read from the command line, using the long name when there is one.
+enum Argument {
+    Option(String, Option<String>),
+    Positional(String),
+}
+
+fn takes_value(long: &str) -> bool {
+    LONG_OPTIONS_WITH_VALUE.contains(&long) || IGNORED_LONG_OPTIONS_WITH_VALUE.contains(&long)
+}
+
+/// Groups the words of the command line into options and their values.
+fn parse_arguments(words: &[String]) -> Result<Vec<Argument>, ImportError> {
+    let mut arguments = Vec::new();
+    let mut words = words.iter();
+    let mut only_positional = false;
+
+    while let Some(word) = words.next() {
+        if only_positional || word == "-" || !word.starts_with('-') {
+            arguments.push(Argument::Positional(word.clone()));
+        } else if word == "--" {
+            only_positional = true;
+        } else if let Some(long) = word.strip_prefix("--") {
+            let (long, attached) = match long.split_once('=') {
+                Some((name, value)) if takes_value(name) => (name, Some(value.to_string())),
+                _ => (long, None),
+            };
+            if takes_value(long) {
+                let value = match attached {
+                    Some(value) => value,
+                    None => words
+                        .next()
+                        .cloned()
+                        .ok_or_else(|| ImportError::MissingValue(format!("--{long}")))?,
+                };
+                arguments.push(Argument::Option(long.to_string(), Some(value)));
+            } else {
+                arguments.push(Argument::Option(long.to_string(), None));
+            }
+        } else {
+            // Short options can be bundled together, as in -sSL, and the last one
+            // of them may have its value attached, as in -XPOST.
+            let mut shorts = word.chars().skip(1);
+            while let Some(short) = shorts.next() {
+                let name = long_name(short)
+                    .map(String::from)
+                    .unwrap_or_else(|| short.to_string());
+                if SHORT_OPTIONS_WITH_VALUE.contains(short) {
+                    let rest: String = shorts.by_ref().collect();
+                    let value = if rest.is_empty() {
+                        words
+                            .next()
+                            .cloned()
+                            .ok_or_else(|| ImportError::MissingValue(format!("-{short}")))?
+                    } else {
+                        rest
+                    };
+                    arguments.push(Argument::Option(name, Some(value)));
+                } else {
+                    arguments.push(Argument::Option(name, None));
+                }
+            }
+        }
+    }
+    Ok(arguments)
+}
+
+/// Encodes the value of a --data-urlencode option the way curl would do, which
+/// writes spaces as `%20` instead of `+`.
+fn urlencode_data(value: &str) -> Result<String, ImportError> {
+    let encode = |content: &str| uri_encode(content.as_bytes());
+    // Both @file and name@file read the content from a file.
+    if value.starts_with('@') || (!value.contains('=') && value.contains('@')) {
+        return Err(ImportError::Unsupported("reading data from files".into()));
+    }
+    match value.split_once('=') {
+        Some(("", content)) => Ok(encode(content)),
+        Some((name, content)) => Ok(format!("{name}={}", encode(content))),
+        None => Ok(encode(value)),
+    }
+}
+
+/// Parses a header given with -H. Returns None when the header removes an internal one.
+fn parse_header(header: &str) -> Option<KeyValue> {
+    if let Some(name) = header.strip_suffix(';') {
+        if !name.contains(':') {
+            return Some(KeyValue::from((name.trim(), "")));
+        }
+    }
+    let (name, value) = header.split_once(':')?;
+    let value = value.trim();
+    if value.is_empty() {
+        None
+    } else {
+        Some(KeyValue::from((name.trim(), value)))
+    }
+}
+
+/// Reads a value of a -F option, which may be quoted, up to the next semicolon.
+fn read_form_value<I: Iterator<Item = char>>(chars: &mut std::iter::Peekable<I>) -> String {
+    let mut value = String::new();
+    if chars.peek() == Some(&'"') {
+        chars.next();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => value.extend(chars.next()),
+                '"' => break,
+                c => value.push(c),
+            }
+        }
+        // Anything between the closing quote and the semicolon is ignored by curl.
+        for c in chars.by_ref() {
+            if c == ';' {
+                break;
+            }
+        }
+    } else {
+        for c in chars.by_ref() {
+            if c == ';' {
+                break;
+            }
+            value.push(c);
+        }
+    }
+    value
+}
+
+/// Parses the file given to a -F option, such as `avatar=@me.png;type=image/png`.
+fn parse_form_file(name: &str, spec: &str) -> FormFile {
+    let mut chars = spec.chars().peekable();
+    let path = read_form_value(&mut chars);
+    let mut file = FormFile::new(name, &path);
+    while chars.peek().is_some() {
+        let key: String = chars.by_ref().take_while(|&c| c != '=').collect();
+        let value = read_form_value(&mut chars);
+        match key.trim() {
+            "type" => file.content_type = Some(value),
+            "filename" => file.filename = Some(value),
+            _ => {}
+        }
+    }
+    file
+}
+
+/// The Content-Type given with -H. curl sends the last one if there are many.
+fn content_type(headers: &KeyValueTable) -> Option<String> {
+    headers
+        .iter()
+        .rev()
+        .find(|header| header.name.eq_ignore_ascii_case("Content-Type"))
+        .map(|header| header.value.clone())
+}
+
+/// Picks the kind of body that better represents the data given to curl.
+fn guess_payload(data: &str, content_type: Option<&str>) -> RequestPayload {
+    let raw = |encoding: RawEncoding| RequestPayload::Raw {
+        encoding,
+        content: data.as_bytes().to_vec(),
+    };
+
+    if let Some(content_type) = content_type {
+        let encoding = raw_encoding(content_type);
+        if encoding != RawEncoding::OctetStream {
+            return raw(encoding);
+        }
+    } else {
+        let trimmed = data.trim_start();
+        if (trimmed.starts_with('{') || trimmed.starts_with('['))
+            && serde_json::from_str::<serde_json::Value>(data).is_ok()
+        {
+            return raw(RawEncoding::Json);
+        }
+    }
+
+    let is_form = match content_type {
+        None => true,
+        Some(ct) => ct.to_lowercase().starts_with(URLENCODED_CONTENT_TYPE),
+    };
+    if is_form && !data.is_empty() && data.split('&').all(|part| part.contains('=')) {
+        if let Ok(pairs) = serde_urlencoded::from_str::<Vec<(String, String)>>(data) {
+            return RequestPayload::Urlencoded(pairs.into_iter().map(KeyValue::from).collect());
+        }
+    }
+
+    raw(RawEncoding::OctetStream)
+}
+
+/// Builds an endpoint out of a curl command line.
+///
+/// The command may span multiple lines using backslashes and it may use the quoting
+/// rules of a POSIX shell. Options that do not change the request, such as the ones
+/// that control the output or the timeouts, are ignored.
+pub fn parse_curl(command: &str) -> Result<EndpointData, ImportError> {
+    let words = shell::split(command)?;
+    let Some((program, words)) = words.split_first() else {
+        return Err(ImportError::NotCurl);
+    };
+    if program != "curl" && program != "curl.exe" && !program.ends_with("/curl") {
+        return Err(ImportError::NotCurl);
+    }
+
+    let mut url: Option<String> = None;
+    let mut method: Option<String> = None;
+    let mut headers = KeyValueTable::default();
+    let mut data: Vec<String> = Vec::new();
+    let mut form: Vec<KeyValue> = Vec::new();
+    let mut files: Vec<FormFile> = Vec::new();
+    let mut body_file: Option<String> = None;
+    let mut get = false;
+    let mut head = false;
+    // -u sends Basic credentials unless --digest is also given.
+    let mut user: Option<String> = None;
+    let mut digest = false;
+
+    for argument in parse_arguments(words)? {
+        let (name, value) = match argument {
+            Argument::Positional(value) => {
+                url.get_or_insert(value);
+                continue;
+            }
+            Argument::Option(name, value) => (name, value.unwrap_or_default()),
+        };
+        match name.as_str() {
+            "url" => {
+                url.get_or_insert(value);
+            }
+            "request" => method = Some(value),
+            "header" => headers.extend(parse_header(&value)),
+            "user-agent" => headers.push(KeyValue::from(("User-Agent", value.as_str()))),
+            "referer" => headers.push(KeyValue::from(("Referer", value.as_str()))),
+            "cookie" if value.contains('=') => {
+                headers.push(KeyValue::from(("Cookie", value.as_str())))
+            }
+            "oauth2-bearer" => {
+                let token = format!("Bearer {value}");
+                headers.push(KeyValue::from(("Authorization", token.as_str())));
+            }
+            "user" => user = Some(value),
+            "digest" => digest = true,
+            "basic" => digest = false,
+            // Only --data-binary sends the file as is, -d strips its newlines.
+            "data-binary" if value.starts_with('@') && value != "@-" => {
+                if body_file.is_some() {
+                    return Err(ImportError::Unsupported(
+                        "sending many files as the body".into(),
+                    ));
+                }
+                body_file = Some(value[1..].to_string());
+            }
+            "data" | "data-ascii" | "data-binary" | "json" if value.starts_with('@') => {
+                return Err(ImportError::Unsupported("reading data from files".into()));
+            }
+            "data" | "data-ascii" => data.push(value.replace(['\r', '\n'], "")),
+            "data-binary" | "data-raw" => data.push(value),
+            "data-urlencode" => data.push(urlencode_data(&value)?),
+            "json" => {
+                data.push(value);
+                if headers.header("Content-Type").is_none() {
+                    headers.push(KeyValue::from(("Content-Type", "application/json")));
+                }
+                if headers.header("Accept").is_none() {
+                    headers.push(KeyValue::from(("Accept", "application/json")));
+                }
+            }
+            "form" | "form-string" => {
+                let Some((key, content)) = value.split_once('=') else {
+                    return Err(ImportError::MissingValue(format!("--{name}")));
+                };
+                if name == "form" && content.starts_with('<') {
+                    return Err(ImportError::Unsupported(
+                        "reading form fields from files".into(),
+                    ));
+                }
+                match content.strip_prefix('@') {
+                    Some(spec) if name == "form" => files.push(parse_form_file(key, spec)),
+                    _ => form.push(KeyValue::from((key, content))),
+                }
+            }
+            "get" => get = true,
+            "head" => head = true,
+            _ => {}
+        }
+    }
+
+    let auth = match user {
+        Some(credentials) => {
+            let (username, password) = credentials.split_once(':').unwrap_or((&credentials, ""));
+            let (username, password) = (username.into(), password.into());
+            if digest {
+                RequestAuth::Digest { username, password }
+            } else {
+                RequestAuth::Basic { username, password }
+            }
+        }
+        None => RequestAuth::None,
+    };
+
+    let mut url = url.ok_or(ImportError::MissingUrl)?;
+    if !url.contains("://") && !url.starts_with("{{") {
+        url = format!("http://{url}");
+    }
+
+    let method = match method {
+        Some(method) => RequestMethod::try_from(method.as_str())
+            .map_err(|_| ImportError::InvalidMethod(method))?,
+        None if head => RequestMethod::Head,
+        None if !get
+            && (!data.is_empty()
+                || !form.is_empty()
+                || !files.is_empty()
+                || body_file.is_some()) =>
+        {
+            RequestMethod::Post
+        }
+        None => RequestMethod::Get,
+    };
+
+    let body = if let Some(path) = body_file {
+        if get || !data.is_empty() || !form.is_empty() || !files.is_empty() {
+            return Err(ImportError::Unsupported(
+                "mixing a file with other data".into(),
+            ));
+        }
+        let content_type = content_type(&headers);
+        headers.retain(|header| !header.name.eq_ignore_ascii_case("Content-Type"));
+        RequestPayload::File { path, content_type }
+    } else if get {
+        if !data.is_empty() {
+            let separator = if url.contains('?') { '&' } else { '?' };
+            url = format!("{url}{separator}{}", data.join("&"));
+        }
+        RequestPayload::None
+    } else if !form.is_empty() || !files.is_empty() {
+        RequestPayload::Multipart {
+            params: KeyValueTable::new(&form),
+            files,
+        }
+    } else if !data.is_empty() {
+        let content_type = content_type(&headers);
+        guess_payload(&data.join("&"), content_type.as_deref())
+    } else {
+        RequestPayload::None
+    };
+    drop_automatic_content_type(&mut headers, &body);
+
+    Ok(EndpointData {
+        url,
+        method,
+        headers,
+        body,
+        auth,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::{
+        FormFile, KeyValue, KeyValueTable, RawEncoding, RequestAuth, RequestMethod, RequestPayload,
+    };
+    use crate::import::ImportError;
+
+    use super::parse_curl;
+
+    #[test]
+    fn test_parse_simple_get() {
+        let endpoint = parse_curl("curl https://example.com/users").unwrap();
+        assert_eq!(endpoint.url, "https://example.com/users");
+        assert_eq!(endpoint.method, RequestMethod::Get);
+        assert!(endpoint.headers.is_empty());
+        assert_eq!(endpoint.body, RequestPayload::None);
+    }
+
+    #[test]
+    fn test_parse_rejects_other_commands() {
+        assert!(matches!(
+            parse_curl("wget https://example.com"),
+            Err(ImportError::NotCurl)
+        ));
+        assert!(matches!(parse_curl("   "), Err(ImportError::NotCurl)));
+        assert!(matches!(
+            parse_curl("curl -s"),
+            Err(ImportError::MissingUrl)
+        ));
+        assert!(parse_curl("/usr/bin/curl https://example.com").is_ok());
+    }
+
+    #[test]
+    fn test_parse_method_and_headers() {
+        let command = "curl -X PUT --url 'https://example.com/users/1' \\\n  -H 'Accept: application/json' \\\n  -H \"X-Token: abc:def\" \\\n  -H 'X-Empty;' \\\n  -H 'Host:'";
+        let endpoint = parse_curl(command).unwrap();
+        assert_eq!(endpoint.url, "https://example.com/users/1");
+        assert_eq!(endpoint.method, RequestMethod::Put);
+        assert_eq!(
+            endpoint.headers,
+            KeyValueTable::new(&[
+                ("Accept", "application/json").into(),
+                ("X-Token", "abc:def").into(),
+                ("X-Empty", "").into(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_bundled_short_options() {
+        let endpoint = parse_curl("curl -sSLXPATCH -HAccept:text/plain example.com").unwrap();
+        assert_eq!(endpoint.url, "http://example.com");
+        assert_eq!(endpoint.method, RequestMethod::Patch);
+        assert_eq!(
+            endpoint.headers,
+            KeyValueTable::new(&[("Accept", "text/plain").into()])
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_method() {
+        assert!(matches!(
+            parse_curl("curl -X 'NOT VALID' https://example.com"),
+            Err(ImportError::InvalidMethod(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_custom_method() {
+        let endpoint = parse_curl("curl -X PROPFIND https://example.com/dav/").unwrap();
+        assert_eq!(endpoint.method, RequestMethod::Custom("PROPFIND".into()));
+    }
+
+    #[test]
+    fn test_parse_ignores_output_options() {
+        let command =
+            "curl -o out.txt --max-time 10 --compressed -w '%{http_code}' https://example.com";
+        let endpoint = parse_curl(command).unwrap();
+        assert_eq!(endpoint.url, "https://example.com");
+    }
+
+    #[test]
+    fn test_parse_urlencoded_data() {
+        let endpoint = parse_curl("curl -d 'a=1' --data 'b=2' https://example.com").unwrap();
+        assert_eq!(endpoint.method, RequestMethod::Post);
+        assert_eq!(
+            endpoint.body,
+            RequestPayload::Urlencoded(KeyValueTable::new(&[("a", "1").into(), ("b", "2").into()]))
+        );
+    }
+
+    #[test]
+    fn test_parse_data_urlencode() {
+        let endpoint = parse_curl(
+            "curl --data-urlencode 'q=hello world' --data-urlencode '=x&y' https://example.com",
+        )
+        .unwrap();
+        let RequestPayload::Raw { encoding, content } = endpoint.body else {
+            panic!("Expected a raw body");
+        };
+        assert_eq!(encoding, RawEncoding::OctetStream);
+        assert_eq!(content, b"q=hello%20world&x%26y");
+
+        let endpoint = parse_curl("curl --data-urlencode 'q=a b&c' https://example.com").unwrap();
+        assert_eq!(
+            endpoint.body,
+            RequestPayload::Urlencoded(KeyValueTable::new(&[("q", "a b&c").into()]))
+        );
+    }
+
+    #[test]
+    fn test_parse_data_urlencode_like_curl() {
+        let endpoint = parse_curl("curl --data-urlencode 'a b~c*(d)' https://example.com").unwrap();
+        assert_eq!(
+            endpoint.body,
+            RequestPayload::Raw {
+                encoding: RawEncoding::OctetStream,
+                content: b"a%20b~c%2A%28d%29".to_vec(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_uses_last_content_type() {
+        let command = "curl -H 'Content-Type: application/json' -H 'Content-Type: text/xml' -d '<a>1</a>' https://example.com";
+        let endpoint = parse_curl(command).unwrap();
+        assert!(matches!(
+            endpoint.body,
+            RequestPayload::Raw {
+                encoding: RawEncoding::Xml,
+                ..
+            }
+        ));
+
+        let command = "curl -H 'Content-Type: application/x-protobuf' -H 'Content-Type: image/png' --data-binary @me.png https://example.com";
+        let endpoint = parse_curl(command).unwrap();
+        assert_eq!(
+            endpoint.body,
+            RequestPayload::File {
+                path: "me.png".into(),
+                content_type: Some("image/png".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_json_data() {
+        let endpoint = parse_curl(
+            "curl https://example.com -H 'Content-Type: application/json' --data-raw '{\"name\": \"john\"}'",
+        )
+        .unwrap();
+        assert!(endpoint.headers.is_empty());
+        assert_eq!(
+            endpoint.body,
+            RequestPayload::Raw {
+                encoding: RawEncoding::Json,
+                content: b"{\"name\": \"john\"}".to_vec(),
+            }
+        );
+
+        let endpoint = parse_curl("curl https://example.com -d '[1, 2]'").unwrap();
+        assert!(matches!(
+            endpoint.body,
+            RequestPayload::Raw {
+                encoding: RawEncoding::Json,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_parse_json_option() {
+        let endpoint = parse_curl("curl --json '{\"a\": 1}' https://example.com").unwrap();
+        assert_eq!(endpoint.method, RequestMethod::Post);
+        assert_eq!(
+            endpoint.headers,
+            KeyValueTable::new(&[("Accept", "application/json").into()])
+        );
+        assert!(matches!(
+            endpoint.body,
+            RequestPayload::Raw {
+                encoding: RawEncoding::Json,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_parse_custom_content_type_is_kept() {
+        let endpoint =
+            parse_curl("curl -H 'Content-Type: application/ld+json' -d '{}' https://example.com")
+                .unwrap();
+        assert_eq!(
+            endpoint.headers,
+            KeyValueTable::new(&[("Content-Type", "application/ld+json").into()])
+        );
+
+        let endpoint =
+            parse_curl("curl -H 'Content-Type: text/plain' -d 'hello' https://example.com")
+                .unwrap();
+        assert_eq!(
+            endpoint.headers,
+            KeyValueTable::new(&[("Content-Type", "text/plain").into()])
+        );
+        assert_eq!(
+            endpoint.body,
+            RequestPayload::Raw {
+                encoding: RawEncoding::OctetStream,
+                content: b"hello".to_vec(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_xml_data() {
+        let endpoint =
+            parse_curl("curl -H 'content-type: text/xml' -d '<a>1</a>' https://example.com")
+                .unwrap();
+        assert_eq!(endpoint.headers.len(), 1);
+        assert!(matches!(
+            endpoint.body,
+            RequestPayload::Raw {
+                encoding: RawEncoding::Xml,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_parse_get_with_data() {
+        let endpoint =
+            parse_curl("curl -G -d 'q=rust' -d 'page=2' 'https://example.com/search?lang=en'")
+                .unwrap();
+        assert_eq!(endpoint.method, RequestMethod::Get);
+        assert_eq!(
+            endpoint.url,
+            "https://example.com/search?lang=en&q=rust&page=2"
+        );
+        assert_eq!(endpoint.body, RequestPayload::None);
+    }
+
+    #[test]
+    fn test_parse_head() {
+        let endpoint = parse_curl("curl -I https://example.com").unwrap();
+        assert_eq!(endpoint.method, RequestMethod::Head);
+    }
+
+    #[test]
+    fn test_parse_form() {
+        let endpoint = parse_curl(
+            "curl -F 'name=john' --form-string 'file=@literal' -H 'Content-Type: multipart/form-data' https://example.com",
+        )
+        .unwrap();
+        assert_eq!(endpoint.method, RequestMethod::Post);
+        assert!(endpoint.headers.is_empty());
+        assert_eq!(
+            endpoint.body,
+            RequestPayload::Multipart {
+                params: KeyValueTable::new(&[("name", "john").into(), ("file", "@literal").into()]),
+                files: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_form_files() {
+        let endpoint = parse_curl(
+            r#"curl -F 'avatar=@me.png' -F 'cv=@"/home/john/my;cv.pdf";type=application/pdf;filename="resume.pdf"' https://example.com"#,
+        )
+        .unwrap();
+        let mut cv = FormFile::new("cv", "/home/john/my;cv.pdf");
+        cv.content_type = Some("application/pdf".into());
+        cv.filename = Some("resume.pdf".into());
+        assert_eq!(endpoint.method, RequestMethod::Post);
+        assert_eq!(
+            endpoint.body,
+            RequestPayload::Multipart {
+                params: KeyValueTable::default(),
+                files: vec![FormFile::new("avatar", "me.png"), cv],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_files_are_not_supported() {
+        assert!(matches!(
+            parse_curl("curl -F 'file=<notes.txt' https://example.com"),
+            Err(ImportError::Unsupported(_))
+        ));
+        assert!(matches!(
+            parse_curl("curl -d @body.json https://example.com"),
+            Err(ImportError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_body_file() {
+        let command = "curl -X PUT https://example.com/blobs -H 'Content-Type: application/x-protobuf' --data-binary @user.bin";
+        let endpoint = parse_curl(command).unwrap();
+        assert_eq!(endpoint.method, RequestMethod::Put);
+        assert!(endpoint.headers.is_empty());
+        assert_eq!(
+            endpoint.body,
+            RequestPayload::File {
+                path: "user.bin".into(),
+                content_type: Some("application/x-protobuf".into()),
+            }
+        );
+
+        let endpoint = parse_curl("curl https://example.com --data-binary @user.bin").unwrap();
+        assert_eq!(endpoint.method, RequestMethod::Post);
+
+        assert!(matches!(
+            parse_curl("curl https://example.com --data-binary @user.bin -d a=1"),
+            Err(ImportError::Unsupported(_))
+        ));
+        assert!(matches!(
+            parse_curl("curl https://example.com --data-binary @-"),
+            Err(ImportError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_authentication() {
+        let endpoint = parse_curl("curl -u 'john:secret' https://example.com").unwrap();
+        assert!(endpoint.headers.is_empty());
+        assert_eq!(
+            endpoint.auth,
+            RequestAuth::Basic {
+                username: "john".into(),
+                password: "secret".into(),
+            }
+        );
+
+        let endpoint = parse_curl("curl -u first:one -u john https://example.com").unwrap();
+        assert_eq!(
+            endpoint.auth,
+            RequestAuth::Basic {
+                username: "john".into(),
+                password: "".into(),
+            }
+        );
+
+        let endpoint = parse_curl("curl --oauth2-bearer abc https://example.com").unwrap();
+        assert_eq!(
+            endpoint.headers,
+            KeyValueTable::new(&[("Authorization", "Bearer abc").into()])
+        );
+    }
+
+    #[test]
+    fn test_parse_digest_authentication() {
+        let endpoint =
+            parse_curl("curl -H 'Accept: */*' --digest -u 'john:it:s' https://example.com")
+                .unwrap();
+        assert_eq!(
+            endpoint.headers,
+            KeyValueTable::new(&[("Accept", "*/*").into()])
+        );
+        assert_eq!(
+            endpoint.auth,
+            RequestAuth::Digest {
+                username: "john".into(),
+                password: "it:s".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_header_shortcuts() {
+        let endpoint =
+            parse_curl("curl -A 'Cartero' -e https://a.com -b 'a=1' -b jar.txt https://b.com")
+                .unwrap();
+        assert_eq!(
+            endpoint.headers,
+            KeyValueTable::new(&[
+                KeyValue::from(("User-Agent", "Cartero")),
+                KeyValue::from(("Referer", "https://a.com")),
+                KeyValue::from(("Cookie", "a=1")),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_keeps_variables() {
+        let endpoint =
+            parse_curl("curl -H 'Authorization: Bearer {{TOKEN}}' '{{API_ROOT}}/users'").unwrap();
+        assert_eq!(endpoint.url, "{{API_ROOT}}/users");
+        assert_eq!(
+            endpoint.headers,
+            KeyValueTable::new(&[("Authorization", "Bearer {{TOKEN}}").into()])
+        );
+    }
+}
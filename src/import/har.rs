@@ -0,0 +1,746 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Reads and writes HAR documents, the HTTP archives exported by the developer
+//! tools of web browsers.
+//!
+//! See <http://www.softwareishard.com/blog/har-12-spec/> for the format.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use isahc::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::client::{civil_from_days, BoundPayload, BoundRequest};
+use crate::entities::{
+    EndpointData, FormFile, KeyValue, KeyValueTable, RequestMethod, RequestPayload, ResponseData,
+};
+
+use super::{drop_automatic_content_type, raw_encoding, ImportError, URLENCODED_CONTENT_TYPE};
+
+#[derive(Deserialize, Serialize)]
+struct Har {
+    log: Log,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Log {
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    creator: Creator,
+    #[serde(default)]
+    entries: Vec<Entry>,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+struct Creator {
+    name: String,
+    version: String,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Entry {
+    #[serde(default)]
+    started_date_time: String,
+    #[serde(default)]
+    time: f64,
+    request: Request,
+    #[serde(default)]
+    response: Response,
+    #[serde(default)]
+    cache: Cache,
+    #[serde(default)]
+    timings: Timings,
+}
+
+#[derive(Deserialize, Serialize)]
+struct NameValue {
+    name: String,
+    #[serde(default)]
+    value: String,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Request {
+    method: String,
+    url: String,
+    #[serde(default)]
+    http_version: String,
+    #[serde(default)]
+    cookies: Vec<NameValue>,
+    #[serde(default)]
+    headers: Vec<NameValue>,
+    #[serde(default)]
+    query_string: Vec<NameValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    post_data: Option<PostData>,
+    #[serde(default = "unknown_size")]
+    headers_size: i64,
+    #[serde(default = "unknown_size")]
+    body_size: i64,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PostData {
+    #[serde(default)]
+    mime_type: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    params: Vec<Param>,
+    #[serde(default)]
+    text: String,
+    /// A body sent from a file is not included, only its path is kept here.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    comment: String,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Param {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_type: Option<String>,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Response {
+    status: i64,
+    status_text: String,
+    http_version: String,
+    cookies: Vec<NameValue>,
+    headers: Vec<NameValue>,
+    content: Content,
+    #[serde(rename = "redirectURL")]
+    redirect_url: String,
+    headers_size: i64,
+    body_size: i64,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Content {
+    size: i64,
+    mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    encoding: Option<String>,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+struct Cache {}
+
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
+struct Timings {
+    send: f64,
+    wait: f64,
+    receive: f64,
+}
+
+fn unknown_size() -> i64 {
+    -1
+}
+
+impl TryFrom<Request> for EndpointData {
+    type Error = ImportError;
+
+    fn try_from(request: Request) -> Result<Self, Self::Error> {
+        let method = RequestMethod::try_from(request.method.as_str())
+            .map_err(|_| ImportError::InvalidMethod(request.method.clone()))?;
+
+        let mut url = request.url;
+        if !url.contains('?') && !request.query_string.is_empty() {
+            let pairs: Vec<(&str, &str)> = request
+                .query_string
+                .iter()
+                .map(|param| (param.name.as_str(), param.value.as_str()))
+                .collect();
+            if let Ok(query) = serde_urlencoded::to_string(pairs) {
+                url = format!("{url}?{query}");
+            }
+        }
+
+        // HTTP/2 pseudo-headers and the length of the body are computed when the
+        // request is sent, so they would only get in the way.
+        let mut headers: KeyValueTable = request
+            .headers
+            .iter()
+            .filter(|header| !header.name.starts_with(':'))
+            .filter(|header| !header.name.eq_ignore_ascii_case("Content-Length"))
+            .map(|header| KeyValue::from((header.name.as_str(), header.value.as_str())))
+            .collect();
+
+        let body = match request.post_data {
+            None => RequestPayload::None,
+            Some(post_data) => {
+                let mime_type = post_data.mime_type.to_lowercase();
+                if mime_type.starts_with(URLENCODED_CONTENT_TYPE) {
+                    let params: KeyValueTable = if post_data.params.is_empty() {
+                        serde_urlencoded::from_str::<Vec<(String, String)>>(&post_data.text)
+                            .map_err(|e| ImportError::Unsupported(e.to_string()))?
+                            .into_iter()
+                            .map(KeyValue::from)
+                            .collect()
+                    } else {
+                        post_data
+                            .params
+                            .into_iter()
+                            .map(|p| KeyValue::from((p.name, p.value.unwrap_or_default())))
+                            .collect()
+                    };
+                    RequestPayload::Urlencoded(params)
+                } else if mime_type.starts_with("multipart/form-data") {
+                    // HAR files only keep the name of the uploaded files, so they
+                    // are expected to be found next to the request file.
+                    let (uploads, fields): (Vec<Param>, Vec<Param>) = post_data
+                        .params
+                        .into_iter()
+                        .partition(|p| p.file_name.is_some());
+                    let params = fields
+                        .into_iter()
+                        .map(|p| KeyValue::from((p.name, p.value.unwrap_or_default())))
+                        .collect();
+                    let files = uploads
+                        .into_iter()
+                        .map(|p| {
+                            let mut file =
+                                FormFile::new(&p.name, &p.file_name.unwrap_or_default());
+                            file.content_type = p.content_type;
+                            file
+                        })
+                        .collect();
+                    RequestPayload::Multipart { params, files }
+                } else {
+                    if headers.header("Content-Type").is_none() && !mime_type.is_empty() {
+                        headers.push(KeyValue::from((
+                            "Content-Type",
+                            post_data.mime_type.as_str(),
+                        )));
+                    }
+                    RequestPayload::Raw {
+                        encoding: raw_encoding(&mime_type),
+                        content: post_data.text.into_bytes(),
+                    }
+                }
+            }
+        };
+        drop_automatic_content_type(&mut headers, &body);
+
+        Ok(EndpointData {
+            url,
+            method,
+            headers,
+            body,
+            ..Default::default()
+        })
+    }
+}
+
+/// Reads every entry of a HAR document as an endpoint.
+pub fn parse_har(contents: &str) -> Result<Vec<EndpointData>, ImportError> {
+    let har: Har = serde_json::from_str(contents)?;
+    har.log
+        .entries
+        .into_iter()
+        .map(|entry| EndpointData::try_from(entry.request))
+        .collect()
+}
+
+/// Formats a point in time as an ISO 8601 date in UTC, such as `2024-05-01T10:20:30.000Z`.
+fn format_timestamp(time: SystemTime) -> String {
+    let elapsed = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = elapsed.as_secs();
+    let (days, seconds_of_day) = ((seconds / 86400) as i64, seconds % 86400);
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60,
+        elapsed.subsec_millis(),
+    )
+}
+
+fn name_values(table: &KeyValueTable) -> Vec<NameValue> {
+    table
+        .iter()
+        .map(|kv| NameValue {
+            name: kv.name.clone(),
+            value: kv.value.clone(),
+        })
+        .collect()
+}
+
+/// Writes a request and the response it got, if any, as a HAR 1.2 document.
+///
+/// The request should be the bound one, so that the variables are already replaced
+/// by their values. When there is no response, the entry has a status of 0.
+pub fn export_har(
+    request: &BoundRequest,
+    response: Option<&ResponseData>,
+    started: SystemTime,
+) -> Result<String, serde_json::Error> {
+    let content_type = |headers: &KeyValueTable| {
+        headers
+            .iter()
+            .find(|header| header.name.eq_ignore_ascii_case("Content-Type"))
+            .map(|header| header.value.clone())
+            .unwrap_or_default()
+    };
+
+    let query_string = Url::parse(&request.url)
+        .map(|url| {
+            url.query_pairs()
+                .map(|(name, value)| NameValue {
+                    name: name.into_owned(),
+                    value: value.into_owned(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let post_data = request.body.as_ref().map(|body| {
+        let mime_type = content_type(&request.headers);
+        let text = String::from_utf8_lossy(body).into_owned();
+        let params = if mime_type.starts_with(URLENCODED_CONTENT_TYPE) {
+            serde_urlencoded::from_str::<Vec<(String, String)>>(&text)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, value)| Param {
+                    name,
+                    value: Some(value),
+                    file_name: None,
+                    content_type: None,
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        PostData {
+            mime_type,
+            params,
+            text,
+            comment: String::new(),
+        }
+    });
+    let (post_data, body_size) = match &request.payload {
+        BoundPayload::File(path) => {
+            let post_data = PostData {
+                mime_type: content_type(&request.headers),
+                params: Vec::new(),
+                text: String::new(),
+                comment: path.to_string_lossy().into_owned(),
+            };
+            let size = std::fs::metadata(path).map_or(-1, |metadata| metadata.len() as i64);
+            (Some(post_data), size)
+        }
+        // The files of a form are not included either, only their names.
+        BoundPayload::Multipart { fields, files, .. } => {
+            let fields = fields.iter().map(|(name, value)| Param {
+                name: name.clone(),
+                value: Some(value.clone()),
+                file_name: None,
+                content_type: None,
+            });
+            let files = files.iter().map(|file| Param {
+                name: file.name.clone(),
+                value: None,
+                file_name: Some(file.filename.clone()),
+                content_type: Some(file.content_type.clone()),
+            });
+            let post_data = PostData {
+                mime_type: content_type(&request.headers),
+                params: fields.chain(files).collect(),
+                text: String::new(),
+                comment: String::new(),
+            };
+            let size = match request.open_body() {
+                Ok(Some(stream)) => stream.length.map_or(-1, |length| length as i64),
+                _ => -1,
+            };
+            (Some(post_data), size)
+        }
+        _ => (
+            post_data,
+            request.body.as_ref().map_or(0, |body| body.len() as i64),
+        ),
+    };
+
+    let method: String = request.method.clone().into();
+    let har_request = Request {
+        method,
+        url: request.url.clone(),
+        http_version: "HTTP/1.1".into(),
+        cookies: Vec::new(),
+        headers: name_values(&request.headers),
+        query_string,
+        post_data,
+        headers_size: -1,
+        body_size,
+    };
+
+    let (har_response, duration) = match response {
+        Some(response) => {
+            let (text, encoding) = match std::str::from_utf8(&response.body) {
+                Ok(text) => (text.to_string(), None),
+                Err(_) => (base64::encode(&response.body), Some("base64".to_string())),
+            };
+            let status_text = u16::try_from(response.status_code)
+                .ok()
+                .and_then(|code| StatusCode::from_u16(code).ok())
+                .and_then(|code| code.canonical_reason())
+                .unwrap_or_default();
+            let har_response = Response {
+                status: response.status_code.into(),
+                status_text: status_text.into(),
+                http_version: "HTTP/1.1".into(),
+                cookies: Vec::new(),
+                headers: name_values(&response.headers),
+                content: Content {
+                    size: response.body.len() as i64,
+                    mime_type: content_type(&response.headers),
+                    text: Some(text),
+                    encoding,
+                },
+                redirect_url: response
+                    .headers
+                    .header("Location")
+                    .map(|location| location[0].to_string())
+                    .unwrap_or_default(),
+                headers_size: -1,
+                body_size: response.body.len() as i64,
+            };
+            (har_response, response.duration as f64)
+        }
+        None => (Response::default(), 0.0),
+    };
+
+    let har = Har {
+        log: Log {
+            version: "1.2".into(),
+            creator: Creator {
+                name: "Cartero".into(),
+                version: env!("CARGO_PKG_VERSION").into(),
+            },
+            entries: vec![Entry {
+                started_date_time: format_timestamp(started),
+                time: duration,
+                request: har_request,
+                response: har_response,
+                cache: Cache {},
+                timings: Timings {
+                    send: 0.0,
+                    wait: duration,
+                    receive: 0.0,
+                },
+            }],
+        },
+    };
+    serde_json::to_string_pretty(&har)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::client::{BoundFile, BoundPayload, BoundRequest};
+    use crate::entities::{
+        FormFile, KeyValueTable, RawEncoding, RequestMethod, RequestPayload, ResponseData,
+    };
+
+    use super::{export_har, format_timestamp, parse_har};
+
+    const HAR: &str = r#"{
+      "log": {
+        "version": "1.2",
+        "creator": { "name": "Firefox", "version": "125.0" },
+        "entries": [
+          {
+            "startedDateTime": "2024-05-01T10:20:30.000Z",
+            "request": {
+              "method": "GET",
+              "url": "https://example.com/api/users?page=2",
+              "headers": [
+                { "name": ":authority", "value": "example.com" },
+                { "name": "Accept", "value": "application/json" }
+              ],
+              "queryString": [{ "name": "page", "value": "2" }]
+            },
+            "response": { "status": 200 }
+          },
+          {
+            "request": {
+              "method": "POST",
+              "url": "https://example.com/login",
+              "headers": [
+                { "name": "Content-Type", "value": "application/x-www-form-urlencoded" },
+                { "name": "Content-Length", "value": "20" }
+              ],
+              "postData": {
+                "mimeType": "application/x-www-form-urlencoded",
+                "params": [
+                  { "name": "user", "value": "john" },
+                  { "name": "password", "value": "secret" }
+                ]
+              }
+            }
+          },
+          {
+            "request": {
+              "method": "PUT",
+              "url": "https://example.com/api/users/1",
+              "postData": { "mimeType": "application/json", "text": "{\"name\":\"john\"}" }
+            }
+          },
+          {
+            "request": {
+              "method": "POST",
+              "url": "https://example.com/upload",
+              "headers": [
+                { "name": "Content-Type", "value": "multipart/form-data; boundary=abc" }
+              ],
+              "postData": {
+                "mimeType": "multipart/form-data; boundary=abc",
+                "params": [
+                  { "name": "title", "value": "Hello" },
+                  { "name": "avatar", "fileName": "me.png", "contentType": "image/png" }
+                ]
+              }
+            }
+          },
+          {
+            "request": {
+              "method": "POST",
+              "url": "https://example.com/notes",
+              "queryString": [{ "name": "draft", "value": "true" }],
+              "postData": { "mimeType": "text/plain", "text": "Hello" }
+            }
+          }
+        ]
+      }
+    }"#;
+
+    #[test]
+    fn test_parse_har_entries() {
+        let endpoints = parse_har(HAR).unwrap();
+        assert_eq!(endpoints.len(), 5);
+
+        assert_eq!(endpoints[0].method, RequestMethod::Get);
+        assert_eq!(endpoints[0].url, "https://example.com/api/users?page=2");
+        assert_eq!(
+            endpoints[0].headers,
+            KeyValueTable::new(&[("Accept", "application/json").into()])
+        );
+        assert_eq!(endpoints[0].body, RequestPayload::None);
+
+        assert!(endpoints[1].headers.is_empty());
+        assert_eq!(
+            endpoints[1].body,
+            RequestPayload::Urlencoded(KeyValueTable::new(&[
+                ("user", "john").into(),
+                ("password", "secret").into(),
+            ]))
+        );
+
+        assert!(endpoints[2].headers.is_empty());
+        assert_eq!(
+            endpoints[2].body,
+            RequestPayload::Raw {
+                encoding: RawEncoding::Json,
+                content: b"{\"name\":\"john\"}".to_vec(),
+            }
+        );
+
+        assert!(endpoints[3].headers.is_empty());
+        assert_eq!(
+            endpoints[3].body,
+            RequestPayload::Multipart {
+                params: KeyValueTable::new(&[("title", "Hello").into()]),
+                files: vec![FormFile {
+                    content_type: Some("image/png".into()),
+                    ..FormFile::new("avatar", "me.png")
+                }],
+            }
+        );
+
+        assert_eq!(endpoints[4].url, "https://example.com/notes?draft=true");
+        assert_eq!(
+            endpoints[4].headers,
+            KeyValueTable::new(&[("Content-Type", "text/plain").into()])
+        );
+        assert_eq!(
+            endpoints[4].body,
+            RequestPayload::Raw {
+                encoding: RawEncoding::OctetStream,
+                content: b"Hello".to_vec(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_har_errors() {
+        assert!(parse_har("not json").is_err());
+        assert!(parse_har(
+            r#"{"log": {"entries": [{"request": {"method": "BREW TEA", "url": "x"}}]}}"#
+        )
+        .is_err());
+        assert!(parse_har(r#"{"log": {}}"#).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+        let time = UNIX_EPOCH + Duration::from_millis(1_709_210_096_789);
+        assert_eq!(format_timestamp(time), "2024-02-29T12:34:56.789Z");
+    }
+
+    #[test]
+    fn test_export_har() {
+        let request = BoundRequest {
+            url: "https://example.com/login?next=%2Fhome".into(),
+            method: RequestMethod::Post,
+            headers: KeyValueTable::new(&[
+                ("Content-Type", "application/x-www-form-urlencoded").into()
+            ]),
+            body: Some(b"user=john".to_vec()),
+            ..Default::default()
+        };
+        let response = ResponseData {
+            status_code: 404,
+            duration: 120,
+            size: 9,
+            headers: KeyValueTable::new(&[("Content-Type", "text/plain").into()]),
+            body: b"Not found".to_vec(),
+            redirects: vec![],
+            proxy: None,
+            cookies: vec![],
+        };
+        let document = export_har(&request, Some(&response), UNIX_EPOCH).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&document).unwrap();
+        let entry = &value["log"]["entries"][0];
+
+        assert_eq!(value["log"]["version"], "1.2");
+        assert_eq!(entry["startedDateTime"], "1970-01-01T00:00:00.000Z");
+        assert_eq!(entry["time"], 120.0);
+        assert_eq!(entry["request"]["method"], "POST");
+        assert_eq!(entry["request"]["queryString"][0]["value"], "/home");
+        assert_eq!(entry["request"]["postData"]["text"], "user=john");
+        assert_eq!(entry["request"]["postData"]["params"][0]["name"], "user");
+        assert_eq!(entry["response"]["status"], 404);
+        assert_eq!(entry["response"]["statusText"], "Not Found");
+        assert_eq!(entry["response"]["content"]["text"], "Not found");
+        assert_eq!(entry["response"]["content"]["mimeType"], "text/plain");
+        assert_eq!(entry["timings"]["wait"], 120.0);
+
+        // What is exported can be imported back.
+        let endpoints = parse_har(&document).unwrap();
+        assert_eq!(endpoints[0].url, request.url);
+        assert_eq!(
+            endpoints[0].body,
+            RequestPayload::Urlencoded(KeyValueTable::new(&[("user", "john").into()]))
+        );
+    }
+
+    #[test]
+    fn test_export_har_body_file() {
+        let path = std::env::temp_dir().join(format!("cartero-har-{}.bin", std::process::id()));
+        std::fs::write(&path, b"\x00\xff").unwrap();
+        let request = BoundRequest {
+            url: "https://example.com/blobs".into(),
+            method: RequestMethod::Put,
+            headers: KeyValueTable::new(&[("Content-Type", "image/png").into()]),
+            payload: BoundPayload::File(path.clone()),
+            ..Default::default()
+        };
+        let document = export_har(&request, None, UNIX_EPOCH).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&document).unwrap();
+        let request = &value["log"]["entries"][0]["request"];
+
+        assert_eq!(request["bodySize"], 2);
+        assert_eq!(request["postData"]["mimeType"], "image/png");
+        assert_eq!(request["postData"]["text"], "");
+        assert_eq!(
+            request["postData"]["comment"],
+            path.to_string_lossy().as_ref()
+        );
+    }
+
+    #[test]
+    fn test_export_har_multipart_files() {
+        let request = BoundRequest {
+            url: "https://example.com/upload".into(),
+            method: RequestMethod::Post,
+            headers: KeyValueTable::new(&[
+                ("Content-Type", "multipart/form-data; boundary=XYZ").into()
+            ]),
+            payload: BoundPayload::Multipart {
+                fields: vec![("title".into(), "Hello".into())],
+                files: vec![BoundFile {
+                    name: "avatar".into(),
+                    path: "/does/not/exist.png".into(),
+                    filename: "me.png".into(),
+                    content_type: "image/png".into(),
+                }],
+                boundary: "XYZ".into(),
+            },
+            ..Default::default()
+        };
+        let document = export_har(&request, None, UNIX_EPOCH).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&document).unwrap();
+        let request = &value["log"]["entries"][0]["request"];
+
+        assert_eq!(request["bodySize"], -1);
+        assert_eq!(request["postData"]["text"], "");
+        let params = &request["postData"]["params"];
+        assert_eq!(params[0]["name"], "title");
+        assert_eq!(params[0]["value"], "Hello");
+        assert_eq!(params[1]["name"], "avatar");
+        assert_eq!(params[1]["fileName"], "me.png");
+        assert_eq!(params[1]["contentType"], "image/png");
+    }
+
+    #[test]
+    fn test_export_har_binary_response() {
+        let response = ResponseData {
+            status_code: 200,
+            duration: 0,
+            size: 2,
+            headers: KeyValueTable::default(),
+            body: vec![0xff, 0xfe],
+            redirects: vec![],
+            proxy: None,
+            cookies: vec![],
+        };
+        let document = export_har(&BoundRequest::default(), Some(&response), UNIX_EPOCH).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&document).unwrap();
+        let content = &value["log"]["entries"][0]["response"]["content"];
+        assert_eq!(content["encoding"], "base64");
+        assert_eq!(content["text"], "//4=");
+    }
+}
@@ -0,0 +1,164 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Conversion of requests written for other tools into Cartero endpoints.
+
+pub mod curl;
+pub mod har;
+pub mod openapi;
+pub mod postman;
+pub mod shell;
+
+use thiserror::Error;
+use url::Url;
+
+use crate::entities::{CollectionData, EndpointData, KeyValueTable, RawEncoding, RequestPayload};
+
+const URLENCODED_CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("Unterminated quote")]
+    UnterminatedQuote,
+
+    #[error("This is not a curl command")]
+    NotCurl,
+
+    #[error("Missing value for {0}")]
+    MissingValue(String),
+
+    #[error("The request has no URL")]
+    MissingUrl,
+
+    #[error("Invalid HTTP method: {0}")]
+    InvalidMethod(String),
+
+    #[error("Not supported: {0}")]
+    Unsupported(String),
+
+    #[error("Invalid document: {0}")]
+    InvalidDocument(#[from] serde_json::Error),
+
+    #[error("Invalid document: {0}")]
+    InvalidYaml(#[from] serde_yaml::Error),
+}
+
+/// A request read from a document that groups many of them, such as a Postman collection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedRequest {
+    /// The names of the folders that contain the request, from the outermost one.
+    pub folders: Vec<String>,
+    pub name: String,
+    pub endpoint: EndpointData,
+}
+
+/// A set of requests imported from another tool, ready to be saved as a collection.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ImportedCollection {
+    pub name: String,
+    pub collection: CollectionData,
+    pub requests: Vec<ImportedRequest>,
+
+    /// Describes everything that could not be translated, such as scripts.
+    pub warnings: Vec<String>,
+}
+
+/// Guesses the encoding of a raw body given its Content-Type.
+fn raw_encoding(content_type: &str) -> RawEncoding {
+    let content_type = content_type.to_lowercase();
+    if content_type.contains("json") {
+        RawEncoding::Json
+    } else if content_type.contains("xml") {
+        RawEncoding::Xml
+    } else {
+        RawEncoding::OctetStream
+    }
+}
+
+/// Removes the Content-Type header when it is the same one that Cartero adds on its own.
+///
+/// Multipart headers are always removed, since their boundary would not match the one
+/// generated when the request is sent.
+fn drop_automatic_content_type(headers: &mut KeyValueTable, body: &RequestPayload) {
+    let automatic = match body {
+        RequestPayload::None | RequestPayload::File { .. } => return,
+        RequestPayload::Urlencoded(_) => URLENCODED_CONTENT_TYPE,
+        RequestPayload::Multipart { .. } => "multipart/",
+        RequestPayload::Raw { encoding, .. } => match encoding {
+            RawEncoding::Json => "application/json",
+            RawEncoding::Xml => "application/xml",
+            RawEncoding::OctetStream => "application/octet-stream",
+        },
+    };
+    headers.retain(|header| {
+        if !header.name.eq_ignore_ascii_case("Content-Type") {
+            return true;
+        }
+        let value = header.value.trim().to_lowercase();
+        match body {
+            RequestPayload::Multipart { .. } => !value.starts_with(automatic),
+            _ => value != automatic,
+        }
+    });
+}
+
+/// Suggests a file name for an imported request, such as `GET users`.
+pub fn request_name(endpoint: &EndpointData) -> String {
+    let method = endpoint.method.as_str();
+    let target = Url::parse(&endpoint.url).ok().and_then(|url| {
+        let segment = url
+            .path_segments()
+            .and_then(|segments| segments.rev().find(|s| !s.is_empty()))
+            .map(String::from);
+        segment.or_else(|| url.host_str().map(String::from))
+    });
+    match target {
+        Some(target) => format!("{method} {target}"),
+        None => method.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::{EndpointData, RequestMethod};
+
+    use super::request_name;
+
+    #[test]
+    fn test_request_name() {
+        let endpoint = |method, url: &str| EndpointData {
+            method,
+            url: url.into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            request_name(&endpoint(
+                RequestMethod::Get,
+                "https://example.com/api/users/"
+            )),
+            "GET users"
+        );
+        assert_eq!(
+            request_name(&endpoint(RequestMethod::Post, "https://example.com")),
+            "POST example.com"
+        );
+        assert_eq!(
+            request_name(&endpoint(RequestMethod::Delete, "{{API}}/users")),
+            "DELETE"
+        );
+    }
+}
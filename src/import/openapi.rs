@@ -0,0 +1,813 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Generates a request for every operation described by an OpenAPI 3 document.
+//!
+//! See <https://spec.openapis.org/oas/v3.1.0> for the format.
+
+use serde_json::{Map, Value};
+
+use crate::entities::{
+    CollectionData, EndpointData, FormFile, KeyValue, KeyValueTable, RawEncoding, RequestMethod,
+    RequestPayload,
+};
+
+use super::{
+    drop_automatic_content_type, raw_encoding, ImportError, ImportedCollection, ImportedRequest,
+    URLENCODED_CONTENT_TYPE,
+};
+
+/// The variable that holds the URL of the server, which prefixes every request.
+pub const BASE_URL_VARIABLE: &str = "BASE_URL";
+
+const METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// How deep the example of a schema can get, so that recursive schemas end.
+const MAX_EXAMPLE_DEPTH: usize = 8;
+
+/// Converts a YAML value into the equivalent JSON value.
+///
+/// Keys that are not strings, such as the status codes of the responses, are
+/// turned into strings.
+fn yaml_to_json(value: serde_yaml::Value) -> Value {
+    match value {
+        serde_yaml::Value::Null => Value::Null,
+        serde_yaml::Value::Bool(b) => Value::Bool(b),
+        serde_yaml::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::from(i)
+            } else if let Some(u) = n.as_u64() {
+                Value::from(u)
+            } else {
+                n.as_f64().map(Value::from).unwrap_or(Value::Null)
+            }
+        }
+        serde_yaml::Value::String(s) => Value::String(s),
+        serde_yaml::Value::Sequence(items) => {
+            Value::Array(items.into_iter().map(yaml_to_json).collect())
+        }
+        serde_yaml::Value::Mapping(mapping) => {
+            let map = mapping
+                .into_iter()
+                .map(|(key, value)| {
+                    let key = match yaml_to_json(key) {
+                        Value::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    (key, yaml_to_json(value))
+                })
+                .collect();
+            Value::Object(map)
+        }
+        serde_yaml::Value::Tagged(tagged) => yaml_to_json(tagged.value),
+    }
+}
+
+/// Turns a JSON value into the text used in an URL or in a header.
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(string) => string.clone(),
+        other => other.to_string(),
+    }
+}
+
+struct Converter<'a> {
+    document: &'a Value,
+    warnings: Vec<String>,
+}
+
+impl<'a> Converter<'a> {
+    /// Follows a `$ref` to another part of the document, if the value is a reference.
+    ///
+    /// Only references to the same document are supported.
+    fn resolve(&mut self, value: &'a Value, path: &str) -> Option<&'a Value> {
+        let mut value = value;
+        // A limit on the number of jumps avoids looping on circular references.
+        for _ in 0..16 {
+            let Some(reference) = value.get("$ref").and_then(Value::as_str) else {
+                return Some(value);
+            };
+            match reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.document.pointer(pointer))
+            {
+                Some(target) => value = target,
+                None => {
+                    self.warnings
+                        .push(format!("{path}: cannot resolve the reference {reference}"));
+                    return None;
+                }
+            }
+        }
+        None
+    }
+
+    /// Builds an example value for a schema, preferring the examples in the document.
+    fn example(&mut self, schema: &'a Value, path: &str, depth: usize) -> Value {
+        if depth > MAX_EXAMPLE_DEPTH {
+            return Value::Null;
+        }
+        let Some(schema) = self.resolve(schema, path) else {
+            return Value::Null;
+        };
+        if let Some(example) = schema.get("example") {
+            return example.clone();
+        }
+        if let Some(example) = schema.get("examples").and_then(|e| e.get(0)) {
+            return example.clone();
+        }
+        if let Some(default) = schema.get("default") {
+            return default.clone();
+        }
+        if let Some(value) = schema.get("const") {
+            return value.clone();
+        }
+        if let Some(value) = schema.get("enum").and_then(|e| e.get(0)) {
+            return value.clone();
+        }
+        if let Some(Value::Array(parts)) = schema.get("allOf") {
+            let mut merged = Map::new();
+            for part in parts {
+                match self.example(part, path, depth + 1) {
+                    Value::Object(map) => merged.extend(map),
+                    other if merged.is_empty() && parts.len() == 1 => return other,
+                    _ => {}
+                }
+            }
+            return Value::Object(merged);
+        }
+        for key in ["oneOf", "anyOf"] {
+            if let Some(first) = schema.get(key).and_then(|options| options.get(0)) {
+                return self.example(first, path, depth + 1);
+            }
+        }
+
+        // In OpenAPI 3.1 the type may be a list, such as ["string", "null"].
+        let kind = match schema.get("type") {
+            Some(Value::String(kind)) => Some(kind.as_str()),
+            Some(Value::Array(kinds)) => kinds
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|kind| *kind != "null"),
+            _ => None,
+        };
+        let kind = kind.or_else(|| schema.get("properties").map(|_| "object"));
+
+        match kind {
+            Some("object") => {
+                let mut object = Map::new();
+                if let Some(Value::Object(properties)) = schema.get("properties") {
+                    for (name, property) in properties {
+                        object.insert(name.clone(), self.example(property, path, depth + 1));
+                    }
+                }
+                Value::Object(object)
+            }
+            Some("array") => match schema.get("items") {
+                Some(items) => Value::Array(vec![self.example(items, path, depth + 1)]),
+                None => Value::Array(Vec::new()),
+            },
+            Some("string") => {
+                let format = schema.get("format").and_then(Value::as_str);
+                let example = match format {
+                    Some("date-time") => "2024-01-01T00:00:00Z",
+                    Some("date") => "2024-01-01",
+                    Some("time") => "00:00:00",
+                    Some("uuid") => "00000000-0000-0000-0000-000000000000",
+                    Some("email") => "user@example.com",
+                    Some("uri") | Some("url") => "https://example.com",
+                    Some("ipv4") => "127.0.0.1",
+                    Some("ipv6") => "::1",
+                    _ => "string",
+                };
+                Value::from(example)
+            }
+            Some("integer") | Some("number") => Value::from(0),
+            Some("boolean") => Value::from(false),
+            _ => Value::Null,
+        }
+    }
+
+    /// Picks the example value of a parameter.
+    fn parameter_example(&mut self, parameter: &'a Value, path: &str) -> String {
+        if let Some(example) = parameter.get("example") {
+            return value_to_string(example);
+        }
+        if let Some(Value::Object(examples)) = parameter.get("examples") {
+            if let Some(example) = examples.values().next() {
+                if let Some(example) = self.resolve(example, path) {
+                    return example
+                        .get("value")
+                        .map(value_to_string)
+                        .unwrap_or_default();
+                }
+            }
+        }
+        match parameter.get("schema") {
+            Some(schema) => {
+                let Some(schema) = self.resolve(schema, path) else {
+                    return String::new();
+                };
+                ["example", "default"]
+                    .iter()
+                    .find_map(|key| schema.get(*key))
+                    .or_else(|| schema.get("enum").and_then(|e| e.get(0)))
+                    .map(value_to_string)
+                    .unwrap_or_default()
+            }
+            None => String::new(),
+        }
+    }
+
+    /// Picks the example of a media type object, such as the one of a request body.
+    fn media_example(&mut self, media: &'a Value, path: &str) -> Value {
+        if let Some(example) = media.get("example") {
+            return example.clone();
+        }
+        if let Some(Value::Object(examples)) = media.get("examples") {
+            if let Some(example) = examples.values().next() {
+                if let Some(value) = self
+                    .resolve(example, path)
+                    .and_then(|example| example.get("value"))
+                {
+                    return value.clone();
+                }
+            }
+        }
+        match media.get("schema") {
+            Some(schema) => self.example(schema, path, 0),
+            None => Value::Null,
+        }
+    }
+
+    /// Builds the body of a request out of the request body of an operation.
+    fn convert_body(
+        &mut self,
+        body: &'a Value,
+        headers: &mut KeyValueTable,
+        path: &str,
+    ) -> RequestPayload {
+        let Some(body) = self.resolve(body, path) else {
+            return RequestPayload::None;
+        };
+        let Some(Value::Object(content)) = body.get("content") else {
+            return RequestPayload::None;
+        };
+
+        let json = content
+            .iter()
+            .find(|(media_type, _)| media_type.as_str() == "application/json")
+            .or_else(|| {
+                content
+                    .iter()
+                    .find(|(media_type, _)| media_type.contains("json"))
+            });
+        if let Some((media_type, media)) = json {
+            headers.push(KeyValue::from(("Content-Type", media_type.as_str())));
+            let example = self.media_example(media, path);
+            return RequestPayload::Raw {
+                encoding: RawEncoding::Json,
+                content: serde_json::to_vec_pretty(&example).unwrap_or_default(),
+            };
+        }
+
+        if let Some(media) = content.get(URLENCODED_CONTENT_TYPE) {
+            let (fields, files) = self.form_fields(media, path);
+            for file in files {
+                self.warnings.push(format!(
+                    "{path}: the file field {} was not imported",
+                    file.name
+                ));
+            }
+            return RequestPayload::Urlencoded(fields);
+        }
+        if let Some(media) = content.get("multipart/form-data") {
+            let (params, files) = self.form_fields(media, path);
+            for file in &files {
+                self.warnings.push(format!(
+                    "{path}: no file was chosen for the file field {}",
+                    file.name
+                ));
+            }
+            return RequestPayload::Multipart { params, files };
+        }
+
+        if let Some((media_type, media)) = content.iter().next() {
+            let example = match self.media_example(media, path) {
+                Value::String(text) => text,
+                Value::Null => String::new(),
+                other => other.to_string(),
+            };
+            let encoding = raw_encoding(media_type);
+            headers.push(KeyValue::from(("Content-Type", media_type.as_str())));
+            if example.is_empty() {
+                self.warnings
+                    .push(format!("{path}: no example for the {media_type} body"));
+            }
+            return RequestPayload::Raw {
+                encoding,
+                content: example.into_bytes(),
+            };
+        }
+        RequestPayload::None
+    }
+
+    /// Lists the fields of a form body, using the properties of its schema.
+    ///
+    /// Binary properties are returned as files without a path, which are kept
+    /// inactive until a file is chosen for them.
+    fn form_fields(&mut self, media: &'a Value, path: &str) -> (KeyValueTable, Vec<FormFile>) {
+        let Value::Object(example) = self.media_example(media, path) else {
+            return (KeyValueTable::default(), vec![]);
+        };
+        let schema = media
+            .get("schema")
+            .and_then(|schema| self.resolve(schema, path));
+        let mut fields = Vec::new();
+        let mut files = Vec::new();
+        for (name, value) in example {
+            let is_binary = schema
+                .and_then(|schema| schema.pointer(&format!("/properties/{name}/format")))
+                .and_then(Value::as_str)
+                .is_some_and(|format| format == "binary" || format == "base64");
+            if is_binary {
+                let content_type = media
+                    .pointer(&format!("/encoding/{name}/contentType"))
+                    .and_then(Value::as_str)
+                    .map(String::from);
+                files.push(FormFile {
+                    content_type,
+                    active: false,
+                    ..FormFile::new(&name, "")
+                });
+            } else {
+                fields.push(KeyValue::from((name, value_to_string(&value))));
+            }
+        }
+        (fields.into_iter().collect(), files)
+    }
+
+    fn convert_operation(
+        &mut self,
+        method: RequestMethod,
+        route: &str,
+        path_item: &'a Value,
+        operation: &'a Value,
+    ) -> ImportedRequest {
+        let method_name = method.as_str();
+        let path = format!("{method_name} {route}");
+
+        // Parameters of the operation replace the ones of the path with the same name.
+        let mut parameters: Vec<&Value> = Vec::new();
+        let declared = [path_item.get("parameters"), operation.get("parameters")];
+        for parameter in declared
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_array)
+            .flatten()
+        {
+            let Some(parameter) = self.resolve(parameter, &path) else {
+                continue;
+            };
+            let key = (parameter.get("name"), parameter.get("in"));
+            parameters.retain(|p| (p.get("name"), p.get("in")) != key);
+            parameters.push(parameter);
+        }
+
+        let mut url = format!("{{{{{BASE_URL_VARIABLE}}}}}{route}");
+        let mut variables = Vec::new();
+        let mut query = Vec::new();
+        let mut headers = Vec::new();
+        for parameter in parameters {
+            let name = parameter
+                .get("name")
+                .map(value_to_string)
+                .unwrap_or_default();
+            let location = parameter.get("in").and_then(Value::as_str).unwrap_or("");
+            let required = parameter
+                .get("required")
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            let value = self.parameter_example(parameter, &path);
+            match location {
+                "path" => {
+                    url = url.replace(&format!("{{{name}}}"), &format!("{{{{{name}}}}}"));
+                    variables.push(KeyValue::from((name.as_str(), value.as_str())));
+                }
+                // Only the required parameters are part of the URL. A placeholder of
+                // an optional one would need a value to be sent, so they are left out.
+                "query" if required => {
+                    query.push(format!("{name}={{{{{name}}}}}"));
+                    variables.push(KeyValue::from((name.as_str(), value.as_str())));
+                }
+                "query" => {}
+                "header" => headers.push(KeyValue {
+                    name,
+                    value,
+                    active: required,
+                    secret: false,
+                }),
+                other => self
+                    .warnings
+                    .push(format!("{path}: {other} parameter {name} was not imported")),
+            }
+        }
+        if !query.is_empty() {
+            url = format!("{url}?{}", query.join("&"));
+        }
+
+        let mut headers: KeyValueTable = headers.into_iter().collect();
+        let body = match operation.get("requestBody") {
+            Some(body) => self.convert_body(body, &mut headers, &path),
+            None => RequestPayload::None,
+        };
+        drop_automatic_content_type(&mut headers, &body);
+
+        let name = ["summary", "operationId"]
+            .iter()
+            .filter_map(|key| operation.get(*key).and_then(Value::as_str))
+            .map(str::trim)
+            .find(|name| !name.is_empty())
+            .map(String::from)
+            .unwrap_or_else(|| path.clone());
+        let folders = operation
+            .get("tags")
+            .and_then(|tags| tags.get(0))
+            .and_then(Value::as_str)
+            .map(|tag| vec![tag.to_string()])
+            .unwrap_or_default();
+
+        ImportedRequest {
+            folders,
+            name,
+            endpoint: EndpointData {
+                url,
+                method,
+                headers,
+                variables: variables.into_iter().collect(),
+                body,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Reads the URL of the first server, replacing its variables by their defaults.
+fn base_url(document: &Value) -> String {
+    let Some(server) = document.get("servers").and_then(|servers| servers.get(0)) else {
+        return "http://localhost".into();
+    };
+    let mut url = server.get("url").map(value_to_string).unwrap_or_default();
+    if let Some(Value::Object(variables)) = server.get("variables") {
+        for (name, variable) in variables {
+            let default = variable
+                .get("default")
+                .map(value_to_string)
+                .unwrap_or_default();
+            url = url.replace(&format!("{{{name}}}"), &default);
+        }
+    }
+    url.trim_end_matches('/').to_string()
+}
+
+/// Generates a request for every operation of an OpenAPI 3 document, in YAML or JSON.
+///
+/// The URL of the first server is kept in the `BASE_URL` variable of the collection,
+/// and the requests are grouped in folders by their first tag. Path parameters and
+/// query parameters become variables of the request, and an example body is generated
+/// using the examples or the schemas of the document.
+pub fn parse_openapi(contents: &str) -> Result<ImportedCollection, ImportError> {
+    let document: Value = if contents.trim_start().starts_with('{') {
+        serde_json::from_str(contents)?
+    } else {
+        yaml_to_json(serde_yaml::from_str(contents)?)
+    };
+
+    let version = document
+        .get("openapi")
+        .map(value_to_string)
+        .unwrap_or_default();
+    if !version.starts_with("3.") {
+        return Err(ImportError::Unsupported(
+            "only OpenAPI 3 documents can be imported".into(),
+        ));
+    }
+
+    let mut converter = Converter {
+        document: &document,
+        warnings: Vec::new(),
+    };
+    let mut requests = Vec::new();
+    if let Some(Value::Object(paths)) = document.get("paths") {
+        for (route, path_item) in paths {
+            let Some(path_item) = converter.resolve(path_item, route) else {
+                continue;
+            };
+            for method in METHODS {
+                let Some(operation) = path_item.get(*method) else {
+                    continue;
+                };
+                let Ok(method) = RequestMethod::try_from(*method) else {
+                    continue;
+                };
+                requests.push(converter.convert_operation(method, route, path_item, operation));
+            }
+        }
+    }
+
+    let name = document
+        .pointer("/info/title")
+        .map(value_to_string)
+        .unwrap_or_default();
+    let variables = KeyValueTable::new(&[(BASE_URL_VARIABLE, base_url(&document).as_str()).into()]);
+    Ok(ImportedCollection {
+        name,
+        collection: CollectionData {
+            variables,
+            ..Default::default()
+        },
+        requests,
+        warnings: converter.warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::{FormFile, KeyValueTable, RawEncoding, RequestMethod, RequestPayload};
+    use crate::import::ImportError;
+
+    use super::parse_openapi;
+
+    const DOCUMENT: &str = r#"
+openapi: 3.0.3
+info:
+  title: Pet Store
+  version: 1.0.0
+servers:
+  - url: https://{region}.petstore.example.com/v1/
+    variables:
+      region:
+        default: eu
+paths:
+  /pets:
+    get:
+      summary: List pets
+      tags: [pets]
+      parameters:
+        - name: limit
+          in: query
+          schema: { type: integer, default: 20 }
+        - name: status
+          in: query
+          required: true
+          schema: { type: string, enum: [available, sold] }
+        - $ref: '#/components/parameters/RequestId'
+      responses:
+        200:
+          description: OK
+    post:
+      operationId: createPet
+      tags: [pets]
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema:
+              $ref: '#/components/schemas/Pet'
+      responses:
+        201:
+          description: Created
+  /pets/{petId}:
+    parameters:
+      - name: petId
+        in: path
+        required: true
+        schema: { type: string }
+        example: rex
+    delete:
+      tags: [pets]
+      parameters:
+        - name: session
+          in: cookie
+          schema: { type: string }
+      responses:
+        204:
+          description: Deleted
+  /login:
+    post:
+      summary: Log in
+      requestBody:
+        content:
+          application/x-www-form-urlencoded:
+            schema:
+              type: object
+              properties:
+                user: { type: string, example: john }
+                remember: { type: boolean }
+      responses:
+        200:
+          description: OK
+  /upload:
+    put:
+      summary: Upload
+      requestBody:
+        content:
+          multipart/form-data:
+            schema:
+              type: object
+              properties:
+                title: { type: string }
+                file: { type: string, format: binary }
+      responses:
+        200:
+          description: OK
+components:
+  parameters:
+    RequestId:
+      name: X-Request-Id
+      in: header
+      required: true
+      schema: { type: string, format: uuid }
+      example: abc
+  schemas:
+    Pet:
+      type: object
+      required: [name]
+      properties:
+        id: { type: integer, format: int64 }
+        name: { type: string, example: Rex }
+        tags:
+          type: array
+          items: { type: string }
+        owner:
+          $ref: '#/components/schemas/Owner'
+        born: { type: string, format: date }
+    Owner:
+      allOf:
+        - type: object
+          properties:
+            name: { type: string }
+        - type: object
+          properties:
+            pets:
+              type: array
+              items:
+                $ref: '#/components/schemas/Pet'
+"#;
+
+    #[test]
+    fn test_parse_openapi_collection() {
+        let imported = parse_openapi(DOCUMENT).unwrap();
+        assert_eq!(imported.name, "Pet Store");
+        assert_eq!(
+            imported.collection.variables,
+            KeyValueTable::new(&[("BASE_URL", "https://eu.petstore.example.com/v1").into()])
+        );
+        let names: Vec<(Vec<String>, &str, RequestMethod)> = imported
+            .requests
+            .iter()
+            .map(|r| {
+                (
+                    r.folders.clone(),
+                    r.name.as_str(),
+                    r.endpoint.method.clone(),
+                )
+            })
+            .collect();
+        let pets = vec!["pets".to_string()];
+        assert_eq!(
+            names,
+            vec![
+                (pets.clone(), "List pets", RequestMethod::Get),
+                (pets.clone(), "createPet", RequestMethod::Post),
+                (pets, "DELETE /pets/{petId}", RequestMethod::Delete),
+                (vec![], "Log in", RequestMethod::Post),
+                (vec![], "Upload", RequestMethod::Put),
+            ]
+        );
+        assert_eq!(
+            imported.warnings,
+            vec![
+                "DELETE /pets/{petId}: cookie parameter session was not imported",
+                "PUT /upload: no file was chosen for the file field file",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_openapi_parameters() {
+        let imported = parse_openapi(DOCUMENT).unwrap();
+        let list = &imported.requests[0].endpoint;
+        assert_eq!(list.url, "{{BASE_URL}}/pets?status={{status}}");
+        assert_eq!(
+            list.variables,
+            KeyValueTable::new(&[("status", "available").into()])
+        );
+        assert_eq!(
+            list.headers,
+            KeyValueTable::new(&[("X-Request-Id", "abc").into()])
+        );
+
+        let delete = &imported.requests[2].endpoint;
+        assert_eq!(delete.url, "{{BASE_URL}}/pets/{{petId}}");
+        assert_eq!(
+            delete.variables,
+            KeyValueTable::new(&[("petId", "rex").into()])
+        );
+    }
+
+    #[test]
+    fn test_parse_openapi_bodies() {
+        let imported = parse_openapi(DOCUMENT).unwrap();
+        let RequestPayload::Raw { encoding, content } = &imported.requests[1].endpoint.body else {
+            panic!("Expected a raw body");
+        };
+        assert_eq!(*encoding, RawEncoding::Json);
+        let body: serde_json::Value = serde_json::from_slice(content).unwrap();
+        assert_eq!(body["id"], 0);
+        assert_eq!(body["name"], "Rex");
+        assert_eq!(body["tags"], serde_json::json!(["string"]));
+        assert_eq!(body["born"], "2024-01-01");
+        assert_eq!(body["owner"]["name"], "string");
+        assert!(body["owner"]["pets"].is_array());
+
+        assert_eq!(
+            imported.requests[3].endpoint.body,
+            RequestPayload::Urlencoded(KeyValueTable::new(&[
+                ("user", "john").into(),
+                ("remember", "false").into(),
+            ]))
+        );
+        assert_eq!(
+            imported.requests[4].endpoint.body,
+            RequestPayload::Multipart {
+                params: KeyValueTable::new(&[("title", "string").into()]),
+                files: vec![FormFile {
+                    active: false,
+                    ..FormFile::new("file", "")
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_openapi_json() {
+        let document = r#"{
+          "openapi": "3.1.0",
+          "info": { "title": "Tiny" },
+          "paths": {
+            "/ping": {
+              "post": {
+                "requestBody": {
+                  "content": {
+                    "application/json": {
+                      "examples": { "hello": { "value": { "message": "hi" } } }
+                    }
+                  }
+                }
+              }
+            }
+          }
+        }"#;
+        let imported = parse_openapi(document).unwrap();
+        assert_eq!(
+            imported.collection.variables,
+            KeyValueTable::new(&[("BASE_URL", "http://localhost").into()])
+        );
+        let endpoint = &imported.requests[0].endpoint;
+        assert_eq!(endpoint.url, "{{BASE_URL}}/ping");
+        assert_eq!(
+            endpoint.body,
+            RequestPayload::Raw {
+                encoding: RawEncoding::Json,
+                content: b"{\n  \"message\": \"hi\"\n}".to_vec(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_openapi_rejects_swagger() {
+        assert!(matches!(
+            parse_openapi("swagger: '2.0'\ninfo:\n  title: Old\n"),
+            Err(ImportError::Unsupported(_))
+        ));
+        assert!(parse_openapi("openapi: [").is_err());
+    }
+}
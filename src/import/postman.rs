@@ -0,0 +1,974 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Reads the collections exported by Postman, using the v2.0 and v2.1 formats.
+//!
+//! See <https://schema.postman.com/collection/json/v2.1.0/draft-07/docs/index.html>.
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::entities::{
+    CollectionData, EndpointData, FormFile, KeyValue, KeyValueTable, RawEncoding, RequestAuth,
+    RequestMethod, RequestPayload,
+};
+
+use super::{drop_automatic_content_type, ImportError, ImportedCollection, ImportedRequest};
+
+#[derive(Deserialize)]
+struct Collection {
+    info: Info,
+    #[serde(default)]
+    item: Vec<Item>,
+    #[serde(default)]
+    variable: Vec<Variable>,
+    auth: Option<Value>,
+    #[serde(default)]
+    event: Vec<Event>,
+}
+
+#[derive(Deserialize)]
+struct Info {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    schema: String,
+}
+
+#[derive(Deserialize)]
+struct Item {
+    #[serde(default)]
+    name: String,
+    item: Option<Vec<Item>>,
+    request: Option<Value>,
+    auth: Option<Value>,
+    #[serde(default)]
+    event: Vec<Event>,
+}
+
+#[derive(Deserialize)]
+struct Variable {
+    key: Option<String>,
+    id: Option<String>,
+    #[serde(default)]
+    value: Value,
+    #[serde(default)]
+    disabled: bool,
+}
+
+#[derive(Deserialize)]
+struct Event {
+    #[serde(default)]
+    listen: String,
+    script: Option<Script>,
+    #[serde(default)]
+    disabled: bool,
+}
+
+#[derive(Deserialize)]
+struct Script {
+    #[serde(default)]
+    exec: Value,
+}
+
+/// Turns a JSON value into the text Postman would use when sending it.
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(string) => string.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Reads a list of `{key, value, disabled}` objects, such as headers or query parameters.
+fn key_values(value: Option<&Value>) -> Vec<KeyValue> {
+    let Some(Value::Array(items)) = value else {
+        return Vec::new();
+    };
+    items
+        .iter()
+        .filter_map(|item| {
+            let name = item.get("key").map(value_to_string)?;
+            Some(KeyValue {
+                name,
+                value: item.get("value").map(value_to_string).unwrap_or_default(),
+                active: !item
+                    .get("disabled")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false),
+                secret: false,
+            })
+        })
+        .collect()
+}
+
+/// Reads a list of headers, which can also be given as a block of `Name: value` lines.
+fn headers(value: Option<&Value>) -> Vec<KeyValue> {
+    match value {
+        Some(Value::String(block)) => block
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| KeyValue::from((name.trim(), value.trim())))
+            .collect(),
+        other => key_values(other),
+    }
+}
+
+/// Reads the value of a parameter of an authentication method.
+///
+/// In v2.1 the parameters are a list of `{key, value}` objects, while in v2.0 they
+/// are a plain object.
+fn auth_parameter(auth: &Value, kind: &str, key: &str) -> String {
+    match auth.get(kind) {
+        Some(Value::Array(params)) => params
+            .iter()
+            .find(|param| param.get("key").and_then(Value::as_str) == Some(key))
+            .and_then(|param| param.get("value"))
+            .map(value_to_string)
+            .unwrap_or_default(),
+        Some(Value::Object(params)) => params.get(key).map(value_to_string).unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+/// Collects the warnings for the scripts attached to an item.
+fn check_events(events: &[Event], path: &str, warnings: &mut Vec<String>) {
+    for event in events.iter().filter(|event| !event.disabled) {
+        let has_code = match event.script.as_ref().map(|script| &script.exec) {
+            Some(Value::String(code)) => !code.trim().is_empty(),
+            Some(Value::Array(lines)) => lines
+                .iter()
+                .any(|line| line.as_str().is_some_and(|line| !line.trim().is_empty())),
+            _ => false,
+        };
+        if has_code {
+            warnings.push(format!(
+                "{path}: {} scripts are not supported",
+                event.listen
+            ));
+        }
+    }
+}
+
+/// Accumulates the state of a conversion while walking the tree of items.
+struct Converter {
+    requests: Vec<ImportedRequest>,
+    warnings: Vec<String>,
+}
+
+impl Converter {
+    fn warn(&mut self, path: &str, message: &str) {
+        self.warnings.push(format!("{path}: {message}"));
+    }
+
+    /// Converts a file field of a form, which may hold many files or none at all.
+    fn form_files(&mut self, path: &str, field: &Value) -> Vec<FormFile> {
+        let name = field.get("key").map(value_to_string).unwrap_or_default();
+        let active = !field
+            .get("disabled")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let content_type = field
+            .get("contentType")
+            .and_then(Value::as_str)
+            .filter(|ct| !ct.is_empty())
+            .map(String::from);
+        let sources: Vec<String> = match field.get("src") {
+            Some(Value::String(src)) if !src.is_empty() => vec![src.clone()],
+            Some(Value::Array(sources)) => sources.iter().map(value_to_string).collect(),
+            _ => vec![],
+        };
+        if sources.is_empty() {
+            // Keep the field, so that the file can be chosen after importing.
+            self.warn(
+                path,
+                &format!("no file was chosen for the file field {name}"),
+            );
+            let mut file = FormFile::new(&name, "");
+            file.active = false;
+            return vec![file];
+        }
+        sources
+            .into_iter()
+            .map(|src| FormFile {
+                content_type: content_type.clone(),
+                active,
+                ..FormFile::new(&name, &src)
+            })
+            .collect()
+    }
+
+    fn visit(&mut self, items: &[Item], folders: &[String], auth: Option<&Value>) {
+        for item in items {
+            let mut path_parts = folders.to_vec();
+            path_parts.push(item.name.clone());
+            let path = path_parts.join(" / ");
+            check_events(&item.event, &path, &mut self.warnings);
+
+            // An item without an authentication method inherits the one of its parent.
+            let auth = item.auth.as_ref().or(auth);
+
+            if let Some(children) = &item.item {
+                self.visit(children, &path_parts, auth);
+            } else if let Some(request) = &item.request {
+                match self.convert_request(request, auth, &path) {
+                    Ok(endpoint) => self.requests.push(ImportedRequest {
+                        folders: folders.to_vec(),
+                        name: item.name.clone(),
+                        endpoint,
+                    }),
+                    Err(e) => self.warn(&path, &e.to_string()),
+                }
+            }
+        }
+    }
+
+    fn convert_request(
+        &mut self,
+        request: &Value,
+        parent_auth: Option<&Value>,
+        path: &str,
+    ) -> Result<EndpointData, ImportError> {
+        // A request can also be given as just its URL.
+        if let Value::String(url) = request {
+            return Ok(EndpointData {
+                url: url.clone(),
+                ..Default::default()
+            });
+        }
+
+        let method = request
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or("GET");
+        let method = RequestMethod::try_from(method)
+            .map_err(|_| ImportError::InvalidMethod(method.to_string()))?;
+
+        let (mut url, variables) = convert_url(request.get("url"));
+        let mut headers: KeyValueTable = headers(request.get("header")).into_iter().collect();
+        let body = self.convert_body(request.get("body"), &mut headers, path);
+
+        let auth = match request.get("auth").or(parent_auth) {
+            Some(auth) => self.convert_auth(auth, &mut headers, &mut url, path),
+            None => RequestAuth::None,
+        };
+        drop_automatic_content_type(&mut headers, &body);
+
+        if url.contains("{{$") || headers.iter().any(|h| h.value.contains("{{$")) {
+            self.warn(
+                path,
+                "dynamic variables such as {{$guid}} are not supported",
+            );
+        }
+
+        Ok(EndpointData {
+            url,
+            method,
+            headers,
+            variables,
+            body,
+            auth,
+            ..Default::default()
+        })
+    }
+
+    fn convert_body(
+        &mut self,
+        body: Option<&Value>,
+        headers: &mut KeyValueTable,
+        path: &str,
+    ) -> RequestPayload {
+        let Some(body) = body else {
+            return RequestPayload::None;
+        };
+        if body
+            .get("disabled")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+        {
+            return RequestPayload::None;
+        }
+
+        match body.get("mode").and_then(Value::as_str).unwrap_or_default() {
+            "raw" => {
+                let content = body.get("raw").map(value_to_string).unwrap_or_default();
+                let language = body
+                    .pointer("/options/raw/language")
+                    .and_then(Value::as_str)
+                    .unwrap_or("text");
+                let (encoding, content_type) = match language {
+                    "json" => (RawEncoding::Json, None),
+                    "xml" => (RawEncoding::Xml, None),
+                    "html" => (RawEncoding::OctetStream, Some("text/html")),
+                    "javascript" => (RawEncoding::OctetStream, Some("application/javascript")),
+                    _ => (RawEncoding::OctetStream, Some("text/plain")),
+                };
+                if let Some(content_type) = content_type {
+                    if headers.header("Content-Type").is_none() {
+                        headers.push(KeyValue::from(("Content-Type", content_type)));
+                    }
+                }
+                RequestPayload::Raw {
+                    encoding,
+                    content: content.into_bytes(),
+                }
+            }
+            "urlencoded" => {
+                RequestPayload::Urlencoded(key_values(body.get("urlencoded")).into_iter().collect())
+            }
+            "formdata" => {
+                let fields = body.get("formdata").and_then(Value::as_array);
+                let mut params = Vec::new();
+                let mut files = Vec::new();
+                for field in fields.into_iter().flatten() {
+                    if field.get("type").and_then(Value::as_str) == Some("file") {
+                        files.extend(self.form_files(path, field));
+                    } else {
+                        params.extend(key_values(Some(&Value::Array(vec![field.clone()]))));
+                    }
+                }
+                RequestPayload::Multipart {
+                    params: params.into_iter().collect(),
+                    files,
+                }
+            }
+            "graphql" => {
+                let query = body
+                    .pointer("/graphql/query")
+                    .map(value_to_string)
+                    .unwrap_or_default();
+                let variables = match body.pointer("/graphql/variables") {
+                    Some(Value::String(text)) if !text.trim().is_empty() => {
+                        serde_json::from_str(text).unwrap_or(Value::Null)
+                    }
+                    Some(Value::Object(map)) => Value::Object(map.clone()),
+                    _ => Value::Null,
+                };
+                let mut document = serde_json::json!({ "query": query });
+                if !variables.is_null() {
+                    document["variables"] = variables;
+                }
+                RequestPayload::Raw {
+                    encoding: RawEncoding::Json,
+                    content: serde_json::to_vec_pretty(&document).unwrap_or_default(),
+                }
+            }
+            "file" => {
+                let src = body
+                    .pointer("/file/src")
+                    .map(value_to_string)
+                    .unwrap_or_default();
+                if src.is_empty() {
+                    self.warn(path, "no file was chosen for the body");
+                }
+                RequestPayload::File {
+                    path: src,
+                    content_type: None,
+                }
+            }
+            "" => RequestPayload::None,
+            other => {
+                self.warn(path, &format!("{other} bodies are not supported"));
+                RequestPayload::None
+            }
+        }
+    }
+
+    /// Turns the authentication of a request into headers or query parameters when
+    /// it is that simple, or into the authentication of the request otherwise.
+    fn convert_auth(
+        &mut self,
+        auth: &Value,
+        headers: &mut KeyValueTable,
+        url: &mut String,
+        path: &str,
+    ) -> RequestAuth {
+        let kind = auth.get("type").and_then(Value::as_str).unwrap_or("noauth");
+        let has_authorization = headers.header("Authorization").is_some();
+        match kind {
+            "noauth" => {}
+            "bearer" if !has_authorization => {
+                let token = auth_parameter(auth, "bearer", "token");
+                let value = format!("Bearer {token}");
+                headers.push(KeyValue::from(("Authorization", value.as_str())));
+            }
+            "basic" if !has_authorization => {
+                let username = auth_parameter(auth, "basic", "username");
+                let password = auth_parameter(auth, "basic", "password");
+                if username.contains("{{") || password.contains("{{") {
+                    // The credentials are encoded once the variables are bound.
+                    return RequestAuth::Basic { username, password };
+                } else {
+                    let credentials = base64::encode(format!("{username}:{password}"));
+                    let value = format!("Basic {credentials}");
+                    headers.push(KeyValue::from(("Authorization", value.as_str())));
+                }
+            }
+            "apikey" => {
+                let key = auth_parameter(auth, "apikey", "key");
+                let value = auth_parameter(auth, "apikey", "value");
+                if auth_parameter(auth, "apikey", "in") == "query" {
+                    let pair = serde_urlencoded::to_string([(&key, &value)]).unwrap_or_default();
+                    let separator = if url.contains('?') { '&' } else { '?' };
+                    *url = format!("{url}{separator}{pair}");
+                } else if headers.header(&key).is_none() {
+                    headers.push(KeyValue::from((key.as_str(), value.as_str())));
+                }
+            }
+            "digest" => {
+                return RequestAuth::Digest {
+                    username: auth_parameter(auth, "digest", "username"),
+                    password: auth_parameter(auth, "digest", "password"),
+                };
+            }
+            "bearer" | "basic" => {}
+            other => self.warn(path, &format!("{other} authentication is not supported")),
+        }
+        RequestAuth::None
+    }
+}
+
+/// Reads the URL of a request, which may be a string or an object.
+///
+/// Path variables such as `:id` are turned into Cartero variables.
+fn convert_url(url: Option<&Value>) -> (String, KeyValueTable) {
+    let Some(url) = url else {
+        return (String::new(), KeyValueTable::default());
+    };
+    let raw = match url {
+        Value::String(raw) => raw.clone(),
+        other => match other.get("raw").and_then(Value::as_str) {
+            Some(raw) => raw.to_string(),
+            None => build_url(other),
+        },
+    };
+
+    let variables: KeyValueTable = key_values(url.get("variable")).into_iter().collect();
+    let raw = variables.iter().fold(raw, |raw, variable| {
+        replace_path_variable(&raw, &variable.name, &format!("{{{{{}}}}}", variable.name))
+    });
+    (raw, variables)
+}
+
+/// Builds a URL out of its parts, when the collection does not have the raw URL.
+fn build_url(url: &Value) -> String {
+    let join = |value: Option<&Value>, separator: &str| match value {
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .map(value_to_string)
+            .collect::<Vec<_>>()
+            .join(separator),
+        Some(other) => value_to_string(other),
+        None => String::new(),
+    };
+    let mut result = String::new();
+    if let Some(protocol) = url.get("protocol").and_then(Value::as_str) {
+        result.push_str(&format!("{protocol}://"));
+    }
+    result.push_str(&join(url.get("host"), "."));
+    if let Some(port) = url.get("port") {
+        result.push_str(&format!(":{}", value_to_string(port)));
+    }
+    let path = join(url.get("path"), "/");
+    if !path.is_empty() {
+        result.push('/');
+        result.push_str(&path);
+    }
+    let query: Vec<(String, String)> = key_values(url.get("query"))
+        .into_iter()
+        .filter(|param| param.active)
+        .map(|param| (param.name, param.value))
+        .collect();
+    if !query.is_empty() {
+        let query = query
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>()
+            .join("&");
+        result.push('?');
+        result.push_str(&query);
+    }
+    result
+}
+
+/// Replaces a path variable such as `:id` by the given text, only in the path of the URL.
+fn replace_path_variable(url: &str, name: &str, replacement: &str) -> String {
+    let (base, rest) = match url.find(['?', '#']) {
+        Some(index) => url.split_at(index),
+        None => (url, ""),
+    };
+    let placeholder = format!(":{name}");
+    let replaced: Vec<String> = base
+        .split('/')
+        .map(|segment| {
+            if segment == placeholder {
+                replacement.to_string()
+            } else {
+                segment.to_string()
+            }
+        })
+        .collect();
+    format!("{}{rest}", replaced.join("/"))
+}
+
+/// Converts a Postman collection into requests and collection settings.
+///
+/// The collection variables become the variables of the collection. The headers and
+/// the authentication of every request are kept in the request, since Postman folders
+/// cannot share headers in Cartero. Anything that cannot be translated, such as the
+/// scripts, is listed in the warnings of the result.
+pub fn parse_postman(contents: &str) -> Result<ImportedCollection, ImportError> {
+    let collection: Collection = serde_json::from_str(contents)?;
+    if !collection.info.schema.contains("v2.0") && !collection.info.schema.contains("v2.1") {
+        return Err(ImportError::Unsupported(
+            "only Postman collections v2.0 and v2.1 can be imported".into(),
+        ));
+    }
+
+    let mut converter = Converter {
+        requests: Vec::new(),
+        warnings: Vec::new(),
+    };
+    check_events(
+        &collection.event,
+        &collection.info.name,
+        &mut converter.warnings,
+    );
+    converter.visit(&collection.item, &[], collection.auth.as_ref());
+
+    let variables = collection
+        .variable
+        .iter()
+        .filter_map(|variable| {
+            let name = variable.key.clone().or_else(|| variable.id.clone())?;
+            Some(KeyValue {
+                name,
+                value: value_to_string(&variable.value),
+                active: !variable.disabled,
+                secret: false,
+            })
+        })
+        .collect();
+
+    Ok(ImportedCollection {
+        name: collection.info.name,
+        collection: CollectionData {
+            variables,
+            ..Default::default()
+        },
+        requests: converter.requests,
+        warnings: converter.warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::{
+        FormFile, KeyValue, KeyValueTable, RawEncoding, RequestAuth, RequestMethod, RequestPayload,
+    };
+    use crate::import::ImportError;
+
+    use super::parse_postman;
+
+    const COLLECTION: &str = r#"{
+      "info": {
+        "name": "Pet Store",
+        "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"
+      },
+      "variable": [
+        { "key": "baseUrl", "value": "https://petstore.example.com" },
+        { "key": "retries", "value": 3 },
+        { "key": "legacy", "value": "yes", "disabled": true }
+      ],
+      "auth": {
+        "type": "bearer",
+        "bearer": [{ "key": "token", "value": "{{token}}", "type": "string" }]
+      },
+      "item": [
+        {
+          "name": "Pets",
+          "item": [
+            {
+              "name": "List pets",
+              "event": [
+                { "listen": "test", "script": { "exec": ["pm.test('ok', () => {});"] } }
+              ],
+              "request": {
+                "method": "GET",
+                "header": [
+                  { "key": "Accept", "value": "application/json" },
+                  { "key": "X-Debug", "value": "1", "disabled": true }
+                ],
+                "url": {
+                  "raw": "{{baseUrl}}/pets?limit=10",
+                  "host": ["{{baseUrl}}"],
+                  "path": ["pets"],
+                  "query": [{ "key": "limit", "value": "10" }]
+                }
+              }
+            },
+            {
+              "name": "Get pet",
+              "request": {
+                "auth": { "type": "noauth" },
+                "method": "GET",
+                "url": {
+                  "raw": "{{baseUrl}}/pets/:petId",
+                  "variable": [{ "key": "petId", "value": "42" }]
+                }
+              }
+            },
+            {
+              "name": "Create pet",
+              "request": {
+                "method": "POST",
+                "header": [{ "key": "Content-Type", "value": "application/json" }],
+                "body": {
+                  "mode": "raw",
+                  "raw": "{\"name\": \"Rex\"}",
+                  "options": { "raw": { "language": "json" } }
+                },
+                "url": "{{baseUrl}}/pets"
+              }
+            }
+          ]
+        },
+        {
+          "name": "Login",
+          "request": {
+            "auth": {
+              "type": "basic",
+              "basic": [
+                { "key": "username", "value": "john" },
+                { "key": "password", "value": "secret" }
+              ]
+            },
+            "method": "POST",
+            "body": {
+              "mode": "urlencoded",
+              "urlencoded": [
+                { "key": "remember", "value": "true" },
+                { "key": "captcha", "value": "x", "disabled": true }
+              ]
+            },
+            "url": "{{baseUrl}}/login"
+          }
+        },
+        {
+          "name": "Upload",
+          "request": {
+            "auth": { "type": "oauth2" },
+            "method": "POST",
+            "body": {
+              "mode": "formdata",
+              "formdata": [
+                { "key": "title", "value": "Photo", "type": "text" },
+                { "key": "photo", "src": "/tmp/photo.png", "type": "file", "contentType": "image/png" },
+                { "key": "extra", "src": ["a.txt", "b.txt"], "type": "file", "disabled": true },
+                { "key": "missing", "type": "file" }
+              ]
+            },
+            "url": "{{baseUrl}}/upload"
+          }
+        }
+      ]
+    }"#;
+
+    #[test]
+    fn test_parse_postman_collection() {
+        let imported = parse_postman(COLLECTION).unwrap();
+        assert_eq!(imported.name, "Pet Store");
+        assert_eq!(
+            imported.collection.variables,
+            KeyValueTable::new(&[
+                ("baseUrl", "https://petstore.example.com").into(),
+                ("retries", "3").into(),
+                KeyValue {
+                    name: "legacy".into(),
+                    value: "yes".into(),
+                    active: false,
+                    secret: false,
+                },
+            ])
+        );
+
+        let names: Vec<(&[String], &str)> = imported
+            .requests
+            .iter()
+            .map(|r| (r.folders.as_slice(), r.name.as_str()))
+            .collect();
+        let pets = ["Pets".to_string()];
+        assert_eq!(
+            names,
+            vec![
+                (&pets[..], "List pets"),
+                (&pets[..], "Get pet"),
+                (&pets[..], "Create pet"),
+                (&[][..], "Login"),
+                (&[][..], "Upload"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_postman_headers_and_auth() {
+        let imported = parse_postman(COLLECTION).unwrap();
+        let list = &imported.requests[0].endpoint;
+        assert_eq!(list.method, RequestMethod::Get);
+        assert_eq!(list.url, "{{baseUrl}}/pets?limit=10");
+        assert_eq!(
+            list.headers,
+            KeyValueTable::new(&[
+                ("Accept", "application/json").into(),
+                KeyValue {
+                    name: "X-Debug".into(),
+                    value: "1".into(),
+                    active: false,
+                    secret: false,
+                },
+                ("Authorization", "Bearer {{token}}").into(),
+            ])
+        );
+
+        let get = &imported.requests[1].endpoint;
+        assert_eq!(get.url, "{{baseUrl}}/pets/{{petId}}");
+        assert_eq!(get.variables, KeyValueTable::new(&[("petId", "42").into()]));
+        assert!(get.headers.is_empty());
+
+        let login = &imported.requests[3].endpoint;
+        assert_eq!(
+            login.headers,
+            KeyValueTable::new(&[("Authorization", "Basic am9objpzZWNyZXQ=").into()])
+        );
+    }
+
+    #[test]
+    fn test_parse_postman_bodies() {
+        let imported = parse_postman(COLLECTION).unwrap();
+        let create = &imported.requests[2].endpoint;
+        assert_eq!(create.method, RequestMethod::Post);
+        assert_eq!(
+            create.headers,
+            KeyValueTable::new(&[("Authorization", "Bearer {{token}}").into()])
+        );
+        assert_eq!(
+            create.body,
+            RequestPayload::Raw {
+                encoding: RawEncoding::Json,
+                content: b"{\"name\": \"Rex\"}".to_vec(),
+            }
+        );
+
+        let login = &imported.requests[3].endpoint;
+        assert_eq!(
+            login.body,
+            RequestPayload::Urlencoded(KeyValueTable::new(&[
+                ("remember", "true").into(),
+                KeyValue {
+                    name: "captcha".into(),
+                    value: "x".into(),
+                    active: false,
+                    secret: false,
+                },
+            ]))
+        );
+
+        let upload = &imported.requests[4].endpoint;
+        assert_eq!(
+            upload.body,
+            RequestPayload::Multipart {
+                params: KeyValueTable::new(&[("title", "Photo").into()]),
+                files: vec![
+                    FormFile {
+                        content_type: Some("image/png".into()),
+                        ..FormFile::new("photo", "/tmp/photo.png")
+                    },
+                    FormFile {
+                        active: false,
+                        ..FormFile::new("extra", "a.txt")
+                    },
+                    FormFile {
+                        active: false,
+                        ..FormFile::new("extra", "b.txt")
+                    },
+                    FormFile {
+                        active: false,
+                        ..FormFile::new("missing", "")
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_postman_file_body() {
+        let collection = r#"{
+          "info": {
+            "name": "Blobs",
+            "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"
+          },
+          "item": [
+            {
+              "name": "Upload",
+              "request": {
+                "method": "PUT",
+                "body": { "mode": "file", "file": { "src": "/tmp/user.bin" } },
+                "url": "https://example.com/blobs"
+              }
+            },
+            {
+              "name": "Empty",
+              "request": {
+                "method": "PUT",
+                "body": { "mode": "file", "file": {} },
+                "url": "https://example.com/blobs"
+              }
+            }
+          ]
+        }"#;
+        let imported = parse_postman(collection).unwrap();
+        assert_eq!(
+            imported.requests[0].endpoint.body,
+            RequestPayload::File {
+                path: "/tmp/user.bin".into(),
+                content_type: None,
+            }
+        );
+        assert_eq!(
+            imported.warnings,
+            vec!["Empty: no file was chosen for the body"]
+        );
+    }
+
+    #[test]
+    fn test_parse_postman_warnings() {
+        let imported = parse_postman(COLLECTION).unwrap();
+        assert_eq!(
+            imported.warnings,
+            vec![
+                "Pets / List pets: test scripts are not supported",
+                "Upload: no file was chosen for the file field missing",
+                "Upload: oauth2 authentication is not supported",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_postman_v2_0_and_string_urls() {
+        let collection = r#"{
+          "info": {
+            "name": "Old",
+            "schema": "https://schema.getpostman.com/json/collection/v2.0.0/collection.json"
+          },
+          "item": [
+            { "name": "Home", "request": "https://example.com" },
+            {
+              "name": "Search",
+              "request": {
+                "method": "GET",
+                "header": "Accept: text/html\nX-Token: abc",
+                "auth": { "type": "apikey", "apikey": { "key": "api_key", "value": "k", "in": "query" } },
+                "url": {
+                  "protocol": "https",
+                  "host": ["example", "com"],
+                  "path": ["search"],
+                  "query": [{ "key": "q", "value": "cats" }]
+                }
+              }
+            }
+          ]
+        }"#;
+        let imported = parse_postman(collection).unwrap();
+        assert_eq!(imported.requests[0].endpoint.url, "https://example.com");
+        let search = &imported.requests[1].endpoint;
+        assert_eq!(search.url, "https://example.com/search?q=cats&api_key=k");
+        assert_eq!(
+            search.headers,
+            KeyValueTable::new(&[("Accept", "text/html").into(), ("X-Token", "abc").into()])
+        );
+        assert!(imported.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_parse_postman_keeps_auth_that_needs_variables_or_challenges() {
+        let collection = r#"{
+          "info": {
+            "name": "Auth",
+            "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"
+          },
+          "item": [
+            {
+              "name": "Basic",
+              "request": {
+                "method": "GET",
+                "auth": {
+                  "type": "basic",
+                  "basic": [
+                    { "key": "username", "value": "{{user}}" },
+                    { "key": "password", "value": "{{password}}" }
+                  ]
+                },
+                "url": "https://example.com/basic"
+              }
+            },
+            {
+              "name": "Digest",
+              "request": {
+                "method": "GET",
+                "auth": {
+                  "type": "digest",
+                  "digest": [
+                    { "key": "username", "value": "john" },
+                    { "key": "password", "value": "secret" }
+                  ]
+                },
+                "url": "https://example.com/digest"
+              }
+            }
+          ]
+        }"#;
+        let imported = parse_postman(collection).unwrap();
+        assert_eq!(
+            imported.requests[0].endpoint.auth,
+            RequestAuth::Basic {
+                username: "{{user}}".into(),
+                password: "{{password}}".into(),
+            }
+        );
+        assert_eq!(
+            imported.requests[1].endpoint.auth,
+            RequestAuth::Digest {
+                username: "john".into(),
+                password: "secret".into(),
+            }
+        );
+        assert!(imported.requests[1].endpoint.headers.is_empty());
+        assert!(imported.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_parse_postman_rejects_other_documents() {
+        assert!(matches!(
+            parse_postman(r#"{"info": {"name": "x", "schema": "v1"}}"#),
+            Err(ImportError::Unsupported(_))
+        ));
+        assert!(matches!(
+            parse_postman("[]"),
+            Err(ImportError::InvalidDocument(_))
+        ));
+    }
+}
@@ -0,0 +1,202 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Splits a command line into words following the rules of a POSIX shell.
+
+use super::ImportError;
+
+/// Reads the value of an ANSI-C quoted string, such as `$'a\nb'`, after the opening quote.
+fn read_ansi_c_quoted<I: Iterator<Item = char>>(
+    chars: &mut std::iter::Peekable<I>,
+    word: &mut String,
+) -> Result<(), ImportError> {
+    loop {
+        match chars.next() {
+            None => return Err(ImportError::UnterminatedQuote),
+            Some('\'') => return Ok(()),
+            Some('\\') => {
+                let Some(escape) = chars.next() else {
+                    return Err(ImportError::UnterminatedQuote);
+                };
+                match escape {
+                    'n' => word.push('\n'),
+                    't' => word.push('\t'),
+                    'r' => word.push('\r'),
+                    'a' => word.push('\u{07}'),
+                    'b' => word.push('\u{08}'),
+                    'e' | 'E' => word.push('\u{1b}'),
+                    'f' => word.push('\u{0c}'),
+                    'v' => word.push('\u{0b}'),
+                    'x' | 'u' | 'U' => {
+                        let digits = match escape {
+                            'x' => 2,
+                            'u' => 4,
+                            _ => 8,
+                        };
+                        let mut code = String::new();
+                        while code.len() < digits {
+                            match chars.peek() {
+                                Some(c) if c.is_ascii_hexdigit() => code.push(*c),
+                                _ => break,
+                            }
+                            chars.next();
+                        }
+                        match u32::from_str_radix(&code, 16).ok().and_then(char::from_u32) {
+                            Some(c) => word.push(c),
+                            None => {
+                                word.push('\\');
+                                word.push(escape);
+                                word.push_str(&code);
+                            }
+                        }
+                    }
+                    '\\' | '\'' | '"' | '?' => word.push(escape),
+                    other => {
+                        word.push('\\');
+                        word.push(other);
+                    }
+                }
+            }
+            Some(c) => word.push(c),
+        }
+    }
+}
+
+/// Splits a command line into words, the same way a POSIX shell would do.
+///
+/// Single quotes, double quotes, ANSI-C quotes (`$'...'`) and backslash escapes are
+/// understood, and a backslash at the end of a line continues the command in the next
+/// line. Variables and other expansions are kept as they are.
+pub fn split(command: &str) -> Result<Vec<String>, ImportError> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' | '\n' | '\r' => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some('\r') if chars.peek() == Some(&'\n') => {
+                    chars.next();
+                }
+                Some(escaped) => {
+                    word.push(escaped);
+                    in_word = true;
+                }
+                None => {}
+            },
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(ImportError::UnterminatedQuote),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('\n') => {}
+                            Some(c @ ('$' | '`' | '"' | '\\')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err(ImportError::UnterminatedQuote),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(ImportError::UnterminatedQuote),
+                    }
+                }
+            }
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                in_word = true;
+                read_ansi_c_quoted(&mut chars, &mut word)?;
+            }
+            c => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split;
+
+    #[test]
+    fn test_split_plain_words() {
+        assert_eq!(
+            split("curl  -X\tPOST https://example.com").unwrap(),
+            vec!["curl", "-X", "POST", "https://example.com"]
+        );
+    }
+
+    #[test]
+    fn test_split_quotes() {
+        assert_eq!(
+            split(r#"a 'b c' "d \"e\" \$f \g" 'h'"i"j ''"#).unwrap(),
+            vec!["a", "b c", r#"d "e" $f \g"#, "hij", ""]
+        );
+    }
+
+    #[test]
+    fn test_split_backslashes() {
+        assert_eq!(split(r"a\ b c\'d \\").unwrap(), vec!["a b", "c'd", "\\"]);
+    }
+
+    #[test]
+    fn test_split_line_continuations() {
+        assert_eq!(
+            split("curl \\\n  -H 'A: 1' \\\r\n  https://example.com").unwrap(),
+            vec!["curl", "-H", "A: 1", "https://example.com"]
+        );
+    }
+
+    #[test]
+    fn test_split_ansi_c_quotes() {
+        assert_eq!(
+            split(r"$'a\nb\t\'c\' \x41é'").unwrap(),
+            vec!["a\nb\t'c' Aé"]
+        );
+    }
+
+    #[test]
+    fn test_split_unterminated_quotes() {
+        assert!(split("curl 'abc").is_err());
+        assert!(split("curl \"abc").is_err());
+        assert!(split("curl $'abc").is_err());
+    }
+}
@@ -0,0 +1,29 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Core, UI independent, building blocks of Cartero.
+//!
+//! These modules know how to read and write request files and how to send
+//! requests, but they do not depend on GTK, so they can be used from the
+//! graphical application and from the command line runner alike.
+
+pub mod client;
+pub mod entities;
+pub mod error;
+pub mod export;
+pub mod file;
+pub mod import;
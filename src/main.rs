@@ -18,17 +18,15 @@
 #![windows_subsystem = "windows"]
 
 mod app;
-mod client;
-mod error;
-mod file;
 mod widgets;
 #[rustfmt::skip]
 mod config;
-mod entities;
 mod objects;
 mod utils;
 mod win;
 
+use cartero::{client, entities, error, export, file, import};
+
 use std::path::PathBuf;
 
 use gettextrs::LocaleCategory;
@@ -0,0 +1,225 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use gettextrs::gettext;
+use glib::subclass::types::ObjectSubclassIsExt;
+use gtk::gio::{SimpleAction, SimpleActionGroup};
+use gtk::glib::Object;
+use gtk::prelude::*;
+
+use crate::entities::{Assertion, AssertionKind};
+
+mod imp {
+    use std::cell::RefCell;
+    use std::sync::OnceLock;
+
+    use glib::subclass::{InitializingObject, Signal};
+    use glib::Properties;
+    use gtk::subclass::prelude::*;
+    use gtk::{prelude::*, CompositeTemplate};
+
+    #[derive(CompositeTemplate, Default, Properties)]
+    #[properties(wrapper_type = super::AssertionRow)]
+    #[template(resource = "/es/danirod/Cartero/assertion_row.ui")]
+    pub struct AssertionRow {
+        #[template_child]
+        pub(super) entry_subject: TemplateChild<gtk::Entry>,
+        #[template_child]
+        pub(super) entry_expected: TemplateChild<gtk::Entry>,
+
+        #[property(get, set)]
+        active: RefCell<bool>,
+        /// The position of the kind in the dropdown, as in `super::KINDS`.
+        #[property(get, set)]
+        kind: RefCell<u32>,
+        #[property(get, set)]
+        subject: RefCell<String>,
+        #[property(get, set)]
+        expected: RefCell<String>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for AssertionRow {
+        const NAME: &'static str = "CarteroAssertionRow";
+        type Type = super::AssertionRow;
+        type ParentType = gtk::ListBoxRow;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for AssertionRow {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            let obj = self.obj();
+            obj.setup_actions();
+            obj.setup_signals();
+            obj.sync_entries();
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder("delete").build(),
+                    Signal::builder("changed").build(),
+                ]
+            })
+        }
+    }
+
+    impl WidgetImpl for AssertionRow {}
+    impl ListBoxRowImpl for AssertionRow {}
+}
+
+/// The kinds in the order of the dropdown.
+const KINDS: [AssertionKind; 9] = [
+    AssertionKind::StatusEquals,
+    AssertionKind::StatusInRange,
+    AssertionKind::HeaderExists,
+    AssertionKind::HeaderMatches,
+    AssertionKind::JsonPathEquals,
+    AssertionKind::JsonPathMatches,
+    AssertionKind::JsonPathType,
+    AssertionKind::BodyContains,
+    AssertionKind::DurationUnder,
+];
+
+glib::wrapper! {
+    pub struct AssertionRow(ObjectSubclass<imp::AssertionRow>)
+        @extends gtk::Widget, gtk::ListBoxRow,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Actionable;
+}
+
+impl Default for AssertionRow {
+    fn default() -> Self {
+        Object::builder().property("active", true).build()
+    }
+}
+
+impl From<&Assertion> for AssertionRow {
+    fn from(assertion: &Assertion) -> Self {
+        let kind = KINDS
+            .iter()
+            .position(|kind| *kind == assertion.kind)
+            .unwrap_or_default() as u32;
+        Object::builder()
+            .property("active", assertion.active)
+            .property("kind", kind)
+            .property("subject", &assertion.subject)
+            .property("expected", &assertion.expected)
+            .build()
+    }
+}
+
+impl AssertionRow {
+    /// Returns the assertion represented by this row.
+    pub fn assertion(&self) -> Assertion {
+        Assertion {
+            kind: self.assertion_kind(),
+            subject: self.subject(),
+            expected: self.expected(),
+            active: self.active(),
+        }
+    }
+
+    fn assertion_kind(&self) -> AssertionKind {
+        KINDS.get(self.kind() as usize).copied().unwrap_or_default()
+    }
+
+    pub fn connect_delete<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "delete",
+            true,
+            glib::closure_local!(|ref row| {
+                f(row);
+            }),
+        )
+    }
+
+    pub fn connect_changed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "changed",
+            true,
+            glib::closure_local!(|ref row| {
+                f(row);
+            }),
+        )
+    }
+
+    /// Only shows the entries that the kind of assertion uses, and hints what to
+    /// write in them.
+    fn sync_entries(&self) {
+        let imp = self.imp();
+        let (subject, expected) = match self.assertion_kind() {
+            AssertionKind::StatusEquals => (None, Some(gettext("Status code"))),
+            AssertionKind::StatusInRange => (None, Some(gettext("Range, such as 200-299"))),
+            AssertionKind::HeaderExists => (Some(gettext("Header")), None),
+            AssertionKind::HeaderMatches => {
+                (Some(gettext("Header")), Some(gettext("Regular expression")))
+            }
+            AssertionKind::JsonPathEquals => (Some("JSONPath".into()), Some(gettext("Value"))),
+            AssertionKind::JsonPathMatches => {
+                (Some("JSONPath".into()), Some(gettext("Regular expression")))
+            }
+            AssertionKind::JsonPathType => (
+                Some("JSONPath".into()),
+                Some(gettext("string, number, boolean, array, object or null")),
+            ),
+            AssertionKind::BodyContains => (None, Some(gettext("Text"))),
+            AssertionKind::DurationUnder => (None, Some(gettext("Milliseconds"))),
+        };
+        imp.entry_subject.set_visible(subject.is_some());
+        imp.entry_subject.set_placeholder_text(subject.as_deref());
+        imp.entry_expected.set_visible(expected.is_some());
+        imp.entry_expected.set_placeholder_text(expected.as_deref());
+    }
+
+    fn setup_signals(&self) {
+        self.connect_active_notify(|row| {
+            if row.active() {
+                row.remove_css_class("inactive-header");
+            } else {
+                row.add_css_class("inactive-header");
+            }
+        });
+        self.connect_kind_notify(|row| row.sync_entries());
+        for property in ["active", "kind", "subject", "expected"] {
+            self.connect_notify_local(Some(property), |row, _| {
+                row.emit_by_name::<()>("changed", &[]);
+            });
+        }
+    }
+
+    fn setup_actions(&self) {
+        let ag = SimpleActionGroup::new();
+        self.insert_action_group("row", Some(&ag));
+
+        let delete = SimpleAction::new("delete", None);
+        delete.connect_activate(glib::clone!(@weak self as row => move |_, _| {
+            row.emit_by_name::<()>("delete", &[]);
+        }));
+        ag.add_action(&delete);
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use glib::{object::ObjectExt, subclass::types::ObjectSubclassIsExt, Object};
+
+use crate::entities::Assertion;
+
+mod imp {
+    use std::sync::OnceLock;
+
+    use adw::subclass::bin::BinImpl;
+    use glib::subclass::{InitializingObject, Signal};
+    use gtk::subclass::prelude::*;
+    use gtk::{prelude::*, CompositeTemplate};
+
+    use crate::entities::Assertion;
+    use crate::widgets::AssertionRow;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/es/danirod/Cartero/assertions_pane.ui")]
+    pub struct AssertionsPane {
+        #[template_child]
+        assertions: TemplateChild<gtk::ListBox>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for AssertionsPane {
+        const NAME: &'static str = "CarteroAssertionsPane";
+        type Type = super::AssertionsPane;
+        type ParentType = adw::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for AssertionsPane {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![Signal::builder("changed").build()])
+        }
+    }
+
+    impl WidgetImpl for AssertionsPane {}
+
+    impl BinImpl for AssertionsPane {}
+
+    #[gtk::template_callbacks]
+    impl AssertionsPane {
+        #[template_callback]
+        fn on_add_assertion_clicked(&self) {
+            self.add_assertion_row(AssertionRow::default());
+            self.obj().emit_by_name::<()>("changed", &[]);
+        }
+
+        fn add_assertion_row(&self, row: AssertionRow) {
+            row.connect_changed(glib::clone!(@weak self as pane => move |_| {
+                pane.obj().emit_by_name::<()>("changed", &[]);
+            }));
+            row.connect_delete(glib::clone!(@weak self as pane => move |row| {
+                pane.assertions.remove(row);
+                pane.obj().emit_by_name::<()>("changed", &[]);
+            }));
+            self.assertions.append(&row);
+        }
+
+        fn assertion_rows(&self) -> Vec<AssertionRow> {
+            let mut rows = Vec::new();
+            let mut child = self.assertions.first_child();
+            while let Some(widget) = child {
+                child = widget.next_sibling();
+                if let Ok(row) = widget.downcast::<AssertionRow>() {
+                    rows.push(row);
+                }
+            }
+            rows
+        }
+
+        pub(super) fn assertions(&self) -> Vec<Assertion> {
+            self.assertion_rows()
+                .iter()
+                .map(AssertionRow::assertion)
+                .collect()
+        }
+
+        pub(super) fn set_assertions(&self, assertions: &[Assertion]) {
+            for row in self.assertion_rows() {
+                self.assertions.remove(&row);
+            }
+            for assertion in assertions {
+                self.add_assertion_row(AssertionRow::from(assertion));
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct AssertionsPane(ObjectSubclass<imp::AssertionsPane>)
+        @extends gtk::Widget, adw::Bin,
+        @implements gtk::Accessible, gtk::Buildable;
+}
+
+impl Default for AssertionsPane {
+    fn default() -> Self {
+        Object::builder().build()
+    }
+}
+
+impl AssertionsPane {
+    pub fn connect_changed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "changed",
+            true,
+            glib::closure_local!(|ref pane| {
+                f(pane);
+            }),
+        )
+    }
+
+    /// Reads the assertions of the request from the rows of the pane.
+    pub fn assertions(&self) -> Vec<Assertion> {
+        self.imp().assertions()
+    }
+
+    pub fn set_assertions(&self, assertions: &[Assertion]) {
+        self.imp().set_assertions(assertions)
+    }
+}
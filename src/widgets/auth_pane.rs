@@ -0,0 +1,356 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use glib::{object::ObjectExt, subclass::types::ObjectSubclassIsExt, Object};
+
+use crate::entities::RequestAuth;
+
+mod imp {
+    use std::sync::OnceLock;
+
+    use adw::prelude::*;
+    use adw::subclass::bin::BinImpl;
+    use glib::subclass::{InitializingObject, Signal};
+    use gtk::subclass::prelude::*;
+    use gtk::CompositeTemplate;
+
+    use crate::entities::{
+        ApiKeyLocation, AwsSigV4Settings, HmacAlgorithm, HmacSettings, OAuth2Grant, OAuth2Settings,
+        RequestAuth, SignatureEncoding,
+    };
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/es/danirod/Cartero/auth_pane.ui")]
+    pub struct AuthPane {
+        #[template_child]
+        auth_type: TemplateChild<adw::ComboRow>,
+
+        #[template_child]
+        grant: TemplateChild<adw::ComboRow>,
+
+        #[template_child]
+        token_url: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        authorization_url: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        client_id: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        client_secret: TemplateChild<adw::PasswordEntryRow>,
+
+        #[template_child]
+        scope: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        username: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        password: TemplateChild<adw::PasswordEntryRow>,
+
+        #[template_child]
+        token: TemplateChild<adw::PasswordEntryRow>,
+
+        #[template_child]
+        key_name: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        key_value: TemplateChild<adw::PasswordEntryRow>,
+
+        #[template_child]
+        key_location: TemplateChild<adw::ComboRow>,
+
+        #[template_child]
+        access_key: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        secret_key: TemplateChild<adw::PasswordEntryRow>,
+
+        #[template_child]
+        session_token: TemplateChild<adw::PasswordEntryRow>,
+
+        #[template_child]
+        region: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        service: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        algorithm: TemplateChild<adw::ComboRow>,
+
+        #[template_child]
+        hmac_key: TemplateChild<adw::PasswordEntryRow>,
+
+        #[template_child]
+        message: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        signature_header: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        timestamp_header: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        encoding: TemplateChild<adw::ComboRow>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for AuthPane {
+        const NAME: &'static str = "CarteroAuthPane";
+        type Type = super::AuthPane;
+        type ParentType = adw::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for AuthPane {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![Signal::builder("changed").build()])
+        }
+    }
+
+    impl WidgetImpl for AuthPane {}
+
+    impl BinImpl for AuthPane {}
+
+    #[gtk::template_callbacks]
+    impl AuthPane {
+        #[template_callback]
+        fn on_changed(&self) {
+            self.obj().emit_by_name::<()>("changed", &[]);
+        }
+
+        #[template_callback]
+        fn on_auth_type_changed(&self) {
+            // Only show the rows that the picked type of authentication uses.
+            let selected = self.auth_type.selected();
+            let oauth2 = selected == 5;
+            let grant = self.grant.selected();
+            let credentials = selected == 1 || selected == 4 || (oauth2 && grant == 1);
+            self.grant.set_visible(oauth2);
+            self.token_url.set_visible(oauth2);
+            self.authorization_url.set_visible(oauth2 && grant == 2);
+            self.client_id.set_visible(oauth2);
+            self.client_secret.set_visible(oauth2);
+            self.scope.set_visible(oauth2);
+            self.username.set_visible(credentials);
+            self.password.set_visible(credentials);
+            self.token.set_visible(selected == 2);
+            self.key_name.set_visible(selected == 3);
+            self.key_value.set_visible(selected == 3);
+            self.key_location.set_visible(selected == 3);
+            let aws = selected == 6;
+            self.access_key.set_visible(aws);
+            self.secret_key.set_visible(aws);
+            self.session_token.set_visible(aws);
+            self.region.set_visible(aws);
+            self.service.set_visible(aws);
+            let hmac = selected == 7;
+            self.algorithm.set_visible(hmac);
+            self.hmac_key.set_visible(hmac);
+            self.message.set_visible(hmac);
+            self.signature_header.set_visible(hmac);
+            self.timestamp_header.set_visible(hmac);
+            self.encoding.set_visible(hmac);
+            self.on_changed();
+        }
+
+        pub(super) fn auth(&self) -> RequestAuth {
+            let username = self.username.text().to_string();
+            let password = self.password.text().to_string();
+            match self.auth_type.selected() {
+                1 => RequestAuth::Basic { username, password },
+                2 => RequestAuth::Bearer {
+                    token: self.token.text().to_string(),
+                },
+                3 => RequestAuth::ApiKey {
+                    name: self.key_name.text().to_string(),
+                    value: self.key_value.text().to_string(),
+                    location: match self.key_location.selected() {
+                        1 => ApiKeyLocation::Query,
+                        _ => ApiKeyLocation::Header,
+                    },
+                },
+                4 => RequestAuth::Digest { username, password },
+                5 => RequestAuth::OAuth2(OAuth2Settings {
+                    grant: match self.grant.selected() {
+                        1 => OAuth2Grant::Password,
+                        2 => OAuth2Grant::AuthorizationCode,
+                        _ => OAuth2Grant::ClientCredentials,
+                    },
+                    token_url: self.token_url.text().to_string(),
+                    authorization_url: self.authorization_url.text().to_string(),
+                    client_id: self.client_id.text().to_string(),
+                    client_secret: self.client_secret.text().to_string(),
+                    scope: self.scope.text().to_string(),
+                    username,
+                    password,
+                }),
+                6 => RequestAuth::AwsSigV4(AwsSigV4Settings {
+                    access_key: self.access_key.text().to_string(),
+                    secret_key: self.secret_key.text().to_string(),
+                    session_token: self.session_token.text().to_string(),
+                    region: self.region.text().to_string(),
+                    service: self.service.text().to_string(),
+                }),
+                7 => RequestAuth::Hmac(HmacSettings {
+                    algorithm: match self.algorithm.selected() {
+                        0 => HmacAlgorithm::Sha1,
+                        2 => HmacAlgorithm::Sha512,
+                        _ => HmacAlgorithm::Sha256,
+                    },
+                    key: self.hmac_key.text().to_string(),
+                    message: self.message.text().to_string(),
+                    header: self.signature_header.text().to_string(),
+                    timestamp_header: self.timestamp_header.text().to_string(),
+                    encoding: match self.encoding.selected() {
+                        1 => SignatureEncoding::Base64,
+                        _ => SignatureEncoding::Hex,
+                    },
+                }),
+                _ => RequestAuth::None,
+            }
+        }
+
+        pub(super) fn set_auth(&self, auth: &RequestAuth) {
+            let (username, password) = match auth {
+                RequestAuth::Basic { username, password }
+                | RequestAuth::Digest { username, password } => {
+                    (username.as_str(), password.as_str())
+                }
+                RequestAuth::OAuth2(settings) => {
+                    (settings.username.as_str(), settings.password.as_str())
+                }
+                _ => ("", ""),
+            };
+            self.username.set_text(username);
+            self.password.set_text(password);
+            let settings = match auth {
+                RequestAuth::OAuth2(settings) => settings.clone(),
+                _ => OAuth2Settings::default(),
+            };
+            self.grant.set_selected(match settings.grant {
+                OAuth2Grant::ClientCredentials => 0,
+                OAuth2Grant::Password => 1,
+                OAuth2Grant::AuthorizationCode => 2,
+            });
+            self.token_url.set_text(&settings.token_url);
+            self.authorization_url.set_text(&settings.authorization_url);
+            self.client_id.set_text(&settings.client_id);
+            self.client_secret.set_text(&settings.client_secret);
+            self.scope.set_text(&settings.scope);
+            let token = match auth {
+                RequestAuth::Bearer { token } => token.as_str(),
+                _ => "",
+            };
+            self.token.set_text(token);
+            let (name, value, location) = match auth {
+                RequestAuth::ApiKey {
+                    name,
+                    value,
+                    location,
+                } => (name.as_str(), value.as_str(), *location),
+                _ => ("", "", ApiKeyLocation::Header),
+            };
+            self.key_name.set_text(name);
+            self.key_value.set_text(value);
+            self.key_location.set_selected(match location {
+                ApiKeyLocation::Header => 0,
+                ApiKeyLocation::Query => 1,
+            });
+            let aws = match auth {
+                RequestAuth::AwsSigV4(settings) => settings.clone(),
+                _ => AwsSigV4Settings::default(),
+            };
+            self.access_key.set_text(&aws.access_key);
+            self.secret_key.set_text(&aws.secret_key);
+            self.session_token.set_text(&aws.session_token);
+            self.region.set_text(&aws.region);
+            self.service.set_text(&aws.service);
+            let hmac = match auth {
+                RequestAuth::Hmac(settings) => settings.clone(),
+                _ => HmacSettings::default(),
+            };
+            self.algorithm.set_selected(match hmac.algorithm {
+                HmacAlgorithm::Sha1 => 0,
+                HmacAlgorithm::Sha256 => 1,
+                HmacAlgorithm::Sha512 => 2,
+            });
+            self.hmac_key.set_text(&hmac.key);
+            self.message.set_text(&hmac.message);
+            self.signature_header.set_text(&hmac.header);
+            self.timestamp_header.set_text(&hmac.timestamp_header);
+            self.encoding.set_selected(match hmac.encoding {
+                SignatureEncoding::Hex => 0,
+                SignatureEncoding::Base64 => 1,
+            });
+            let position = match auth {
+                RequestAuth::None => 0,
+                RequestAuth::Basic { .. } => 1,
+                RequestAuth::Bearer { .. } => 2,
+                RequestAuth::ApiKey { .. } => 3,
+                RequestAuth::Digest { .. } => 4,
+                RequestAuth::OAuth2(_) => 5,
+                RequestAuth::AwsSigV4(_) => 6,
+                RequestAuth::Hmac(_) => 7,
+            };
+            self.auth_type.set_selected(position);
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct AuthPane(ObjectSubclass<imp::AuthPane>)
+        @extends gtk::Widget, adw::Bin,
+        @implements gtk::Accessible, gtk::Buildable;
+}
+
+impl Default for AuthPane {
+    fn default() -> Self {
+        Object::builder().build()
+    }
+}
+
+impl AuthPane {
+    pub fn connect_changed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "changed",
+            true,
+            glib::closure_local!(|ref pane| {
+                f(pane);
+            }),
+        )
+    }
+
+    /// Reads the authentication of the request from the widgets of the pane.
+    pub fn auth(&self) -> RequestAuth {
+        self.imp().auth()
+    }
+
+    pub fn set_auth(&self, auth: &RequestAuth) {
+        self.imp().set_auth(auth)
+    }
+}
@@ -0,0 +1,220 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use glib::subclass::types::ObjectSubclassIsExt;
+use gtk::gio::{SimpleAction, SimpleActionGroup};
+use gtk::glib::Object;
+use gtk::prelude::*;
+
+use crate::entities::{Capture, CaptureScope, CaptureSource};
+
+mod imp {
+    use std::cell::RefCell;
+    use std::sync::OnceLock;
+
+    use glib::subclass::{InitializingObject, Signal};
+    use glib::Properties;
+    use gtk::subclass::prelude::*;
+    use gtk::{prelude::*, CompositeTemplate};
+
+    #[derive(CompositeTemplate, Default, Properties)]
+    #[properties(wrapper_type = super::CaptureRow)]
+    #[template(resource = "/es/danirod/Cartero/capture_row.ui")]
+    pub struct CaptureRow {
+        #[template_child]
+        entry_expression: TemplateChild<gtk::Entry>,
+
+        #[property(get, set)]
+        active: RefCell<bool>,
+        #[property(get, set)]
+        variable: RefCell<String>,
+        /// The position of the source in the dropdown.
+        #[property(get, set)]
+        source: RefCell<u32>,
+        #[property(get, set)]
+        expression: RefCell<String>,
+        /// The position of the scope in the dropdown, as in `super::SCOPES`.
+        #[property(get, set)]
+        scope: RefCell<u32>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for CaptureRow {
+        const NAME: &'static str = "CarteroCaptureRow";
+        type Type = super::CaptureRow;
+        type ParentType = gtk::ListBoxRow;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for CaptureRow {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            let obj = self.obj();
+            obj.setup_actions();
+            obj.setup_signals();
+            self.sync_expression_visibility();
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder("delete").build(),
+                    Signal::builder("changed").build(),
+                ]
+            })
+        }
+    }
+
+    impl WidgetImpl for CaptureRow {}
+    impl ListBoxRowImpl for CaptureRow {}
+
+    impl CaptureRow {
+        /// The status code needs no expression, so its entry is hidden.
+        pub(super) fn sync_expression_visibility(&self) {
+            let status = *self.source.borrow() == super::STATUS_CODE;
+            self.entry_expression.set_visible(!status);
+        }
+    }
+}
+
+// The positions of the sources in the dropdown.
+const JSON_PATH: u32 = 0;
+const XPATH: u32 = 1;
+const HEADER: u32 = 2;
+const REGEX: u32 = 3;
+const STATUS_CODE: u32 = 4;
+
+/// The scopes in the order of the dropdown.
+const SCOPES: [CaptureScope; 3] = [
+    CaptureScope::Request,
+    CaptureScope::Environment,
+    CaptureScope::Session,
+];
+
+glib::wrapper! {
+    pub struct CaptureRow(ObjectSubclass<imp::CaptureRow>)
+        @extends gtk::Widget, gtk::ListBoxRow,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Actionable;
+}
+
+impl Default for CaptureRow {
+    fn default() -> Self {
+        Object::builder().property("active", true).build()
+    }
+}
+
+impl From<&Capture> for CaptureRow {
+    fn from(capture: &Capture) -> Self {
+        let (source, expression) = match &capture.source {
+            CaptureSource::JsonPath(expression) => (JSON_PATH, expression.as_str()),
+            CaptureSource::XPath(expression) => (XPATH, expression.as_str()),
+            CaptureSource::Header(expression) => (HEADER, expression.as_str()),
+            CaptureSource::Regex(expression) => (REGEX, expression.as_str()),
+            CaptureSource::StatusCode => (STATUS_CODE, ""),
+        };
+        let scope = SCOPES
+            .iter()
+            .position(|scope| *scope == capture.scope)
+            .unwrap_or_default() as u32;
+        Object::builder()
+            .property("active", capture.active)
+            .property("variable", &capture.variable)
+            .property("source", source)
+            .property("expression", expression)
+            .property("scope", scope)
+            .build()
+    }
+}
+
+impl CaptureRow {
+    /// Returns the capture represented by this row.
+    pub fn capture(&self) -> Capture {
+        let expression = self.expression();
+        Capture {
+            variable: self.variable(),
+            source: match self.source() {
+                XPATH => CaptureSource::XPath(expression),
+                HEADER => CaptureSource::Header(expression),
+                REGEX => CaptureSource::Regex(expression),
+                STATUS_CODE => CaptureSource::StatusCode,
+                _ => CaptureSource::JsonPath(expression),
+            },
+            scope: SCOPES
+                .get(self.scope() as usize)
+                .copied()
+                .unwrap_or_default(),
+            active: self.active(),
+        }
+    }
+
+    pub fn connect_delete<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "delete",
+            true,
+            glib::closure_local!(|ref row| {
+                f(row);
+            }),
+        )
+    }
+
+    pub fn connect_changed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "changed",
+            true,
+            glib::closure_local!(|ref row| {
+                f(row);
+            }),
+        )
+    }
+
+    fn setup_signals(&self) {
+        self.connect_active_notify(|row| {
+            if row.active() {
+                row.remove_css_class("inactive-header");
+            } else {
+                row.add_css_class("inactive-header");
+            }
+        });
+        self.connect_source_notify(|row| row.imp().sync_expression_visibility());
+        for property in ["active", "variable", "source", "expression", "scope"] {
+            self.connect_notify_local(Some(property), |row, _| {
+                row.emit_by_name::<()>("changed", &[]);
+            });
+        }
+    }
+
+    fn setup_actions(&self) {
+        let ag = SimpleActionGroup::new();
+        self.insert_action_group("row", Some(&ag));
+
+        let delete = SimpleAction::new("delete", None);
+        delete.connect_activate(glib::clone!(@weak self as row => move |_, _| {
+            row.emit_by_name::<()>("delete", &[]);
+        }));
+        ag.add_action(&delete);
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use glib::{object::ObjectExt, subclass::types::ObjectSubclassIsExt, Object};
+
+use crate::entities::Capture;
+
+mod imp {
+    use std::sync::OnceLock;
+
+    use adw::subclass::bin::BinImpl;
+    use glib::subclass::{InitializingObject, Signal};
+    use gtk::subclass::prelude::*;
+    use gtk::{prelude::*, CompositeTemplate};
+
+    use crate::entities::Capture;
+    use crate::widgets::CaptureRow;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/es/danirod/Cartero/captures_pane.ui")]
+    pub struct CapturesPane {
+        #[template_child]
+        captures: TemplateChild<gtk::ListBox>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for CapturesPane {
+        const NAME: &'static str = "CarteroCapturesPane";
+        type Type = super::CapturesPane;
+        type ParentType = adw::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for CapturesPane {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![Signal::builder("changed").build()])
+        }
+    }
+
+    impl WidgetImpl for CapturesPane {}
+
+    impl BinImpl for CapturesPane {}
+
+    #[gtk::template_callbacks]
+    impl CapturesPane {
+        #[template_callback]
+        fn on_add_capture_clicked(&self) {
+            self.add_capture_row(CaptureRow::default());
+            self.obj().emit_by_name::<()>("changed", &[]);
+        }
+
+        fn add_capture_row(&self, row: CaptureRow) {
+            row.connect_changed(glib::clone!(@weak self as pane => move |_| {
+                pane.obj().emit_by_name::<()>("changed", &[]);
+            }));
+            row.connect_delete(glib::clone!(@weak self as pane => move |row| {
+                pane.captures.remove(row);
+                pane.obj().emit_by_name::<()>("changed", &[]);
+            }));
+            self.captures.append(&row);
+        }
+
+        fn capture_rows(&self) -> Vec<CaptureRow> {
+            let mut rows = Vec::new();
+            let mut child = self.captures.first_child();
+            while let Some(widget) = child {
+                child = widget.next_sibling();
+                if let Ok(row) = widget.downcast::<CaptureRow>() {
+                    rows.push(row);
+                }
+            }
+            rows
+        }
+
+        pub(super) fn captures(&self) -> Vec<Capture> {
+            self.capture_rows()
+                .iter()
+                .map(CaptureRow::capture)
+                .collect()
+        }
+
+        pub(super) fn set_captures(&self, captures: &[Capture]) {
+            for row in self.capture_rows() {
+                self.captures.remove(&row);
+            }
+            for capture in captures {
+                self.add_capture_row(CaptureRow::from(capture));
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct CapturesPane(ObjectSubclass<imp::CapturesPane>)
+        @extends gtk::Widget, adw::Bin,
+        @implements gtk::Accessible, gtk::Buildable;
+}
+
+impl Default for CapturesPane {
+    fn default() -> Self {
+        Object::builder().build()
+    }
+}
+
+impl CapturesPane {
+    pub fn connect_changed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "changed",
+            true,
+            glib::closure_local!(|ref pane| {
+                f(pane);
+            }),
+        )
+    }
+
+    /// Reads the captures of the request from the rows of the pane.
+    pub fn captures(&self) -> Vec<Capture> {
+        self.imp().captures()
+    }
+
+    pub fn set_captures(&self, captures: &[Capture]) {
+        self.imp().set_captures(captures)
+    }
+}
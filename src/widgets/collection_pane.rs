@@ -0,0 +1,558 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use glib::subclass::types::ObjectSubclassIsExt;
+use glib::Object;
+use gtk::{gio, glib};
+
+mod imp {
+    use std::cell::RefCell;
+
+    use adw::prelude::*;
+    use adw::subclass::prelude::*;
+    use gettextrs::gettext;
+    use glib::subclass::InitializingObject;
+    use gtk::{gdk, gio, glib, graphene, CompositeTemplate};
+
+    use crate::entities::EndpointData;
+    use crate::error::CarteroError;
+    use crate::file::{collection_item_name, duplicate_file_name, REQUEST_SUFFIX};
+    use crate::win::CarteroWindow;
+
+    const FILE_ATTRIBUTES: &str = "standard::name,standard::display-name,standard::type";
+
+    fn is_directory(info: &gio::FileInfo) -> bool {
+        info.file_type() == gio::FileType::Directory
+    }
+
+    fn gio_error(error: glib::Error) -> CarteroError {
+        CarteroError::FileError(std::io::Error::other(error.to_string()))
+    }
+
+    /// Lists the folders and the request files inside a folder, folders first.
+    fn directory_model(folder: &gio::File) -> gio::ListModel {
+        let list = gtk::DirectoryList::new(Some(FILE_ATTRIBUTES), Some(folder));
+
+        let filter = gtk::CustomFilter::new(|item| {
+            let info = item.downcast_ref::<gio::FileInfo>().unwrap();
+            let name = info.name();
+            let name = name.to_string_lossy();
+            !name.starts_with('.') && (is_directory(info) || name.ends_with(REQUEST_SUFFIX))
+        });
+        let filtered = gtk::FilterListModel::new(Some(list), Some(filter));
+
+        let sorter = gtk::CustomSorter::new(|a, b| {
+            let a = a.downcast_ref::<gio::FileInfo>().unwrap();
+            let b = b.downcast_ref::<gio::FileInfo>().unwrap();
+            is_directory(b)
+                .cmp(&is_directory(a))
+                .then_with(|| {
+                    let a = a.display_name().to_lowercase();
+                    let b = b.display_name().to_lowercase();
+                    a.cmp(&b)
+                })
+                .into()
+        });
+        gtk::SortListModel::new(Some(filtered), Some(sorter)).upcast()
+    }
+
+    fn row_file(row: &gtk::TreeListRow) -> Option<(gio::File, gio::FileInfo)> {
+        let info = row.item().and_downcast::<gio::FileInfo>()?;
+        let file = info
+            .attribute_object("standard::file")
+            .and_downcast::<gio::File>()?;
+        Some((file, info))
+    }
+
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/es/danirod/Cartero/collection_pane.ui")]
+    pub struct CollectionPane {
+        #[template_child]
+        title: TemplateChild<gtk::Label>,
+
+        #[template_child]
+        stack: TemplateChild<gtk::Stack>,
+
+        #[template_child]
+        list_view: TemplateChild<gtk::ListView>,
+
+        #[template_child]
+        item_menu: TemplateChild<gio::MenuModel>,
+
+        pub(super) root: RefCell<Option<gio::File>>,
+
+        selection: RefCell<Option<gtk::SingleSelection>>,
+
+        popover: RefCell<Option<gtk::PopoverMenu>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for CollectionPane {
+        const NAME: &'static str = "CarteroCollectionPane";
+        type Type = super::CollectionPane;
+        type ParentType = adw::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+
+            klass.install_action_async("collection.new-request", None, |pane, _, _| async move {
+                let imp = pane.imp();
+                imp.report(imp.new_request().await);
+            });
+            klass.install_action_async("collection.new-folder", None, |pane, _, _| async move {
+                let imp = pane.imp();
+                imp.report(imp.new_folder().await);
+            });
+            klass.install_action_async("collection.rename", None, |pane, _, _| async move {
+                let imp = pane.imp();
+                imp.report(imp.rename().await);
+            });
+            klass.install_action_async("collection.duplicate", None, |pane, _, _| async move {
+                let imp = pane.imp();
+                imp.report(imp.duplicate().await);
+            });
+            klass.install_action_async("collection.move", None, |pane, _, _| async move {
+                let imp = pane.imp();
+                imp.report(imp.move_to().await);
+            });
+            klass.install_action_async("collection.delete", None, |pane, _, _| async move {
+                let imp = pane.imp();
+                imp.report(imp.delete().await);
+            });
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for CollectionPane {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            let popover = gtk::PopoverMenu::from_model(Some(&*self.item_menu));
+            popover.set_parent(&*self.list_view);
+            popover.set_has_arrow(false);
+            popover.set_halign(gtk::Align::Start);
+            self.popover.replace(Some(popover));
+
+            let factory = gtk::SignalListItemFactory::new();
+            factory.connect_setup(glib::clone!(@weak self as pane => move |_, item| {
+                let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+                let icon = gtk::Image::new();
+                let label = gtk::Label::builder()
+                    .xalign(0.0)
+                    .ellipsize(gtk::pango::EllipsizeMode::End)
+                    .build();
+                let content = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+                content.append(&icon);
+                content.append(&label);
+                let expander = gtk::TreeExpander::new();
+                expander.set_child(Some(&content));
+
+                let gesture = gtk::GestureClick::builder()
+                    .button(gdk::BUTTON_SECONDARY)
+                    .build();
+                gesture.connect_pressed(
+                    glib::clone!(@weak pane, @weak item, @weak expander => move |_, _, x, y| {
+                        pane.show_item_menu(item.position(), &expander, x, y);
+                    }),
+                );
+                expander.add_controller(gesture);
+                item.set_child(Some(&expander));
+            }));
+            factory.connect_bind(|_, item| {
+                let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+                let Some(row) = item.item().and_downcast::<gtk::TreeListRow>() else {
+                    return;
+                };
+                let expander = item.child().and_downcast::<gtk::TreeExpander>().unwrap();
+                expander.set_list_row(Some(&row));
+
+                let Some((_, info)) = row_file(&row) else {
+                    return;
+                };
+                let content = expander.child().and_downcast::<gtk::Box>().unwrap();
+                let icon = content.first_child().and_downcast::<gtk::Image>().unwrap();
+                let label = content.last_child().and_downcast::<gtk::Label>().unwrap();
+                let icon_name = if is_directory(&info) {
+                    "folder-symbolic"
+                } else {
+                    "text-x-generic-symbolic"
+                };
+                icon.set_icon_name(Some(icon_name));
+                let name = info.display_name();
+                label.set_label(name.strip_suffix(REQUEST_SUFFIX).unwrap_or(&name));
+            });
+            self.list_view.set_factory(Some(&factory));
+
+            self.list_view.connect_activate(
+                glib::clone!(@weak self as pane => move |_, position| {
+                    pane.activate_position(position);
+                }),
+            );
+
+            self.set_root(None);
+        }
+
+        fn dispose(&self) {
+            if let Some(popover) = self.popover.take() {
+                popover.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for CollectionPane {}
+
+    impl BinImpl for CollectionPane {}
+
+    impl CollectionPane {
+        fn window(&self) -> Option<CarteroWindow> {
+            self.obj().root().and_downcast::<CarteroWindow>()
+        }
+
+        fn report(&self, result: Result<(), CarteroError>) {
+            match result {
+                Ok(()) | Err(CarteroError::NoFilePicked) => {}
+                Err(e) => {
+                    if let Some(window) = self.window() {
+                        window.toast_error(e);
+                    }
+                }
+            }
+        }
+
+        pub(super) fn set_root(&self, root: Option<&gio::File>) {
+            self.root.replace(root.cloned());
+
+            match root {
+                Some(root) => {
+                    let tree =
+                        gtk::TreeListModel::new(directory_model(root), false, false, |item| {
+                            let info = item.downcast_ref::<gio::FileInfo>()?;
+                            if !is_directory(info) {
+                                return None;
+                            }
+                            let folder = info
+                                .attribute_object("standard::file")
+                                .and_downcast::<gio::File>()?;
+                            Some(directory_model(&folder))
+                        });
+                    let selection = gtk::SingleSelection::new(Some(tree));
+                    selection.set_autoselect(false);
+                    selection.set_can_unselect(true);
+                    selection.connect_selected_item_notify(
+                        glib::clone!(@weak self as pane => move |_| pane.update_actions()),
+                    );
+                    self.list_view.set_model(Some(&selection));
+                    self.selection.replace(Some(selection));
+
+                    let name = root
+                        .basename()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    self.title.set_label(&name);
+                    self.stack.set_visible_child_name("tree");
+                }
+                None => {
+                    self.list_view.set_model(None::<&gtk::SelectionModel>);
+                    self.selection.replace(None);
+                    self.title.set_label(&gettext("Collection"));
+                    self.stack.set_visible_child_name("empty");
+                }
+            }
+
+            self.update_actions();
+        }
+
+        fn row_at(&self, position: u32) -> Option<gtk::TreeListRow> {
+            let selection = self.selection.borrow();
+            selection.as_ref()?.item(position).and_downcast()
+        }
+
+        fn selected(&self) -> Option<(gio::File, gio::FileInfo)> {
+            let selection = self.selection.borrow();
+            let row = selection
+                .as_ref()?
+                .selected_item()
+                .and_downcast::<gtk::TreeListRow>()?;
+            row_file(&row)
+        }
+
+        /// The folder where new items are created: the selected folder, the folder
+        /// of the selected request or the root of the collection.
+        fn target_folder(&self) -> Option<gio::File> {
+            match self.selected() {
+                Some((file, info)) if is_directory(&info) => Some(file),
+                Some((file, _)) => file.parent(),
+                None => self.root.borrow().clone(),
+            }
+        }
+
+        fn update_actions(&self) {
+            let obj = self.obj();
+            let has_root = self.root.borrow().is_some();
+            let selected = self.selected();
+            let is_request = selected
+                .as_ref()
+                .is_some_and(|(_, info)| !is_directory(info));
+            obj.action_set_enabled("collection.new-request", has_root);
+            obj.action_set_enabled("collection.new-folder", has_root);
+            obj.action_set_enabled("collection.rename", selected.is_some());
+            obj.action_set_enabled("collection.move", selected.is_some());
+            obj.action_set_enabled("collection.delete", selected.is_some());
+            obj.action_set_enabled("collection.duplicate", is_request);
+        }
+
+        fn show_item_menu(&self, position: u32, widget: &impl IsA<gtk::Widget>, x: f64, y: f64) {
+            if let Some(selection) = self.selection.borrow().as_ref() {
+                selection.set_selected(position);
+            }
+            let point = graphene::Point::new(x as f32, y as f32);
+            let Some(point) = widget.compute_point(&*self.list_view, &point) else {
+                return;
+            };
+            if let Some(popover) = self.popover.borrow().as_ref() {
+                let rect = gdk::Rectangle::new(point.x() as i32, point.y() as i32, 1, 1);
+                popover.set_pointing_to(Some(&rect));
+                popover.popup();
+            }
+        }
+
+        fn activate_position(&self, position: u32) {
+            let Some(row) = self.row_at(position) else {
+                return;
+            };
+            if row.is_expandable() {
+                row.set_expanded(!row.is_expanded());
+                return;
+            }
+            let (Some((file, _)), Some(window)) = (row_file(&row), self.window()) else {
+                return;
+            };
+            glib::spawn_future_local(async move {
+                window.add_endpoint(Some(&file)).await;
+            });
+        }
+
+        async fn ask_name(&self, heading: &str, initial: &str, accept: &str) -> Option<String> {
+            let entry = gtk::Entry::builder()
+                .text(initial)
+                .activates_default(true)
+                .build();
+            let dialog = adw::AlertDialog::builder()
+                .heading(heading)
+                .extra_child(&entry)
+                .close_response("cancel")
+                .default_response("accept")
+                .build();
+            dialog.add_response("cancel", &gettext("_Cancel"));
+            dialog.add_response("accept", accept);
+            dialog.set_response_appearance("accept", adw::ResponseAppearance::Suggested);
+
+            let response = dialog.choose_future(&*self.obj()).await;
+            (response.as_str() == "accept").then(|| entry.text().to_string())
+        }
+
+        async fn new_request(&self) -> Result<(), CarteroError> {
+            let Some(folder) = self.target_folder() else {
+                return Ok(());
+            };
+            let Some(name) = self
+                .ask_name(&gettext("New request"), "", &gettext("_Create"))
+                .await
+            else {
+                return Ok(());
+            };
+            let name = collection_item_name(&name, Some(REQUEST_SUFFIX))
+                .ok_or(CarteroError::InvalidFileName)?;
+            let file = folder.child(name);
+            if file.query_exists(gio::Cancellable::NONE) {
+                return Err(CarteroError::FileAlreadyExists);
+            }
+
+            let contents = crate::file::store_toml(&EndpointData::default())?;
+            crate::widgets::write_file(&file, &contents).await?;
+            if let Some(window) = self.window() {
+                window.add_endpoint(Some(&file)).await;
+            }
+            Ok(())
+        }
+
+        async fn new_folder(&self) -> Result<(), CarteroError> {
+            let Some(folder) = self.target_folder() else {
+                return Ok(());
+            };
+            let Some(name) = self
+                .ask_name(&gettext("New folder"), "", &gettext("_Create"))
+                .await
+            else {
+                return Ok(());
+            };
+            let name = collection_item_name(&name, None).ok_or(CarteroError::InvalidFileName)?;
+            let file = folder.child(name);
+            if file.query_exists(gio::Cancellable::NONE) {
+                return Err(CarteroError::FileAlreadyExists);
+            }
+            file.make_directory_future(glib::Priority::DEFAULT)
+                .await
+                .map_err(gio_error)
+        }
+
+        async fn rename(&self) -> Result<(), CarteroError> {
+            let Some((file, info)) = self.selected() else {
+                return Ok(());
+            };
+            let current = info.display_name();
+            let initial = current.strip_suffix(REQUEST_SUFFIX).unwrap_or(&current);
+            let Some(name) = self
+                .ask_name(&gettext("Rename"), initial, &gettext("_Rename"))
+                .await
+            else {
+                return Ok(());
+            };
+            let suffix = (!is_directory(&info)).then_some(REQUEST_SUFFIX);
+            let name = collection_item_name(&name, suffix).ok_or(CarteroError::InvalidFileName)?;
+            if name == current.as_str() {
+                return Ok(());
+            }
+            if let Some(parent) = file.parent() {
+                if parent.child(&name).query_exists(gio::Cancellable::NONE) {
+                    return Err(CarteroError::FileAlreadyExists);
+                }
+            }
+
+            let renamed = file
+                .set_display_name_future(&name, glib::Priority::DEFAULT)
+                .await
+                .map_err(gio_error)?;
+            if let Some(window) = self.window() {
+                window.relocate_files(&file, Some(&renamed));
+            }
+            Ok(())
+        }
+
+        async fn duplicate(&self) -> Result<(), CarteroError> {
+            let Some((file, info)) = self.selected() else {
+                return Ok(());
+            };
+            let Some(parent) = file.parent().filter(|_| !is_directory(&info)) else {
+                return Ok(());
+            };
+            let name = duplicate_file_name(&info.name().to_string_lossy(), |name| {
+                parent.child(name).query_exists(gio::Cancellable::NONE)
+            });
+            let copy = parent.child(name);
+
+            let contents = crate::widgets::read_file(&file).await?;
+            crate::widgets::write_file(&copy, &contents).await?;
+            if let Some(window) = self.window() {
+                window.add_endpoint(Some(&copy)).await;
+            }
+            Ok(())
+        }
+
+        async fn move_to(&self) -> Result<(), CarteroError> {
+            let (Some((file, _)), Some(root), Some(window)) =
+                (self.selected(), self.root.borrow().clone(), self.window())
+            else {
+                return Ok(());
+            };
+            let destination =
+                crate::widgets::open_folder(&window, &gettext("Move to"), Some(&root)).await?;
+
+            // Items cannot leave the collection, and a folder cannot go inside itself.
+            let inside_root = destination.equal(&root) || destination.has_prefix(&root);
+            let inside_self = destination.equal(&file) || destination.has_prefix(&file);
+            if !inside_root || inside_self {
+                return Err(CarteroError::InvalidDestination);
+            }
+            let Some(name) = file.basename() else {
+                return Err(CarteroError::InvalidFileName);
+            };
+            let target = destination.child(name);
+            if target.equal(&file) {
+                return Ok(());
+            }
+            if target.query_exists(gio::Cancellable::NONE) {
+                return Err(CarteroError::FileAlreadyExists);
+            }
+
+            file.move_(
+                &target,
+                gio::FileCopyFlags::NONE,
+                gio::Cancellable::NONE,
+                None,
+            )
+            .map_err(gio_error)?;
+            window.relocate_files(&file, Some(&target));
+            Ok(())
+        }
+
+        async fn delete(&self) -> Result<(), CarteroError> {
+            let Some((file, info)) = self.selected() else {
+                return Ok(());
+            };
+            let name = info.display_name();
+            let heading = gettext("Delete “{}”?").replace("{}", &name);
+            let dialog = adw::AlertDialog::builder()
+                .heading(heading)
+                .body(gettext("It will be moved to the trash."))
+                .close_response("cancel")
+                .default_response("cancel")
+                .build();
+            dialog.add_response("cancel", &gettext("_Cancel"));
+            dialog.add_response("delete", &gettext("_Delete"));
+            dialog.set_response_appearance("delete", adw::ResponseAppearance::Destructive);
+            if dialog.choose_future(&*self.obj()).await.as_str() != "delete" {
+                return Ok(());
+            }
+
+            file.trash_future(glib::Priority::DEFAULT)
+                .await
+                .map_err(gio_error)?;
+            if let Some(window) = self.window() {
+                window.relocate_files(&file, None);
+            }
+            Ok(())
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct CollectionPane(ObjectSubclass<imp::CollectionPane>)
+        @extends gtk::Widget, adw::Bin,
+        @implements gtk::Accessible, gtk::Buildable;
+}
+
+impl Default for CollectionPane {
+    fn default() -> Self {
+        Object::builder().build()
+    }
+}
+
+impl CollectionPane {
+    /// The folder that holds the collection, if one is open.
+    pub fn root(&self) -> Option<gio::File> {
+        self.imp().root.borrow().clone()
+    }
+
+    /// Shows the tree of the collection stored in the given folder, or nothing.
+    pub fn set_root(&self, root: Option<&gio::File>) {
+        self.imp().set_root(root);
+    }
+}
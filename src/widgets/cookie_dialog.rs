@@ -0,0 +1,185 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use glib::subclass::types::ObjectSubclassIsExt;
+use glib::Object;
+
+use crate::win::CarteroWindow;
+
+mod imp {
+    use std::cell::RefCell;
+
+    use adw::prelude::*;
+    use adw::subclass::prelude::*;
+    use gettextrs::gettext;
+    use glib::subclass::InitializingObject;
+    use gtk::CompositeTemplate;
+
+    use crate::app::CarteroApplication;
+    use crate::client::{unix_now, Cookie};
+    use crate::win::CarteroWindow;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/es/danirod/Cartero/cookie_dialog.ui")]
+    pub struct CookieDialog {
+        #[template_child]
+        persist: TemplateChild<adw::SwitchRow>,
+
+        #[template_child]
+        cookie_group: TemplateChild<adw::PreferencesGroup>,
+
+        #[template_child]
+        clear: TemplateChild<gtk::Button>,
+
+        rows: RefCell<Vec<adw::EntryRow>>,
+
+        pub window: glib::WeakRef<CarteroWindow>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for CookieDialog {
+        const NAME: &'static str = "CarteroCookieDialog";
+        type Type = super::CookieDialog;
+        type ParentType = adw::PreferencesDialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for CookieDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            let settings = CarteroApplication::get().settings();
+            settings
+                .bind("persist-cookies", &*self.persist, "active")
+                .build();
+        }
+    }
+
+    impl WidgetImpl for CookieDialog {}
+
+    impl AdwDialogImpl for CookieDialog {}
+
+    impl PreferencesDialogImpl for CookieDialog {}
+
+    #[gtk::template_callbacks]
+    impl CookieDialog {
+        #[template_callback]
+        fn on_clear_clicked(&self) {
+            if let Some(window) = self.window.upgrade() {
+                window.cookie_jar().lock().unwrap().clear();
+            }
+            self.save();
+            self.refresh();
+        }
+
+        /// Shows a row for every cookie in the jar of the window.
+        pub(super) fn refresh(&self) {
+            for row in self.rows.take() {
+                self.cookie_group.remove(&row);
+            }
+            let cookies = match self.window.upgrade() {
+                Some(window) => window.cookie_jar().lock().unwrap().cookies().to_vec(),
+                None => Vec::new(),
+            };
+
+            let description = if cookies.is_empty() {
+                gettext("No cookies have been stored yet")
+            } else {
+                String::new()
+            };
+            self.cookie_group.set_description(Some(&description));
+            self.clear.set_sensitive(!cookies.is_empty());
+
+            let rows: Vec<adw::EntryRow> =
+                cookies.into_iter().map(|c| self.cookie_row(c)).collect();
+            for row in &rows {
+                self.cookie_group.add(row);
+            }
+            self.rows.replace(rows);
+        }
+
+        /// Builds a row that edits the value of a cookie, with a button to delete it.
+        fn cookie_row(&self, cookie: Cookie) -> adw::EntryRow {
+            let row = adw::EntryRow::new();
+            row.set_title(&format!(
+                "{} ({}{})",
+                cookie.name, cookie.domain, cookie.path
+            ));
+            row.set_text(&cookie.value);
+            row.set_show_apply_button(true);
+
+            let delete = gtk::Button::from_icon_name("user-trash-symbolic");
+            delete.set_tooltip_text(Some(&gettext("Delete cookie")));
+            delete.set_valign(gtk::Align::Center);
+            delete.add_css_class("flat");
+            row.add_suffix(&delete);
+
+            let edited = cookie.clone();
+            row.connect_apply(glib::clone!(@weak self as dialog => move |row| {
+                let cookie = Cookie {
+                    value: row.text().to_string(),
+                    ..edited.clone()
+                };
+                if let Some(window) = dialog.window.upgrade() {
+                    window.cookie_jar().lock().unwrap().store(cookie, unix_now());
+                }
+                dialog.save();
+            }));
+            delete.connect_clicked(glib::clone!(@weak self as dialog => move |_| {
+                if let Some(window) = dialog.window.upgrade() {
+                    window.cookie_jar().lock().unwrap().remove(&cookie);
+                }
+                dialog.save();
+                dialog.refresh();
+            }));
+            row
+        }
+
+        fn save(&self) {
+            let Some(window) = self.window.upgrade() else {
+                return;
+            };
+            glib::spawn_future_local(async move {
+                window.save_cookies().await;
+            });
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct CookieDialog(ObjectSubclass<imp::CookieDialog>)
+        @extends gtk::Widget, adw::Dialog, adw::PreferencesDialog,
+        @implements gtk::Accessible, gtk::Buildable;
+}
+
+impl CookieDialog {
+    /// Creates a dialog that manages the cookie jar of the given window.
+    pub fn new(window: &CarteroWindow) -> Self {
+        let dialog: Self = Object::builder().build();
+        dialog.imp().window.set(Some(window));
+        dialog.imp().refresh();
+        dialog
+    }
+}
@@ -23,24 +23,31 @@ use crate::{entities::EndpointData, error::CarteroError};
 mod imp {
     use std::cell::RefCell;
     use std::sync::{Arc, Mutex};
-    use std::time::Instant;
+    use std::time::SystemTime;
 
     use adw::subclass::breakpoint_bin::BreakpointBinImpl;
     use glib::subclass::InitializingObject;
     use glib::Properties;
     use gtk::subclass::prelude::*;
-    use gtk::{prelude::*, CompositeTemplate};
-    use isahc::RequestExt;
+    use gtk::{gio, prelude::*, CompositeTemplate};
     use url::Url;
 
     use crate::app::CarteroApplication;
-    use crate::client::{BoundRequest, RequestError};
-    use crate::entities::{EndpointData, KeyValue, RequestExportType};
+    use crate::client::{
+        authorize, run_assertions, run_captures, run_post_response, run_pre_request,
+        store_captures, BoundRequest, CapturedValue, RequestError, ScriptStage,
+    };
+    use crate::entities::{
+        EndpointData, KeyValue, KeyValueTable, ProxySettings, RequestExportType, ResponseData,
+    };
     use crate::error::CarteroError;
+    use crate::import::har::export_har;
     use crate::objects::KeyValueItem;
     use crate::widgets::{
-        ExportTab, ExportType, ItemPane, KeyValuePane, MethodDropdown, PayloadTab, ResponsePanel,
+        AssertionsPane, AuthPane, CapturesPane, ExportTab, ExportType, ItemPane, KeyValuePane,
+        MethodDropdown, PayloadTab, RequestSettingsPane, ResponsePanel, ScriptsPane,
     };
+    use crate::win::CarteroWindow;
 
     #[derive(CompositeTemplate, Properties, Default)]
     #[template(resource = "/es/danirod/Cartero/endpoint_pane.ui")]
@@ -49,6 +56,9 @@ mod imp {
         #[template_child(id = "send")]
         pub send_button: TemplateChild<gtk::Button>,
 
+        #[template_child(id = "cancel")]
+        pub cancel_button: TemplateChild<gtk::Button>,
+
         #[template_child]
         pub parameter_pane: TemplateChild<KeyValuePane>,
 
@@ -67,6 +77,21 @@ mod imp {
         #[template_child]
         pub payload_pane: TemplateChild<PayloadTab>,
 
+        #[template_child]
+        pub auth_pane: TemplateChild<AuthPane>,
+
+        #[template_child]
+        pub captures_pane: TemplateChild<CapturesPane>,
+
+        #[template_child]
+        pub assertions_pane: TemplateChild<AssertionsPane>,
+
+        #[template_child]
+        pub scripts_pane: TemplateChild<ScriptsPane>,
+
+        #[template_child]
+        pub settings_pane: TemplateChild<RequestSettingsPane>,
+
         #[template_child]
         pub export_pane: TemplateChild<ExportTab>,
 
@@ -80,6 +105,12 @@ mod imp {
         pub item_pane: RefCell<Option<ItemPane>>,
 
         variable_changing: Arc<Mutex<bool>>,
+
+        /// Cancels the request in flight, if a request is being sent.
+        pub(super) cancellable: RefCell<Option<gio::Cancellable>>,
+
+        /// The last request that was sent, the response it got and when it was sent.
+        last_exchange: RefCell<Option<(BoundRequest, ResponseData, SystemTime)>>,
     }
 
     #[glib::object_subclass]
@@ -138,7 +169,7 @@ mod imp {
             // update export pane data when user selects another option in the combo box.
             self.export_pane
                 .connect_changed(glib::clone!(@weak self as window => move |_| {
-                    if window.export_pane.imp().export_type() == ExportType::Curl {
+                    if window.export_pane.imp().export_type() != ExportType::NONE {
                         if let Ok(data) = window.extract_endpoint() {
                             window.export_pane_load_endpoint_data(&data);
                         }
@@ -146,6 +177,7 @@ mod imp {
                 }));
 
             self.configure_export_pane_bindings();
+            self.init_curl_paste();
         }
     }
 
@@ -214,6 +246,50 @@ mod imp {
                 .connect_changed(glib::clone!(@weak self as pane => move |_| pane.mark_dirty()));
             self.variable_pane
                 .connect_changed(glib::clone!(@weak self as pane => move |_| pane.mark_dirty()));
+            self.auth_pane
+                .connect_changed(glib::clone!(@weak self as pane => move |_| pane.mark_dirty()));
+            self.captures_pane
+                .connect_changed(glib::clone!(@weak self as pane => move |_| pane.mark_dirty()));
+            self.assertions_pane
+                .connect_changed(glib::clone!(@weak self as pane => move |_| pane.mark_dirty()));
+            self.scripts_pane
+                .connect_changed(glib::clone!(@weak self as pane => move |_| pane.mark_dirty()));
+            self.settings_pane
+                .connect_changed(glib::clone!(@weak self as pane => move |_| pane.mark_dirty()));
+        }
+
+        /// Turns a curl command pasted into the URL entry into a request.
+        ///
+        /// If the command cannot be understood, the text is pasted as usual.
+        fn init_curl_paste(&self) {
+            let Some(delegate) = self.request_url.delegate() else {
+                return;
+            };
+            delegate.connect_insert_text(
+                glib::clone!(@weak self as pane => move |editable, text, _| {
+                    let text = text.trim_start();
+                    if !text.starts_with("curl ") && !text.starts_with("curl\t") {
+                        return;
+                    }
+                    match crate::import::curl::parse_curl(text) {
+                        Ok(mut endpoint) => {
+                            editable.stop_signal_emission_by_name("insert-text");
+                            if let Ok(current) = pane.extract_endpoint() {
+                                endpoint.variables = current.variables;
+                            }
+                            glib::idle_add_local_once(glib::clone!(@weak pane => move || {
+                                pane.assign_request(&endpoint);
+                            }));
+                        }
+                        Err(e) => {
+                            let obj = pane.obj();
+                            if let Some(window) = obj.root().and_downcast::<CarteroWindow>() {
+                                window.toast_error(e.into());
+                            }
+                        }
+                    }
+                }),
+            );
         }
 
         fn init_settings(&self) {
@@ -253,6 +329,55 @@ mod imp {
             let _ = self.obj().activate_action("win.request", None);
         }
 
+        #[template_callback]
+        fn on_cancel_clicked(&self) {
+            if let Some(cancellable) = &*self.cancellable.borrow() {
+                cancellable.cancel();
+            }
+        }
+
+        /// Swaps the Send button for the Cancel button while a request is in flight,
+        /// and prevents the request from being edited in the meantime.
+        fn set_busy(&self, busy: bool) {
+            self.send_button.set_visible(!busy);
+            self.cancel_button.set_visible(busy);
+            self.request_method.set_sensitive(!busy);
+            self.request_url.set_sensitive(!busy);
+            self.paned.set_sensitive(!busy);
+        }
+
+        /// Merges the settings that this request inherits from the window into the given
+        /// endpoint: the variables captured in the session and the ones of the active
+        /// environment, and the headers and variables of the collection that holds the
+        /// request file. Relative paths of uploaded files are resolved against the
+        /// directory of the request file.
+        fn apply_inherited(&self, endpoint: EndpointData) -> EndpointData {
+            let obj = self.obj();
+            let Some(window) = obj.root().and_downcast::<CarteroWindow>() else {
+                return endpoint;
+            };
+
+            let endpoint = endpoint.with_session(&window.session_variables());
+            let endpoint = match window.active_environment() {
+                Some(environment) => endpoint.with_environment(&environment),
+                None => endpoint,
+            };
+            let file = obj.item_pane().and_then(|pane| pane.file());
+            let endpoint = match file
+                .as_ref()
+                .and_then(|f| f.parent())
+                .and_then(|d| d.path())
+            {
+                Some(dir) => endpoint.with_base_dir(&dir),
+                None => endpoint,
+            };
+            let collection = file.and_then(|file| window.collection_for(&file));
+            match collection {
+                Some(collection) => endpoint.with_collection(&collection),
+                None => endpoint,
+            }
+        }
+
         /// Loads data for the export pane module by using an `EndpointData` structure.
         fn export_pane_load_endpoint_data(&self, endpoint: &EndpointData) {
             let req_export_type = self.export_pane.request_export_type();
@@ -261,14 +386,15 @@ mod imp {
                 return;
             }
 
-            if let RequestExportType::Curl(_) = req_export_type {
+            if let RequestExportType::Code(_) = req_export_type {
+                let endpoint = self.apply_inherited(endpoint.clone());
                 self.export_pane
-                    .set_request_export_type(&RequestExportType::Curl(endpoint.clone()));
+                    .set_request_export_type(&RequestExportType::Code(Box::new(endpoint)));
             }
         }
 
         /// Retrieves `EndpointData` and builds a new state for the export request module.
-        fn update_export_pane(&self) {
+        pub(super) fn update_export_pane(&self) {
             if let Ok(data) = self.extract_endpoint() {
                 self.export_pane_load_endpoint_data(&data);
             }
@@ -316,6 +442,13 @@ mod imp {
             self.header_pane.set_entries(&headers);
             self.variable_pane.set_entries(&variables);
             self.payload_pane.set_payload(&endpoint.body);
+            self.auth_pane.set_auth(&endpoint.auth);
+            self.captures_pane.set_captures(&endpoint.captures);
+            self.assertions_pane.set_assertions(&endpoint.assertions);
+            self.scripts_pane.set_scripts(&endpoint.scripts);
+            self.settings_pane.set_options(&endpoint.options);
+            self.settings_pane.set_tls(&endpoint.tls);
+            self.settings_pane.set_proxy(&endpoint.proxy);
             self.export_pane_load_endpoint_data(endpoint);
         }
 
@@ -347,30 +480,183 @@ mod imp {
                 .collect();
 
             let body = self.payload_pane.payload();
+            let options = self.settings_pane.options();
+            let tls = self.settings_pane.tls();
+            let proxy = self.settings_pane.proxy();
+            let auth = self.auth_pane.auth();
+            let captures = self.captures_pane.captures();
+            let assertions = self.assertions_pane.assertions();
+            let scripts = self.scripts_pane.scripts();
             Ok(EndpointData {
                 url,
                 method,
                 headers,
                 variables,
                 body,
+                options,
+                tls,
+                proxy,
+                auth,
+                captures,
+                assertions,
+                scripts,
             })
         }
 
         /// Executes an HTTP request based on the current contents of the pane.
+        ///
+        /// The request uses the timeout and the proxy picked in the settings, unless it
+        /// has its own.
+        /// It can be stopped with the Cancel button, which gives `RequestError::Cancelled`.
         pub(super) async fn perform_request(&self) -> Result<(), CarteroError> {
-            let request = self.extract_endpoint()?;
-            let request = BoundRequest::try_from(request)?;
-            let request_obj = isahc::Request::try_from(request)?;
-
-            let start = Instant::now();
-            let mut response_obj = request_obj
-                .send_async()
-                .await
-                .map_err(RequestError::NetworkError)?;
-            let response = crate::client::extract_isahc_response(&mut response_obj, &start).await?;
+            let settings = CarteroApplication::get().settings();
+            let timeout = settings.get::<String>("request-timeout");
+            let timeout = timeout.parse::<u64>().unwrap_or_default();
+            let proxy = ProxySettings {
+                url: settings.get("proxy-url"),
+                username: settings.get("proxy-username"),
+                password: settings.get("proxy-password"),
+                no_proxy: settings.get("no-proxy"),
+            };
+            let mut endpoint = self
+                .apply_inherited(self.extract_endpoint()?)
+                .with_default_timeout(timeout)
+                .with_default_proxy(&proxy);
+            let pre_request = run_pre_request(&mut endpoint);
+            self.scripts_pane.append_console(&pre_request.console);
+            if let Some(e) = pre_request.error {
+                return Err(CarteroError::Script(ScriptStage::PreRequest, e));
+            }
+            self.store_values(&pre_request.variables);
+            let mut scripted = endpoint.clone();
+            let mut request = BoundRequest::try_from(endpoint)?;
+            let window = self.obj().root().and_downcast::<CarteroWindow>();
+            request.cookie_jar = window.as_ref().map(|window| window.cookie_jar());
+            let tokens = window
+                .as_ref()
+                .map(|window| window.token_cache())
+                .unwrap_or_default();
+            let open_browser = {
+                let window = window.clone();
+                move |url: &str| match &window {
+                    Some(window) => window.open_browser(url),
+                    None => Ok(()),
+                }
+            };
+
+            let cancellable = gio::Cancellable::new();
+            self.cancellable.replace(Some(cancellable.clone()));
+            self.set_busy(true);
+            let started = SystemTime::now();
+            // Logging in with OAuth 2.0 can take a while, so it can be cancelled too.
+            let future = async move {
+                authorize(&mut request, &tokens, &open_browser).await?;
+                let response = crate::client::send_request(request.clone()).await?;
+                Ok::<_, RequestError>((request, response))
+            };
+            let outcome = gio::CancellableFuture::new(future, cancellable).await;
+            self.set_busy(false);
+            self.cancellable.replace(None);
+
+            let (request, response) = outcome.map_err(|_| RequestError::Cancelled)??;
+            if let Some(window) = window.filter(|_| !response.cookies.is_empty()) {
+                window.save_cookies().await;
+            }
             self.response.assign_from_response(&response);
+            let assertions = self.assertions_pane.assertions();
+            self.response
+                .set_assertion_results(&run_assertions(&assertions, &response));
+            for value in self.apply_captures(&response) {
+                let variable = KeyValue::from((value.variable.as_str(), value.value.as_str()));
+                scripted.variables.push(variable);
+            }
+            self.apply_post_response(&scripted, &response);
+            self.last_exchange
+                .replace(Some((request, response, started)));
             Ok(())
         }
+
+        /// Keeps the values captured from the response in their variables, and gives
+        /// them back. The captures that cannot take their value are reported, but do not
+        /// stop the others.
+        fn apply_captures(&self, response: &ResponseData) -> Vec<CapturedValue> {
+            let captures = self.captures_pane.captures();
+            let window = self.obj().root().and_downcast::<CarteroWindow>();
+            let mut values = Vec::new();
+            for outcome in run_captures(&captures, response) {
+                match outcome {
+                    Ok(value) => values.push(value),
+                    Err((variable, e)) => {
+                        if let Some(window) = &window {
+                            window.toast_error(CarteroError::Capture(variable, e));
+                        }
+                    }
+                }
+            }
+            self.store_values(&values);
+            values
+        }
+
+        /// Runs the post-response script, and keeps the variables that it set.
+        fn apply_post_response(&self, endpoint: &EndpointData, response: &ResponseData) {
+            let post_response = run_post_response(endpoint, response);
+            self.scripts_pane.append_console(&post_response.console);
+            match post_response.error {
+                Some(e) => {
+                    if let Some(window) = self.obj().root().and_downcast::<CarteroWindow>() {
+                        window.toast_error(CarteroError::Script(ScriptStage::PostResponse, e));
+                    }
+                }
+                None => self.store_values(&post_response.variables),
+            }
+        }
+
+        /// Keeps values set by the captures or by the scripts in their scopes. If the
+        /// variables of the request change, the request is marked as modified.
+        fn store_values(&self, values: &[CapturedValue]) {
+            if values.is_empty() {
+                return;
+            }
+
+            let window = self.obj().root().and_downcast::<CarteroWindow>();
+            let Ok(endpoint) = self.extract_endpoint() else {
+                return;
+            };
+            let mut variables = endpoint.variables.clone();
+            match &window {
+                Some(window) => window.store_captures(values, &mut variables),
+                None => {
+                    let mut session = KeyValueTable::default();
+                    store_captures(values, &mut variables, None, &mut session);
+                }
+            }
+            if variables != endpoint.variables {
+                let items: Vec<KeyValueItem> = variables
+                    .iter()
+                    .map(|item| KeyValueItem::from(item.clone()))
+                    .collect();
+                self.variable_pane.set_entries(&items);
+                self.mark_dirty();
+                self.update_export_pane();
+            }
+        }
+
+        /// Exports the last request and its response as a HAR document.
+        ///
+        /// If no request has been sent yet, the current request is exported alone.
+        pub(super) fn export_har(&self) -> Result<String, CarteroError> {
+            let document = match &*self.last_exchange.borrow() {
+                Some((request, response, started)) => {
+                    export_har(request, Some(response), *started)?
+                }
+                None => {
+                    let request = self.apply_inherited(self.extract_endpoint()?);
+                    let request = BoundRequest::try_from(request)?;
+                    export_har(&request, None, SystemTime::now())?
+                }
+            };
+            Ok(document)
+        }
     }
 }
 
@@ -399,6 +685,21 @@ impl EndpointPane {
         imp.extract_endpoint()
     }
 
+    /// Regenerates the contents of the export pane.
+    ///
+    /// Used when something outside of the pane changes the exported request,
+    /// such as picking another environment.
+    pub fn refresh_export(&self) {
+        let imp = self.imp();
+        imp.update_export_pane();
+    }
+
+    /// Exports the last request and its response as a HAR document.
+    pub fn export_har(&self) -> Result<String, CarteroError> {
+        let imp = self.imp();
+        imp.export_har()
+    }
+
     /// Executes an HTTP request based on the current contents of the pane.
     ///
     /// TODO: Should actually the EndpointPane do the requests? This method
@@ -406,6 +707,9 @@ impl EndpointPane {
     /// since the EndpointPane would be probably bound to an Endpoint object.
     pub async fn perform_request(&self) -> Result<(), CarteroError> {
         let imp = self.imp();
+        if imp.cancellable.borrow().is_some() {
+            return Ok(());
+        }
         imp.response.set_spinning(true);
         let outcome = imp.perform_request().await;
         imp.response.set_spinning(false);
@@ -17,7 +17,7 @@
 
 use glib::{object::ObjectExt, subclass::types::ObjectSubclassIsExt};
 
-use crate::entities::{EndpointData, RequestExportType};
+use crate::entities::RequestExportType;
 
 use super::{BaseExportPaneExt, CodeExportService};
 
@@ -53,7 +53,7 @@ mod imp {
         #[template_child]
         copy_button: TemplateChild<Button>,
 
-        #[property(get = Self::format, set = Self::set_format, builder(ExportType::default()))]
+        #[property(get = Self::format, set = Self::set_format)]
         _format: RefCell<ExportType>,
     }
 
@@ -129,24 +129,16 @@ mod imp {
         }
 
         fn format(&self) -> ExportType {
-            // Maybe in a future we're gonna add axios and others here, not sure
-            // if that thing should be in another widget or this could could be
-            // reutilized for that purpose, since we're just gonna use anyways a
-            // textbox, [axios export issue](https://github.com/danirod/cartero/issues/64)
-            ExportType::Curl
+            *self._format.borrow()
         }
 
         fn set_format(&self, format: ExportType) {
             let manager = LanguageManager::default();
-
-            // TODO: I'm not really sure what the language id should be here,
-            // already tried bash shellscript sh shell etc.
-            let language = match format {
-                ExportType::Curl => manager.language("shellscript"),
-                _ => None,
-            };
-
+            let language = format
+                .generator()
+                .and_then(|generator| manager.language(generator.language()));
             self.buffer.set_language(language.as_ref());
+            self._format.replace(format);
         }
 
         fn init_settings(&self) {
@@ -256,18 +248,22 @@ impl BaseExportPaneExt for CodeExportPane {
         // gonna generate curl output or idk, like reparse the generated content
         // to extract its data and regenerate a new endpoint data.
         match self.format() {
-            super::ExportType::Curl => RequestExportType::Curl(EndpointData::default()),
-            _ => RequestExportType::None,
+            super::ExportType::NONE => RequestExportType::None,
+            _ => RequestExportType::Code(Box::default()),
         }
     }
 
     fn set_request_export_type(&self, req_export_type: &RequestExportType) {
-        if let RequestExportType::Curl(data) = req_export_type {
-            let service = CodeExportService::new(data.clone());
+        let Some(generator) = self.format().generator() else {
+            return;
+        };
+
+        if let RequestExportType::Code(data) = req_export_type {
+            let service = CodeExportService::new(data.as_ref().clone());
             let imp = self.imp();
 
-            if let Ok(command) = service.generate() {
-                imp.set_buffer_content(command.as_bytes());
+            if let Ok(code) = service.generate_with(generator) {
+                imp.set_buffer_content(code.as_bytes());
             }
         }
     }
@@ -15,11 +15,10 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use serde_json::{Error, Value};
-
 use crate::client::BoundRequest;
-use crate::entities::{EndpointData, RequestPayload};
+use crate::entities::EndpointData;
 use crate::error::CarteroError;
+use crate::export::{curl::Curl, CodeGenerator, ShellDialect};
 
 pub struct CodeExportService {
     endpoint_data: EndpointData,
@@ -30,71 +29,102 @@ impl CodeExportService {
         Self { endpoint_data }
     }
 
+    /// Generates the curl command for the endpoint.
     pub fn generate(&self) -> Result<String, CarteroError> {
-        let bound_request = BoundRequest::try_from(self.endpoint_data.clone())?;
-        let mut command = "curl".to_string();
-
-        command.push_str(&{
-            let method_str: String = bound_request.method.into();
-            format!(" -X {} '{}'", method_str, bound_request.url)
-        });
-
-        if !bound_request.headers.is_empty() {
-            let size = bound_request.headers.len();
-            let mut keys: Vec<&String> = bound_request.headers.keys().collect();
-            keys.sort();
-
-            command.push_str(" \\\n");
-
-            for (i, key) in keys.iter().enumerate() {
-                let val = bound_request.headers.get(*key).unwrap();
-
-                command.push_str(&{
-                    let mut initial = format!("  -H '{key}: {val}'");
-
-                    if i < size - 1 {
-                        initial.push_str(" \\\n");
-                    }
+        self.generate_with(&Curl(ShellDialect::Posix))
+    }
 
-                    initial
-                });
-            }
-        }
+    /// Generates the code for the endpoint using the given generator.
+    pub fn generate_with(&self, generator: &dyn CodeGenerator) -> Result<String, CarteroError> {
+        let bound_request = BoundRequest::try_from(self.endpoint_data.clone())?;
+        Ok(generator.generate(&bound_request))
+    }
+}
 
-        if let RequestPayload::Urlencoded(_) = &self.endpoint_data.body {
-            if let Some(bd) = bound_request.body {
-                let str = String::from_utf8_lossy(&bd).to_string();
-                command.push_str(&format!(" \\\n  -d '{str}'"));
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use crate::entities::{
+        EndpointData, KeyValueTable, RawEncoding, RequestAuth, RequestMethod, RequestPayload,
+    };
+    use crate::import::curl::parse_curl;
 
-        if let RequestPayload::Raw {
-            encoding: _,
-            content,
-        } = &self.endpoint_data.body
-        {
-            command.push_str(&'fmt: {
-                let body = String::from_utf8_lossy(content).to_string();
-                let value: Result<Value, Error> = serde_json::from_str(body.as_ref());
+    use super::CodeExportService;
 
-                if value.is_err() {
-                    break 'fmt String::new();
-                }
+    fn assert_round_trip(endpoint: EndpointData) {
+        let command = CodeExportService::new(endpoint.clone()).generate().unwrap();
+        assert_eq!(parse_curl(&command).unwrap(), endpoint);
+    }
 
-                let value = value.unwrap();
-                let trimmed_json_str = serde_json::to_string(&value);
+    #[test]
+    fn test_curl_export_keeps_repeated_headers_in_order() {
+        let endpoint = EndpointData {
+            url: "https://www.example.com/".into(),
+            method: RequestMethod::Get,
+            headers: KeyValueTable::new(&[
+                ("Cookie", "b=2").into(),
+                ("Accept", "text/html").into(),
+                ("Cookie", "a=1").into(),
+            ]),
+            ..Default::default()
+        };
+
+        let command = CodeExportService::new(endpoint).generate().unwrap();
+        assert_eq!(
+            command,
+            "curl -X GET 'https://www.example.com/' \\\n  -H 'Cookie: b=2' \\\n  -H 'Accept: text/html' \\\n  -H 'Cookie: a=1'"
+        );
+    }
 
-                if trimmed_json_str.is_err() {
-                    break 'fmt String::new();
-                }
+    #[test]
+    fn test_curl_export_round_trip_with_headers() {
+        assert_round_trip(EndpointData {
+            url: "https://www.example.com/users?page=2".into(),
+            method: RequestMethod::Delete,
+            headers: KeyValueTable::new(&[
+                ("Accept", "application/json").into(),
+                ("Authorization", "Bearer abc").into(),
+            ]),
+            ..Default::default()
+        });
+    }
 
-                let trimmed_json_str = trimmed_json_str.unwrap();
-                let trimmed_json_str = trimmed_json_str.replace("'", "\\\\'");
+    #[test]
+    fn test_curl_export_round_trip_with_urlencoded_body() {
+        assert_round_trip(EndpointData {
+            url: "https://www.example.com/login".into(),
+            method: RequestMethod::Post,
+            body: RequestPayload::Urlencoded(KeyValueTable::new(&[
+                ("user", "john").into(),
+                ("password", "a b&c").into(),
+            ])),
+            ..Default::default()
+        });
+    }
 
-                format!(" \\\n  -d '{}'", trimmed_json_str)
-            });
-        }
+    #[test]
+    fn test_curl_export_round_trip_with_json_body() {
+        assert_round_trip(EndpointData {
+            url: "https://www.example.com/users".into(),
+            method: RequestMethod::Put,
+            headers: KeyValueTable::new(&[("Accept", "application/json").into()]),
+            body: RequestPayload::Raw {
+                encoding: RawEncoding::Json,
+                content: br#"{"name":"john","tags":[1,2]}"#.to_vec(),
+            },
+            ..Default::default()
+        });
+    }
 
-        Ok(command)
+    #[test]
+    fn test_curl_export_round_trip_with_digest_auth() {
+        assert_round_trip(EndpointData {
+            url: "https://www.example.com/private".into(),
+            method: RequestMethod::Get,
+            auth: RequestAuth::Digest {
+                username: "john".into(),
+                password: "it's a secret".into(),
+            },
+            ..Default::default()
+        });
     }
 }
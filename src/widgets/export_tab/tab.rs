@@ -20,22 +20,45 @@ use std::sync::OnceLock;
 use glib::object::{CastNone, ObjectExt};
 use gtk::subclass::prelude::*;
 
+use gettextrs::gettext;
+
 use crate::entities::RequestExportType;
+use crate::export::{self, CodeGenerator};
 
 use super::{BaseExportPaneExt, CodeExportPane};
 
-#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, glib::Enum)]
-#[enum_type(name = "CarteroExportType")]
-pub enum ExportType {
-    #[default]
-    None,
-    Curl,
-}
+/// An entry of the export combo: either nothing is exported, or the code
+/// generator at the given position of `export::generators()`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, glib::Boxed)]
+#[boxed_type(name = "CarteroExportType")]
+pub struct ExportType(Option<usize>);
 
 impl ExportType {
+    pub const NONE: ExportType = ExportType(None);
+
+    /// The generator picked when code export is enabled: the first one, curl.
+    pub const DEFAULT_CODE: ExportType = ExportType(Some(0));
+
     pub fn types() -> &'static [ExportType] {
         static TYPES: OnceLock<Vec<ExportType>> = OnceLock::new();
-        TYPES.get_or_init(|| vec![ExportType::None, ExportType::Curl])
+        TYPES.get_or_init(|| {
+            let generators = (0..export::generators().len()).map(|n| ExportType(Some(n)));
+            std::iter::once(ExportType::NONE)
+                .chain(generators)
+                .collect()
+        })
+    }
+
+    /// The code generator for this export type, or None if nothing is exported.
+    pub fn generator(&self) -> Option<&'static dyn CodeGenerator> {
+        self.0.map(|n| export::generators()[n])
+    }
+
+    pub fn label(&self) -> String {
+        match self.generator() {
+            Some(generator) => generator.name().to_string(),
+            None => gettext("(none)"),
+        }
     }
 }
 
@@ -49,8 +72,8 @@ mod imp {
     use glib::subclass::Signal;
     use glib::{subclass::InitializingObject, Properties};
     use gtk::template_callbacks;
-    use gtk::Separator;
     use gtk::{CompositeTemplate, Stack};
+    use gtk::{Separator, StringList};
 
     use crate::widgets::BaseExportPane;
     use crate::widgets::CodeExportPane;
@@ -73,7 +96,7 @@ mod imp {
         #[template_child]
         code: TemplateChild<CodeExportPane>,
 
-        #[property(get = Self::export_type, set = Self::set_export_type, builder(ExportType::default()))]
+        #[property(get = Self::export_type, set = Self::set_export_type)]
         _payload_type: RefCell<ExportType>,
     }
 
@@ -97,6 +120,10 @@ mod imp {
     impl ObjectImpl for ExportTab {
         fn constructed(&self) {
             self.parent_constructed();
+
+            let labels: Vec<String> = ExportType::types().iter().map(|t| t.label()).collect();
+            let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+            self.combo.set_model(Some(&StringList::new(&labels)));
             self.on_selection_changed();
 
             self.combo
@@ -126,12 +153,12 @@ mod imp {
         fn on_selection_changed(&self) {
             let export_type = self.export_type();
             let tab = match export_type {
-                ExportType::None => "none",
-                ExportType::Curl => "code",
+                ExportType::NONE => "none",
+                _ => "code",
             };
 
             self.stack.set_visible_child_name(tab);
-            self.sep.set_visible(export_type != ExportType::None);
+            self.sep.set_visible(export_type != ExportType::NONE);
             self.code.set_format(export_type);
         }
 
@@ -147,8 +174,8 @@ mod imp {
 
         pub(super) fn get_active_widget(&self) -> Option<BaseExportPane> {
             match self.export_type() {
-                ExportType::None => None,
-                ExportType::Curl => Some(self.code.upcast_ref::<BaseExportPane>().clone()),
+                ExportType::NONE => None,
+                _ => Some(self.code.upcast_ref::<BaseExportPane>().clone()),
             }
         }
     }
@@ -174,16 +201,21 @@ impl ExportTab {
     pub fn set_request_export_type(&self, req_export_type: &RequestExportType) {
         let imp = self.imp();
 
-        imp.set_export_type(match req_export_type {
-            RequestExportType::None => ExportType::None,
-            RequestExportType::Curl(_) => ExportType::Curl,
-        });
+        // Code can be exported to many languages, so the selected one is kept.
+        match req_export_type {
+            RequestExportType::None => imp.set_export_type(ExportType::NONE),
+            RequestExportType::Code(_) => {
+                if imp.export_type() == ExportType::NONE {
+                    imp.set_export_type(ExportType::DEFAULT_CODE);
+                }
+            }
+        }
 
         let widget = imp.get_active_widget();
 
         match imp.export_type() {
-            ExportType::None => {}
-            ExportType::Curl => {
+            ExportType::NONE => {}
+            _ => {
                 let widget = widget.and_downcast::<CodeExportPane>().unwrap();
                 widget.set_request_export_type(req_export_type);
             }
@@ -195,8 +227,8 @@ impl ExportTab {
         let imp = self.imp();
 
         match imp.export_type() {
-            ExportType::None => RequestExportType::None,
-            ExportType::Curl => {
+            ExportType::NONE => RequestExportType::None,
+            _ => {
                 let widget = widget.and_downcast::<CodeExportPane>().unwrap();
                 widget.request_export_type()
             }
@@ -2,7 +2,7 @@ use gettextrs::gettext;
 use glib::{prelude::Cast, types::StaticType};
 use gtk::{
     gio::{self, ListStore},
-    prelude::{FileExt, ListModelExtManual, SettingsExtManual},
+    prelude::{FileExt, FileExtManual, ListModelExtManual, SettingsExtManual},
     DialogError, FileDialog, FileFilter,
 };
 use std::path::PathBuf;
@@ -22,6 +22,58 @@ fn get_cartero_file_filter() -> FileFilter {
     filter
 }
 
+fn get_environment_file_filter() -> FileFilter {
+    let filter = FileFilter::new();
+    filter.add_pattern(&format!("*{}", crate::file::ENVIRONMENT_SUFFIX));
+    filter.set_name(Some(&gettext("Environment (.env.toml)")));
+    filter
+}
+
+fn get_har_file_filter() -> FileFilter {
+    let filter = FileFilter::new();
+    filter.add_pattern("*.har");
+    filter.set_name(Some(&gettext("HTTP Archive (.har)")));
+    filter
+}
+
+fn get_postman_file_filter() -> FileFilter {
+    let filter = FileFilter::new();
+    filter.add_pattern("*.json");
+    filter.set_name(Some(&gettext("Postman collection (.json)")));
+    filter
+}
+
+fn get_openapi_file_filter() -> FileFilter {
+    let filter = FileFilter::new();
+    filter.add_pattern("*.yaml");
+    filter.add_pattern("*.yml");
+    filter.add_pattern("*.json");
+    filter.set_name(Some(&gettext("OpenAPI document (.yaml, .json)")));
+    filter
+}
+
+fn get_data_file_filter() -> FileFilter {
+    let filter = FileFilter::new();
+    filter.add_pattern("*.csv");
+    filter.add_pattern("*.json");
+    filter.set_name(Some(&gettext("Data file (.csv, .json)")));
+    filter
+}
+
+fn get_junit_file_filter() -> FileFilter {
+    let filter = FileFilter::new();
+    filter.add_pattern("*.xml");
+    filter.set_name(Some(&gettext("JUnit report (.xml)")));
+    filter
+}
+
+fn get_json_report_file_filter() -> FileFilter {
+    let filter = FileFilter::new();
+    filter.add_pattern("*.json");
+    filter.set_name(Some(&gettext("JSON report (.json)")));
+    filter
+}
+
 // Allowing dead_code here because I am going to use this later.
 #[allow(dead_code)]
 pub async fn open_file(win: &CarteroWindow) -> Result<gio::File, CarteroError> {
@@ -68,6 +120,231 @@ pub async fn open_file(win: &CarteroWindow) -> Result<gio::File, CarteroError> {
     Ok(file)
 }
 
+pub async fn open_environment_file(win: &CarteroWindow) -> Result<gio::File, CarteroError> {
+    let filters = ListStore::with_type(FileFilter::static_type());
+    let environment = get_environment_file_filter();
+    filters.append(&environment);
+
+    let dialog = FileDialog::builder()
+        .accept_label(gettext("Open"))
+        .title(gettext("Open environment"))
+        .filters(&filters)
+        .default_filter(&environment)
+        .modal(true)
+        .build();
+
+    let app = CarteroApplication::get();
+    let settings = app.settings();
+    if let Some(dir) = settings.get::<Option<String>>("last-open-dir") {
+        let path = PathBuf::from(&dir);
+        let file = gtk::gio::File::for_path(path);
+        dialog.set_initial_folder(Some(&file));
+    }
+
+    dialog.open_future(Some(win)).await.map_err(|e| {
+        if let Some(file_error) = e.kind::<DialogError>() {
+            match file_error {
+                DialogError::Dismissed => CarteroError::NoFilePicked,
+                _ => CarteroError::FileDialogError,
+            }
+        } else {
+            CarteroError::FileDialogError
+        }
+    })
+}
+
+/// Asks for a file exported by another application, in order to import it.
+async fn open_import_file(
+    win: &CarteroWindow,
+    title: &str,
+    filter: &FileFilter,
+) -> Result<gio::File, CarteroError> {
+    let filters = ListStore::with_type(FileFilter::static_type());
+    filters.append(filter);
+
+    let dialog = FileDialog::builder()
+        .accept_label(gettext("Import"))
+        .title(title)
+        .filters(&filters)
+        .default_filter(filter)
+        .modal(true)
+        .build();
+
+    let app = CarteroApplication::get();
+    let settings = app.settings();
+    if let Some(dir) = settings.get::<Option<String>>("last-open-dir") {
+        let path = PathBuf::from(&dir);
+        let file = gtk::gio::File::for_path(path);
+        dialog.set_initial_folder(Some(&file));
+    }
+
+    dialog.open_future(Some(win)).await.map_err(|e| {
+        if let Some(file_error) = e.kind::<DialogError>() {
+            match file_error {
+                DialogError::Dismissed => CarteroError::NoFilePicked,
+                _ => CarteroError::FileDialogError,
+            }
+        } else {
+            CarteroError::FileDialogError
+        }
+    })
+}
+
+pub async fn open_har_file(win: &CarteroWindow) -> Result<gio::File, CarteroError> {
+    open_import_file(win, &gettext("Import HAR file"), &get_har_file_filter()).await
+}
+
+pub async fn open_postman_file(win: &CarteroWindow) -> Result<gio::File, CarteroError> {
+    open_import_file(
+        win,
+        &gettext("Import Postman collection"),
+        &get_postman_file_filter(),
+    )
+    .await
+}
+
+pub async fn open_openapi_file(win: &CarteroWindow) -> Result<gio::File, CarteroError> {
+    open_import_file(
+        win,
+        &gettext("Import OpenAPI document"),
+        &get_openapi_file_filter(),
+    )
+    .await
+}
+
+/// Asks for any file, without filtering by type.
+async fn open_any_file(win: &CarteroWindow, title: &str) -> Result<gio::File, CarteroError> {
+    let dialog = FileDialog::builder()
+        .accept_label(gettext("Select"))
+        .title(title)
+        .modal(true)
+        .build();
+
+    let app = CarteroApplication::get();
+    let settings = app.settings();
+    if let Some(dir) = settings.get::<Option<String>>("last-open-dir") {
+        let path = PathBuf::from(&dir);
+        let file = gtk::gio::File::for_path(path);
+        dialog.set_initial_folder(Some(&file));
+    }
+
+    dialog.open_future(Some(win)).await.map_err(|e| {
+        if let Some(file_error) = e.kind::<DialogError>() {
+            match file_error {
+                DialogError::Dismissed => CarteroError::NoFilePicked,
+                _ => CarteroError::FileDialogError,
+            }
+        } else {
+            CarteroError::FileDialogError
+        }
+    })
+}
+
+/// Asks for the file to upload in a multipart form.
+pub async fn open_upload_file(win: &CarteroWindow) -> Result<gio::File, CarteroError> {
+    open_any_file(win, &gettext("Choose file to upload")).await
+}
+
+/// Asks for a client certificate, a private key or a CA bundle.
+pub async fn open_certificate_file(win: &CarteroWindow) -> Result<gio::File, CarteroError> {
+    open_any_file(win, &gettext("Choose certificate file")).await
+}
+
+/// Asks for the CSV or JSON file whose rows drive the iterations of a collection run.
+pub async fn open_data_file(win: &CarteroWindow) -> Result<gio::File, CarteroError> {
+    open_import_file(win, &gettext("Choose data file"), &get_data_file_filter()).await
+}
+
+/// Asks where to save a file exported in the format of the given filter.
+async fn save_export_file(
+    win: &CarteroWindow,
+    title: &str,
+    filter: &FileFilter,
+    initial_name: &str,
+) -> Result<gio::File, CarteroError> {
+    let filters = ListStore::with_type(FileFilter::static_type());
+    filters.append(filter);
+
+    let dialog = FileDialog::builder()
+        .accept_label(gettext("Export"))
+        .title(title)
+        .modal(true)
+        .filters(&filters)
+        .default_filter(filter)
+        .initial_name(initial_name)
+        .build();
+
+    let app = CarteroApplication::get();
+    let settings = app.settings();
+    if let Some(dir) = settings.get::<Option<String>>("last-save-dir") {
+        let path = PathBuf::from(&dir);
+        let file = gtk::gio::File::for_path(path);
+        dialog.set_initial_folder(Some(&file));
+    }
+
+    dialog.save_future(Some(win)).await.map_err(|e| {
+        if let Some(file_error) = e.kind::<DialogError>() {
+            match file_error {
+                DialogError::Dismissed => CarteroError::NoFilePicked,
+                _ => CarteroError::FileDialogError,
+            }
+        } else {
+            CarteroError::FileDialogError
+        }
+    })
+}
+
+pub async fn save_har_file(win: &CarteroWindow) -> Result<gio::File, CarteroError> {
+    let title = gettext("Export as HAR");
+    save_export_file(win, &title, &get_har_file_filter(), "request.har").await
+}
+
+pub async fn save_junit_report_file(win: &CarteroWindow) -> Result<gio::File, CarteroError> {
+    let title = gettext("Export JUnit report");
+    save_export_file(win, &title, &get_junit_file_filter(), "report.xml").await
+}
+
+pub async fn save_json_report_file(win: &CarteroWindow) -> Result<gio::File, CarteroError> {
+    let title = gettext("Export JSON report");
+    save_export_file(win, &title, &get_json_report_file_filter(), "report.json").await
+}
+
+/// Asks for a folder, such as the root of a collection.
+pub async fn open_folder(
+    win: &CarteroWindow,
+    title: &str,
+    initial_folder: Option<&gio::File>,
+) -> Result<gio::File, CarteroError> {
+    let dialog = FileDialog::builder()
+        .accept_label(gettext("Select"))
+        .title(title)
+        .modal(true)
+        .build();
+
+    let app = CarteroApplication::get();
+    let settings = app.settings();
+    match initial_folder {
+        Some(folder) => dialog.set_initial_folder(Some(folder)),
+        None => {
+            if let Some(dir) = settings.get::<Option<String>>("last-open-dir") {
+                let file = gtk::gio::File::for_path(PathBuf::from(&dir));
+                dialog.set_initial_folder(Some(&file));
+            }
+        }
+    }
+
+    dialog.select_folder_future(Some(win)).await.map_err(|e| {
+        if let Some(file_error) = e.kind::<DialogError>() {
+            match file_error {
+                DialogError::Dismissed => CarteroError::NoFilePicked,
+                _ => CarteroError::FileDialogError,
+            }
+        } else {
+            CarteroError::FileDialogError
+        }
+    })
+}
+
 pub async fn open_files(win: &CarteroWindow) -> Result<Vec<gio::File>, CarteroError> {
     let filters = ListStore::with_type(FileFilter::static_type());
     let cartero = get_cartero_file_filter();
@@ -168,3 +445,24 @@ pub async fn save_file(win: &CarteroWindow) -> Result<gio::File, CarteroError> {
 
     Ok(file)
 }
+
+pub async fn read_file(file: &gio::File) -> Result<String, CarteroError> {
+    file.load_contents_future()
+        .await
+        .map(|data| String::from_utf8_lossy(&data.0).to_string())
+        .map_err(|err| {
+            println!("{err:?}");
+            CarteroError::FileDialogError
+        })
+}
+
+pub async fn write_file(file: &gio::File, contents: &str) -> Result<(), CarteroError> {
+    file.replace_contents_future(contents.to_string(), None, true, gio::FileCreateFlags::NONE)
+        .await
+        .map_err(|result| {
+            let error = result.1;
+            println!("{error:?}");
+            CarteroError::FileDialogError
+        })?;
+    Ok(())
+}
@@ -0,0 +1,198 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use gtk::gio::{SimpleAction, SimpleActionGroup};
+use gtk::glib::Object;
+use gtk::prelude::*;
+
+use crate::entities::FormFile;
+use crate::error::CarteroError;
+use crate::win::CarteroWindow;
+
+use super::open_upload_file;
+
+mod imp {
+    use std::cell::RefCell;
+    use std::sync::OnceLock;
+
+    use glib::subclass::{InitializingObject, Signal};
+    use glib::Properties;
+    use gtk::subclass::prelude::*;
+    use gtk::{prelude::*, CompositeTemplate};
+
+    #[derive(CompositeTemplate, Default, Properties)]
+    #[properties(wrapper_type = super::FormFileRow)]
+    #[template(resource = "/es/danirod/Cartero/form_file_row.ui")]
+    pub struct FormFileRow {
+        #[property(get, set)]
+        active: RefCell<bool>,
+        #[property(get, set)]
+        field_name: RefCell<String>,
+        #[property(get, set)]
+        path: RefCell<String>,
+        #[property(get, set)]
+        filename: RefCell<String>,
+        #[property(get, set)]
+        content_type: RefCell<String>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for FormFileRow {
+        const NAME: &'static str = "CarteroFormFileRow";
+        type Type = super::FormFileRow;
+        type ParentType = gtk::ListBoxRow;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for FormFileRow {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            let obj = self.obj();
+            obj.setup_actions();
+            obj.setup_signals();
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder("delete").build(),
+                    Signal::builder("changed").build(),
+                ]
+            })
+        }
+    }
+
+    impl WidgetImpl for FormFileRow {}
+    impl ListBoxRowImpl for FormFileRow {}
+}
+
+glib::wrapper! {
+    pub struct FormFileRow(ObjectSubclass<imp::FormFileRow>)
+        @extends gtk::Widget, gtk::ListBoxRow,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Actionable;
+}
+
+impl Default for FormFileRow {
+    fn default() -> Self {
+        Object::builder().property("active", true).build()
+    }
+}
+
+impl From<&FormFile> for FormFileRow {
+    fn from(file: &FormFile) -> Self {
+        Object::builder()
+            .property("active", file.active)
+            .property("field-name", &file.name)
+            .property("path", &file.path)
+            .property("filename", file.filename.clone().unwrap_or_default())
+            .property(
+                "content-type",
+                file.content_type.clone().unwrap_or_default(),
+            )
+            .build()
+    }
+}
+
+impl FormFileRow {
+    /// Returns the file represented by this row. Blank optional fields are left unset.
+    pub fn form_file(&self) -> FormFile {
+        let optional = |value: String| (!value.is_empty()).then_some(value);
+        FormFile {
+            name: self.field_name(),
+            path: self.path(),
+            filename: optional(self.filename()),
+            content_type: optional(self.content_type()),
+            active: self.active(),
+        }
+    }
+
+    pub fn connect_delete<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "delete",
+            true,
+            glib::closure_local!(|ref row| {
+                f(row);
+            }),
+        )
+    }
+
+    pub fn connect_changed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "changed",
+            true,
+            glib::closure_local!(|ref row| {
+                f(row);
+            }),
+        )
+    }
+
+    fn setup_signals(&self) {
+        self.connect_active_notify(|row| {
+            if row.active() {
+                row.remove_css_class("inactive-header");
+            } else {
+                row.add_css_class("inactive-header");
+            }
+        });
+        for property in ["active", "field-name", "path", "filename", "content-type"] {
+            self.connect_notify_local(Some(property), |row, _| {
+                row.emit_by_name::<()>("changed", &[]);
+            });
+        }
+    }
+
+    fn setup_actions(&self) {
+        let ag = SimpleActionGroup::new();
+        self.insert_action_group("row", Some(&ag));
+
+        let choose = SimpleAction::new("choose", None);
+        choose.connect_activate(glib::clone!(@weak self as row => move |_, _| {
+            let Some(window) = row.root().and_downcast::<CarteroWindow>() else {
+                return;
+            };
+            glib::spawn_future_local(glib::clone!(@weak row, @weak window => async move {
+                match open_upload_file(&window).await {
+                    Ok(file) => {
+                        if let Some(path) = file.path() {
+                            row.set_path(path.to_string_lossy().to_string());
+                        }
+                    }
+                    Err(CarteroError::NoFilePicked) => {}
+                    Err(e) => window.toast_error(e),
+                }
+            }));
+        }));
+
+        let delete = SimpleAction::new("delete", None);
+        delete.connect_activate(glib::clone!(@weak self as row => move |_, _| {
+            row.emit_by_name::<()>("delete", &[]);
+        }));
+
+        ag.add_action(&choose);
+        ag.add_action(&delete);
+    }
+}
@@ -72,7 +72,7 @@ impl ItemPane {
         pane.set_child(Some(&child_pane));
 
         if let Some(path) = file {
-            let contents = crate::file::read_file(path).await?;
+            let contents = crate::widgets::read_file(path).await?;
             let endpoint = crate::file::parse_toml(&contents)?;
             child_pane.assign_endpoint(&endpoint);
         }
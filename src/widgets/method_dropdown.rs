@@ -27,7 +27,10 @@ mod imp {
         object::{Cast, ObjectExt},
         subclass::{InitializingObject, Signal},
     };
-    use gtk::{prelude::ListModelExt, CompositeTemplate, StringObject, TemplateChild};
+    use gtk::{
+        prelude::{EditableExt, ListModelExt, PopoverExt, WidgetExt},
+        CompositeTemplate, StringObject, TemplateChild,
+    };
 
     use crate::entities::RequestMethod;
 
@@ -39,6 +42,12 @@ mod imp {
 
         #[template_child]
         verbs_string_list: TemplateChild<gtk::StringList>,
+
+        #[template_child]
+        custom_popover: TemplateChild<gtk::Popover>,
+
+        #[template_child]
+        custom_entry: TemplateChild<gtk::Entry>,
     }
 
     #[glib::object_subclass]
@@ -75,6 +84,26 @@ mod imp {
             self.obj().emit_by_name::<()>("changed", &[]);
         }
 
+        #[template_callback]
+        fn on_custom_changed(&self) {
+            let text = self.custom_entry.text();
+            if text.is_empty() || RequestMethod::try_from(text.as_str()).is_ok() {
+                self.custom_entry.remove_css_class("error");
+            } else {
+                self.custom_entry.add_css_class("error");
+            }
+        }
+
+        #[template_callback]
+        fn on_custom_activated(&self) {
+            let text = self.custom_entry.text();
+            if let Ok(method) = RequestMethod::try_from(text.as_str()) {
+                self.set_request_method(method);
+                self.custom_entry.set_text("");
+                self.custom_popover.popdown();
+            }
+        }
+
         pub(super) fn request_method(&self) -> RequestMethod {
             let method = self
                 .dropdown
@@ -83,9 +112,8 @@ mod imp {
                 .downcast::<StringObject>()
                 .unwrap()
                 .string();
-            // Note: we should probably be safe from unwrapping here, since it would
-            // be impossible to have a method that is not an acceptable value without
-            // completely hacking and wrecking the user interface.
+            // Note: we should probably be safe from unwrapping here, since only
+            // token-valid methods are ever appended to the list of verbs.
             RequestMethod::try_from(method.as_str()).unwrap()
         }
 
@@ -100,8 +128,14 @@ mod imp {
                 }
                 false
             });
-            if let Some(pos) = target_position {
-                self.dropdown.set_selected(pos);
+            match target_position {
+                Some(pos) => self.dropdown.set_selected(pos),
+                None => {
+                    // Custom methods are kept in the list once seen, so that
+                    // they can be selected again from the dropdown.
+                    self.verbs_string_list.append(&verb_to_find);
+                    self.dropdown.set_selected(element_count);
+                }
             }
         }
     }
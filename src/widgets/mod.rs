@@ -15,26 +15,54 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+mod assertion_row;
+mod assertions_pane;
+mod auth_pane;
+mod capture_row;
+mod captures_pane;
+mod collection_pane;
+mod cookie_dialog;
 mod endpoint_pane;
 mod export_tab;
 mod file_dialogs;
+mod form_file_row;
 mod item_pane;
 mod key_value_pane;
 mod key_value_row;
 mod method_dropdown;
+mod proxy_dialog;
 mod request_body;
+mod request_settings_pane;
+mod response_assertions;
+mod response_cookies;
 mod response_headers;
 mod response_panel;
+mod runner_dialog;
 mod save_dialog;
+mod scripts_pane;
 
+pub use assertion_row::AssertionRow;
+pub use assertions_pane::AssertionsPane;
+pub use auth_pane::AuthPane;
+pub use capture_row::CaptureRow;
+pub use captures_pane::CapturesPane;
+pub use collection_pane::CollectionPane;
+pub use cookie_dialog::CookieDialog;
 pub use endpoint_pane::EndpointPane;
 pub use export_tab::*;
 pub use file_dialogs::*;
+pub use form_file_row::FormFileRow;
 pub use item_pane::ItemPane;
 pub use key_value_pane::KeyValuePane;
 pub use key_value_row::KeyValueRow;
 pub use method_dropdown::MethodDropdown;
+pub use proxy_dialog::ProxyDialog;
 pub use request_body::*;
+pub use request_settings_pane::RequestSettingsPane;
+pub use response_assertions::ResponseAssertions;
+pub use response_cookies::ResponseCookies;
 pub use response_headers::ResponseHeaders;
 pub use response_panel::ResponsePanel;
+pub use runner_dialog::RunnerDialog;
 pub use save_dialog::SaveDialog;
+pub use scripts_pane::ScriptsPane;
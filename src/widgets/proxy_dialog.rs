@@ -0,0 +1,100 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use glib::Object;
+
+mod imp {
+    use adw::prelude::*;
+    use adw::subclass::prelude::*;
+    use glib::subclass::InitializingObject;
+    use gtk::CompositeTemplate;
+
+    use crate::app::CarteroApplication;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/es/danirod/Cartero/proxy_dialog.ui")]
+    pub struct ProxyDialog {
+        #[template_child]
+        url: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        username: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        password: TemplateChild<adw::PasswordEntryRow>,
+
+        #[template_child]
+        no_proxy: TemplateChild<adw::EntryRow>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ProxyDialog {
+        const NAME: &'static str = "CarteroProxyDialog";
+        type Type = super::ProxyDialog;
+        type ParentType = adw::PreferencesDialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ProxyDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.init_settings();
+        }
+    }
+
+    impl WidgetImpl for ProxyDialog {}
+
+    impl AdwDialogImpl for ProxyDialog {}
+
+    impl PreferencesDialogImpl for ProxyDialog {}
+
+    impl ProxyDialog {
+        /// The rows edit the settings directly, so there is nothing to save.
+        fn init_settings(&self) {
+            let app = CarteroApplication::get();
+            let settings = app.settings();
+
+            settings.bind("proxy-url", &*self.url, "text").build();
+            settings
+                .bind("proxy-username", &*self.username, "text")
+                .build();
+            settings
+                .bind("proxy-password", &*self.password, "text")
+                .build();
+            settings.bind("no-proxy", &*self.no_proxy, "text").build();
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct ProxyDialog(ObjectSubclass<imp::ProxyDialog>)
+        @extends gtk::Widget, adw::Dialog, adw::PreferencesDialog,
+        @implements gtk::Accessible, gtk::Buildable;
+}
+
+impl Default for ProxyDialog {
+    fn default() -> Self {
+        Object::builder().build()
+    }
+}
@@ -0,0 +1,139 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use glib::{object::ObjectExt, subclass::types::ObjectSubclassIsExt};
+use gtk::prelude::EditableExt;
+
+use crate::entities::RequestPayload;
+
+use super::{BasePayloadPane, BasePayloadPaneExt};
+
+mod imp {
+    use std::sync::OnceLock;
+
+    use adw::prelude::*;
+    use glib::subclass::{InitializingObject, Signal};
+    use gtk::subclass::prelude::*;
+    use gtk::CompositeTemplate;
+
+    use crate::error::CarteroError;
+    use crate::widgets::{open_upload_file, BasePayloadPane, BasePayloadPaneImpl};
+    use crate::win::CarteroWindow;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/es/danirod/Cartero/file_payload_pane.ui")]
+    pub struct FilePayloadPane {
+        #[template_child]
+        pub(super) path: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        pub(super) content_type: TemplateChild<adw::EntryRow>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for FilePayloadPane {
+        const NAME: &'static str = "CarteroFilePayloadPane";
+        type Type = super::FilePayloadPane;
+        type ParentType = BasePayloadPane;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for FilePayloadPane {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![Signal::builder("changed").build()])
+        }
+    }
+
+    impl WidgetImpl for FilePayloadPane {}
+
+    impl BasePayloadPaneImpl for FilePayloadPane {}
+
+    #[gtk::template_callbacks]
+    impl FilePayloadPane {
+        #[template_callback]
+        fn on_changed(&self) {
+            self.obj().emit_by_name::<()>("changed", &[]);
+        }
+
+        #[template_callback]
+        fn on_choose_clicked(&self) {
+            let Some(window) = self.obj().root().and_downcast::<CarteroWindow>() else {
+                return;
+            };
+            glib::spawn_future_local(
+                glib::clone!(@weak self as pane, @weak window => async move {
+                    match open_upload_file(&window).await {
+                        Ok(file) => {
+                            if let Some(path) = file.path() {
+                                pane.path.set_text(&path.to_string_lossy());
+                            }
+                        }
+                        Err(CarteroError::NoFilePicked) => {}
+                        Err(e) => window.toast_error(e),
+                    }
+                }),
+            );
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct FilePayloadPane(ObjectSubclass<imp::FilePayloadPane>)
+        @extends gtk::Widget, BasePayloadPane,
+    @implements gtk::Accessible, gtk::Buildable;
+}
+
+impl FilePayloadPane {
+    pub fn connect_changed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "changed",
+            true,
+            glib::closure_local!(|ref pane| {
+                f(pane);
+            }),
+        )
+    }
+}
+
+impl BasePayloadPaneExt for FilePayloadPane {
+    fn payload(&self) -> RequestPayload {
+        let imp = self.imp();
+        let content_type = imp.content_type.text().to_string();
+        RequestPayload::File {
+            path: imp.path.text().to_string(),
+            content_type: (!content_type.is_empty()).then_some(content_type),
+        }
+    }
+
+    fn set_payload(&self, payload: &RequestPayload) {
+        let imp = self.imp();
+        if let RequestPayload::File { path, content_type } = payload {
+            imp.path.set_text(path);
+            imp.content_type
+                .set_text(content_type.as_deref().unwrap_or_default());
+        }
+    }
+}
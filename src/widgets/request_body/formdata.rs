@@ -29,10 +29,11 @@ mod imp {
     use std::cell::RefCell;
     use std::sync::OnceLock;
 
+    use crate::entities::FormFile;
     use crate::entities::KeyValue;
     use crate::entities::KeyValueTable;
     use crate::objects::KeyValueItem;
-    use crate::widgets::{BasePayloadPane, BasePayloadPaneImpl, KeyValuePane};
+    use crate::widgets::{BasePayloadPane, BasePayloadPaneImpl, FormFileRow, KeyValuePane};
 
     #[derive(Default, Properties, CompositeTemplate)]
     #[properties(wrapper_type = super::FormdataPayloadPane)]
@@ -41,6 +42,9 @@ mod imp {
         #[template_child]
         data: TemplateChild<KeyValuePane>,
 
+        #[template_child]
+        files: TemplateChild<gtk::ListBox>,
+
         #[property(get, set)]
         boundary: RefCell<String>,
     }
@@ -53,6 +57,7 @@ mod imp {
 
         fn class_init(klass: &mut Self::Class) {
             klass.bind_template();
+            klass.bind_template_callbacks();
         }
 
         fn instance_init(obj: &InitializingObject<Self>) {
@@ -86,7 +91,53 @@ mod imp {
 
     impl BasePayloadPaneImpl for FormdataPayloadPane {}
 
+    #[gtk::template_callbacks]
     impl FormdataPayloadPane {
+        #[template_callback]
+        fn on_add_file_clicked(&self) {
+            self.add_file_row(FormFileRow::default());
+            self.obj().emit_by_name::<()>("changed", &[]);
+        }
+
+        fn add_file_row(&self, row: FormFileRow) {
+            row.connect_changed(glib::clone!(@weak self as pane => move |_| {
+                pane.obj().emit_by_name::<()>("changed", &[]);
+            }));
+            row.connect_delete(glib::clone!(@weak self as pane => move |row| {
+                pane.files.remove(row);
+                pane.obj().emit_by_name::<()>("changed", &[]);
+            }));
+            self.files.append(&row);
+        }
+
+        fn file_rows(&self) -> Vec<FormFileRow> {
+            let mut rows = Vec::new();
+            let mut child = self.files.first_child();
+            while let Some(widget) = child {
+                child = widget.next_sibling();
+                if let Ok(row) = widget.downcast::<FormFileRow>() {
+                    rows.push(row);
+                }
+            }
+            rows
+        }
+
+        pub(super) fn get_files(&self) -> Vec<FormFile> {
+            self.file_rows()
+                .iter()
+                .map(FormFileRow::form_file)
+                .collect()
+        }
+
+        pub(super) fn set_files(&self, files: &[FormFile]) {
+            for row in self.file_rows() {
+                self.files.remove(&row);
+            }
+            for file in files {
+                self.add_file_row(FormFileRow::from(file));
+            }
+        }
+
         pub(super) fn get_table(&self) -> KeyValueTable {
             let entries = self.data.get_entries();
             let key_values: Vec<KeyValue> = entries.into_iter().map(KeyValue::from).collect();
@@ -124,14 +175,17 @@ impl FormdataPayloadPane {
 impl BasePayloadPaneExt for FormdataPayloadPane {
     fn payload(&self) -> RequestPayload {
         let imp = self.imp();
-        let table = imp.get_table();
-        RequestPayload::Multipart { params: table }
+        RequestPayload::Multipart {
+            params: imp.get_table(),
+            files: imp.get_files(),
+        }
     }
 
     fn set_payload(&self, payload: &RequestPayload) {
         let imp = self.imp();
-        if let RequestPayload::Multipart { params } = payload {
+        if let RequestPayload::Multipart { params, files } = payload {
             imp.set_table(params);
+            imp.set_files(files);
         }
     }
 }
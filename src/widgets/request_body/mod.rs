@@ -16,12 +16,14 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 mod base;
+mod file;
 mod formdata;
 mod raw;
 mod tab;
 mod urlencoded;
 
 pub use base::*;
+pub use file::*;
 pub use formdata::*;
 pub use raw::*;
 pub use tab::*;
@@ -22,7 +22,9 @@ use gtk::subclass::prelude::*;
 
 use crate::entities::{RawEncoding, RequestPayload};
 
-use super::{BasePayloadPaneExt, FormdataPayloadPane, RawPayloadPane, UrlencodedPayloadPane};
+use super::{
+    BasePayloadPaneExt, FilePayloadPane, FormdataPayloadPane, RawPayloadPane, UrlencodedPayloadPane,
+};
 
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, glib::Enum)]
 #[enum_type(name = "CarteroPayloadType")]
@@ -34,6 +36,7 @@ pub enum PayloadType {
     Json,
     Xml,
     Raw,
+    File,
 }
 
 impl PayloadType {
@@ -47,6 +50,7 @@ impl PayloadType {
                 PayloadType::Json,
                 PayloadType::Xml,
                 PayloadType::Raw,
+                PayloadType::File,
             ]
         })
     }
@@ -66,6 +70,7 @@ mod imp {
     use gtk::{CompositeTemplate, Stack};
 
     use crate::widgets::BasePayloadPane;
+    use crate::widgets::FilePayloadPane;
     use crate::widgets::FormdataPayloadPane;
     use crate::widgets::RawPayloadPane;
     use crate::widgets::UrlencodedPayloadPane;
@@ -94,6 +99,9 @@ mod imp {
         #[template_child]
         formdata: TemplateChild<FormdataPayloadPane>,
 
+        #[template_child]
+        file: TemplateChild<FilePayloadPane>,
+
         #[property(get = Self::payload_type, set = Self::set_payload_type, builder(PayloadType::default()))]
         _payload_type: RefCell<PayloadType>,
     }
@@ -139,6 +147,11 @@ mod imp {
                 .connect_changed(glib::clone!(@weak self as pane => move |_| {
                     pane.obj().emit_by_name::<()>("changed", &[]);
                 }));
+
+            self.file
+                .connect_changed(glib::clone!(@weak self as pane => move |_| {
+                    pane.obj().emit_by_name::<()>("changed", &[]);
+                }));
         }
 
         fn signals() -> &'static [Signal] {
@@ -163,6 +176,7 @@ mod imp {
                 PayloadType::Xml => "raw",
                 PayloadType::UrlEncoded => "urlencoded",
                 PayloadType::MultipartFormData => "formdata",
+                PayloadType::File => "file",
             };
             self.stack.set_visible_child_name(tab);
 
@@ -192,6 +206,7 @@ mod imp {
                 PayloadType::Json | PayloadType::Xml | PayloadType::Raw => {
                     Some(self.raw.upcast_ref::<BasePayloadPane>().clone())
                 }
+                PayloadType::File => Some(self.file.upcast_ref::<BasePayloadPane>().clone()),
             }
         }
     }
@@ -218,7 +233,7 @@ impl PayloadTab {
         let payload_type = match payload {
             RequestPayload::None => PayloadType::None,
             RequestPayload::Urlencoded(_) => PayloadType::UrlEncoded,
-            RequestPayload::Multipart { params: _ } => PayloadType::MultipartFormData,
+            RequestPayload::Multipart { .. } => PayloadType::MultipartFormData,
             RequestPayload::Raw {
                 encoding,
                 content: _,
@@ -227,6 +242,7 @@ impl PayloadTab {
                 RawEncoding::Xml => PayloadType::Xml,
                 RawEncoding::OctetStream => PayloadType::Raw,
             },
+            RequestPayload::File { .. } => PayloadType::File,
         };
         self.set_payload_type(payload_type);
 
@@ -245,6 +261,10 @@ impl PayloadTab {
                 let widget = widget.and_downcast::<RawPayloadPane>().unwrap();
                 widget.set_payload(payload);
             }
+            PayloadType::File => {
+                let widget = widget.and_downcast::<FilePayloadPane>().unwrap();
+                widget.set_payload(payload);
+            }
         }
     }
 
@@ -264,6 +284,10 @@ impl PayloadTab {
                 let widget = widget.and_downcast::<RawPayloadPane>().unwrap();
                 widget.payload()
             }
+            PayloadType::File => {
+                let widget = widget.and_downcast::<FilePayloadPane>().unwrap();
+                widget.payload()
+            }
         }
     }
 }
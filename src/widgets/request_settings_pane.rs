@@ -0,0 +1,318 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use glib::{object::ObjectExt, subclass::types::ObjectSubclassIsExt, Object};
+
+use crate::entities::{RequestOptions, RequestProxy, TlsSettings};
+
+mod imp {
+    use std::sync::OnceLock;
+
+    use adw::prelude::*;
+    use adw::subclass::bin::BinImpl;
+    use glib::subclass::{InitializingObject, Signal};
+    use gtk::subclass::prelude::*;
+    use gtk::CompositeTemplate;
+
+    use crate::entities::{
+        CertificateFormat, HttpVersion, ProxySettings, RequestOptions, RequestProxy, TlsSettings,
+    };
+    use crate::error::CarteroError;
+    use crate::widgets::open_certificate_file;
+    use crate::win::CarteroWindow;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/es/danirod/Cartero/request_settings_pane.ui")]
+    pub struct RequestSettingsPane {
+        #[template_child]
+        custom_timeout: TemplateChild<adw::ExpanderRow>,
+
+        #[template_child]
+        timeout: TemplateChild<adw::SpinRow>,
+
+        #[template_child]
+        http_version: TemplateChild<adw::ComboRow>,
+
+        #[template_child]
+        verify_tls: TemplateChild<adw::SwitchRow>,
+
+        #[template_child]
+        decompress: TemplateChild<adw::SwitchRow>,
+
+        #[template_child]
+        follow_redirects: TemplateChild<adw::ExpanderRow>,
+
+        #[template_child]
+        max_redirects: TemplateChild<adw::SpinRow>,
+
+        #[template_child]
+        proxy_mode: TemplateChild<adw::ComboRow>,
+
+        #[template_child]
+        proxy_url: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        proxy_username: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        proxy_password: TemplateChild<adw::PasswordEntryRow>,
+
+        #[template_child]
+        no_proxy: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        certificate: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        certificate_format: TemplateChild<adw::ComboRow>,
+
+        #[template_child]
+        key: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        password: TemplateChild<adw::PasswordEntryRow>,
+
+        #[template_child]
+        ca_bundle: TemplateChild<adw::EntryRow>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RequestSettingsPane {
+        const NAME: &'static str = "CarteroRequestSettingsPane";
+        type Type = super::RequestSettingsPane;
+        type ParentType = adw::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for RequestSettingsPane {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![Signal::builder("changed").build()])
+        }
+    }
+
+    impl WidgetImpl for RequestSettingsPane {}
+
+    impl BinImpl for RequestSettingsPane {}
+
+    #[gtk::template_callbacks]
+    impl RequestSettingsPane {
+        #[template_callback]
+        fn on_changed(&self) {
+            self.obj().emit_by_name::<()>("changed", &[]);
+        }
+
+        #[template_callback]
+        fn on_proxy_mode_changed(&self) {
+            // The proxy details only make sense for a custom proxy.
+            let custom = self.proxy_mode.selected() == 2;
+            self.proxy_url.set_visible(custom);
+            self.proxy_username.set_visible(custom);
+            self.proxy_password.set_visible(custom);
+            self.no_proxy.set_visible(custom);
+            self.on_changed();
+        }
+
+        #[template_callback]
+        fn on_choose_certificate(&self) {
+            self.choose_file(&self.certificate);
+        }
+
+        #[template_callback]
+        fn on_choose_key(&self) {
+            self.choose_file(&self.key);
+        }
+
+        #[template_callback]
+        fn on_choose_ca_bundle(&self) {
+            self.choose_file(&self.ca_bundle);
+        }
+
+        /// Asks for a file and puts its path in the given row.
+        fn choose_file(&self, row: &adw::EntryRow) {
+            let Some(window) = self.obj().root().and_downcast::<CarteroWindow>() else {
+                return;
+            };
+            let row = row.clone();
+            glib::spawn_future_local(glib::clone!(@weak row, @weak window => async move {
+                match open_certificate_file(&window).await {
+                    Ok(file) => {
+                        if let Some(path) = file.path() {
+                            row.set_text(&path.to_string_lossy());
+                        }
+                    }
+                    Err(CarteroError::NoFilePicked) => {}
+                    Err(e) => window.toast_error(e),
+                }
+            }));
+        }
+
+        pub(super) fn options(&self) -> RequestOptions {
+            let timeout = self
+                .custom_timeout
+                .enables_expansion()
+                .then(|| self.timeout.value() as u64);
+            let http_version = match self.http_version.selected() {
+                1 => HttpVersion::Http11,
+                2 => HttpVersion::Http2,
+                _ => HttpVersion::Auto,
+            };
+            RequestOptions {
+                timeout,
+                follow_redirects: self.follow_redirects.enables_expansion(),
+                max_redirects: self.max_redirects.value() as u32,
+                verify_tls: self.verify_tls.is_active(),
+                http_version,
+                decompress: self.decompress.is_active(),
+            }
+        }
+
+        pub(super) fn set_options(&self, options: &RequestOptions) {
+            self.custom_timeout
+                .set_enable_expansion(options.timeout.is_some());
+            if let Some(timeout) = options.timeout {
+                self.timeout.set_value(timeout as f64);
+            }
+            let position = match options.http_version {
+                HttpVersion::Auto => 0,
+                HttpVersion::Http11 => 1,
+                HttpVersion::Http2 => 2,
+            };
+            self.http_version.set_selected(position);
+            self.verify_tls.set_active(options.verify_tls);
+            self.decompress.set_active(options.decompress);
+            self.follow_redirects
+                .set_enable_expansion(options.follow_redirects);
+            self.max_redirects.set_value(options.max_redirects as f64);
+        }
+
+        pub(super) fn proxy(&self) -> RequestProxy {
+            match self.proxy_mode.selected() {
+                1 => RequestProxy::Direct,
+                2 => RequestProxy::Custom(ProxySettings {
+                    url: self.proxy_url.text().to_string(),
+                    username: self.proxy_username.text().to_string(),
+                    password: self.proxy_password.text().to_string(),
+                    no_proxy: self.no_proxy.text().to_string(),
+                }),
+                _ => RequestProxy::Default,
+            }
+        }
+
+        pub(super) fn set_proxy(&self, proxy: &RequestProxy) {
+            let settings = match proxy {
+                RequestProxy::Custom(settings) => settings.clone(),
+                _ => ProxySettings::default(),
+            };
+            self.proxy_url.set_text(&settings.url);
+            self.proxy_username.set_text(&settings.username);
+            self.proxy_password.set_text(&settings.password);
+            self.no_proxy.set_text(&settings.no_proxy);
+            let position = match proxy {
+                RequestProxy::Default => 0,
+                RequestProxy::Direct => 1,
+                RequestProxy::Custom(_) => 2,
+            };
+            self.proxy_mode.set_selected(position);
+        }
+
+        pub(super) fn tls(&self) -> TlsSettings {
+            let format = match self.certificate_format.selected() {
+                1 => CertificateFormat::Pkcs12,
+                _ => CertificateFormat::Pem,
+            };
+            TlsSettings {
+                certificate: self.certificate.text().to_string(),
+                format,
+                key: self.key.text().to_string(),
+                password: self.password.text().to_string(),
+                ca_bundle: self.ca_bundle.text().to_string(),
+            }
+        }
+
+        pub(super) fn set_tls(&self, tls: &TlsSettings) {
+            self.certificate.set_text(&tls.certificate);
+            let position = match tls.format {
+                CertificateFormat::Pem => 0,
+                CertificateFormat::Pkcs12 => 1,
+            };
+            self.certificate_format.set_selected(position);
+            self.key.set_text(&tls.key);
+            self.password.set_text(&tls.password);
+            self.ca_bundle.set_text(&tls.ca_bundle);
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct RequestSettingsPane(ObjectSubclass<imp::RequestSettingsPane>)
+        @extends gtk::Widget, adw::Bin,
+        @implements gtk::Accessible, gtk::Buildable;
+}
+
+impl Default for RequestSettingsPane {
+    fn default() -> Self {
+        Object::builder().build()
+    }
+}
+
+impl RequestSettingsPane {
+    pub fn connect_changed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "changed",
+            true,
+            glib::closure_local!(|ref pane| {
+                f(pane);
+            }),
+        )
+    }
+
+    /// Reads the options of the request from the widgets of the pane.
+    pub fn options(&self) -> RequestOptions {
+        self.imp().options()
+    }
+
+    pub fn set_options(&self, options: &RequestOptions) {
+        self.imp().set_options(options)
+    }
+
+    /// Reads whether the request goes through a proxy.
+    pub fn proxy(&self) -> RequestProxy {
+        self.imp().proxy()
+    }
+
+    pub fn set_proxy(&self, proxy: &RequestProxy) {
+        self.imp().set_proxy(proxy)
+    }
+
+    /// Reads the client certificate and CA bundle of the request.
+    pub fn tls(&self) -> TlsSettings {
+        self.imp().tls()
+    }
+
+    pub fn set_tls(&self, tls: &TlsSettings) {
+        self.imp().set_tls(tls)
+    }
+}
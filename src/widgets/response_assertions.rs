@@ -0,0 +1,102 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use gettextrs::gettext;
+use glib::subclass::types::ObjectSubclassIsExt;
+use glib::Object;
+use gtk::prelude::*;
+
+use crate::client::AssertionResult;
+
+mod imp {
+    use adw::subclass::prelude::*;
+    use glib::subclass::InitializingObject;
+    use gtk::{CompositeTemplate, ListBox, TemplateChild};
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/es/danirod/Cartero/response_assertions.ui")]
+    pub struct ResponseAssertions {
+        #[template_child]
+        pub list_box: TemplateChild<ListBox>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ResponseAssertions {
+        const NAME: &'static str = "CarteroResponseAssertions";
+        type Type = super::ResponseAssertions;
+        type ParentType = adw::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ResponseAssertions {}
+
+    impl WidgetImpl for ResponseAssertions {}
+
+    impl BinImpl for ResponseAssertions {}
+}
+
+glib::wrapper! {
+    pub struct ResponseAssertions(ObjectSubclass<imp::ResponseAssertions>)
+        @extends gtk::Widget, adw::Bin;
+}
+
+impl ResponseAssertions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shows the outcome of the assertions, one row per assertion with a badge that
+    /// tells whether it passed. Failed assertions also tell why.
+    pub fn set_results(&self, results: &[AssertionResult]) {
+        let list_box = &self.imp().list_box;
+        list_box.remove_all();
+        for result in results {
+            let row = adw::ActionRow::new();
+            row.set_use_markup(false);
+            row.set_title(&result.description());
+            row.set_title_selectable(true);
+            if let Err(e) = &result.outcome {
+                row.set_subtitle(&e.to_string());
+                row.set_subtitle_selectable(true);
+            }
+
+            let (label, class) = match result.passed() {
+                true => (gettext("Passed"), "success"),
+                false => (gettext("Failed"), "error"),
+            };
+            let badge = gtk::Label::new(Some(&label));
+            badge.add_css_class("assertion-badge");
+            badge.add_css_class(class);
+            badge.set_valign(gtk::Align::Center);
+            row.add_suffix(&badge);
+            list_box.append(&row);
+        }
+    }
+}
+
+impl Default for ResponseAssertions {
+    fn default() -> Self {
+        Object::builder().build()
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use gettextrs::gettext;
+use glib::subclass::types::ObjectSubclassIsExt;
+use glib::Object;
+use gtk::prelude::*;
+
+use crate::client::Cookie;
+
+mod imp {
+    use adw::subclass::prelude::*;
+    use glib::subclass::InitializingObject;
+    use gtk::{CompositeTemplate, ListBox, TemplateChild};
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/es/danirod/Cartero/response_cookies.ui")]
+    pub struct ResponseCookies {
+        #[template_child]
+        pub list_box: TemplateChild<ListBox>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ResponseCookies {
+        const NAME: &'static str = "CarteroResponseCookies";
+        type Type = super::ResponseCookies;
+        type ParentType = adw::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ResponseCookies {}
+
+    impl WidgetImpl for ResponseCookies {}
+
+    impl BinImpl for ResponseCookies {}
+}
+
+glib::wrapper! {
+    pub struct ResponseCookies(ObjectSubclass<imp::ResponseCookies>)
+        @extends gtk::Widget, adw::Bin;
+}
+
+impl ResponseCookies {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shows the cookies set by a response, one row per cookie.
+    pub fn set_cookies(&self, cookies: &[Cookie]) {
+        let list_box = &self.imp().list_box;
+        list_box.remove_all();
+        for cookie in cookies {
+            let row = adw::ActionRow::new();
+            row.set_use_markup(false);
+            row.set_title(&cookie.name);
+            row.set_title_selectable(true);
+            row.set_subtitle(&cookie.value);
+            row.set_subtitle_selectable(true);
+            row.add_css_class("property");
+
+            let attributes = gtk::Label::new(Some(&cookie_attributes(cookie)));
+            attributes.add_css_class("dim-label");
+            attributes.set_wrap(true);
+            attributes.set_xalign(1.0);
+            row.add_suffix(&attributes);
+            list_box.append(&row);
+        }
+    }
+}
+
+impl Default for ResponseCookies {
+    fn default() -> Self {
+        Object::builder().build()
+    }
+}
+
+/// Summarizes where a cookie is sent and how, such as `example.com/api · Secure`.
+fn cookie_attributes(cookie: &Cookie) -> String {
+    let mut attributes = vec![format!("{}{}", cookie.domain, cookie.path)];
+    if cookie.expires.is_none() {
+        attributes.push(gettext("Session"));
+    }
+    if cookie.secure {
+        attributes.push("Secure".into());
+    }
+    if cookie.http_only {
+        attributes.push("HttpOnly".into());
+    }
+    if !cookie.same_site.is_empty() {
+        attributes.push(format!("SameSite={}", cookie.same_site));
+    }
+    attributes.join(" · ")
+}
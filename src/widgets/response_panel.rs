@@ -17,6 +17,7 @@
 
 use std::path::PathBuf;
 
+use gettextrs::{gettext, ngettext};
 use glib::Object;
 use gtk::gio::{ListModel, ListStore};
 use gtk::glib;
@@ -26,6 +27,7 @@ use serde_json::Value;
 use sourceview5::prelude::BufferExt;
 use sourceview5::LanguageManager;
 
+use crate::client::AssertionResult;
 use crate::entities::ResponseData;
 use crate::objects::KeyValueItem;
 use glib::subclass::types::ObjectSubclassIsExt;
@@ -49,7 +51,7 @@ mod imp {
     use sourceview5::StyleSchemeManager;
 
     use crate::app::CarteroApplication;
-    use crate::widgets::ResponseHeaders;
+    use crate::widgets::{ResponseAssertions, ResponseCookies, ResponseHeaders};
 
     #[derive(CompositeTemplate, Default, Properties)]
     #[properties(wrapper_type = super::ResponsePanel)]
@@ -60,10 +62,22 @@ mod imp {
         #[template_child]
         pub response_headers: TemplateChild<ResponseHeaders>,
         #[template_child]
+        pub response_cookies: TemplateChild<ResponseCookies>,
+        #[template_child]
+        pub response_assertions: TemplateChild<ResponseAssertions>,
+        #[template_child]
         pub response_body: TemplateChild<sourceview5::View>,
         #[template_child]
         pub response_meta: TemplateChild<Box>,
         #[template_child]
+        pub proxy: TemplateChild<Label>,
+        #[template_child]
+        pub redirects: TemplateChild<gtk::MenuButton>,
+        #[template_child]
+        pub redirect_list: TemplateChild<Label>,
+        #[template_child]
+        pub assertions_summary: TemplateChild<Label>,
+        #[template_child]
         pub status_code: TemplateChild<Label>,
         #[template_child]
         pub duration: TemplateChild<Label>,
@@ -223,6 +237,26 @@ impl ResponsePanel {
         imp.metadata_stack.set_visible_child(&*imp.spinner);
     }
 
+    /// Shows the outcome of the assertions of the request, along with a summary next
+    /// to the status code. The summary is hidden if the request has no assertions.
+    pub fn set_assertion_results(&self, results: &[AssertionResult]) {
+        let imp = self.imp();
+        imp.response_assertions.set_results(results);
+
+        let passed = results.iter().filter(|result| result.passed()).count();
+        let summary = gettext("{passed}/{total} passed")
+            .replace("{passed}", &passed.to_string())
+            .replace("{total}", &results.len().to_string());
+        imp.assertions_summary.set_text(&summary);
+        imp.assertions_summary.set_visible(!results.is_empty());
+        let (add, remove) = match passed == results.len() {
+            true => ("success", "error"),
+            false => ("error", "success"),
+        };
+        imp.assertions_summary.remove_css_class(remove);
+        imp.assertions_summary.add_css_class(add);
+    }
+
     pub fn assign_from_response(&self, resp: &ResponseData) {
         let imp = self.imp();
 
@@ -237,6 +271,22 @@ impl ResponsePanel {
         store.extend_from_slice(&headers);
         let model = store.upcast::<ListModel>();
         imp.response_headers.set_headers(Some(&model));
+        imp.response_cookies.set_cookies(&resp.cookies);
+
+        imp.proxy.set_tooltip_text(resp.proxy.as_deref());
+        imp.proxy.set_visible(resp.proxy.is_some());
+
+        let redirects = resp.redirects.len();
+        let label = ngettext("{} redirect", "{} redirects", redirects as u32);
+        imp.redirects
+            .set_label(&label.replace("{}", &redirects.to_string()));
+        let hops: Vec<String> = resp
+            .redirects
+            .iter()
+            .map(|r| format!("{} {} → {}", r.status_code, r.url, r.location))
+            .collect();
+        imp.redirect_list.set_text(&hops.join("\n"));
+        imp.redirects.set_visible(redirects > 0);
 
         let status = format!("• HTTP {}", resp.status_code);
         imp.status_code.set_text(&status);
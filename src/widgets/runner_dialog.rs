@@ -0,0 +1,339 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+use glib::subclass::types::ObjectSubclassIsExt;
+use glib::Object;
+
+use crate::win::CarteroWindow;
+
+mod imp {
+    use std::cell::RefCell;
+
+    use adw::prelude::*;
+    use adw::subclass::prelude::*;
+    use gettextrs::{gettext, ngettext};
+    use glib::subclass::InitializingObject;
+    use gtk::{gio, CompositeTemplate};
+
+    use crate::app::CarteroApplication;
+    use crate::client::{
+        collection_items, json_report, junit_report, RequestRun, RunSettings, Runner,
+    };
+    use crate::entities::{KeyValueTable, ProxySettings};
+    use crate::error::CarteroError;
+    use crate::widgets::{
+        open_data_file, read_file, save_json_report_file, save_junit_report_file, write_file,
+    };
+    use crate::win::CarteroWindow;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/es/danirod/Cartero/runner_dialog.ui")]
+    pub struct RunnerDialog {
+        #[template_child]
+        run_button: TemplateChild<gtk::Button>,
+
+        #[template_child]
+        stop_button: TemplateChild<gtk::Button>,
+
+        #[template_child]
+        export_button: TemplateChild<gtk::MenuButton>,
+
+        #[template_child]
+        toaster: TemplateChild<adw::ToastOverlay>,
+
+        #[template_child]
+        stop_on_failure: TemplateChild<adw::SwitchRow>,
+
+        #[template_child]
+        data_row: TemplateChild<adw::ActionRow>,
+
+        #[template_child]
+        data_label: TemplateChild<gtk::Label>,
+
+        #[template_child]
+        clear_data: TemplateChild<gtk::Button>,
+
+        #[template_child]
+        results_group: TemplateChild<adw::PreferencesGroup>,
+
+        #[template_child]
+        results: TemplateChild<gtk::ListBox>,
+
+        /// The variables of every iteration, read from the data file.
+        data: RefCell<Vec<KeyValueTable>>,
+
+        /// The name of the collection of the last run, used in the reports.
+        name: RefCell<String>,
+
+        runs: RefCell<Vec<RequestRun>>,
+
+        cancellable: RefCell<Option<gio::Cancellable>>,
+
+        pub window: glib::WeakRef<CarteroWindow>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RunnerDialog {
+        const NAME: &'static str = "CarteroRunnerDialog";
+        type Type = super::RunnerDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+
+            klass.install_action_async("runner.export-junit", None, |dialog, _, _| async move {
+                let imp = dialog.imp();
+                imp.report(imp.export_junit().await);
+            });
+            klass.install_action_async("runner.export-json", None, |dialog, _, _| async move {
+                let imp = dialog.imp();
+                imp.report(imp.export_json().await);
+            });
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for RunnerDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            // Closing the dialog stops the run, since its results could not be seen.
+            self.obj().connect_closed(|dialog| {
+                if let Some(cancellable) = dialog.imp().cancellable.borrow().as_ref() {
+                    cancellable.cancel();
+                }
+            });
+        }
+    }
+
+    impl WidgetImpl for RunnerDialog {}
+
+    impl AdwDialogImpl for RunnerDialog {}
+
+    #[gtk::template_callbacks]
+    impl RunnerDialog {
+        #[template_callback]
+        fn on_run_clicked(&self) {
+            glib::spawn_future_local(glib::clone!(@weak self as dialog => async move {
+                dialog.report(dialog.run().await);
+            }));
+        }
+
+        #[template_callback]
+        fn on_stop_clicked(&self) {
+            if let Some(cancellable) = self.cancellable.borrow().as_ref() {
+                cancellable.cancel();
+            }
+        }
+
+        #[template_callback]
+        fn on_choose_data_clicked(&self) {
+            glib::spawn_future_local(glib::clone!(@weak self as dialog => async move {
+                dialog.report(dialog.choose_data().await);
+            }));
+        }
+
+        #[template_callback]
+        fn on_clear_data_clicked(&self) {
+            self.data.replace(Vec::new());
+            self.data_label.set_visible(false);
+            self.clear_data.set_visible(false);
+        }
+
+        fn report(&self, result: Result<(), CarteroError>) {
+            match result {
+                Ok(()) | Err(CarteroError::NoFilePicked) => {}
+                Err(e) => self.toaster.add_toast(adw::Toast::new(&e.to_string())),
+            }
+        }
+
+        /// Reads the iterations of the run from a CSV or JSON file picked by the user.
+        async fn choose_data(&self) -> Result<(), CarteroError> {
+            let Some(window) = self.window.upgrade() else {
+                return Ok(());
+            };
+            let file = open_data_file(&window).await?;
+            let contents = read_file(&file).await?;
+            let file_name = file
+                .basename()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let data = crate::file::parse_data_file(&file_name, &contents)?;
+
+            let label = ngettext("{file}, {} row", "{file}, {} rows", data.len() as u32)
+                .replace("{file}", &file_name)
+                .replace("{}", &data.len().to_string());
+            self.data_label.set_label(&label);
+            self.data_label.set_visible(true);
+            self.clear_data.set_visible(true);
+            self.data.replace(data);
+            Ok(())
+        }
+
+        /// Swaps the Run button for the Stop button while the requests are being sent.
+        fn set_busy(&self, busy: bool) {
+            self.run_button.set_visible(!busy);
+            self.stop_button.set_visible(busy);
+            self.stop_on_failure.set_sensitive(!busy);
+            self.data_row.set_sensitive(!busy);
+            let has_runs = !self.runs.borrow().is_empty();
+            self.export_button.set_sensitive(!busy && has_runs);
+        }
+
+        /// Sends the requests of the open collection, as they are saved on disk.
+        ///
+        /// The run starts with the variables of the window, but what the requests
+        /// capture is only kept during the run. The cookies and the OAuth 2.0 tokens
+        /// are shared with the window.
+        async fn run(&self) -> Result<(), CarteroError> {
+            let Some(window) = self.window.upgrade() else {
+                return Ok(());
+            };
+            let Some(root) = window.collection_root() else {
+                return Ok(());
+            };
+            let Some(dir) = root.path() else {
+                return Ok(());
+            };
+            let items = collection_items(&dir)?;
+
+            let settings = CarteroApplication::get().settings();
+            let timeout = settings.get::<String>("request-timeout");
+            let proxy = ProxySettings {
+                url: settings.get("proxy-url"),
+                username: settings.get("proxy-username"),
+                password: settings.get("proxy-password"),
+                no_proxy: settings.get("no-proxy"),
+            };
+            let mut runner = Runner::new(RunSettings {
+                stop_on_failure: self.stop_on_failure.is_active(),
+                timeout: Some(timeout.parse::<u64>().unwrap_or_default()),
+                proxy,
+                variables: Vec::new(),
+            });
+            runner.environment = window.active_environment();
+            runner.cookie_jar = window.cookie_jar();
+            runner.tokens = window.token_cache();
+            runner.variables = window.session_variables();
+
+            let name = root
+                .basename()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            self.name.replace(name);
+            self.results.remove_all();
+            self.runs.replace(Vec::new());
+            self.results_group.set_description(None);
+
+            let data = self.data.borrow().clone();
+            let cancellable = gio::Cancellable::new();
+            self.cancellable.replace(Some(cancellable.clone()));
+            self.set_busy(true);
+            let open_browser = |url: &str| window.open_browser(url);
+            let future = runner.run(&items, &data, &open_browser, |run| self.add_result(run));
+            let outcome = gio::CancellableFuture::new(future, cancellable).await;
+            self.cancellable.replace(None);
+            self.set_busy(false);
+
+            window.save_cookies().await;
+            if outcome.is_err() {
+                self.toaster
+                    .add_toast(adw::Toast::new(&gettext("The run was stopped")));
+            }
+            Ok(())
+        }
+
+        /// Shows a row for the result of a request, and updates the summary.
+        fn add_result(&self, run: RequestRun) {
+            let row = adw::ActionRow::new();
+            row.set_use_markup(false);
+            let title = match self.data.borrow().len() > 1 {
+                true => format!("{} #{}", run.name, run.iteration),
+                false => run.name.clone(),
+            };
+            row.set_title(&title);
+            let failures = run.failures();
+            if !failures.is_empty() {
+                row.set_subtitle(&failures.join("\n"));
+                row.set_subtitle_selectable(true);
+            }
+
+            if let Some(response) = run.response() {
+                let text = format!("HTTP {} • {} s", response.status_code, response.seconds());
+                let details = gtk::Label::new(Some(&text));
+                details.add_css_class("dim-label");
+                details.add_css_class("numeric");
+                row.add_suffix(&details);
+            }
+            let (label, class) = match run.passed() {
+                true => (gettext("Passed"), "success"),
+                false => (gettext("Failed"), "error"),
+            };
+            let badge = gtk::Label::new(Some(&label));
+            badge.add_css_class("assertion-badge");
+            badge.add_css_class(class);
+            badge.set_valign(gtk::Align::Center);
+            row.add_suffix(&badge);
+            self.results.append(&row);
+
+            let mut runs = self.runs.borrow_mut();
+            runs.push(run);
+            let passed = runs.iter().filter(|run| run.passed()).count();
+            let summary = gettext("{passed}/{total} passed")
+                .replace("{passed}", &passed.to_string())
+                .replace("{total}", &runs.len().to_string());
+            self.results_group.set_description(Some(&summary));
+        }
+
+        async fn export_junit(&self) -> Result<(), CarteroError> {
+            let Some(window) = self.window.upgrade() else {
+                return Ok(());
+            };
+            let report = junit_report(&self.name.borrow(), &self.runs.borrow());
+            let file = save_junit_report_file(&window).await?;
+            write_file(&file, &report).await
+        }
+
+        async fn export_json(&self) -> Result<(), CarteroError> {
+            let Some(window) = self.window.upgrade() else {
+                return Ok(());
+            };
+            let report = json_report(&self.name.borrow(), &self.runs.borrow());
+            let report = serde_json::to_string_pretty(&report)?;
+            let file = save_json_report_file(&window).await?;
+            write_file(&file, &report).await
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct RunnerDialog(ObjectSubclass<imp::RunnerDialog>)
+        @extends gtk::Widget, adw::Dialog,
+        @implements gtk::Accessible, gtk::Buildable;
+}
+
+impl RunnerDialog {
+    /// Creates a dialog that runs the requests of the collection open in the given window.
+    pub fn new(window: &CarteroWindow) -> Self {
+        let dialog: Self = Object::builder().build();
+        dialog.imp().window.set(Some(window));
+        dialog
+    }
+}
@@ -0,0 +1,235 @@
+// Copyright 2024 the Cartero authors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use glib::{object::ObjectExt, subclass::types::ObjectSubclassIsExt, Object};
+
+use crate::client::ConsoleLine;
+use crate::entities::RequestScripts;
+
+mod imp {
+    use std::sync::OnceLock;
+
+    use adw::subclass::bin::BinImpl;
+    use glib::subclass::{InitializingObject, Signal};
+    use gtk::gio::SettingsBindFlags;
+    use gtk::subclass::prelude::*;
+    use gtk::{prelude::*, CompositeTemplate};
+    use sourceview5::prelude::*;
+    use sourceview5::{Buffer, StyleSchemeManager, View};
+
+    use crate::app::CarteroApplication;
+    use crate::client::{ConsoleLevel, ConsoleLine};
+    use crate::entities::RequestScripts;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/es/danirod/Cartero/scripts_pane.ui")]
+    pub struct ScriptsPane {
+        #[template_child]
+        pre_request_view: TemplateChild<View>,
+        #[template_child]
+        pre_request_buffer: TemplateChild<Buffer>,
+        #[template_child]
+        post_response_view: TemplateChild<View>,
+        #[template_child]
+        post_response_buffer: TemplateChild<Buffer>,
+        #[template_child]
+        console: TemplateChild<gtk::TextView>,
+        #[template_child]
+        console_buffer: TemplateChild<gtk::TextBuffer>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ScriptsPane {
+        const NAME: &'static str = "CarteroScriptsPane";
+        type Type = super::ScriptsPane;
+        type ParentType = adw::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ScriptsPane {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![Signal::builder("changed").build()])
+        }
+
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.init_settings();
+            self.init_source_view_style();
+
+            for buffer in [&*self.pre_request_buffer, &*self.post_response_buffer] {
+                buffer.connect_changed(glib::clone!(@weak self as pane => move |_| {
+                    pane.obj().emit_by_name::<()>("changed", &[]);
+                }));
+            }
+            self.console_buffer
+                .create_tag(Some("error"), &[("foreground", &"#c01c28")]);
+            self.console_buffer
+                .create_tag(Some("stage"), &[("weight", &700)]);
+        }
+    }
+
+    impl WidgetImpl for ScriptsPane {}
+
+    impl BinImpl for ScriptsPane {}
+
+    #[gtk::template_callbacks]
+    impl ScriptsPane {
+        #[template_callback]
+        fn on_clear_console_clicked(&self) {
+            self.console_buffer.set_text("");
+        }
+
+        fn buffer_text(buffer: &Buffer) -> String {
+            let (start, end) = buffer.bounds();
+            buffer.text(&start, &end, true).to_string()
+        }
+
+        pub(super) fn scripts(&self) -> RequestScripts {
+            RequestScripts {
+                pre_request: Self::buffer_text(&self.pre_request_buffer),
+                post_response: Self::buffer_text(&self.post_response_buffer),
+            }
+        }
+
+        pub(super) fn set_scripts(&self, scripts: &RequestScripts) {
+            self.pre_request_buffer.set_text(&scripts.pre_request);
+            self.post_response_buffer.set_text(&scripts.post_response);
+        }
+
+        pub(super) fn append_console(&self, lines: &[ConsoleLine]) {
+            let buffer = &self.console_buffer;
+            for line in lines {
+                let mut end = buffer.end_iter();
+                buffer.insert_with_tags_by_name(&mut end, &format!("{}: ", line.stage), &["stage"]);
+                let tags: &[&str] = match line.level {
+                    ConsoleLevel::Error => &["error"],
+                    ConsoleLevel::Output | ConsoleLevel::Debug => &[],
+                };
+                buffer.insert_with_tags_by_name(&mut end, &line.text, tags);
+                buffer.insert(&mut end, "\n");
+            }
+            if !lines.is_empty() {
+                let mark = buffer.create_mark(None, &buffer.end_iter(), false);
+                self.console.scroll_mark_onscreen(&mark);
+                buffer.delete_mark(&mark);
+            }
+        }
+
+        fn init_settings(&self) {
+            let app = CarteroApplication::get();
+            let settings = app.settings();
+
+            for view in [&*self.pre_request_view, &*self.post_response_view] {
+                settings
+                    .bind("show-line-numbers", view, "show-line-numbers")
+                    .flags(SettingsBindFlags::GET)
+                    .build();
+                settings
+                    .bind("auto-indent", view, "auto-indent")
+                    .flags(SettingsBindFlags::GET)
+                    .build();
+                settings
+                    .bind("indent-style", view, "insert-spaces-instead-of-tabs")
+                    .flags(SettingsBindFlags::GET)
+                    .mapping(|variant, _| {
+                        let mode = variant
+                            .get::<String>()
+                            .expect("The variant is not a string");
+                        let use_spaces = mode == "spaces";
+                        Some(use_spaces.to_value())
+                    })
+                    .build();
+                for property in ["tab-width", "indent-width"] {
+                    settings
+                        .bind("tab-width", view, property)
+                        .flags(SettingsBindFlags::GET)
+                        .mapping(|variant, _| {
+                            let width = variant.get::<String>().unwrap_or("4".into());
+                            let value = width.parse::<i32>().unwrap_or(4);
+                            Some(value.to_value())
+                        })
+                        .build();
+                }
+            }
+        }
+
+        fn update_source_view_style(&self) {
+            let dark_mode = adw::StyleManager::default().is_dark();
+            let color_theme = if dark_mode { "Adwaita-dark" } else { "Adwaita" };
+            let theme = StyleSchemeManager::default().scheme(color_theme);
+
+            for buffer in [&*self.pre_request_buffer, &*self.post_response_buffer] {
+                buffer.set_style_scheme(theme.as_ref());
+            }
+        }
+
+        fn init_source_view_style(&self) {
+            self.update_source_view_style();
+            adw::StyleManager::default().connect_dark_notify(
+                glib::clone!(@weak self as pane => move |_| {
+                    pane.update_source_view_style();
+                }),
+            );
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct ScriptsPane(ObjectSubclass<imp::ScriptsPane>)
+        @extends gtk::Widget, adw::Bin,
+        @implements gtk::Accessible, gtk::Buildable;
+}
+
+impl Default for ScriptsPane {
+    fn default() -> Self {
+        Object::builder().build()
+    }
+}
+
+impl ScriptsPane {
+    pub fn connect_changed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "changed",
+            true,
+            glib::closure_local!(|ref pane| {
+                f(pane);
+            }),
+        )
+    }
+
+    pub fn scripts(&self) -> RequestScripts {
+        self.imp().scripts()
+    }
+
+    pub fn set_scripts(&self, scripts: &RequestScripts) {
+        self.imp().set_scripts(scripts)
+    }
+
+    /// Writes what a script printed to the console, below what was there.
+    pub fn append_console(&self, lines: &[ConsoleLine]) {
+        self.imp().append_console(lines)
+    }
+}
@@ -15,19 +15,32 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use crate::{app::CarteroApplication, error::CarteroError};
+use crate::app::CarteroApplication;
+use crate::client::{CapturedValue, SharedCookieJar, SharedTokenCache};
+use crate::entities::{CollectionData, Environment, KeyValueTable};
+use crate::error::CarteroError;
+use gettextrs::gettext;
 use glib::subclass::types::ObjectSubclassIsExt;
 use glib::Object;
 use gtk::{gio, glib, prelude::SettingsExtManual};
 
 mod imp {
+    use std::cell::{Cell, RefCell};
+
     use adw::prelude::AlertDialogExtManual;
     use adw::AboutWindow;
     use adw::{subclass::prelude::*, TabPage};
-    use gettextrs::gettext;
+    use gettextrs::{gettext, ngettext};
     use gtk::gio::{self, ActionEntry};
     use gtk::prelude::*;
 
+    use crate::client::{
+        store_captures, unix_now, CapturedValue, CookieJar, RequestError, SharedCookieJar,
+        SharedTokenCache,
+    };
+    use crate::entities::{CollectionData, EndpointData, Environment, KeyValueTable};
+    use crate::file::{collection_item_name, unique_file_name, COOKIE_FILE, REQUEST_SUFFIX};
+    use crate::import::{request_name, ImportedCollection};
     use crate::utils::SingleExpressionWatch;
     use crate::{app::CarteroApplication, error::CarteroError};
     use crate::{config, widgets::*};
@@ -53,6 +66,33 @@ mod imp {
         #[template_child]
         stack: TemplateChild<gtk::Stack>,
 
+        #[template_child]
+        environment_dropdown: TemplateChild<gtk::DropDown>,
+
+        #[template_child]
+        environment_list: TemplateChild<gtk::StringList>,
+
+        environments: RefCell<Vec<(gio::File, Environment)>>,
+
+        environments_loaded: Cell<bool>,
+
+        #[template_child]
+        split_view: TemplateChild<adw::OverlaySplitView>,
+
+        #[template_child]
+        collection_pane: TemplateChild<CollectionPane>,
+
+        collection: RefCell<Option<CollectionData>>,
+
+        collection_monitor: RefCell<Option<gio::FileMonitor>>,
+
+        pub(super) cookie_jar: SharedCookieJar,
+
+        pub(super) token_cache: SharedTokenCache,
+
+        /// The variables captured in the session, or in the environment when none is active.
+        pub(super) session_variables: RefCell<KeyValueTable>,
+
         window_title_binding: SingleExpressionWatch,
 
         window_subtitle_binding: SingleExpressionWatch,
@@ -73,6 +113,33 @@ mod imp {
 
         #[template_child]
         stack: TemplateChild<gtk::Stack>,
+
+        #[template_child]
+        environment_dropdown: TemplateChild<gtk::DropDown>,
+
+        #[template_child]
+        environment_list: TemplateChild<gtk::StringList>,
+
+        environments: RefCell<Vec<(gio::File, Environment)>>,
+
+        environments_loaded: Cell<bool>,
+
+        #[template_child]
+        split_view: TemplateChild<adw::OverlaySplitView>,
+
+        #[template_child]
+        collection_pane: TemplateChild<CollectionPane>,
+
+        collection: RefCell<Option<CollectionData>>,
+
+        collection_monitor: RefCell<Option<gio::FileMonitor>>,
+
+        pub(super) cookie_jar: SharedCookieJar,
+
+        pub(super) token_cache: SharedTokenCache,
+
+        /// The variables captured in the session, or in the environment when none is active.
+        pub(super) session_variables: RefCell<KeyValueTable>,
     }
 
     #[gtk::template_callbacks]
@@ -80,7 +147,7 @@ mod imp {
         fn update_tab_actions(&self) {
             let has_tabs = self.tabview.n_pages() > 0;
             let obj = self.obj();
-            let actions = vec!["save", "save-as", "close"];
+            let actions = vec!["save", "save-as", "close", "export-har"];
             for action in actions {
                 if let Some(action) = obj.lookup_action(action) {
                     action.set_property("enabled", has_tabs);
@@ -117,6 +184,15 @@ mod imp {
         #[cfg(not(feature = "csd"))]
         fn bind_current_tab(&self, _: Option<&ItemPane>) {}
 
+        /// Enables the actions that only make sense while a collection is open.
+        fn update_collection_actions(&self) {
+            let obj = self.obj();
+            let has_collection = self.collection_pane.root().is_some();
+            if let Some(action) = obj.lookup_action("run-collection") {
+                action.set_property("enabled", has_collection);
+            }
+        }
+
         fn init_settings(&self) {
             let app = CarteroApplication::get();
             let settings = app.settings();
@@ -126,6 +202,7 @@ mod imp {
                 "auto-indent",
                 "body-wrap",
                 "indent-style",
+                "request-timeout",
                 "show-line-numbers",
                 "tab-width",
             ];
@@ -134,6 +211,16 @@ mod imp {
                 obj.add_action(&action);
             }
 
+            // Keep the cookies that are already in the jar once they start being saved.
+            settings.connect_changed(
+                Some("persist-cookies"),
+                glib::clone!(@weak self as window => move |_, _| {
+                    glib::spawn_future_local(glib::clone!(@weak window => async move {
+                        window.save_cookies().await;
+                    }));
+                }),
+            );
+
             // The following settings are only read once. They will be saved when the window closes.
             let width = settings.get::<i32>("window-width");
             let height = settings.get::<i32>("window-height");
@@ -189,6 +276,330 @@ mod imp {
             Some(page)
         }
 
+        /// Returns the environment picked in the header bar, if any.
+        pub fn active_environment(&self) -> Option<Environment> {
+            // The first item of the selector is "No environment".
+            let position = self.environment_dropdown.selected().checked_sub(1)?;
+            self.environments
+                .borrow()
+                .get(position as usize)
+                .map(|(_, environment)| environment.clone())
+        }
+
+        /// Keeps the captured values in the active environment or in the session. The
+        /// values captured in the request scope go to the given variables.
+        ///
+        /// The environment only changes in memory, so its file is left as it was.
+        pub fn store_captures(&self, values: &[CapturedValue], request: &mut KeyValueTable) {
+            let position = self.environment_dropdown.selected().checked_sub(1);
+            let mut environments = self.environments.borrow_mut();
+            let environment = position
+                .and_then(|position| environments.get_mut(position as usize))
+                .map(|(_, environment)| environment);
+            let mut session = self.session_variables.borrow_mut();
+            store_captures(values, request, environment, &mut session);
+        }
+
+        async fn read_environment(&self, file: &gio::File) -> Result<Environment, CarteroError> {
+            let contents = crate::widgets::read_file(file).await?;
+            let file_name = file
+                .basename()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let name = crate::file::environment_name(&file_name);
+            let mut environment = crate::file::parse_environment_toml(&name, &contents)?;
+            // Certificate paths are relative to the environment file.
+            if let Some(dir) = file.parent().and_then(|d| d.path()) {
+                environment.tls = environment.tls.with_base_dir(&dir);
+            }
+            Ok(environment)
+        }
+
+        /// Rebuilds the items of the environment selector after the first one.
+        fn sync_environment_list(&self) {
+            let names: Vec<String> = self
+                .environments
+                .borrow()
+                .iter()
+                .map(|(_, environment)| environment.name.clone())
+                .collect();
+            let names: Vec<&str> = names.iter().map(String::as_str).collect();
+            let previous = self.environment_list.n_items() - 1;
+            self.environment_list.splice(1, previous, &names);
+        }
+
+        fn save_environment_files(&self) {
+            let paths: Vec<String> = self
+                .environments
+                .borrow()
+                .iter()
+                .filter_map(|(file, _)| file.path())
+                .map(|path| path.display().to_string())
+                .collect();
+
+            let app = CarteroApplication::get();
+            let settings = app.settings();
+            let _ = settings.set("environment-files", paths);
+        }
+
+        /// Loads the environment files stored in the settings and restores the active one.
+        pub async fn load_environments(&self) {
+            let app = CarteroApplication::get();
+            let settings = app.settings();
+            let paths = settings.get::<Vec<String>>("environment-files");
+            let active = settings.get::<Option<String>>("active-environment");
+
+            let mut environments = Vec::new();
+            for path in paths {
+                let file = gio::File::for_path(path);
+                match self.read_environment(&file).await {
+                    Ok(environment) => environments.push((file, environment)),
+                    Err(e) => self.toast_error(e),
+                }
+            }
+
+            let selected = active
+                .map(gio::File::for_path)
+                .and_then(|active| environments.iter().position(|(f, _)| f.equal(&active)))
+                .map_or(0, |position| position as u32 + 1);
+            self.environments.replace(environments);
+            self.sync_environment_list();
+            self.environment_dropdown.set_selected(selected);
+            self.environments_loaded.set(true);
+        }
+
+        async fn trigger_open_environment(&self) -> Result<(), CarteroError> {
+            let obj = self.obj();
+            let file = crate::widgets::open_environment_file(&obj).await?;
+            let environment = self.read_environment(&file).await?;
+
+            // Opening an environment that is already in the list reloads it.
+            let position = {
+                let mut environments = self.environments.borrow_mut();
+                match environments.iter().position(|(f, _)| f.equal(&file)) {
+                    Some(position) => {
+                        environments[position].1 = environment;
+                        position
+                    }
+                    None => {
+                        environments.push((file, environment));
+                        environments.len() - 1
+                    }
+                }
+            };
+            self.sync_environment_list();
+            self.save_environment_files();
+            self.environments_loaded.set(true);
+            self.environment_dropdown.set_selected(position as u32 + 1);
+            self.on_environment_selected();
+            Ok(())
+        }
+
+        #[template_callback]
+        fn on_environment_selected(&self) {
+            // The selector changes while the template is built, which must not
+            // replace the environment saved in the settings.
+            if !self.environments_loaded.get() {
+                return;
+            }
+
+            let path = self
+                .environment_dropdown
+                .selected()
+                .checked_sub(1)
+                .and_then(|position| {
+                    let environments = self.environments.borrow();
+                    let (file, _) = environments.get(position as usize)?;
+                    file.path()
+                })
+                .map(|path| path.display().to_string());
+            let app = CarteroApplication::get();
+            let settings = app.settings();
+            let _ = settings.set("active-environment", path);
+
+            self.refresh_export_panes();
+        }
+
+        /// Regenerates the export pane of every tab, after a change in the settings
+        /// that every request inherits, such as the environment or the collection.
+        fn refresh_export_panes(&self) {
+            for page in self.tabview.pages().iter::<TabPage>().flatten() {
+                let item = page.child().downcast::<ItemPane>().unwrap();
+                if let Some(endpoint) = item.endpoint() {
+                    endpoint.refresh_export();
+                }
+            }
+        }
+
+        /// Returns the folder of the open collection, if any.
+        pub fn collection_root(&self) -> Option<gio::File> {
+            self.collection_pane.root()
+        }
+
+        /// Returns the settings of the open collection if the given file is part of it.
+        pub fn collection_for(&self, file: &gio::File) -> Option<CollectionData> {
+            let root = self.collection_pane.root()?;
+            if file.has_prefix(&root) {
+                self.collection.borrow().clone()
+            } else {
+                None
+            }
+        }
+
+        /// Reads the collection.toml file of the open collection.
+        async fn load_collection_file(&self) {
+            let Some(root) = self.collection_pane.root() else {
+                self.collection.replace(None);
+                return;
+            };
+
+            let file = root.child(crate::file::COLLECTION_FILE);
+            let collection = if file.query_exists(gio::Cancellable::NONE) {
+                let contents = crate::widgets::read_file(&file).await;
+                match contents.and_then(|c| crate::file::parse_collection_toml(&c)) {
+                    Ok(mut collection) => {
+                        if let Some(dir) = root.path() {
+                            collection.tls = collection.tls.with_base_dir(&dir);
+                        }
+                        collection
+                    }
+                    Err(e) => {
+                        self.toast_error(e);
+                        CollectionData::default()
+                    }
+                }
+            } else {
+                CollectionData::default()
+            };
+            self.collection.replace(Some(collection));
+            self.refresh_export_panes();
+        }
+
+        /// Shows the collection stored in a folder in the sidebar, or closes it.
+        pub async fn open_collection(&self, folder: Option<&gio::File>) {
+            self.collection_pane.set_root(folder);
+            self.collection_monitor.replace(None);
+            self.update_collection_actions();
+
+            let path = folder
+                .and_then(|f| f.path())
+                .map(|path| path.display().to_string());
+            let app = CarteroApplication::get();
+            let settings = app.settings();
+            let _ = settings.set("collection", path);
+
+            if let Some(folder) = folder {
+                // Keep the inherited settings up to date when collection.toml is edited.
+                let file = folder.child(crate::file::COLLECTION_FILE);
+                if let Ok(monitor) =
+                    file.monitor_file(gio::FileMonitorFlags::NONE, gio::Cancellable::NONE)
+                {
+                    monitor.connect_changed(
+                        glib::clone!(@weak self as window => move |_, _, _, event| {
+                            if matches!(
+                                event,
+                                gio::FileMonitorEvent::ChangesDoneHint
+                                    | gio::FileMonitorEvent::Created
+                                    | gio::FileMonitorEvent::Deleted
+                            ) {
+                                glib::spawn_future_local(glib::clone!(@weak window => async move {
+                                    window.load_collection_file().await;
+                                }));
+                            }
+                        }),
+                    );
+                    self.collection_monitor.replace(Some(monitor));
+                }
+                self.split_view.set_show_sidebar(true);
+            }
+
+            self.load_collection_file().await;
+            self.load_cookies().await;
+        }
+
+        /// Returns the file where the cookie jar is saved, or `None` if cookies
+        /// are only kept in memory. Each collection has its own cookies, and the
+        /// cookies used without a collection are kept in the user data folder.
+        fn cookie_file(&self) -> Option<gio::File> {
+            let settings = CarteroApplication::get().settings();
+            if !settings.get::<bool>("persist-cookies") {
+                return None;
+            }
+            let file = match self.collection_pane.root() {
+                Some(root) => root.child(COOKIE_FILE),
+                None => {
+                    let dir = glib::user_data_dir().join("cartero");
+                    gio::File::for_path(dir.join(COOKIE_FILE))
+                }
+            };
+            Some(file)
+        }
+
+        /// Replaces the cookies in the jar with the ones saved to disk.
+        pub async fn load_cookies(&self) {
+            let Some(file) = self.cookie_file() else {
+                return;
+            };
+            let jar = if file.query_exists(gio::Cancellable::NONE) {
+                match crate::widgets::read_file(&file).await {
+                    Ok(contents) => CookieJar::parse_netscape(&contents, unix_now()),
+                    Err(e) => {
+                        self.toast_error(e);
+                        return;
+                    }
+                }
+            } else {
+                CookieJar::new()
+            };
+            *self.cookie_jar.lock().unwrap() = jar;
+        }
+
+        /// Saves the cookie jar to disk, if cookies are being remembered.
+        pub async fn save_cookies(&self) {
+            let Some(file) = self.cookie_file() else {
+                return;
+            };
+            if let Some(dir) = file.parent() {
+                // Fails when the folder already exists, which is fine.
+                let _ = dir.make_directory_with_parents(gio::Cancellable::NONE);
+            }
+            let contents = self.cookie_jar.lock().unwrap().to_netscape();
+            if let Err(e) = crate::widgets::write_file(&file, &contents).await {
+                self.toast_error(e);
+            }
+        }
+
+        async fn trigger_open_collection(&self) -> Result<(), CarteroError> {
+            let obj = self.obj();
+            let title = gettext("Open collection");
+            let folder = crate::widgets::open_folder(&obj, &title, None).await?;
+            self.open_collection(Some(&folder)).await;
+            Ok(())
+        }
+
+        /// Updates the tabs after a file or a folder of the collection is renamed,
+        /// moved or deleted. Tabs whose file has been deleted become unsaved drafts.
+        pub fn relocate_files(&self, from: &gio::File, to: Option<&gio::File>) {
+            for page in self.tabview.pages().iter::<TabPage>().flatten() {
+                let item = page.child().downcast::<ItemPane>().unwrap();
+                let Some(file) = item.file() else {
+                    continue;
+                };
+                let destination = if file.equal(from) {
+                    to.cloned()
+                } else if let Some(relative) = from.relative_path(&file) {
+                    to.map(|to| to.resolve_relative_path(relative))
+                } else {
+                    continue;
+                };
+                if destination.is_none() {
+                    item.set_dirty(true);
+                }
+                item.set_file(destination);
+            }
+            self.save_visible_tabs();
+        }
+
         fn find_pane_by_path(&self, file: &gio::File) -> Option<TabPage> {
             self.tabview
                 .pages()
@@ -221,15 +632,35 @@ mod imp {
             }
 
             match ItemPane::new_for_endpoint(file).await {
+                Ok(pane) => self.add_pane(&pane),
+                Err(e) => {
+                    self.obj().toast_error(e);
+                }
+            };
+        }
+
+        fn add_pane(&self, pane: &ItemPane) {
+            self.stack.set_visible_child_name("tabview");
+            let page = self.tabview.add_page(pane, None);
+            pane.window_title_binding().bind(&page, "title", Some(pane));
+            pane.window_subtitle_binding()
+                .bind(&page, "tooltip", Some(pane));
+            self.tabview.set_selected_page(&page);
+            self.save_visible_tabs();
+            if let Some(endpoint) = pane.endpoint() {
+                endpoint.refresh_export();
+            }
+        }
+
+        /// Opens a new tab with the given endpoint, as an unsaved document.
+        async fn add_draft(&self, endpoint: &EndpointData) {
+            match ItemPane::new_for_endpoint(None).await {
                 Ok(pane) => {
-                    self.stack.set_visible_child_name("tabview");
-                    let page = self.tabview.add_page(&pane, None);
-                    pane.window_title_binding()
-                        .bind(&page, "title", Some(&pane));
-                    pane.window_subtitle_binding()
-                        .bind(&page, "tooltip", Some(&pane));
-                    self.tabview.set_selected_page(&page);
-                    self.save_visible_tabs();
+                    if let Some(endpoint_pane) = pane.endpoint() {
+                        endpoint_pane.assign_endpoint(endpoint);
+                    }
+                    pane.set_dirty(true);
+                    self.add_pane(&pane);
                 }
                 Err(e) => {
                     self.obj().toast_error(e);
@@ -237,6 +668,225 @@ mod imp {
             };
         }
 
+        /// Opens imported requests as new tabs, or saves them into a folder.
+        async fn import_requests(&self, endpoints: Vec<EndpointData>) -> Result<(), CarteroError> {
+            if endpoints.is_empty() {
+                self.toast_message(&gettext("There are no requests to import"));
+                return Ok(());
+            }
+            if endpoints.len() > 1 {
+                let dialog = adw::AlertDialog::builder()
+                    .heading(gettext("Import requests"))
+                    .body(
+                        ngettext(
+                            "{} request was found.",
+                            "{} requests were found.",
+                            endpoints.len() as u32,
+                        )
+                        .replace("{}", &endpoints.len().to_string()),
+                    )
+                    .close_response("cancel")
+                    .default_response("tabs")
+                    .build();
+                dialog.add_response("cancel", &gettext("_Cancel"));
+                dialog.add_response("folder", &gettext("_Save to Folder"));
+                dialog.add_response("tabs", &gettext("_Open as Tabs"));
+                dialog.set_response_appearance("tabs", adw::ResponseAppearance::Suggested);
+                match dialog.choose_future(&*self.obj()).await.as_str() {
+                    "folder" => return self.save_imported_requests(&endpoints).await,
+                    "tabs" => {}
+                    _ => return Ok(()),
+                }
+            }
+            for endpoint in endpoints {
+                self.add_draft(&endpoint).await;
+            }
+            Ok(())
+        }
+
+        async fn save_imported_requests(
+            &self,
+            endpoints: &[EndpointData],
+        ) -> Result<(), CarteroError> {
+            let obj = self.obj();
+            let root = self.collection_pane.root();
+            let folder = open_folder(&obj, &gettext("Save requests"), root.as_ref()).await?;
+            for endpoint in endpoints {
+                let name = collection_item_name(&request_name(endpoint), Some(REQUEST_SUFFIX))
+                    .ok_or(CarteroError::InvalidFileName)?;
+                let name = unique_file_name(&name, |name| {
+                    folder.child(name).query_exists(gio::Cancellable::NONE)
+                });
+                let contents = crate::file::store_toml(endpoint)?;
+                write_file(&folder.child(name), &contents).await?;
+            }
+            let message = ngettext(
+                "{} request was imported",
+                "{} requests were imported",
+                endpoints.len() as u32,
+            );
+            self.toast_message(&message.replace("{}", &endpoints.len().to_string()));
+            Ok(())
+        }
+
+        /// Saves an imported collection into a new folder and opens it.
+        async fn save_imported_collection(
+            &self,
+            imported: &ImportedCollection,
+        ) -> Result<(), CarteroError> {
+            let gio_error =
+                |e: glib::Error| CarteroError::FileError(std::io::Error::other(e.to_string()));
+            let file_name = |name: &str, suffix: Option<&str>| {
+                collection_item_name(&name.replace(['/', '\\'], "-"), suffix)
+            };
+
+            let obj = self.obj();
+            let parent = open_folder(&obj, &gettext("Save collection"), None).await?;
+            let name = file_name(&imported.name, None).unwrap_or_else(|| gettext("Collection"));
+            let name = unique_file_name(&name, |name| {
+                parent.child(name).query_exists(gio::Cancellable::NONE)
+            });
+            let root = parent.child(name);
+            root.make_directory_future(glib::Priority::DEFAULT)
+                .await
+                .map_err(gio_error)?;
+
+            let contents = crate::file::store_collection_toml(&imported.collection)?;
+            write_file(&root.child(crate::file::COLLECTION_FILE), &contents).await?;
+
+            for request in &imported.requests {
+                let mut folder = root.clone();
+                for name in &request.folders {
+                    if let Some(name) = file_name(name, None) {
+                        folder = folder.child(name);
+                    }
+                }
+                if !folder.query_exists(gio::Cancellable::NONE) {
+                    folder
+                        .make_directory_with_parents(gio::Cancellable::NONE)
+                        .map_err(gio_error)?;
+                }
+                let name = file_name(&request.name, Some(REQUEST_SUFFIX))
+                    .or_else(|| file_name(&request_name(&request.endpoint), Some(REQUEST_SUFFIX)))
+                    .ok_or(CarteroError::InvalidFileName)?;
+                let name = unique_file_name(&name, |name| {
+                    folder.child(name).query_exists(gio::Cancellable::NONE)
+                });
+                let contents = crate::file::store_toml(&request.endpoint)?;
+                write_file(&folder.child(name), &contents).await?;
+            }
+
+            self.open_collection(Some(&root)).await;
+            if !imported.warnings.is_empty() {
+                self.show_import_warnings(&imported.warnings).await;
+            }
+            Ok(())
+        }
+
+        /// Lists the parts of an imported document that could not be translated.
+        async fn show_import_warnings(&self, warnings: &[String]) {
+            let label = gtk::Label::builder()
+                .label(warnings.join("\n"))
+                .selectable(true)
+                .wrap(true)
+                .xalign(0.0)
+                .yalign(0.0)
+                .margin_top(6)
+                .margin_bottom(6)
+                .margin_start(6)
+                .margin_end(6)
+                .build();
+            let scroll = gtk::ScrolledWindow::builder()
+                .child(&label)
+                .min_content_height(160)
+                .min_content_width(360)
+                .css_classes(["card"])
+                .build();
+            let dialog = adw::AlertDialog::builder()
+                .heading(gettext("Some items could not be imported"))
+                .body(gettext(
+                    "The rest of the collection was imported, but these items have to be reviewed.",
+                ))
+                .extra_child(&scroll)
+                .close_response("close")
+                .default_response("close")
+                .build();
+            dialog.add_response("close", &gettext("_Close"));
+            dialog.choose_future(&*self.obj()).await;
+        }
+
+        async fn trigger_import_postman(&self) -> Result<(), CarteroError> {
+            let file = open_postman_file(&self.obj()).await?;
+            let contents = read_file(&file).await?;
+            let imported = crate::import::postman::parse_postman(&contents)?;
+            self.save_imported_collection(&imported).await
+        }
+
+        async fn trigger_import_openapi(&self) -> Result<(), CarteroError> {
+            let file = open_openapi_file(&self.obj()).await?;
+            let contents = read_file(&file).await?;
+            let imported = crate::import::openapi::parse_openapi(&contents)?;
+            self.save_imported_collection(&imported).await
+        }
+
+        async fn trigger_import_har(&self) -> Result<(), CarteroError> {
+            let file = open_har_file(&self.obj()).await?;
+            let contents = read_file(&file).await?;
+            let endpoints = crate::import::har::parse_har(&contents)?;
+            self.import_requests(endpoints).await
+        }
+
+        async fn trigger_export_har(&self) -> Result<(), CarteroError> {
+            let Some(endpoint) = self.current_pane().and_then(|pane| pane.endpoint()) else {
+                return Ok(());
+            };
+            let document = endpoint.export_har()?;
+            let file = save_har_file(&self.obj()).await?;
+            write_file(&file, &document).await
+        }
+
+        async fn trigger_new_from_curl(&self) -> Result<(), CarteroError> {
+            let buffer = gtk::TextBuffer::new(None);
+            let text_view = gtk::TextView::builder()
+                .buffer(&buffer)
+                .monospace(true)
+                .wrap_mode(gtk::WrapMode::WordChar)
+                .top_margin(6)
+                .bottom_margin(6)
+                .left_margin(6)
+                .right_margin(6)
+                .build();
+            let scroll = gtk::ScrolledWindow::builder()
+                .child(&text_view)
+                .min_content_height(160)
+                .min_content_width(360)
+                .css_classes(["card"])
+                .build();
+
+            let dialog = adw::AlertDialog::builder()
+                .heading(gettext("New from curl"))
+                .body(gettext(
+                    "Paste a curl command to create a request out of it.",
+                ))
+                .extra_child(&scroll)
+                .close_response("cancel")
+                .default_response("import")
+                .build();
+            dialog.add_response("cancel", &gettext("_Cancel"));
+            dialog.add_response("import", &gettext("_Import"));
+            dialog.set_response_appearance("import", adw::ResponseAppearance::Suggested);
+
+            let response = dialog.choose_future(&*self.obj()).await;
+            if response.as_str() != "import" {
+                return Ok(());
+            }
+            let (start, end) = buffer.bounds();
+            let command = buffer.text(&start, &end, false);
+            let endpoint = crate::import::curl::parse_curl(&command)?;
+            self.add_draft(&endpoint).await;
+            Ok(())
+        }
+
         async fn trigger_open(&self) -> Result<(), CarteroError> {
             // In order to place the modal, we need a reference to the public type.
             let obj = self.obj();
@@ -263,7 +913,7 @@ mod imp {
 
             let endpoint = endpoint.extract_endpoint()?;
             let serialized_payload = crate::file::store_toml(&endpoint)?;
-            crate::file::write_file(&file, &serialized_payload).await?;
+            crate::widgets::write_file(&file, &serialized_payload).await?;
             pane.set_file(Some(file.clone()));
             pane.set_dirty(false);
 
@@ -280,7 +930,7 @@ mod imp {
 
             let endpoint = endpoint.extract_endpoint()?;
             let serialized_payload = crate::file::store_toml(&endpoint)?;
-            crate::file::write_file(&file, &serialized_payload).await?;
+            crate::widgets::write_file(&file, &serialized_payload).await?;
             pane.set_file(Some(file.clone()));
             pane.set_dirty(false);
 
@@ -364,6 +1014,7 @@ mod imp {
         type ParentType = gtk::ApplicationWindow;
 
         fn class_init(klass: &mut Self::Class) {
+            CollectionPane::static_type();
             KeyValueRow::static_type();
             KeyValuePane::static_type();
             EndpointPane::static_type();
@@ -451,15 +1102,76 @@ mod imp {
                 }))
                 .build();
 
+            let action_new_from_curl = ActionEntry::builder("new-from-curl")
+                .activate(glib::clone!(@weak self as window => move |_, _, _| {
+                    glib::spawn_future_local(glib::clone!(@weak window => async move {
+                        if let Err(e) = window.trigger_new_from_curl().await {
+                            window.toast_error(e);
+                        }
+                    }));
+                }))
+                .build();
+
+            let action_import_har = ActionEntry::builder("import-har")
+                .activate(glib::clone!(@weak self as window => move |_, _, _| {
+                    glib::spawn_future_local(glib::clone!(@weak window => async move {
+                        if let Err(e) = window.trigger_import_har().await {
+                            match e {
+                                CarteroError::NoFilePicked => {},
+                                e => window.toast_error(e),
+                            };
+                        }
+                    }));
+                }))
+                .build();
+            let action_import_postman = ActionEntry::builder("import-postman")
+                .activate(glib::clone!(@weak self as window => move |_, _, _| {
+                    glib::spawn_future_local(glib::clone!(@weak window => async move {
+                        if let Err(e) = window.trigger_import_postman().await {
+                            match e {
+                                CarteroError::NoFilePicked => {},
+                                e => window.toast_error(e),
+                            };
+                        }
+                    }));
+                }))
+                .build();
+            let action_import_openapi = ActionEntry::builder("import-openapi")
+                .activate(glib::clone!(@weak self as window => move |_, _, _| {
+                    glib::spawn_future_local(glib::clone!(@weak window => async move {
+                        if let Err(e) = window.trigger_import_openapi().await {
+                            match e {
+                                CarteroError::NoFilePicked => {},
+                                e => window.toast_error(e),
+                            };
+                        }
+                    }));
+                }))
+                .build();
+            let action_export_har = ActionEntry::builder("export-har")
+                .activate(glib::clone!(@weak self as window => move |_, _, _| {
+                    glib::spawn_future_local(glib::clone!(@weak window => async move {
+                        if let Err(e) = window.trigger_export_har().await {
+                            match e {
+                                CarteroError::NoFilePicked => {},
+                                e => window.toast_error(e),
+                            };
+                        }
+                    }));
+                }))
+                .build();
+
             let action_request = ActionEntry::builder("request")
                 .activate(glib::clone!(@weak self as window => move |_, _, _| {
                     glib::spawn_future_local(glib::clone!(@weak window => async move {
                         if let Some(pane) = window.current_pane().and_then(|e| e.endpoint()) {
-                            pane.set_sensitive(false);
-                            if let Err(e) = pane.perform_request().await {
-                                window.toast_error(e);
+                            match pane.perform_request().await {
+                                Ok(_) | Err(CarteroError::Request(RequestError::Cancelled)) => {},
+                                Err(CarteroError::Request(e @ RequestError::Timeout(_))) => {
+                                    window.toast_message(&e.to_string());
+                                }
+                                Err(e) => window.toast_error(e),
                             }
-                            pane.set_sensitive(true);
                         }
                     }));
                 }))
@@ -502,6 +1214,60 @@ mod imp {
                 }))
                 .build();
 
+            let action_open_collection = ActionEntry::builder("open-collection")
+                .activate(glib::clone!(@weak self as window => move |_, _, _| {
+                    glib::spawn_future_local(glib::clone!(@weak window => async move {
+                        if let Err(e) = window.trigger_open_collection().await {
+                            match e {
+                                CarteroError::NoFilePicked => {},
+                                e => window.toast_error(e),
+                            };
+                        }
+                    }));
+                }))
+                .build();
+            let action_close_collection = ActionEntry::builder("close-collection")
+                .activate(glib::clone!(@weak self as window => move |_, _, _| {
+                    glib::spawn_future_local(glib::clone!(@weak window => async move {
+                        window.open_collection(None).await;
+                    }));
+                }))
+                .build();
+
+            let action_run_collection = ActionEntry::builder("run-collection")
+                .activate(glib::clone!(@weak self as window => move |_, _, _| {
+                    let dialog = RunnerDialog::new(&window.obj());
+                    dialog.present(&*window.obj());
+                }))
+                .build();
+
+            let action_open_environment = ActionEntry::builder("open-environment")
+                .activate(glib::clone!(@weak self as window => move |_, _, _| {
+                    glib::spawn_future_local(glib::clone!(@weak window => async move {
+                        if let Err(e) = window.trigger_open_environment().await {
+                            match e {
+                                CarteroError::NoFilePicked => {},
+                                e => window.toast_error(e),
+                            };
+                        }
+                    }));
+                }))
+                .build();
+
+            let action_proxy_settings = ActionEntry::builder("proxy-settings")
+                .activate(glib::clone!(@weak self as window => move |_, _, _| {
+                    let dialog = ProxyDialog::default();
+                    dialog.present(&*window.obj());
+                }))
+                .build();
+
+            let action_cookies = ActionEntry::builder("cookies")
+                .activate(glib::clone!(@weak self as window => move |_, _, _| {
+                    let dialog = CookieDialog::new(&window.obj());
+                    dialog.present(&*window.obj());
+                }))
+                .build();
+
             let action_about = ActionEntry::builder("about")
                 .activate(glib::clone!(@weak self as window => move |_, _, _| {
                     let about = AboutWindow::builder()
@@ -524,14 +1290,26 @@ mod imp {
             let obj = self.obj();
             obj.add_action_entries([
                 action_new,
+                action_new_from_curl,
+                action_import_har,
+                action_import_postman,
+                action_import_openapi,
+                action_export_har,
                 action_request,
                 action_open,
                 action_save,
                 action_save_as,
                 action_close,
+                action_open_collection,
+                action_close_collection,
+                action_run_collection,
+                action_open_environment,
+                action_proxy_settings,
+                action_cookies,
                 action_about,
             ]);
             self.update_tab_actions();
+            self.update_collection_actions();
         }
     }
 
@@ -602,14 +1380,84 @@ impl CarteroWindow {
         imp.toast_message(msg);
     }
 
+    /// Returns the environment picked in the header bar, if any.
+    pub fn active_environment(&self) -> Option<Environment> {
+        let imp = self.imp();
+        imp.active_environment()
+    }
+
+    /// Returns the folder of the open collection, if any.
+    pub fn collection_root(&self) -> Option<gio::File> {
+        self.imp().collection_root()
+    }
+
+    /// Returns the settings of the open collection if the given file is part of it.
+    pub fn collection_for(&self, file: &gio::File) -> Option<CollectionData> {
+        let imp = self.imp();
+        imp.collection_for(file)
+    }
+
+    /// Opens a page in the web browser, such as the login page of an OAuth 2.0 client.
+    pub fn open_browser(&self, url: &str) -> std::io::Result<()> {
+        let window = self.clone();
+        gtk::UriLauncher::new(url).launch(Some(self), gio::Cancellable::NONE, move |outcome| {
+            if outcome.is_err() {
+                window.toast_message(&gettext("Cannot open the web browser to log in"));
+            }
+        });
+        Ok(())
+    }
+
+    /// Returns the cookie jar shared by every tab of the window.
+    pub fn cookie_jar(&self) -> SharedCookieJar {
+        self.imp().cookie_jar.clone()
+    }
+
+    /// Returns the OAuth 2.0 tokens shared by every tab of the window.
+    pub fn token_cache(&self) -> SharedTokenCache {
+        self.imp().token_cache.clone()
+    }
+
+    /// Returns the variables captured in the session by the requests of the window.
+    pub fn session_variables(&self) -> KeyValueTable {
+        self.imp().session_variables.borrow().clone()
+    }
+
+    /// Keeps the values captured from a response in the active environment or in the
+    /// session. The values captured in the request scope go to the given variables.
+    pub fn store_captures(&self, values: &[CapturedValue], request: &mut KeyValueTable) {
+        self.imp().store_captures(values, request);
+    }
+
+    /// Saves the cookie jar to disk, if cookies are being remembered.
+    pub async fn save_cookies(&self) {
+        self.imp().save_cookies().await;
+    }
+
+    pub fn relocate_files(&self, from: &gio::File, to: Option<&gio::File>) {
+        let imp = self.imp();
+        imp.relocate_files(from, to);
+    }
+
     pub fn sync_open_files(&self) {
         let imp = self.imp();
         imp.save_visible_tabs();
     }
 
     pub async fn open_last_session(&self) {
+        self.imp().load_environments().await;
+
         let app = CarteroApplication::get();
         let settings = app.settings();
+        let folder = settings
+            .get::<Option<String>>("collection")
+            .map(gio::File::for_path)
+            .filter(|folder| folder.query_exists(gio::Cancellable::NONE));
+        match folder {
+            Some(folder) => self.imp().open_collection(Some(&folder)).await,
+            None => self.imp().load_cookies().await,
+        }
+
         let open_files = settings.get::<Vec<String>>("open-files");
         for open_file in open_files {
             let typed = open_file.split_once(':');